@@ -0,0 +1,120 @@
+## Shared
+
+next-steps = Nächste Schritte:
+
+## launchpad init
+
+init-header = Launchpad Init
+init-detected-preset = Projekttyp: { $preset }
+init-prompt-preset = Projekttyp
+init-detected-kmp = Kotlin Multiplatform: :{ $module } ({ $integration })
+init-kmp-not-found = Kein Kotlin-Multiplatform-Modul mit iOS-Targets gefunden
+init-kmp-no-embed-phase = Das Xcode-Projekt führt :{ $module }:embedAndSignAppleFrameworkForXcode nicht aus; füge es als Run-Script-Build-Phase hinzu
+init-found-project = iOS-Projekt gefunden unter: { $path }
+init-using-project-first = Verwende iOS-Projekt: { $path } (erstes von { $count })
+init-select-project = Mehrere Xcode-Projekte gefunden. Bitte eines auswählen:
+init-detected-scheme = Erkanntes Schema: { $scheme }
+init-using-scheme-first = Verwende Schema: { $scheme } (erstes von { $count })
+init-select-scheme = Mehrere Schemata gefunden. Bitte eines auswählen:
+init-using-bundle-id = Verwende Bundle-ID: { $bundle_id }
+init-prompt-bundle-id = Bundle-Identifier
+init-using-bundle-id-app = Verwende Bundle-ID: { $bundle_id } (App-Target, eine von { $count })
+init-select-bundle-id = Das Schema baut mehrere Bundle-IDs. Bitte die der App auswählen:
+init-asc-app-id = App-Store-Connect-App-ID: { $app_id }
+init-asc-app-id-unknown = App-Store-Connect-App-ID nicht gefunden ({ $error }); API-Aufrufe suchen sie über die Bundle-ID
+init-signing = Signierung: { $style }
+init-signing-unknown = Signierungsart nicht erkannt; sie wird beim Deploy ermittelt
+init-signing-manual-todo = Manuelle Signierung: vor dem Deploy [signing.profiles] in .launchpad.toml eintragen
+init-prompt-profile = Name des Provisioning-Profils für { $bundle_id }
+init-git-tag-default = Git-Tags: aktiviert (Standard)
+init-prompt-git-tag = Nach dem Deploy Git-Tags erstellen?
+init-prompt-push-tags = Tags zum Remote pushen?
+init-created-config = .launchpad.toml erstellt
+init-created-example = .launchpad.toml.example erstellt (als Referenz fürs Team)
+init-prompt-gitignore-outputs = Build-Ausgaben von launchpad zu .gitignore hinzufügen?
+init-prompt-gitignore = .launchpad.toml zu .gitignore hinzufügen?
+init-added-gitignore = Zu .gitignore hinzugefügt
+init-complete = Einrichtung abgeschlossen!
+init-next-doctor = 1. 'launchpad doctor' ausführen, um die Einrichtung zu prüfen
+init-next-deploy = 2. 'launchpad deploy' ausführen, um nach TestFlight zu deployen
+init-fastlane-found = fastlane gefunden
+init-fastlane-missing = fastlane nicht gefunden
+init-fastlane-installing-yes = Installiere fastlane (--yes-Modus)...
+init-prompt-install-fastlane = fastlane installieren?
+init-fastlane-running-brew = Führe aus: brew install fastlane
+init-fastlane-installing = Installiere fastlane...
+init-fastlane-install-failed = fastlane konnte nicht über brew installiert werden
+init-fastlane-try-manual = Manuell versuchen: brew install fastlane
+init-fastlane-installed = fastlane installiert
+init-fastfile-found = Fastfile gefunden unter: { $path }
+init-fastfile-missing = Kein Fastfile in { $path }/fastlane/ gefunden
+init-fastfile-creating-yes = Erstelle Fastfile (--yes-Modus)...
+init-prompt-create-fastfile = Fastfile mit den benötigten Lanes erstellen?
+init-fastfile-skipped = Fastfile wird nicht erstellt. Du musst es manuell anlegen.
+init-created-file = { $path } erstellt
+
+## launchpad reconfigure
+
+reconfigure-header = Launchpad Reconfigure
+reconfigure-prompt-ios-path = Pfad zum iOS-Projekt
+reconfigure-prompt-scheme = Xcode-Scheme
+reconfigure-schemes-unavailable = Schemes in { $path } konnten nicht ermittelt werden
+reconfigure-changed = { $key }: { $old } → { $new }
+reconfigure-unchanged = Keine Änderungen; .launchpad.toml bleibt unverändert
+reconfigure-saved = .launchpad.toml aktualisiert
+reconfigure-asc-app-id-stale = project.asc_app_id ({ $app_id }) gehört zur alten Bundle-ID; bitte anpassen oder entfernen
+
+## launchpad refresh
+
+refresh-header = Launchpad Refresh
+refresh-ios-path-missing = In { $path } liegt kein Xcode-Projekt mehr; suche danach
+refresh-scheme-found = Scheme { $scheme } existiert noch
+refresh-scheme-missing = Scheme { $scheme } existiert nicht mehr
+refresh-bundle-id-unknown = Bundle-ID von { $scheme } konnte nicht gelesen werden
+refresh-target = Target { $target } ({ $bundle_id })
+refresh-targets-unknown = Targets konnten nicht ermittelt werden: { $error }
+refresh-profile-missing = Kein Provisioning-Profil für { $bundle_id }; trage es unter [signing.profiles] ein
+refresh-profile-stale = [signing.profiles] enthält { $bundle_id }, das kein Target mehr verwendet
+refresh-up-to-date = .launchpad.toml passt zum Xcode-Projekt
+refresh-prompt-apply = .launchpad.toml aktualisieren?
+refresh-not-applied = .launchpad.toml bleibt unverändert
+
+## launchpad setup
+
+setup-header = Launchpad Setup
+setup-intro = Hiermit werden deine App Store Connect API-Zugangsdaten eingerichtet.
+setup-intro-key-url = Du brauchst einen API-Schlüssel von: { $url }
+setup-prompt-overwrite = Vorhandene Konfiguration gefunden. Überschreiben?
+setup-prompt-key-id = API-Schlüssel-ID
+setup-prompt-issuer-id = Issuer-ID
+setup-prompt-key-path = Pfad zur .p8-Schlüsseldatei
+setup-key-not-found = Warnung: Schlüsseldatei nicht gefunden unter { $path }
+setup-prompt-continue = Trotzdem fortfahren?
+setup-overwrite-yes = Vorhandene Konfiguration wird überschrieben (--yes)
+setup-using-env = { $name }: aus { $env }
+setup-using-payload = Zugangsdaten aus { $path }
+setup-key-not-found-yes = Ohne Schlüsseldatei wird nicht fortgefahren (--yes)
+setup-copied-key = Schlüssel kopiert nach { $path }
+setup-saved = Konfiguration gespeichert
+setup-running-doctor = Führe Diagnose aus...
+setup-checks-failed = Einige Prüfungen sind fehlgeschlagen: { $error }
+setup-complete = Einrichtung abgeschlossen!
+setup-next-cd = 1. In dein iOS-Projekt wechseln
+setup-next-init = 2. 'launchpad init' ausführen
+setup-next-deploy = 3. 'launchpad deploy' ausführen
+setup-encrypt-header = Launchpad-Konfiguration verschlüsseln
+setup-encrypt-keychain = Generiertes Passwort im Anmeldeschlüsselbund gespeichert
+setup-encrypted-file = { $path } verschlüsselt
+setup-encrypt-key-outside = { $path } liegt außerhalb von ~/.launchpad/keys und wurde nicht verschlüsselt
+setup-encrypt-unlock = Launchpad fragt bei Bedarf nach dem Passwort (oder liest { $env })
+setup-decrypt-header = Launchpad-Konfiguration entschlüsseln
+setup-decrypted-file = { $path } entschlüsselt
+setup-prompt-passphrase = Neues Passwort
+setup-prompt-passphrase-confirm = Passwort wiederholen
+setup-passphrase-mismatch = Die Passwörter stimmen nicht überein
+setup-export-header = Onboarding-Paket exportieren
+setup-export-no-key = Der Schlüssel ist nicht enthalten; beim Import wird er unter { $path } erwartet (--include-key packt ihn mit ein)
+setup-exported = { $path } geschrieben
+setup-export-next = Auf dem neuen Rechner: launchpad setup import { $path } (Passwort per Abfrage oder aus { $env })
+setup-import-header = Onboarding-Paket importieren
+setup-prompt-bundle-passphrase = Paket-Passwort
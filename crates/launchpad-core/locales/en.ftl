@@ -0,0 +1,120 @@
+## Shared
+
+next-steps = Next steps:
+
+## launchpad init
+
+init-header = Launchpad Init
+init-detected-preset = Project type: { $preset }
+init-prompt-preset = Project type
+init-detected-kmp = Kotlin Multiplatform: :{ $module } ({ $integration })
+init-kmp-not-found = No Kotlin Multiplatform module with iOS targets found
+init-kmp-no-embed-phase = The Xcode project doesn't run :{ $module }:embedAndSignAppleFrameworkForXcode; add it as a Run Script build phase
+init-found-project = Found iOS project at: { $path }
+init-using-project-first = Using iOS project: { $path } (first of { $count })
+init-select-project = Multiple Xcode projects found. Please select one:
+init-detected-scheme = Detected scheme: { $scheme }
+init-using-scheme-first = Using scheme: { $scheme } (first of { $count })
+init-select-scheme = Multiple schemes found. Please select one:
+init-using-bundle-id = Using bundle ID: { $bundle_id }
+init-prompt-bundle-id = Bundle identifier
+init-using-bundle-id-app = Using bundle ID: { $bundle_id } (app target, one of { $count })
+init-select-bundle-id = The scheme builds several bundle IDs. Select the app's:
+init-asc-app-id = App Store Connect app ID: { $app_id }
+init-asc-app-id-unknown = Couldn't look up the App Store Connect app ID ({ $error }); API calls will look it up by bundle ID
+init-signing = Signing: { $style }
+init-signing-unknown = Could not detect signing style; it will be detected at deploy time
+init-signing-manual-todo = Manual signing: add [signing.profiles] to .launchpad.toml before deploying
+init-prompt-profile = Provisioning profile name for { $bundle_id }
+init-git-tag-default = Git tagging: enabled (default)
+init-prompt-git-tag = Create git tags after deploy?
+init-prompt-push-tags = Push tags to remote?
+init-created-config = Created .launchpad.toml
+init-created-example = Created .launchpad.toml.example (for team reference)
+init-prompt-gitignore-outputs = Add launchpad's build outputs to .gitignore?
+init-prompt-gitignore = Add .launchpad.toml to .gitignore?
+init-added-gitignore = Added to .gitignore
+init-complete = Setup Complete!
+init-next-doctor = 1. Run 'launchpad doctor' to verify setup
+init-next-deploy = 2. Run 'launchpad deploy' to deploy to TestFlight
+init-fastlane-found = fastlane found
+init-fastlane-missing = fastlane not found
+init-fastlane-installing-yes = Installing fastlane (--yes mode)...
+init-prompt-install-fastlane = Install fastlane?
+init-fastlane-running-brew = Running: brew install fastlane
+init-fastlane-installing = Installing fastlane...
+init-fastlane-install-failed = Failed to install fastlane via brew
+init-fastlane-try-manual = Try manually: brew install fastlane
+init-fastlane-installed = fastlane installed
+init-fastfile-found = Fastfile found at: { $path }
+init-fastfile-missing = Fastfile not found in { $path }/fastlane/
+init-fastfile-creating-yes = Creating Fastfile (--yes mode)...
+init-prompt-create-fastfile = Create Fastfile with required lanes?
+init-fastfile-skipped = Skipping Fastfile creation. You'll need to create it manually.
+init-created-file = Created { $path }
+
+## launchpad reconfigure
+
+reconfigure-header = Launchpad Reconfigure
+reconfigure-prompt-ios-path = Path to iOS project
+reconfigure-prompt-scheme = Xcode scheme
+reconfigure-schemes-unavailable = Could not list schemes in { $path }
+reconfigure-changed = { $key }: { $old } → { $new }
+reconfigure-unchanged = Nothing changed; .launchpad.toml left as is
+reconfigure-saved = Updated .launchpad.toml
+reconfigure-asc-app-id-stale = project.asc_app_id ({ $app_id }) belongs to the old bundle ID; update or remove it
+
+## launchpad refresh
+
+refresh-header = Launchpad Refresh
+refresh-ios-path-missing = No Xcode project in { $path } anymore; looking for it
+refresh-scheme-found = Scheme { $scheme } still exists
+refresh-scheme-missing = Scheme { $scheme } no longer exists
+refresh-bundle-id-unknown = Could not read the bundle ID of { $scheme }
+refresh-target = Target { $target } ({ $bundle_id })
+refresh-targets-unknown = Could not list targets: { $error }
+refresh-profile-missing = No provisioning profile for { $bundle_id }; add it to [signing.profiles]
+refresh-profile-stale = [signing.profiles] has { $bundle_id }, which no target uses anymore
+refresh-up-to-date = .launchpad.toml matches the Xcode project
+refresh-prompt-apply = Update .launchpad.toml?
+refresh-not-applied = Left .launchpad.toml unchanged
+
+## launchpad setup
+
+setup-header = Launchpad Setup
+setup-intro = This will configure your Apple App Store Connect API credentials.
+setup-intro-key-url = You'll need an API key from: { $url }
+setup-prompt-overwrite = Existing config found. Overwrite?
+setup-prompt-key-id = API Key ID
+setup-prompt-issuer-id = Issuer ID
+setup-prompt-key-path = Path to .p8 key file
+setup-key-not-found = Warning: Key file not found at { $path }
+setup-prompt-continue = Continue anyway?
+setup-overwrite-yes = Overwriting existing config (--yes)
+setup-using-env = { $name }: from { $env }
+setup-using-payload = Using credentials from { $path }
+setup-key-not-found-yes = Not continuing without the key file (--yes)
+setup-copied-key = Copied key to { $path }
+setup-saved = Configuration saved
+setup-running-doctor = Running diagnostics...
+setup-checks-failed = Some checks failed: { $error }
+setup-complete = Setup Complete!
+setup-next-cd = 1. cd into your iOS project
+setup-next-init = 2. Run 'launchpad init'
+setup-next-deploy = 3. Run 'launchpad deploy'
+setup-encrypt-header = Encrypt Launchpad Config
+setup-encrypt-keychain = Stored a generated passphrase in the login keychain
+setup-encrypted-file = Encrypted { $path }
+setup-encrypt-key-outside = { $path } is outside ~/.launchpad/keys and was left unencrypted
+setup-encrypt-unlock = Launchpad will ask for the passphrase when needed (or read { $env })
+setup-decrypt-header = Decrypt Launchpad Config
+setup-decrypted-file = Decrypted { $path }
+setup-prompt-passphrase = New passphrase
+setup-prompt-passphrase-confirm = Repeat passphrase
+setup-passphrase-mismatch = Passphrases don't match
+setup-export-header = Export Onboarding Bundle
+setup-export-no-key = The key isn't included; importers need it at { $path } (use --include-key to bundle it)
+setup-exported = Wrote { $path }
+setup-export-next = On the new machine: launchpad setup import { $path } (passphrase prompted or from { $env })
+setup-import-header = Import Onboarding Bundle
+setup-prompt-bundle-passphrase = Bundle passphrase
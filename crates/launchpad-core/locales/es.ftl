@@ -0,0 +1,120 @@
+## Shared
+
+next-steps = Próximos pasos:
+
+## launchpad init
+
+init-header = Launchpad Init
+init-detected-preset = Tipo de proyecto: { $preset }
+init-prompt-preset = Tipo de proyecto
+init-detected-kmp = Kotlin Multiplatform: :{ $module } ({ $integration })
+init-kmp-not-found = No se encontró ningún módulo Kotlin Multiplatform con targets de iOS
+init-kmp-no-embed-phase = El proyecto de Xcode no ejecuta :{ $module }:embedAndSignAppleFrameworkForXcode; añádelo como fase Run Script
+init-found-project = Proyecto iOS encontrado en: { $path }
+init-using-project-first = Usando el proyecto iOS: { $path } (primero de { $count })
+init-select-project = Se encontraron varios proyectos de Xcode. Selecciona uno:
+init-detected-scheme = Esquema detectado: { $scheme }
+init-using-scheme-first = Usando el esquema: { $scheme } (el primero de { $count })
+init-select-scheme = Se encontraron varios esquemas. Selecciona uno:
+init-using-bundle-id = Usando el bundle ID: { $bundle_id }
+init-prompt-bundle-id = Identificador de bundle
+init-using-bundle-id-app = Usando el bundle ID: { $bundle_id } (target de la app, uno de { $count })
+init-select-bundle-id = El esquema genera varios bundle IDs. Selecciona el de la app:
+init-asc-app-id = ID de la app en App Store Connect: { $app_id }
+init-asc-app-id-unknown = No se pudo obtener el ID de la app en App Store Connect ({ $error }); las llamadas a la API lo buscarán por bundle ID
+init-signing = Firma: { $style }
+init-signing-unknown = No se pudo detectar el tipo de firma; se detectará al desplegar
+init-signing-manual-todo = Firma manual: añade [signing.profiles] a .launchpad.toml antes de desplegar
+init-prompt-profile = Nombre del perfil de aprovisionamiento para { $bundle_id }
+init-git-tag-default = Etiquetas de git: activadas (por defecto)
+init-prompt-git-tag = ¿Crear etiquetas de git después de desplegar?
+init-prompt-push-tags = ¿Subir las etiquetas al remoto?
+init-created-config = Se creó .launchpad.toml
+init-created-example = Se creó .launchpad.toml.example (como referencia para el equipo)
+init-prompt-gitignore-outputs = ¿Añadir los artefactos de compilación de launchpad a .gitignore?
+init-prompt-gitignore = ¿Añadir .launchpad.toml a .gitignore?
+init-added-gitignore = Añadido a .gitignore
+init-complete = ¡Configuración completa!
+init-next-doctor = 1. Ejecuta 'launchpad doctor' para verificar la configuración
+init-next-deploy = 2. Ejecuta 'launchpad deploy' para desplegar en TestFlight
+init-fastlane-found = fastlane encontrado
+init-fastlane-missing = fastlane no encontrado
+init-fastlane-installing-yes = Instalando fastlane (modo --yes)...
+init-prompt-install-fastlane = ¿Instalar fastlane?
+init-fastlane-running-brew = Ejecutando: brew install fastlane
+init-fastlane-installing = Instalando fastlane...
+init-fastlane-install-failed = No se pudo instalar fastlane con brew
+init-fastlane-try-manual = Prueba manualmente: brew install fastlane
+init-fastlane-installed = fastlane instalado
+init-fastfile-found = Fastfile encontrado en: { $path }
+init-fastfile-missing = No se encontró el Fastfile en { $path }/fastlane/
+init-fastfile-creating-yes = Creando Fastfile (modo --yes)...
+init-prompt-create-fastfile = ¿Crear un Fastfile con los lanes necesarios?
+init-fastfile-skipped = Se omite la creación del Fastfile. Tendrás que crearlo manualmente.
+init-created-file = Se creó { $path }
+
+## launchpad reconfigure
+
+reconfigure-header = Launchpad Reconfigure
+reconfigure-prompt-ios-path = Ruta del proyecto iOS
+reconfigure-prompt-scheme = Esquema de Xcode
+reconfigure-schemes-unavailable = No se pudieron listar los esquemas en { $path }
+reconfigure-changed = { $key }: { $old } → { $new }
+reconfigure-unchanged = Sin cambios; .launchpad.toml se queda como estaba
+reconfigure-saved = Se actualizó .launchpad.toml
+reconfigure-asc-app-id-stale = project.asc_app_id ({ $app_id }) pertenece al bundle ID anterior; actualízalo o elimínalo
+
+## launchpad refresh
+
+refresh-header = Launchpad Refresh
+refresh-ios-path-missing = Ya no hay un proyecto de Xcode en { $path }; buscándolo
+refresh-scheme-found = El esquema { $scheme } sigue existiendo
+refresh-scheme-missing = El esquema { $scheme } ya no existe
+refresh-bundle-id-unknown = No se pudo leer el bundle ID de { $scheme }
+refresh-target = Target { $target } ({ $bundle_id })
+refresh-targets-unknown = No se pudieron listar los targets: { $error }
+refresh-profile-missing = No hay perfil de aprovisionamiento para { $bundle_id }; añádelo en [signing.profiles]
+refresh-profile-stale = [signing.profiles] incluye { $bundle_id }, que ya no usa ningún target
+refresh-up-to-date = .launchpad.toml coincide con el proyecto de Xcode
+refresh-prompt-apply = ¿Actualizar .launchpad.toml?
+refresh-not-applied = .launchpad.toml se queda sin cambios
+
+## launchpad setup
+
+setup-header = Launchpad Setup
+setup-intro = Esto configurará tus credenciales de la API de App Store Connect.
+setup-intro-key-url = Necesitarás una clave de API de: { $url }
+setup-prompt-overwrite = Ya existe una configuración. ¿Sobrescribirla?
+setup-prompt-key-id = ID de la clave de API
+setup-prompt-issuer-id = ID del emisor (Issuer ID)
+setup-prompt-key-path = Ruta al archivo de clave .p8
+setup-key-not-found = Aviso: no se encontró el archivo de clave en { $path }
+setup-prompt-continue = ¿Continuar de todos modos?
+setup-overwrite-yes = Sobrescribiendo la configuración existente (--yes)
+setup-using-env = { $name }: desde { $env }
+setup-using-payload = Usando credenciales de { $path }
+setup-key-not-found-yes = No se continúa sin el archivo de clave (--yes)
+setup-copied-key = Clave copiada a { $path }
+setup-saved = Configuración guardada
+setup-running-doctor = Ejecutando diagnósticos...
+setup-checks-failed = Algunas comprobaciones fallaron: { $error }
+setup-complete = ¡Configuración completa!
+setup-next-cd = 1. Entra en el directorio de tu proyecto iOS
+setup-next-init = 2. Ejecuta 'launchpad init'
+setup-next-deploy = 3. Ejecuta 'launchpad deploy'
+setup-encrypt-header = Cifrar la configuración de Launchpad
+setup-encrypt-keychain = Se guardó una contraseña generada en el llavero de inicio de sesión
+setup-encrypted-file = Cifrado { $path }
+setup-encrypt-key-outside = { $path } está fuera de ~/.launchpad/keys y no se ha cifrado
+setup-encrypt-unlock = Launchpad pedirá la contraseña cuando la necesite (o leerá { $env })
+setup-decrypt-header = Descifrar la configuración de Launchpad
+setup-decrypted-file = Descifrado { $path }
+setup-prompt-passphrase = Nueva contraseña
+setup-prompt-passphrase-confirm = Repite la contraseña
+setup-passphrase-mismatch = Las contraseñas no coinciden
+setup-export-header = Exportar paquete de incorporación
+setup-export-no-key = La clave no está incluida; quien importe la necesitará en { $path } (usa --include-key para incluirla)
+setup-exported = Escrito { $path }
+setup-export-next = En la máquina nueva: launchpad setup import { $path } (contraseña solicitada o desde { $env })
+setup-import-header = Importar paquete de incorporación
+setup-prompt-bundle-passphrase = Contraseña del paquete
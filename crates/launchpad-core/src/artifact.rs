@@ -0,0 +1,208 @@
+use crate::git;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::path::Path;
+
+/// Written to build.output_dir after every build that produced an .ipa
+const STAMP_FILENAME: &str = "last-build.json";
+
+/// The last .ipa launchpad built, so an unchanged tree can skip the archive step
+#[derive(Debug, Serialize, Deserialize)]
+pub struct BuildStamp {
+    /// [`source_hash`] right after the build (the version bump included)
+    pub source_hash: String,
+    pub ipa: String,
+
+    /// "1.2.0 (43)", read from the .ipa
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub version: Option<String>,
+
+    /// Unix timestamp (seconds)
+    pub built_at: u64,
+
+    /// `[environments]` entry the build was configured for
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub environment: Option<String>,
+
+    /// Bundle ID the build was made for; differs between brands
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub bundle_id: Option<String>,
+
+    /// Whether TestFlight accepted the upload
+    pub uploaded: bool,
+}
+
+impl BuildStamp {
+    pub fn load(output_dir: &Path) -> Option<Self> {
+        let content = std::fs::read_to_string(output_dir.join(STAMP_FILENAME)).ok()?;
+        serde_json::from_str(&content).ok()
+    }
+
+    pub fn save(&self, output_dir: &Path) -> Result<(), std::io::Error> {
+        std::fs::create_dir_all(output_dir)?;
+        let content = serde_json::to_string_pretty(self).map_err(std::io::Error::other)?;
+        std::fs::write(output_dir.join(STAMP_FILENAME), content + "\n")
+    }
+
+    /// Forget the last build (its .ipa was cleaned up)
+    pub fn remove(output_dir: &Path) -> Result<(), std::io::Error> {
+        match std::fs::remove_file(output_dir.join(STAMP_FILENAME)) {
+            Err(e) if e.kind() != std::io::ErrorKind::NotFound => Err(e),
+            _ => Ok(()),
+        }
+    }
+
+    /// Why this build can't be uploaded again for a tree hashing to `source_hash`,
+    /// deploying `bundle_id` to `environment`
+    pub fn check(
+        &self,
+        source_hash: &str,
+        environment: Option<&str>,
+        bundle_id: &str,
+    ) -> Result<(), String> {
+        if self.source_hash != source_hash {
+            return Err("the source tree changed since the last build".to_string());
+        }
+        if self.environment.as_deref() != environment {
+            return Err(format!(
+                "the last build was made for {}",
+                match &self.environment {
+                    Some(environment) => format!("the {} environment", environment),
+                    None => "no environment".to_string(),
+                }
+            ));
+        }
+        if let Some(built) = self
+            .bundle_id
+            .as_deref()
+            .filter(|built| *built != bundle_id)
+        {
+            return Err(format!("the last build was made for {}", built));
+        }
+        if !Path::new(&self.ipa).exists() {
+            return Err(format!("{} no longer exists", self.ipa));
+        }
+        if self.uploaded {
+            return Err(format!(
+                "build {} is already on TestFlight, and App Store Connect rejects a build number twice",
+                self.version.as_deref().unwrap_or("unknown")
+            ));
+        }
+        Ok(())
+    }
+}
+
+/// Hash of every tracked or unignored file under the current directory, leaving
+/// out files that don't end up in the binary (docs, fastlane metadata, build output).
+/// None outside a git repository.
+pub fn source_hash(output_dir: &str) -> Option<String> {
+    let output = git::output(&[
+        "ls-files",
+        "-z",
+        "--cached",
+        "--others",
+        "--exclude-standard",
+    ])
+    .ok()?;
+
+    let output_dir = output_dir.trim_start_matches("./").trim_end_matches('/');
+    let mut files: Vec<&str> = output
+        .split('\0')
+        .filter(|path| !path.is_empty() && !ignored(path, output_dir))
+        .collect();
+    files.sort_unstable();
+    files.dedup();
+
+    let mut hasher = Sha256::new();
+    for path in files {
+        hasher.update(path.as_bytes());
+        hasher.update([0]);
+        // Deleted but still in the index: the path alone marks it
+        if let Ok(content) = std::fs::read(path) {
+            hasher.update(&content);
+        }
+        hasher.update([0]);
+    }
+
+    Some(format!("{:x}", hasher.finalize()))
+}
+
+/// SHA-256 of a file, recorded in the deploy history to recognize the .ipa or .aab later
+pub fn sha256(path: &Path) -> Option<String> {
+    let mut file = std::fs::File::open(path).ok()?;
+    let mut hasher = Sha256::new();
+    std::io::copy(&mut file, &mut hasher).ok()?;
+    Some(format!("{:x}", hasher.finalize()))
+}
+
+fn ignored(path: &str, output_dir: &str) -> bool {
+    (!output_dir.is_empty() && path.starts_with(&format!("{}/", output_dir)))
+        || path.ends_with(".md")
+        || path.contains("fastlane/metadata/")
+        || path.ends_with("fastlane/report.xml")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A stamp for a build of com.example.app that is still on disk
+    fn stamp() -> BuildStamp {
+        BuildStamp {
+            source_hash: "abc".to_string(),
+            // Any file that exists stands in for the .ipa
+            ipa: concat!(env!("CARGO_MANIFEST_DIR"), "/Cargo.toml").to_string(),
+            version: Some("1.2.0 (43)".to_string()),
+            built_at: 0,
+            environment: Some("staging".to_string()),
+            bundle_id: Some("com.example.app".to_string()),
+            uploaded: false,
+        }
+    }
+
+    #[test]
+    fn unchanged_build_can_be_reused() {
+        assert_eq!(
+            stamp().check("abc", Some("staging"), "com.example.app"),
+            Ok(())
+        );
+    }
+
+    #[test]
+    fn changed_source_environment_or_bundle_id_rebuilds() {
+        let stamp = stamp();
+        assert!(stamp
+            .check("def", Some("staging"), "com.example.app")
+            .unwrap_err()
+            .contains("source tree changed"));
+        assert!(stamp
+            .check("abc", None, "com.example.app")
+            .unwrap_err()
+            .contains("the staging environment"));
+        assert!(stamp
+            .check("abc", Some("staging"), "com.example.other")
+            .unwrap_err()
+            .contains("com.example.app"));
+    }
+
+    #[test]
+    fn missing_or_uploaded_ipa_rebuilds() {
+        let missing = BuildStamp {
+            ipa: "/nonexistent/App.ipa".to_string(),
+            ..stamp()
+        };
+        assert!(missing
+            .check("abc", Some("staging"), "com.example.app")
+            .unwrap_err()
+            .contains("no longer exists"));
+
+        let uploaded = BuildStamp {
+            uploaded: true,
+            ..stamp()
+        };
+        assert!(uploaded
+            .check("abc", Some("staging"), "com.example.app")
+            .unwrap_err()
+            .contains("1.2.0 (43) is already on TestFlight"));
+    }
+}
@@ -0,0 +1,2321 @@
+use crate::config::global::AppleConfig;
+use crate::config::project::ProjectSettings;
+use crate::http;
+use crate::mock;
+use crate::ui;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use std::io::Read;
+use std::path::Path;
+use std::sync::atomic::{AtomicBool, AtomicU32, Ordering};
+use std::sync::Mutex;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use thiserror::Error;
+
+const API_URL: &str = "https://api.appstoreconnect.apple.com/v1";
+
+/// In-app purchases moved to v2 of the API; everything else is still v1
+const API_V2_URL: &str = "https://api.appstoreconnect.apple.com/v2";
+
+/// Tries per request when App Store Connect answers 429/5xx or the connection drops
+const MAX_ATTEMPTS: u32 = 5;
+
+/// Backoff before the first retry; doubled per attempt (plus jitter) up to MAX_BACKOFF
+const BASE_BACKOFF: Duration = Duration::from_secs(2);
+const MAX_BACKOFF: Duration = Duration::from_secs(60);
+
+/// Warn once when less than this share of the hourly quota is left
+const LOW_QUOTA_PERCENT: u32 = 10;
+
+#[derive(Error, Debug)]
+pub enum AscError {
+    #[error("HTTP error: {0}")]
+    Http(#[from] reqwest::Error),
+
+    #[error("App Store Connect API error ({status}): {body}")]
+    Api { status: u16, body: String },
+
+    #[error("Invalid API key: {0}")]
+    Key(String),
+
+    #[error("{0}")]
+    NotFound(String),
+
+    #[error("App Store Connect rate limit reached{}; try again later", describe_quota(.0))]
+    RateLimited(Option<RateLimit>),
+
+    #[error("Request budget used up ({0} App Store Connect calls, LAUNCHPAD_ASC_REQUEST_BUDGET)")]
+    BudgetExhausted(u32),
+
+    #[error("Apple agreement pending: {0}. The Account Holder must accept it before App Store Connect takes requests or uploads.")]
+    AgreementPending(String),
+
+    #[error("IO error: {0}")]
+    Io(#[from] std::io::Error),
+}
+
+impl AscError {
+    /// 409: the thing already exists (review submitted, testers notified, …)
+    pub fn is_conflict(&self) -> bool {
+        matches!(self, AscError::Api { status: 409, .. })
+    }
+
+    /// Network trouble, rate limits or server errors: worth trying again later
+    pub fn is_transient(&self) -> bool {
+        match self {
+            AscError::Http(e) => e.is_connect() || e.is_timeout() || e.is_request(),
+            AscError::Api { status, .. } => *status >= 500,
+            AscError::RateLimited(_) => true,
+            _ => false,
+        }
+    }
+}
+
+/// Error code App Store Connect answers every request with (403) while an
+/// agreement is unsigned or expired
+const AGREEMENT_ERROR: &str = "FORBIDDEN.REQUIRED_AGREEMENTS_MISSING_OR_EXPIRED";
+
+/// Agreements Apple names in that error's detail, and where each is accepted
+const AGREEMENTS: &[(&str, &str)] = &[
+    (
+        "Program License Agreement",
+        "Apple Developer Program License Agreement (developer.apple.com/account)",
+    ),
+    (
+        "Paid Applications",
+        "Paid Apps agreement (App Store Connect > Business)",
+    ),
+    (
+        "Paid Apps",
+        "Paid Apps agreement (App Store Connect > Business)",
+    ),
+    (
+        "Free Applications",
+        "Free Apps agreement (App Store Connect > Business)",
+    ),
+];
+
+#[derive(Deserialize)]
+struct ErrorDocument {
+    #[serde(default)]
+    errors: Vec<ApiError>,
+}
+
+#[derive(Deserialize)]
+struct ApiError {
+    code: String,
+    #[serde(default)]
+    detail: String,
+}
+
+/// Which agreement a 403 body is about, if it is the pending-agreement error
+fn pending_agreement(body: &str) -> Option<String> {
+    let document: ErrorDocument = serde_json::from_str(body).ok()?;
+    let error = document
+        .errors
+        .into_iter()
+        .find(|e| e.code.starts_with(AGREEMENT_ERROR))?;
+
+    let named = AGREEMENTS
+        .iter()
+        .find(|(needle, _)| error.detail.contains(needle))
+        .map(|(_, agreement)| agreement.to_string());
+    // Apple's detail often doesn't say which one; the license agreement is the usual suspect
+    Some(named.unwrap_or_else(|| {
+        format!(
+            "\"{}\" (usually an updated Program License Agreement at developer.apple.com/account, otherwise App Store Connect > Business)",
+            error.detail.trim()
+        )
+    }))
+}
+
+/// Hourly request quota from the `X-Rate-Limit` response header
+#[derive(Debug, Clone, Copy)]
+pub struct RateLimit {
+    pub limit: u32,
+    pub remaining: u32,
+}
+
+impl RateLimit {
+    /// "user-hour-lim:3600;user-hour-rem:3598;"
+    fn parse(header: &str) -> Option<Self> {
+        let mut limit = None;
+        let mut remaining = None;
+        for part in header.split(';') {
+            match part.trim().split_once(':') {
+                Some(("user-hour-lim", value)) => limit = value.parse().ok(),
+                Some(("user-hour-rem", value)) => remaining = value.parse().ok(),
+                _ => {}
+            }
+        }
+
+        Some(Self {
+            limit: limit?,
+            remaining: remaining?,
+        })
+    }
+}
+
+fn describe_quota(rate_limit: &Option<RateLimit>) -> String {
+    match rate_limit {
+        Some(r) => format!(" ({} of {} requests left this hour)", r.remaining, r.limit),
+        None => String::new(),
+    }
+}
+
+#[derive(Serialize)]
+struct Claims<'a> {
+    iss: &'a str,
+    iat: u64,
+    exp: u64,
+    aud: &'a str,
+}
+
+/// JSON:API envelope used by every App Store Connect response
+#[derive(Deserialize)]
+struct Document<T> {
+    data: T,
+}
+
+#[derive(Deserialize)]
+struct Resource<A> {
+    id: String,
+    attributes: A,
+}
+
+#[derive(Deserialize)]
+struct AppAttributes {}
+
+/// The part of a newly created resource callers need
+#[derive(Deserialize)]
+struct Created {
+    id: String,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BuildAttributes {
+    /// Build number (CFBundleVersion)
+    pub version: String,
+    pub processing_state: Option<String>,
+    pub uploaded_date: Option<String>,
+}
+
+#[derive(Debug)]
+pub struct Build {
+    pub id: String,
+    pub number: String,
+    pub processing_state: Option<String>,
+    pub uploaded_date: Option<String>,
+}
+
+impl Build {
+    /// Processed builds can be distributed
+    pub fn is_valid(&self) -> bool {
+        self.processing_state.as_deref() == Some("VALID")
+    }
+
+    /// Processing failed or the build was rejected as invalid
+    pub fn is_failed(&self) -> bool {
+        matches!(
+            self.processing_state.as_deref(),
+            Some("FAILED") | Some("INVALID")
+        )
+    }
+}
+
+/// A build with its marketing version and TestFlight state, as App Store
+/// Connect lists it on the TestFlight page
+#[derive(Debug)]
+pub struct BuildStatus {
+    pub build: Build,
+    pub version: Option<String>,
+    /// internalBuildState: PROCESSING, MISSING_EXPORT_COMPLIANCE, IN_BETA_TESTING, ...
+    pub beta_state: Option<String>,
+}
+
+impl BuildStatus {
+    /// Still processing or waiting for the export compliance answer
+    pub fn is_pending(&self) -> bool {
+        match self.beta_state.as_deref() {
+            Some(state) => matches!(
+                state,
+                "PROCESSING" | "MISSING_EXPORT_COMPLIANCE" | "IN_EXPORT_COMPLIANCE_REVIEW"
+            ),
+            None => self.build.processing_state.as_deref() == Some("PROCESSING"),
+        }
+    }
+
+    /// The state as the TestFlight page words it
+    pub fn state_label(&self) -> String {
+        let state = self
+            .beta_state
+            .as_deref()
+            .or(self.build.processing_state.as_deref())
+            .unwrap_or("UNKNOWN");
+        match state {
+            "PROCESSING" => "Processing".to_string(),
+            "PROCESSING_EXCEPTION" | "FAILED" | "INVALID" => "Processing failed".to_string(),
+            "MISSING_EXPORT_COMPLIANCE" => "Missing Compliance".to_string(),
+            "IN_EXPORT_COMPLIANCE_REVIEW" => "In Compliance Review".to_string(),
+            "READY_FOR_BETA_TESTING" | "VALID" => "Ready to Test".to_string(),
+            "IN_BETA_TESTING" => "Testing".to_string(),
+            "EXPIRED" => "Expired".to_string(),
+            other => other.to_string(),
+        }
+    }
+}
+
+#[derive(Deserialize)]
+struct BuildList {
+    data: Vec<BuildResource>,
+    #[serde(default)]
+    included: Vec<Resource<BuildIncludedAttributes>>,
+}
+
+#[derive(Deserialize)]
+struct BuildResource {
+    id: String,
+    attributes: BuildAttributes,
+    relationships: Option<BuildRelationships>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct BuildRelationships {
+    pre_release_version: Option<Relationship>,
+    build_beta_detail: Option<Relationship>,
+}
+
+/// preReleaseVersions and buildBetaDetails share the included list
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct BuildIncludedAttributes {
+    version: Option<String>,
+    internal_build_state: Option<String>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct EncryptionDeclarationAttributes {
+    app_description: Option<String>,
+    app_encryption_declaration_state: Option<String>,
+    created_date: Option<String>,
+}
+
+/// An App Encryption Declaration: the answers (and documents) for an app that
+/// uses non-exempt encryption, which builds get attached to
+#[derive(Debug)]
+pub struct EncryptionDeclaration {
+    pub id: String,
+    pub description: Option<String>,
+    /// CREATED, IN_REVIEW, APPROVED, REJECTED, INVALID or EXPIRED
+    pub state: Option<String>,
+    pub created_date: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct AppStoreVersionList {
+    data: Vec<AppStoreVersionResource>,
+    #[serde(default)]
+    included: Vec<Resource<BuildAttributes>>,
+}
+
+#[derive(Deserialize)]
+struct AppStoreVersionResource {
+    id: String,
+    attributes: AppStoreVersionAttributes,
+    relationships: Option<AppStoreVersionRelationships>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct AppStoreVersionAttributes {
+    version_string: String,
+    app_version_state: Option<String>,
+    /// Older name of appVersionState, still sent alongside it
+    app_store_state: Option<String>,
+    created_date: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct AppStoreVersionRelationships {
+    build: Option<Relationship>,
+}
+
+/// An App Store version and where it is in App Review
+#[derive(Debug)]
+pub struct AppStoreVersion {
+    pub id: String,
+    pub version: String,
+    /// Number of the build attached to the version
+    pub build: Option<String>,
+    /// appVersionState: WAITING_FOR_REVIEW, IN_REVIEW, REJECTED, PENDING_DEVELOPER_RELEASE, ...
+    pub state: String,
+}
+
+impl AppStoreVersion {
+    /// App Review passed; the version is released or waiting to be
+    pub fn is_accepted(&self) -> bool {
+        matches!(
+            self.state.as_str(),
+            "ACCEPTED"
+                | "PENDING_APPLE_RELEASE"
+                | "PENDING_DEVELOPER_RELEASE"
+                | "PROCESSING_FOR_DISTRIBUTION"
+                | "PROCESSING_FOR_APP_STORE"
+                | "READY_FOR_DISTRIBUTION"
+                | "READY_FOR_SALE"
+        )
+    }
+
+    /// App Review (or the developer) sent the version back
+    pub fn is_rejected(&self) -> bool {
+        matches!(
+            self.state.as_str(),
+            "REJECTED" | "METADATA_REJECTED" | "INVALID_BINARY" | "DEVELOPER_REJECTED"
+        )
+    }
+
+    /// The state as App Store Connect words it
+    pub fn state_label(&self) -> String {
+        match self.state.as_str() {
+            "PREPARE_FOR_SUBMISSION" => "Prepare for Submission".to_string(),
+            "READY_FOR_REVIEW" => "Ready for Review".to_string(),
+            "WAITING_FOR_EXPORT_COMPLIANCE" => "Waiting for Export Compliance".to_string(),
+            "WAITING_FOR_REVIEW" => "Waiting for Review".to_string(),
+            "IN_REVIEW" => "In Review".to_string(),
+            "ACCEPTED" => "Accepted".to_string(),
+            "PENDING_DEVELOPER_RELEASE" => "Pending Developer Release".to_string(),
+            "PENDING_APPLE_RELEASE" => "Pending Apple Release".to_string(),
+            "PROCESSING_FOR_DISTRIBUTION" | "PROCESSING_FOR_APP_STORE" => {
+                "Processing for Distribution".to_string()
+            }
+            "READY_FOR_DISTRIBUTION" | "READY_FOR_SALE" => "Ready for Distribution".to_string(),
+            "REJECTED" => "Rejected".to_string(),
+            "METADATA_REJECTED" => "Metadata Rejected".to_string(),
+            "INVALID_BINARY" => "Invalid Binary".to_string(),
+            "DEVELOPER_REJECTED" => "Developer Rejected".to_string(),
+            "REPLACED_WITH_NEW_VERSION" => "Replaced with New Version".to_string(),
+            other => other.to_string(),
+        }
+    }
+
+    /// What a rejection means. The API doesn't expose App Review's messages, so
+    /// the details stay in the Resolution Center.
+    pub fn rejection_reason(&self) -> Option<&'static str> {
+        match self.state.as_str() {
+            "REJECTED" => Some("App Review rejected the binary; the Resolution Center has their notes"),
+            "METADATA_REJECTED" => Some("App Review rejected the metadata (description, screenshots, ...); fix it in App Store Connect and resubmit, no new build needed"),
+            "INVALID_BINARY" => Some("The build failed App Store checks; upload a new one"),
+            "DEVELOPER_REJECTED" => Some("The version was removed from review"),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct DocumentReservationAttributes {
+    #[serde(default)]
+    upload_operations: Vec<UploadOperation>,
+}
+
+/// One part of a file upload, sent straight to Apple's storage
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct UploadOperation {
+    method: String,
+    url: String,
+    length: usize,
+    offset: usize,
+    #[serde(default)]
+    request_headers: Vec<HttpHeader>,
+}
+
+#[derive(Deserialize)]
+struct HttpHeader {
+    name: String,
+    value: String,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct BetaGroupAttributes {
+    name: String,
+    #[serde(default)]
+    is_internal_group: bool,
+}
+
+#[derive(Debug)]
+pub struct BetaGroup {
+    pub id: String,
+    pub name: String,
+    pub is_internal: bool,
+}
+
+#[derive(Deserialize)]
+struct BuildWithBundles {
+    #[serde(default)]
+    included: Vec<Resource<BuildBundleAttributes>>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct BuildBundleAttributes {
+    bundle_id: Option<String>,
+    #[serde(rename = "dSYMUrl")]
+    dsym_url: Option<String>,
+}
+
+/// One bundle (app or extension) inside a build
+#[derive(Debug)]
+pub struct BuildBundle {
+    pub id: String,
+    pub bundle_id: Option<String>,
+    /// Pre-signed download for the dSYMs Apple generated while processing
+    pub dsym_url: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct ProfileList {
+    data: Vec<ProfileResource>,
+    #[serde(default)]
+    included: Vec<Resource<BundleIdAttributes>>,
+}
+
+#[derive(Deserialize)]
+struct ProfileResource {
+    attributes: ProfileAttributes,
+    relationships: Option<ProfileRelationships>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ProfileAttributes {
+    name: String,
+    profile_type: Option<String>,
+    profile_state: Option<String>,
+    expiration_date: Option<String>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ProfileRelationships {
+    bundle_id: Option<Relationship>,
+}
+
+#[derive(Deserialize)]
+struct Relationship {
+    data: Option<Linkage>,
+}
+
+#[derive(Deserialize)]
+struct Linkage {
+    id: String,
+}
+
+#[derive(Deserialize)]
+struct BundleIdAttributes {
+    identifier: String,
+}
+
+#[derive(Deserialize)]
+struct TesterPage {
+    data: Vec<TesterResource>,
+    links: Option<PageLinks>,
+}
+
+#[derive(Deserialize)]
+struct PageLinks {
+    next: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct TesterResource {
+    id: String,
+    attributes: TesterAttributes,
+    relationships: Option<TesterRelationships>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct TesterAttributes {
+    first_name: Option<String>,
+    last_name: Option<String>,
+    email: Option<String>,
+    state: Option<String>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct TesterRelationships {
+    beta_groups: Option<RelationshipList>,
+}
+
+#[derive(Deserialize)]
+struct RelationshipList {
+    #[serde(default)]
+    data: Vec<Linkage>,
+}
+
+#[derive(Deserialize)]
+struct CustomerReviewPage {
+    data: Vec<CustomerReviewResource>,
+    #[serde(default)]
+    included: Vec<Resource<ReviewResponseAttributes>>,
+    links: Option<PageLinks>,
+}
+
+#[derive(Deserialize)]
+struct CustomerReviewResource {
+    id: String,
+    attributes: CustomerReviewAttributes,
+    relationships: Option<CustomerReviewRelationships>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct CustomerReviewAttributes {
+    rating: u8,
+    title: Option<String>,
+    body: Option<String>,
+    reviewer_nickname: Option<String>,
+    territory: Option<String>,
+    created_date: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct CustomerReviewRelationships {
+    response: Option<Relationship>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ReviewResponseAttributes {
+    response_body: Option<String>,
+    state: Option<String>,
+    last_modified_date: Option<String>,
+}
+
+/// An App Store customer review and the developer response to it, if any
+#[derive(Debug, Serialize)]
+pub struct CustomerReview {
+    pub id: String,
+    /// 1 to 5 stars
+    pub rating: u8,
+    pub title: Option<String>,
+    pub body: Option<String>,
+    pub reviewer: Option<String>,
+    /// Storefront, as an ISO 3166-1 alpha-3 code ("USA")
+    pub territory: Option<String>,
+    pub created_date: Option<String>,
+    pub response: Option<ReviewResponse>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ReviewResponse {
+    pub id: String,
+    pub body: String,
+    /// PENDING_PUBLISH until Apple shows it on the App Store, then PUBLISHED
+    pub state: Option<String>,
+    pub last_modified_date: Option<String>,
+}
+
+impl From<Resource<ReviewResponseAttributes>> for ReviewResponse {
+    fn from(resource: Resource<ReviewResponseAttributes>) -> Self {
+        ReviewResponse {
+            id: resource.id,
+            body: resource.attributes.response_body.unwrap_or_default(),
+            state: resource.attributes.state,
+            last_modified_date: resource.attributes.last_modified_date,
+        }
+    }
+}
+
+/// Which customer reviews to list
+#[derive(Debug)]
+pub struct ReviewFilter {
+    /// Only these star ratings (default: all)
+    pub ratings: Vec<u8>,
+    pub territory: Option<String>,
+    /// Only reviews without a developer response
+    pub unanswered: bool,
+    pub limit: usize,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct AnalyticsRequestAttributes {
+    #[serde(default)]
+    stopped_due_to_inactivity: bool,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct AnalyticsInstanceAttributes {
+    processing_date: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct AnalyticsSegmentAttributes {
+    url: String,
+}
+
+/// One row of a report, by column name
+pub type ReportRow = HashMap<String, String>;
+
+/// In-app purchases and auto-renewable subscriptions live behind different
+/// endpoints, but their metadata works the same way
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ProductKind {
+    InAppPurchase,
+    Subscription,
+}
+
+impl ProductKind {
+    fn product_type(self) -> &'static str {
+        match self {
+            ProductKind::InAppPurchase => "inAppPurchases",
+            ProductKind::Subscription => "subscriptions",
+        }
+    }
+
+    fn product_url(self, id: &str) -> String {
+        match self {
+            ProductKind::InAppPurchase => format!("{}/inAppPurchases/{}", API_V2_URL, id),
+            ProductKind::Subscription => format!("{}/subscriptions/{}", API_URL, id),
+        }
+    }
+
+    fn localization_type(self) -> &'static str {
+        match self {
+            ProductKind::InAppPurchase => "inAppPurchaseLocalizations",
+            ProductKind::Subscription => "subscriptionLocalizations",
+        }
+    }
+
+    /// Relationship from a localization (or price schedule) to its product
+    fn parent_relationship(self) -> &'static str {
+        match self {
+            ProductKind::InAppPurchase => "inAppPurchaseV2",
+            ProductKind::Subscription => "subscription",
+        }
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ProductAttributes {
+    name: Option<String>,
+    product_id: String,
+    in_app_purchase_type: Option<String>,
+    subscription_period: Option<String>,
+    review_note: Option<String>,
+}
+
+/// An in-app purchase or subscription as App Store Connect has it
+#[derive(Debug)]
+pub struct StoreProduct {
+    pub id: String,
+    pub product_id: String,
+    pub reference_name: String,
+    /// CONSUMABLE, NON_CONSUMABLE or NON_RENEWING_SUBSCRIPTION (in-app purchases)
+    pub iap_type: Option<String>,
+    /// ONE_WEEK, ONE_MONTH, ... ONE_YEAR (subscriptions)
+    pub period: Option<String>,
+    pub review_note: Option<String>,
+}
+
+impl From<Resource<ProductAttributes>> for StoreProduct {
+    fn from(resource: Resource<ProductAttributes>) -> Self {
+        let attributes = resource.attributes;
+        StoreProduct {
+            id: resource.id,
+            product_id: attributes.product_id,
+            reference_name: attributes.name.unwrap_or_default(),
+            iap_type: attributes.in_app_purchase_type,
+            period: attributes.subscription_period,
+            review_note: attributes.review_note,
+        }
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct SubscriptionGroupAttributes {
+    reference_name: String,
+}
+
+#[derive(Debug)]
+pub struct SubscriptionGroup {
+    pub id: String,
+    pub reference_name: String,
+}
+
+#[derive(Deserialize)]
+struct ProductLocalizationAttributes {
+    locale: String,
+    name: Option<String>,
+    description: Option<String>,
+}
+
+/// Display name and description of a product in one locale
+#[derive(Debug)]
+pub struct ProductLocalization {
+    pub id: String,
+    pub locale: String,
+    pub name: String,
+    pub description: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct ManualPriceList {
+    data: Vec<ManualPriceResource>,
+    #[serde(default)]
+    included: Vec<Resource<PricePointAttributes>>,
+}
+
+#[derive(Deserialize)]
+struct ManualPriceResource {
+    attributes: ManualPriceAttributes,
+    relationships: Option<ManualPriceRelationships>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ManualPriceAttributes {
+    end_date: Option<String>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ManualPriceRelationships {
+    in_app_purchase_price_point: Option<Relationship>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct PricePointAttributes {
+    customer_price: Option<String>,
+}
+
+/// TestFlight tester of an app, with the IDs of the groups they are in
+#[derive(Debug)]
+pub struct BetaTester {
+    pub id: String,
+    pub first_name: String,
+    pub last_name: String,
+    pub email: String,
+    /// NOT_INVITED, INVITED, ACCEPTED, INSTALLED or REVOKED
+    pub state: Option<String>,
+    pub group_ids: Vec<String>,
+}
+
+/// Provisioning profile as the Developer portal knows it
+#[derive(Debug)]
+pub struct PortalProfile {
+    pub name: String,
+    /// e.g. IOS_APP_STORE, IOS_APP_DEVELOPMENT
+    pub profile_type: Option<String>,
+    /// ACTIVE or INVALID
+    pub state: Option<String>,
+    /// ISO 8601
+    pub expiration_date: Option<String>,
+    pub bundle_id: Option<String>,
+}
+
+/// Device registered on the Developer portal for development and ad-hoc builds
+#[derive(Debug)]
+pub struct PortalDevice {
+    pub name: String,
+    pub udid: String,
+    /// IOS or MAC_OS
+    pub platform: Option<String>,
+    /// ENABLED or DISABLED
+    pub status: Option<String>,
+    /// e.g. "iPhone 15 Pro"
+    pub model: Option<String>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct DeviceAttributes {
+    name: String,
+    udid: String,
+    platform: Option<String>,
+    status: Option<String>,
+    model: Option<String>,
+}
+
+/// App Store Connect API client authenticated with the team API key.
+/// Every request goes through `AscClient::execute`, which retries rate limits
+/// and server errors and keeps count against the request budget.
+pub struct AscClient {
+    client: reqwest::Client,
+    key_id: String,
+    issuer_id: String,
+    key: jsonwebtoken::EncodingKey,
+
+    /// Quota reported by the last response
+    rate_limit: Mutex<Option<RateLimit>>,
+    warned_low_quota: AtomicBool,
+
+    /// Requests sent so far, and the most this client may send (LAUNCHPAD_ASC_REQUEST_BUDGET)
+    requests: AtomicU32,
+    budget: Option<u32>,
+
+    /// Tries per request; MAX_ATTEMPTS unless built `without_retries`
+    max_attempts: u32,
+}
+
+impl AscClient {
+    /// A client for the key in `apple` (usually [`GlobalConfig::apple`](crate::GlobalConfig::apple))
+    pub fn new(apple: &AppleConfig) -> Result<Self, AscError> {
+        // Mocked App Store Connect takes any token
+        let key = if mock::enabled() {
+            jsonwebtoken::EncodingKey::from_secret(&[])
+        } else {
+            let key_path = shellexpand::tilde(&apple.key_path).to_string();
+            let pem = std::fs::read(&key_path)?;
+            jsonwebtoken::EncodingKey::from_ec_pem(&pem)
+                .map_err(|e| AscError::Key(e.to_string()))?
+        };
+
+        Ok(Self {
+            client: http::client()?,
+            key_id: apple.key_id.clone(),
+            issuer_id: apple.issuer_id.clone(),
+            key,
+            rate_limit: Mutex::new(None),
+            warned_low_quota: AtomicBool::new(false),
+            requests: AtomicU32::new(0),
+            budget: std::env::var("LAUNCHPAD_ASC_REQUEST_BUDGET")
+                .ok()
+                .and_then(|budget| budget.parse().ok()),
+            max_attempts: MAX_ATTEMPTS,
+        })
+    }
+
+    /// Fail on the first error instead of backing off, for quick checks like `doctor`
+    pub fn without_retries(mut self) -> Self {
+        self.max_attempts = 1;
+        self
+    }
+
+    /// App Store Connect resource ID for a bundle identifier
+    pub async fn app_id(&self, bundle_id: &str) -> Result<String, AscError> {
+        let apps: Vec<Resource<AppAttributes>> = self
+            .get("/apps", &[("filter[bundleId]", bundle_id), ("limit", "1")])
+            .await?;
+
+        apps.into_iter()
+            .next()
+            .map(|app| app.id)
+            .ok_or_else(|| AscError::NotFound(format!("No app with bundle ID {}", bundle_id)))
+    }
+
+    /// Like [`app_id`](Self::app_id), but uses the ID recorded in .launchpad.toml
+    /// when `bundle_id` is the project's
+    pub async fn project_app_id(
+        &self,
+        bundle_id: &str,
+        project: Option<&ProjectSettings>,
+    ) -> Result<String, AscError> {
+        match project {
+            Some(ProjectSettings {
+                bundle_id: recorded,
+                asc_app_id: Some(app_id),
+                ..
+            }) if recorded == bundle_id => Ok(app_id.clone()),
+            _ => self.app_id(bundle_id).await,
+        }
+    }
+
+    /// Cheapest authenticated request; fails with [`AscError::AgreementPending`]
+    /// while Apple blocks the team on an unsigned agreement
+    pub async fn verify_access(&self) -> Result<(), AscError> {
+        let _: Vec<Resource<AppAttributes>> = self.get("/apps", &[("limit", "1")]).await?;
+        Ok(())
+    }
+
+    /// A build by number, optionally narrowed to a marketing version
+    pub async fn find_build(
+        &self,
+        app_id: &str,
+        number: &str,
+        version: Option<&str>,
+    ) -> Result<Option<Build>, AscError> {
+        let mut query = vec![
+            ("filter[app]", app_id),
+            ("filter[version]", number),
+            ("limit", "1"),
+        ];
+        if let Some(version) = version {
+            query.push(("filter[preReleaseVersion.version]", version));
+        }
+
+        let builds: Vec<Resource<BuildAttributes>> = self.get("/builds", &query).await?;
+        Ok(builds.into_iter().next().map(Build::from))
+    }
+
+    /// Most recently uploaded build
+    pub async fn latest_build(&self, app_id: &str) -> Result<Option<Build>, AscError> {
+        let builds: Vec<Resource<BuildAttributes>> = self
+            .get(
+                "/builds",
+                &[
+                    ("filter[app]", app_id),
+                    ("sort", "-uploadedDate"),
+                    ("limit", "1"),
+                ],
+            )
+            .await?;
+        Ok(builds.into_iter().next().map(Build::from))
+    }
+
+    /// Builds that haven't expired, newest upload first, with their TestFlight state
+    pub async fn build_statuses(&self, app_id: &str) -> Result<Vec<BuildStatus>, AscError> {
+        let url = format!("{}/builds", API_URL);
+        let response = self
+            .execute(|| {
+                Ok(self
+                    .client
+                    .get(&url)
+                    .query(&[
+                        ("filter[app]", app_id),
+                        ("filter[expired]", "false"),
+                        ("sort", "-uploadedDate"),
+                        ("include", "preReleaseVersion,buildBetaDetail"),
+                        ("fields[preReleaseVersions]", "version"),
+                        ("fields[buildBetaDetails]", "internalBuildState"),
+                        ("limit", "200"),
+                    ])
+                    .bearer_auth(self.token()?))
+            })
+            .await?;
+        let list: BuildList = response.json().await?;
+
+        let included = |relationship: Option<&Relationship>| {
+            let id = &relationship?.data.as_ref()?.id;
+            list.included.iter().find(|r| &r.id == id)
+        };
+
+        Ok(list
+            .data
+            .into_iter()
+            .map(|b| {
+                let relationships = b.relationships.as_ref();
+                let version = included(relationships.and_then(|r| r.pre_release_version.as_ref()))
+                    .and_then(|r| r.attributes.version.clone());
+                let beta_state = included(relationships.and_then(|r| r.build_beta_detail.as_ref()))
+                    .and_then(|r| r.attributes.internal_build_state.clone());
+                BuildStatus {
+                    build: Build::from(Resource {
+                        id: b.id,
+                        attributes: b.attributes,
+                    }),
+                    version,
+                    beta_state,
+                }
+            })
+            .collect())
+    }
+
+    /// Answer the export compliance question of a processed build
+    pub async fn set_uses_non_exempt_encryption(
+        &self,
+        build_id: &str,
+        uses: bool,
+    ) -> Result<(), AscError> {
+        self.send(
+            reqwest::Method::PATCH,
+            &format!("/builds/{}", build_id),
+            json!({
+                "data": {
+                    "type": "builds",
+                    "id": build_id,
+                    "attributes": { "usesNonExemptEncryption": uses }
+                }
+            }),
+        )
+        .await
+    }
+
+    /// Attach a build to an App Encryption Declaration
+    pub async fn assign_encryption_declaration(
+        &self,
+        build_id: &str,
+        declaration_id: &str,
+    ) -> Result<(), AscError> {
+        self.send(
+            reqwest::Method::PATCH,
+            &format!(
+                "/builds/{}/relationships/appEncryptionDeclaration",
+                build_id
+            ),
+            json!({ "data": { "type": "appEncryptionDeclarations", "id": declaration_id } }),
+        )
+        .await
+    }
+
+    pub async fn encryption_declarations(
+        &self,
+        app_id: &str,
+    ) -> Result<Vec<EncryptionDeclaration>, AscError> {
+        let declarations: Vec<Resource<EncryptionDeclarationAttributes>> = self
+            .get(
+                &format!("/apps/{}/appEncryptionDeclarations", app_id),
+                &[("limit", "200")],
+            )
+            .await?;
+
+        Ok(declarations
+            .into_iter()
+            .map(|d| EncryptionDeclaration {
+                id: d.id,
+                description: d.attributes.app_description,
+                state: d.attributes.app_encryption_declaration_state,
+                created_date: d.attributes.created_date,
+            })
+            .collect())
+    }
+
+    /// The app's newest App Store version for iOS, with its review state
+    pub async fn latest_app_store_version(
+        &self,
+        app_id: &str,
+    ) -> Result<Option<AppStoreVersion>, AscError> {
+        let url = format!("{}/apps/{}/appStoreVersions", API_URL, app_id);
+        let response = self
+            .execute(|| {
+                Ok(self
+                    .client
+                    .get(&url)
+                    .query(&[
+                        ("filter[platform]", "IOS"),
+                        ("include", "build"),
+                        ("fields[builds]", "version"),
+                        ("limit", "10"),
+                    ])
+                    .bearer_auth(self.token()?))
+            })
+            .await?;
+        let list: AppStoreVersionList = response.json().await?;
+
+        // The endpoint can't sort, so pick the newest by creation date
+        let Some(latest) = list
+            .data
+            .into_iter()
+            .max_by(|a, b| a.attributes.created_date.cmp(&b.attributes.created_date))
+        else {
+            return Ok(None);
+        };
+        let build = latest
+            .relationships
+            .as_ref()
+            .and_then(|r| r.build.as_ref())
+            .and_then(|r| r.data.as_ref())
+            .and_then(|linkage| list.included.iter().find(|b| b.id == linkage.id))
+            .map(|b| b.attributes.version.clone());
+        let attributes = latest.attributes;
+
+        Ok(Some(AppStoreVersion {
+            id: latest.id,
+            version: attributes.version_string,
+            build,
+            state: attributes
+                .app_version_state
+                .or(attributes.app_store_state)
+                .unwrap_or_else(|| "UNKNOWN".to_string()),
+        }))
+    }
+
+    /// Upload compliance documentation to a declaration: reserve the file, send
+    /// the parts App Store Connect asks for, then commit it with its MD5
+    pub async fn upload_encryption_document(
+        &self,
+        declaration_id: &str,
+        path: &Path,
+    ) -> Result<(), AscError> {
+        let bytes = tokio::fs::read(path).await?;
+        let file_name = path
+            .file_name()
+            .map(|name| name.to_string_lossy().to_string())
+            .unwrap_or_default();
+
+        let url = format!("{}/appEncryptionDeclarationDocuments", API_URL);
+        let body = json!({
+            "data": {
+                "type": "appEncryptionDeclarationDocuments",
+                "attributes": { "fileName": file_name, "fileSize": bytes.len() },
+                "relationships": {
+                    "appEncryptionDeclaration": {
+                        "data": { "type": "appEncryptionDeclarations", "id": declaration_id }
+                    }
+                }
+            }
+        });
+        let response = self
+            .execute(|| {
+                Ok(self
+                    .client
+                    .post(&url)
+                    .bearer_auth(self.token()?)
+                    .json(&body))
+            })
+            .await?;
+        let reservation: Document<Resource<DocumentReservationAttributes>> =
+            response.json().await?;
+        let reservation = reservation.data;
+
+        for operation in &reservation.attributes.upload_operations {
+            let end = (operation.offset + operation.length).min(bytes.len());
+            let part = bytes
+                .get(operation.offset..end)
+                .unwrap_or_default()
+                .to_vec();
+            let method =
+                reqwest::Method::from_bytes(operation.method.as_bytes()).map_err(|_| {
+                    AscError::NotFound(format!("Unknown upload method {}", operation.method))
+                })?;
+            self.execute(|| {
+                let mut request = self
+                    .client
+                    .request(method.clone(), &operation.url)
+                    .body(part.clone());
+                for header in &operation.request_headers {
+                    request = request.header(&header.name, &header.value);
+                }
+                Ok(request)
+            })
+            .await?;
+        }
+
+        self.send(
+            reqwest::Method::PATCH,
+            &format!("/appEncryptionDeclarationDocuments/{}", reservation.id),
+            json!({
+                "data": {
+                    "type": "appEncryptionDeclarationDocuments",
+                    "id": reservation.id,
+                    "attributes": {
+                        "uploaded": true,
+                        "sourceFileChecksum": format!("{:x}", md5::compute(&bytes))
+                    }
+                }
+            }),
+        )
+        .await
+    }
+
+    pub async fn beta_groups(&self, app_id: &str) -> Result<Vec<BetaGroup>, AscError> {
+        let groups: Vec<Resource<BetaGroupAttributes>> = self
+            .get("/betaGroups", &[("filter[app]", app_id), ("limit", "200")])
+            .await?;
+
+        Ok(groups
+            .into_iter()
+            .map(|g| BetaGroup {
+                id: g.id,
+                name: g.attributes.name,
+                is_internal: g.attributes.is_internal_group,
+            })
+            .collect())
+    }
+
+    pub async fn add_build_to_group(&self, group_id: &str, build_id: &str) -> Result<(), AscError> {
+        self.send(
+            reqwest::Method::POST,
+            &format!("/betaGroups/{}/relationships/builds", group_id),
+            json!({ "data": [{ "type": "builds", "id": build_id }] }),
+        )
+        .await
+    }
+
+    /// External groups only see a build once it passes beta app review
+    pub async fn submit_for_beta_review(&self, build_id: &str) -> Result<(), AscError> {
+        self.send(
+            reqwest::Method::POST,
+            "/betaAppReviewSubmissions",
+            json!({
+                "data": {
+                    "type": "betaAppReviewSubmissions",
+                    "relationships": { "build": { "data": { "type": "builds", "id": build_id } } }
+                }
+            }),
+        )
+        .await
+    }
+
+    /// Turn TestFlight's automatic "new build available" notification on or off
+    pub async fn set_auto_notify(&self, build_id: &str, enabled: bool) -> Result<(), AscError> {
+        let detail: Resource<Value> = self
+            .get(&format!("/builds/{}/buildBetaDetail", build_id), &[])
+            .await?;
+
+        self.send(
+            reqwest::Method::PATCH,
+            &format!("/buildBetaDetails/{}", detail.id),
+            json!({
+                "data": {
+                    "type": "buildBetaDetails",
+                    "id": detail.id,
+                    "attributes": { "autoNotifyEnabled": enabled }
+                }
+            }),
+        )
+        .await
+    }
+
+    /// Email testers who already have access to the build
+    pub async fn notify_testers(&self, build_id: &str) -> Result<(), AscError> {
+        self.send(
+            reqwest::Method::POST,
+            "/buildBetaNotifications",
+            json!({
+                "data": {
+                    "type": "buildBetaNotifications",
+                    "relationships": { "build": { "data": { "type": "builds", "id": build_id } } }
+                }
+            }),
+        )
+        .await
+    }
+
+    /// Every tester of an app, across as many pages as App Store Connect returns
+    pub async fn beta_testers(&self, app_id: &str) -> Result<Vec<BetaTester>, AscError> {
+        let mut testers = Vec::new();
+        let mut url = format!("{}/betaTesters", API_URL);
+        let mut query = vec![
+            ("filter[apps]", app_id),
+            ("include", "betaGroups"),
+            (
+                "fields[betaTesters]",
+                "firstName,lastName,email,state,betaGroups",
+            ),
+            ("limit[betaGroups]", "50"),
+            ("limit", "200"),
+        ];
+
+        loop {
+            let response = self
+                .execute(|| {
+                    Ok(self
+                        .client
+                        .get(&url)
+                        .query(&query)
+                        .bearer_auth(self.token()?))
+                })
+                .await?;
+            let page: TesterPage = response.json().await?;
+
+            testers.extend(page.data.into_iter().map(|t| {
+                BetaTester {
+                    id: t.id,
+                    first_name: t.attributes.first_name.unwrap_or_default(),
+                    last_name: t.attributes.last_name.unwrap_or_default(),
+                    email: t.attributes.email.unwrap_or_default(),
+                    state: t.attributes.state,
+                    group_ids: t
+                        .relationships
+                        .and_then(|r| r.beta_groups)
+                        .map(|groups| groups.data.into_iter().map(|g| g.id).collect())
+                        .unwrap_or_default(),
+                }
+            }));
+
+            // The next link carries the whole query, cursor included
+            match page.links.and_then(|links| links.next) {
+                Some(next) => {
+                    url = next;
+                    query.clear();
+                }
+                None => return Ok(testers),
+            }
+        }
+    }
+
+    /// Customer reviews of an app, newest first, with their developer responses
+    pub async fn customer_reviews(
+        &self,
+        app_id: &str,
+        filter: &ReviewFilter,
+    ) -> Result<Vec<CustomerReview>, AscError> {
+        let mut reviews = Vec::new();
+        let mut url = format!("{}/apps/{}/customerReviews", API_URL, app_id);
+        let ratings = filter
+            .ratings
+            .iter()
+            .map(|rating| rating.to_string())
+            .collect::<Vec<_>>()
+            .join(",");
+        let page_size = filter.limit.clamp(1, 200).to_string();
+        let mut query = vec![
+            ("sort", "-createdDate"),
+            ("include", "response"),
+            ("limit", page_size.as_str()),
+        ];
+        if !ratings.is_empty() {
+            query.push(("filter[rating]", &ratings));
+        }
+        if let Some(territory) = &filter.territory {
+            query.push(("filter[territory]", territory));
+        }
+        if filter.unanswered {
+            query.push(("exists[publishedResponse]", "false"));
+        }
+
+        loop {
+            let response = self
+                .execute(|| {
+                    Ok(self
+                        .client
+                        .get(&url)
+                        .query(&query)
+                        .bearer_auth(self.token()?))
+                })
+                .await?;
+            let mut page: CustomerReviewPage = response.json().await?;
+
+            for review in page.data {
+                let response_id = review
+                    .relationships
+                    .and_then(|r| r.response)
+                    .and_then(|r| r.data)
+                    .map(|linkage| linkage.id);
+                let response = response_id
+                    .and_then(|id| page.included.iter().position(|r| r.id == id))
+                    .map(|index| ReviewResponse::from(page.included.swap_remove(index)));
+                let attributes = review.attributes;
+                reviews.push(CustomerReview {
+                    id: review.id,
+                    rating: attributes.rating,
+                    title: attributes.title,
+                    body: attributes.body,
+                    reviewer: attributes.reviewer_nickname,
+                    territory: attributes.territory,
+                    created_date: attributes.created_date,
+                    response,
+                });
+                if reviews.len() >= filter.limit {
+                    return Ok(reviews);
+                }
+            }
+
+            match page.links.and_then(|links| links.next) {
+                Some(next) => {
+                    url = next;
+                    query.clear();
+                }
+                None => return Ok(reviews),
+            }
+        }
+    }
+
+    /// Respond to a customer review, replacing any earlier response
+    pub async fn respond_to_review(
+        &self,
+        review_id: &str,
+        body: &str,
+    ) -> Result<ReviewResponse, AscError> {
+        let url = format!("{}/customerReviewResponses", API_URL);
+        let request = json!({
+            "data": {
+                "type": "customerReviewResponses",
+                "attributes": { "responseBody": body },
+                "relationships": {
+                    "review": { "data": { "type": "customerReviews", "id": review_id } }
+                }
+            }
+        });
+        let response = self
+            .execute(|| {
+                Ok(self
+                    .client
+                    .post(&url)
+                    .bearer_auth(self.token()?)
+                    .json(&request))
+            })
+            .await?;
+        let created: Document<Resource<ReviewResponseAttributes>> = response.json().await?;
+        Ok(ReviewResponse::from(created.data))
+    }
+
+    /// Daily Sales and Trends summary for `date` ("2024-05-01"), one row per
+    /// product, country and version; `None` while Apple hasn't published it
+    pub async fn sales_report(
+        &self,
+        vendor_number: &str,
+        date: &str,
+    ) -> Result<Option<Vec<ReportRow>>, AscError> {
+        let url = format!("{}/salesReports", API_URL);
+        let result = self
+            .execute(|| {
+                Ok(self
+                    .client
+                    .get(&url)
+                    .query(&[
+                        ("filter[frequency]", "DAILY"),
+                        ("filter[reportType]", "SALES"),
+                        ("filter[reportSubType]", "SUMMARY"),
+                        ("filter[vendorNumber]", vendor_number),
+                        ("filter[reportDate]", date),
+                        ("filter[version]", "1_0"),
+                    ])
+                    .header(reqwest::header::ACCEPT, "application/a-gzip")
+                    .bearer_auth(self.token()?))
+            })
+            .await;
+        let response = match result {
+            Ok(response) => response,
+            Err(AscError::Api { status: 404, .. }) => return Ok(None),
+            Err(e) => return Err(e),
+        };
+        let bytes = response.bytes().await?;
+        Ok(Some(parse_report(&gunzip(&bytes)?)))
+    }
+
+    /// ID of the app's ongoing Analytics Reports request, and whether it was just
+    /// made. Apple needs a day or two to produce the first reports of a new one.
+    pub async fn analytics_report_request(&self, app_id: &str) -> Result<(String, bool), AscError> {
+        let requests: Vec<Resource<AnalyticsRequestAttributes>> = self
+            .get(
+                &format!("/apps/{}/analyticsReportRequests", app_id),
+                &[("filter[accessType]", "ONGOING")],
+            )
+            .await?;
+        if let Some(request) = requests
+            .into_iter()
+            .find(|r| !r.attributes.stopped_due_to_inactivity)
+        {
+            return Ok((request.id, false));
+        }
+
+        let url = format!("{}/analyticsReportRequests", API_URL);
+        let body = json!({
+            "data": {
+                "type": "analyticsReportRequests",
+                "attributes": { "accessType": "ONGOING" },
+                "relationships": { "app": { "data": { "type": "apps", "id": app_id } } }
+            }
+        });
+        let response = self
+            .execute(|| {
+                Ok(self
+                    .client
+                    .post(&url)
+                    .bearer_auth(self.token()?)
+                    .json(&body))
+            })
+            .await?;
+        let created: Document<Resource<AnalyticsRequestAttributes>> = response.json().await?;
+        Ok((created.data.id, true))
+    }
+
+    /// Rows of an analytics report ("App Sessions", "App Crashes") from the daily
+    /// instances processed on `processing_dates`
+    pub async fn analytics_rows(
+        &self,
+        request_id: &str,
+        report: &str,
+        processing_dates: &[String],
+    ) -> Result<Vec<ReportRow>, AscError> {
+        let reports: Vec<Resource<Value>> = self
+            .get(
+                &format!("/analyticsReportRequests/{}/reports", request_id),
+                &[("filter[name]", report)],
+            )
+            .await?;
+        let Some(report) = reports.into_iter().next() else {
+            return Ok(Vec::new());
+        };
+
+        let instances: Vec<Resource<AnalyticsInstanceAttributes>> = self
+            .get(
+                &format!("/analyticsReports/{}/instances", report.id),
+                &[("filter[granularity]", "DAILY"), ("limit", "200")],
+            )
+            .await?;
+
+        let mut rows = Vec::new();
+        for instance in instances {
+            let processed = instance.attributes.processing_date.unwrap_or_default();
+            if !processing_dates.contains(&processed) {
+                continue;
+            }
+            let segments: Vec<Resource<AnalyticsSegmentAttributes>> = self
+                .get(
+                    &format!("/analyticsReportInstances/{}/segments", instance.id),
+                    &[],
+                )
+                .await?;
+            for segment in segments {
+                // Segment URLs are pre-signed; the API token would be rejected
+                let response = self
+                    .execute(|| Ok(self.client.get(&segment.attributes.url)))
+                    .await?;
+                let bytes = response.bytes().await?;
+                rows.extend(parse_report(&gunzip(&bytes)?));
+            }
+        }
+        Ok(rows)
+    }
+
+    /// The app's in-app purchases (consumables, non-consumables, non-renewing subscriptions)
+    pub async fn in_app_purchases(&self, app_id: &str) -> Result<Vec<StoreProduct>, AscError> {
+        let products: Vec<Resource<ProductAttributes>> = self
+            .get(
+                &format!("/apps/{}/inAppPurchasesV2", app_id),
+                &[("limit", "200")],
+            )
+            .await?;
+        Ok(products.into_iter().map(StoreProduct::from).collect())
+    }
+
+    pub async fn subscription_groups(
+        &self,
+        app_id: &str,
+    ) -> Result<Vec<SubscriptionGroup>, AscError> {
+        let groups: Vec<Resource<SubscriptionGroupAttributes>> = self
+            .get(
+                &format!("/apps/{}/subscriptionGroups", app_id),
+                &[("limit", "200")],
+            )
+            .await?;
+        Ok(groups
+            .into_iter()
+            .map(|g| SubscriptionGroup {
+                id: g.id,
+                reference_name: g.attributes.reference_name,
+            })
+            .collect())
+    }
+
+    pub async fn create_subscription_group(
+        &self,
+        app_id: &str,
+        reference_name: &str,
+    ) -> Result<String, AscError> {
+        self.create(
+            "/subscriptionGroups",
+            json!({
+                "data": {
+                    "type": "subscriptionGroups",
+                    "attributes": { "referenceName": reference_name },
+                    "relationships": { "app": { "data": { "type": "apps", "id": app_id } } }
+                }
+            }),
+        )
+        .await
+    }
+
+    /// Auto-renewable subscriptions in a group
+    pub async fn subscriptions(&self, group_id: &str) -> Result<Vec<StoreProduct>, AscError> {
+        let products: Vec<Resource<ProductAttributes>> = self
+            .get(
+                &format!("/subscriptionGroups/{}/subscriptions", group_id),
+                &[("limit", "200")],
+            )
+            .await?;
+        Ok(products.into_iter().map(StoreProduct::from).collect())
+    }
+
+    /// Create an in-app purchase of `iap_type` for the app
+    pub async fn create_in_app_purchase(
+        &self,
+        app_id: &str,
+        product_id: &str,
+        reference_name: &str,
+        iap_type: &str,
+        review_note: Option<&str>,
+    ) -> Result<String, AscError> {
+        self.create(
+            &format!("{}/inAppPurchases", API_V2_URL),
+            json!({
+                "data": {
+                    "type": "inAppPurchases",
+                    "attributes": {
+                        "productId": product_id,
+                        "name": reference_name,
+                        "inAppPurchaseType": iap_type,
+                        "reviewNote": review_note,
+                    },
+                    "relationships": { "app": { "data": { "type": "apps", "id": app_id } } }
+                }
+            }),
+        )
+        .await
+    }
+
+    /// Create an auto-renewable subscription in a group
+    pub async fn create_subscription(
+        &self,
+        group_id: &str,
+        product_id: &str,
+        reference_name: &str,
+        period: Option<&str>,
+        review_note: Option<&str>,
+    ) -> Result<String, AscError> {
+        self.create(
+            "/subscriptions",
+            json!({
+                "data": {
+                    "type": "subscriptions",
+                    "attributes": {
+                        "productId": product_id,
+                        "name": reference_name,
+                        "subscriptionPeriod": period,
+                        "reviewNote": review_note,
+                    },
+                    "relationships": {
+                        "group": { "data": { "type": "subscriptionGroups", "id": group_id } }
+                    }
+                }
+            }),
+        )
+        .await
+    }
+
+    /// Change a product's reference name and review note
+    pub async fn update_product(
+        &self,
+        kind: ProductKind,
+        id: &str,
+        reference_name: &str,
+        review_note: Option<&str>,
+    ) -> Result<(), AscError> {
+        self.send(
+            reqwest::Method::PATCH,
+            &kind.product_url(id),
+            json!({
+                "data": {
+                    "type": kind.product_type(),
+                    "id": id,
+                    "attributes": { "name": reference_name, "reviewNote": review_note }
+                }
+            }),
+        )
+        .await
+    }
+
+    pub async fn product_localizations(
+        &self,
+        kind: ProductKind,
+        id: &str,
+    ) -> Result<Vec<ProductLocalization>, AscError> {
+        let localizations: Vec<Resource<ProductLocalizationAttributes>> = self
+            .get(
+                &format!("{}/{}", kind.product_url(id), kind.localization_type()),
+                &[("limit", "200")],
+            )
+            .await?;
+        Ok(localizations
+            .into_iter()
+            .map(|l| ProductLocalization {
+                id: l.id,
+                locale: l.attributes.locale,
+                name: l.attributes.name.unwrap_or_default(),
+                description: l.attributes.description,
+            })
+            .collect())
+    }
+
+    pub async fn create_product_localization(
+        &self,
+        kind: ProductKind,
+        id: &str,
+        locale: &str,
+        name: &str,
+        description: Option<&str>,
+    ) -> Result<(), AscError> {
+        self.create(
+            &format!("/{}", kind.localization_type()),
+            json!({
+                "data": {
+                    "type": kind.localization_type(),
+                    "attributes": { "locale": locale, "name": name, "description": description },
+                    "relationships": {
+                        kind.parent_relationship(): {
+                            "data": { "type": kind.product_type(), "id": id }
+                        }
+                    }
+                }
+            }),
+        )
+        .await?;
+        Ok(())
+    }
+
+    pub async fn update_product_localization(
+        &self,
+        kind: ProductKind,
+        localization_id: &str,
+        name: &str,
+        description: Option<&str>,
+    ) -> Result<(), AscError> {
+        self.send(
+            reqwest::Method::PATCH,
+            &format!("/{}/{}", kind.localization_type(), localization_id),
+            json!({
+                "data": {
+                    "type": kind.localization_type(),
+                    "id": localization_id,
+                    "attributes": { "name": name, "description": description }
+                }
+            }),
+        )
+        .await
+    }
+
+    /// Current customer price of an in-app purchase in a territory ("USA"), as
+    /// App Store Connect writes it ("0.99"); `None` before a price is set
+    pub async fn iap_price(
+        &self,
+        iap_id: &str,
+        territory: &str,
+    ) -> Result<Option<String>, AscError> {
+        let schedule: Result<Created, AscError> = self
+            .get(
+                &format!("{}/inAppPurchases/{}/iapPriceSchedule", API_V2_URL, iap_id),
+                &[],
+            )
+            .await;
+        let schedule = match schedule {
+            Ok(schedule) => schedule,
+            Err(AscError::Api { status: 404, .. }) => return Ok(None),
+            Err(e) => return Err(e),
+        };
+
+        let url = format!(
+            "{}/inAppPurchasePriceSchedules/{}/manualPrices",
+            API_URL, schedule.id
+        );
+        let response = self
+            .execute(|| {
+                Ok(self
+                    .client
+                    .get(&url)
+                    .query(&[
+                        ("filter[territory]", territory),
+                        ("include", "inAppPurchasePricePoint"),
+                        ("fields[inAppPurchasePricePoints]", "customerPrice"),
+                    ])
+                    .bearer_auth(self.token()?))
+            })
+            .await?;
+        let prices: ManualPriceList = response.json().await?;
+
+        // Scheduled changes are listed too; the current price has no end date
+        let point_id = prices
+            .data
+            .iter()
+            .filter(|p| p.attributes.end_date.is_none())
+            .find_map(|p| {
+                let point = p
+                    .relationships
+                    .as_ref()?
+                    .in_app_purchase_price_point
+                    .as_ref()?;
+                point.data.as_ref().map(|linkage| linkage.id.clone())
+            });
+        Ok(point_id.and_then(|id| {
+            prices
+                .included
+                .into_iter()
+                .find(|point| point.id == id)?
+                .attributes
+                .customer_price
+        }))
+    }
+
+    /// Price an in-app purchase at the `territory` price point matching `price`
+    /// (App Store Connect equalizes the other territories from it). Returns
+    /// `false` when no price point matches.
+    pub async fn set_iap_price(
+        &self,
+        iap_id: &str,
+        territory: &str,
+        price: &str,
+    ) -> Result<bool, AscError> {
+        let points: Vec<Resource<PricePointAttributes>> = self
+            .get(
+                &format!("{}/inAppPurchases/{}/pricePoints", API_V2_URL, iap_id),
+                &[("filter[territory]", territory), ("limit", "8000")],
+            )
+            .await?;
+        let wanted: Option<f64> = price.parse().ok();
+        let Some(point) = points.into_iter().find(|point| {
+            point
+                .attributes
+                .customer_price
+                .as_deref()
+                .and_then(|p| p.parse::<f64>().ok())
+                .is_some_and(|p| Some(p) == wanted)
+        }) else {
+            return Ok(false);
+        };
+
+        self.create(
+            "/inAppPurchasePriceSchedules",
+            json!({
+                "data": {
+                    "type": "inAppPurchasePriceSchedules",
+                    "relationships": {
+                        "inAppPurchase": { "data": { "type": "inAppPurchases", "id": iap_id } },
+                        "baseTerritory": { "data": { "type": "territories", "id": territory } },
+                        "manualPrices": { "data": [{ "type": "inAppPurchasePrices", "id": "${price}" }] }
+                    }
+                },
+                "included": [{
+                    "type": "inAppPurchasePrices",
+                    "id": "${price}",
+                    "attributes": { "startDate": null },
+                    "relationships": {
+                        "inAppPurchaseV2": { "data": { "type": "inAppPurchases", "id": iap_id } },
+                        "inAppPurchasePricePoint": {
+                            "data": { "type": "inAppPurchasePricePoints", "id": point.id }
+                        }
+                    }
+                }]
+            }),
+        )
+        .await?;
+        Ok(true)
+    }
+
+    /// Invite a new tester straight into groups (an external tester needs at least one)
+    pub async fn create_beta_tester(
+        &self,
+        email: &str,
+        first_name: &str,
+        last_name: &str,
+        group_ids: &[String],
+    ) -> Result<(), AscError> {
+        let groups: Vec<Value> = group_ids
+            .iter()
+            .map(|id| json!({ "type": "betaGroups", "id": id }))
+            .collect();
+        self.send(
+            reqwest::Method::POST,
+            "/betaTesters",
+            json!({
+                "data": {
+                    "type": "betaTesters",
+                    "attributes": { "email": email, "firstName": first_name, "lastName": last_name },
+                    "relationships": { "betaGroups": { "data": groups } }
+                }
+            }),
+        )
+        .await
+    }
+
+    pub async fn add_tester_to_group(
+        &self,
+        group_id: &str,
+        tester_id: &str,
+    ) -> Result<(), AscError> {
+        self.send(
+            reqwest::Method::POST,
+            &format!("/betaGroups/{}/relationships/betaTesters", group_id),
+            json!({ "data": [{ "type": "betaTesters", "id": tester_id }] }),
+        )
+        .await
+    }
+
+    /// App and extension bundles of a build, with their dSYM downloads
+    pub async fn build_bundles(&self, build_id: &str) -> Result<Vec<BuildBundle>, AscError> {
+        let url = format!("{}/builds/{}", API_URL, build_id);
+        let response = self
+            .execute(|| {
+                Ok(self
+                    .client
+                    .get(&url)
+                    .query(&[
+                        ("include", "buildBundles"),
+                        ("fields[buildBundles]", "bundleId,dSYMUrl"),
+                    ])
+                    .bearer_auth(self.token()?))
+            })
+            .await?;
+        let document: BuildWithBundles = response.json().await?;
+
+        Ok(document
+            .included
+            .into_iter()
+            .map(|b| BuildBundle {
+                id: b.id,
+                bundle_id: b.attributes.bundle_id,
+                dsym_url: b.attributes.dsym_url,
+            })
+            .collect())
+    }
+
+    /// Every provisioning profile on the team, with its bundle identifier
+    pub async fn profiles(&self) -> Result<Vec<PortalProfile>, AscError> {
+        let url = format!("{}/profiles", API_URL);
+        let response = self
+            .execute(|| {
+                Ok(self
+                    .client
+                    .get(&url)
+                    .query(&[
+                        ("include", "bundleId"),
+                        ("fields[bundleIds]", "identifier"),
+                        ("limit", "200"),
+                    ])
+                    .bearer_auth(self.token()?))
+            })
+            .await?;
+        let list: ProfileList = response.json().await?;
+
+        let identifier = |id: &str| {
+            list.included
+                .iter()
+                .find(|b| b.id == id)
+                .map(|b| b.attributes.identifier.clone())
+        };
+
+        Ok(list
+            .data
+            .iter()
+            .map(|p| PortalProfile {
+                name: p.attributes.name.clone(),
+                profile_type: p.attributes.profile_type.clone(),
+                state: p.attributes.profile_state.clone(),
+                expiration_date: p.attributes.expiration_date.clone(),
+                bundle_id: p
+                    .relationships
+                    .as_ref()
+                    .and_then(|r| r.bundle_id.as_ref())
+                    .and_then(|r| r.data.as_ref())
+                    .and_then(|d| identifier(&d.id)),
+            })
+            .collect())
+    }
+
+    /// Every device registered on the team
+    pub async fn devices(&self) -> Result<Vec<PortalDevice>, AscError> {
+        let devices: Vec<Resource<DeviceAttributes>> = self
+            .get(
+                "/devices",
+                &[
+                    ("fields[devices]", "name,udid,platform,status,model"),
+                    ("limit", "200"),
+                ],
+            )
+            .await?;
+        Ok(devices
+            .into_iter()
+            .map(|d| PortalDevice {
+                name: d.attributes.name,
+                udid: d.attributes.udid,
+                platform: d.attributes.platform,
+                status: d.attributes.status,
+                model: d.attributes.model,
+            })
+            .collect())
+    }
+
+    /// Register a device so development and ad-hoc profiles can include it
+    pub async fn register_device(&self, name: &str, udid: &str) -> Result<(), AscError> {
+        self.send(
+            reqwest::Method::POST,
+            "/devices",
+            json!({
+                "data": {
+                    "type": "devices",
+                    "attributes": { "name": name, "udid": udid, "platform": "IOS" }
+                }
+            }),
+        )
+        .await
+    }
+
+    /// Save a pre-signed download (no API token) to `path`
+    pub async fn download(&self, url: &str, path: &Path) -> Result<(), AscError> {
+        let response = self.execute(|| Ok(self.client.get(url))).await?;
+        let bytes = response.bytes().await?;
+        std::fs::write(path, &bytes)?;
+        Ok(())
+    }
+
+    async fn get<T: DeserializeOwned>(
+        &self,
+        path: &str,
+        query: &[(&str, &str)],
+    ) -> Result<T, AscError> {
+        let url = api_url(path);
+        let response = self
+            .execute(|| {
+                Ok(self
+                    .client
+                    .get(&url)
+                    .query(query)
+                    .bearer_auth(self.token()?))
+            })
+            .await?;
+        let document: Document<T> = response.json().await?;
+        Ok(document.data)
+    }
+
+    async fn send(&self, method: reqwest::Method, path: &str, body: Value) -> Result<(), AscError> {
+        let url = api_url(path);
+        self.execute(|| {
+            Ok(self
+                .client
+                .request(method.clone(), &url)
+                .bearer_auth(self.token()?)
+                .json(&body))
+        })
+        .await?;
+        Ok(())
+    }
+
+    /// POST a new resource and return its ID
+    async fn create(&self, path: &str, body: Value) -> Result<String, AscError> {
+        let url = api_url(path);
+        let response = self
+            .execute(|| {
+                Ok(self
+                    .client
+                    .post(&url)
+                    .bearer_auth(self.token()?)
+                    .json(&body))
+            })
+            .await?;
+        let created: Document<Created> = response.json().await?;
+        Ok(created.data.id)
+    }
+
+    /// Send a request, retrying 429/5xx answers and dropped connections with
+    /// jittered exponential backoff (or the server's Retry-After). A POST may have
+    /// created something before failing, so it's only retried after a 429 or a
+    /// connection that never opened.
+    async fn execute<F>(&self, request: F) -> Result<reqwest::Response, AscError>
+    where
+        F: Fn() -> Result<reqwest::RequestBuilder, AscError>,
+    {
+        let mut attempt = 1;
+        loop {
+            let sent = self.requests.fetch_add(1, Ordering::Relaxed);
+            if let Some(budget) = self.budget.filter(|&budget| sent >= budget) {
+                return Err(AscError::BudgetExhausted(budget));
+            }
+
+            let request = request()?.build()?;
+            let idempotent = request.method() != reqwest::Method::POST;
+            let (problem, server_delay) = match self.dispatch(request).await {
+                Ok(response) => {
+                    self.record_rate_limit(&response);
+                    let status = response.status();
+                    let retryable = if idempotent {
+                        is_retryable(status)
+                    } else {
+                        status == reqwest::StatusCode::TOO_MANY_REQUESTS
+                    };
+                    if !retryable || attempt == self.max_attempts {
+                        return match status.as_u16() {
+                            429 => Err(AscError::RateLimited(*self.rate_limit.lock().unwrap())),
+                            _ => check(response).await,
+                        };
+                    }
+                    (status.to_string(), retry_after(&response))
+                }
+                Err(e)
+                    if (e.is_connect() || (idempotent && e.is_timeout()))
+                        && attempt < self.max_attempts =>
+                {
+                    (e.to_string(), None)
+                }
+                Err(e) => return Err(e.into()),
+            };
+
+            let delay = server_delay.unwrap_or_else(|| backoff(attempt));
+            ui::warn(&format!(
+                "App Store Connect: {}{}; retrying in {}s ({}/{})",
+                problem,
+                describe_quota(&self.rate_limit.lock().unwrap()),
+                delay.as_secs().max(1),
+                attempt + 1,
+                self.max_attempts
+            ));
+            tokio::time::sleep(delay).await;
+            attempt += 1;
+        }
+    }
+
+    /// Send one request, through the `--debug-http` log when it's on; `--mock`
+    /// answers it without sending
+    async fn dispatch(
+        &self,
+        request: reqwest::Request,
+    ) -> Result<reqwest::Response, reqwest::Error> {
+        if !http::debug_enabled() && !mock::enabled() {
+            return self.client.execute(request).await;
+        }
+        let id = http::log_request(&request);
+        let started = std::time::Instant::now();
+        let response = if mock::enabled() {
+            Ok(mock::asc_response(&request))
+        } else {
+            self.client.execute(request).await
+        };
+        match response {
+            Ok(response) => http::log_response(id, response, started.elapsed()).await,
+            Err(e) => {
+                http::log_failure(id, &e, started.elapsed());
+                Err(e)
+            }
+        }
+    }
+
+    fn record_rate_limit(&self, response: &reqwest::Response) {
+        let Some(quota) = response
+            .headers()
+            .get("x-rate-limit")
+            .and_then(|value| value.to_str().ok())
+            .and_then(RateLimit::parse)
+        else {
+            return;
+        };
+        *self.rate_limit.lock().unwrap() = Some(quota);
+
+        let low = quota.remaining * 100 < quota.limit * LOW_QUOTA_PERCENT;
+        if low && !self.warned_low_quota.swap(true, Ordering::Relaxed) {
+            ui::warn(&format!(
+                "App Store Connect quota is running low: {} of {} requests left this hour",
+                quota.remaining, quota.limit
+            ));
+        }
+    }
+
+    /// Short-lived ES256 token; minted per request so long polls never expire
+    fn token(&self) -> Result<String, AscError> {
+        if mock::enabled() {
+            return Ok("mock".to_string());
+        }
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or_default();
+        let claims = Claims {
+            iss: &self.issuer_id,
+            iat: now,
+            exp: now + 15 * 60,
+            aud: "appstoreconnect-v1",
+        };
+
+        let mut header = jsonwebtoken::Header::new(jsonwebtoken::Algorithm::ES256);
+        header.kid = Some(self.key_id.clone());
+
+        jsonwebtoken::encode(&header, &claims, &self.key).map_err(|e| AscError::Key(e.to_string()))
+    }
+}
+
+impl From<Resource<BuildAttributes>> for Build {
+    fn from(resource: Resource<BuildAttributes>) -> Self {
+        Build {
+            id: resource.id,
+            number: resource.attributes.version,
+            processing_state: resource.attributes.processing_state,
+            uploaded_date: resource.attributes.uploaded_date,
+        }
+    }
+}
+
+/// A path under the v1 API, or a full URL (v2 endpoints, pagination links)
+fn api_url(path: &str) -> String {
+    if path.starts_with("https://") {
+        path.to_string()
+    } else {
+        format!("{}{}", API_URL, path)
+    }
+}
+
+/// Reports come gzipped, except when a proxy already inflated them
+fn gunzip(bytes: &[u8]) -> Result<String, std::io::Error> {
+    if !bytes.starts_with(&[0x1f, 0x8b]) {
+        return Ok(String::from_utf8_lossy(bytes).to_string());
+    }
+    let mut text = String::new();
+    flate2::read::MultiGzDecoder::new(bytes).read_to_string(&mut text)?;
+    Ok(text)
+}
+
+/// Tab-separated report with a header row
+fn parse_report(text: &str) -> Vec<ReportRow> {
+    let mut lines = text.lines().filter(|line| !line.trim().is_empty());
+    let Some(header) = lines.next() else {
+        return Vec::new();
+    };
+    let columns: Vec<&str> = header.split('\t').map(str::trim).collect();
+    lines
+        .map(|line| {
+            columns
+                .iter()
+                .zip(line.split('\t'))
+                .map(|(column, value)| (column.to_string(), value.trim().to_string()))
+                .collect()
+        })
+        .collect()
+}
+
+/// Rate limited, or a server error worth trying again
+fn is_retryable(status: reqwest::StatusCode) -> bool {
+    matches!(status.as_u16(), 429 | 500 | 502 | 503 | 504)
+}
+
+/// Retry-After in seconds, capped so a bad header can't stall the run
+fn retry_after(response: &reqwest::Response) -> Option<Duration> {
+    let seconds: u64 = response
+        .headers()
+        .get(reqwest::header::RETRY_AFTER)?
+        .to_str()
+        .ok()?
+        .trim()
+        .parse()
+        .ok()?;
+    Some(Duration::from_secs(seconds).min(MAX_BACKOFF))
+}
+
+/// Exponential backoff with "equal jitter": half fixed, half random, so parallel
+/// CI jobs that hit the limit together don't retry in lockstep
+fn backoff(attempt: u32) -> Duration {
+    let ceiling = BASE_BACKOFF
+        .saturating_mul(1 << (attempt - 1).min(16))
+        .min(MAX_BACKOFF);
+    let half = ceiling / 2;
+
+    // Sub-second clock noise is random enough to spread retries out
+    let noise = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.subsec_nanos())
+        .unwrap_or_default();
+    let jitter_ms = half.as_millis() as u64;
+    half + Duration::from_millis(if jitter_ms == 0 {
+        0
+    } else {
+        noise as u64 % jitter_ms
+    })
+}
+
+async fn check(response: reqwest::Response) -> Result<reqwest::Response, AscError> {
+    let status = response.status();
+    if status.is_success() {
+        return Ok(response);
+    }
+
+    let body = response.text().await.unwrap_or_default();
+    if status.as_u16() == 403 {
+        if let Some(agreement) = pending_agreement(&body) {
+            return Err(AscError::AgreementPending(agreement));
+        }
+    }
+    Err(AscError::Api {
+        status: status.as_u16(),
+        body,
+    })
+}
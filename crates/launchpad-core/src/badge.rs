@@ -0,0 +1,243 @@
+use image::{DynamicImage, ImageFormat, Rgba, RgbaImage};
+use std::path::{Path, PathBuf};
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+pub enum BadgeError {
+    #[error("No {0}.appiconset found in the project")]
+    NoIconSet(String),
+
+    #[error("Invalid badge color '{0}' (expected \"#RRGGBB\")")]
+    InvalidColor(String),
+
+    #[error("Couldn't badge {path}: {source}")]
+    Image {
+        path: PathBuf,
+        source: image::ImageError,
+    },
+
+    #[error("IO error: {0}")]
+    Io(#[from] std::io::Error),
+}
+
+/// Directories that hold other people's assets, or build output
+const SKIPPED_DIRS: &[&str] = &["Pods", "build", "DerivedData", "node_modules", "Carthage"];
+
+/// Icons badged for one build; `restore` puts the originals back
+pub struct BadgedIcons {
+    originals: Vec<(PathBuf, Vec<u8>)>,
+}
+
+impl BadgedIcons {
+    pub fn count(&self) -> usize {
+        self.originals.len()
+    }
+
+    pub fn restore(self) -> Result<(), std::io::Error> {
+        for (path, original) in &self.originals {
+            std::fs::write(path, original)?;
+        }
+        Ok(())
+    }
+}
+
+/// Draw a `text` band in `color` across the bottom of every PNG in the
+/// `<icon_set>.appiconset` under `ios_path`
+pub fn apply(
+    ios_path: &Path,
+    icon_set: &str,
+    text: &str,
+    color: &str,
+) -> Result<BadgedIcons, BadgeError> {
+    let color = parse_color(color)?;
+    let dir = find_icon_set(ios_path, &format!("{}.appiconset", icon_set))
+        .ok_or_else(|| BadgeError::NoIconSet(icon_set.to_string()))?;
+
+    let mut badged = BadgedIcons {
+        originals: Vec::new(),
+    };
+    let mut icons: Vec<PathBuf> = std::fs::read_dir(&dir)?
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| path.extension().is_some_and(|ext| ext == "png"))
+        .collect();
+    icons.sort();
+
+    for path in icons {
+        let original = std::fs::read(&path)?;
+        match badge_png(&original, text, color) {
+            Ok(png) => {
+                std::fs::write(&path, png)?;
+                badged.originals.push((path, original));
+            }
+            Err(source) => {
+                // Half-badged icons are worse than none
+                let _ = badged.restore();
+                return Err(BadgeError::Image { path, source });
+            }
+        }
+    }
+    Ok(badged)
+}
+
+fn find_icon_set(dir: &Path, name: &str) -> Option<PathBuf> {
+    let entries = std::fs::read_dir(dir).ok()?;
+    let mut dirs: Vec<PathBuf> = entries
+        .flatten()
+        .filter(|entry| entry.file_type().is_ok_and(|t| t.is_dir()))
+        .map(|entry| entry.path())
+        .collect();
+    dirs.sort();
+
+    for path in dirs {
+        let file_name = path.file_name()?.to_string_lossy();
+        if file_name == name {
+            return Some(path);
+        }
+        if file_name.starts_with('.') || SKIPPED_DIRS.contains(&file_name.as_ref()) {
+            continue;
+        }
+        if let Some(found) = find_icon_set(&path, name) {
+            return Some(found);
+        }
+    }
+    None
+}
+
+/// "#RRGGBB" (or "RRGGBB")
+fn parse_color(value: &str) -> Result<[u8; 3], BadgeError> {
+    let invalid = || BadgeError::InvalidColor(value.to_string());
+    let hex = value.trim().trim_start_matches('#');
+    if hex.len() != 6 || !hex.is_ascii() {
+        return Err(invalid());
+    }
+    let channel = |i: usize| u8::from_str_radix(&hex[i..i + 2], 16).map_err(|_| invalid());
+    Ok([channel(0)?, channel(2)?, channel(4)?])
+}
+
+fn badge_png(original: &[u8], text: &str, color: [u8; 3]) -> Result<Vec<u8>, image::ImageError> {
+    let image = image::load_from_memory_with_format(original, ImageFormat::Png)?;
+    // The App Store rejects icons with an alpha channel, so keep the original's
+    let has_alpha = image.color().has_alpha();
+    let mut canvas = image.to_rgba8();
+    draw_badge(&mut canvas, text, color);
+
+    let badged = if has_alpha {
+        DynamicImage::ImageRgba8(canvas)
+    } else {
+        DynamicImage::ImageRgb8(DynamicImage::ImageRgba8(canvas).to_rgb8())
+    };
+    let mut png = std::io::Cursor::new(Vec::new());
+    badged.write_to(&mut png, ImageFormat::Png)?;
+    Ok(png.into_inner())
+}
+
+fn draw_badge(canvas: &mut RgbaImage, text: &str, color: [u8; 3]) {
+    let (width, height) = canvas.dimensions();
+    let band = (height * 22 / 100).max(1);
+    let top = height - band;
+    for y in top..height {
+        for x in 0..width {
+            blend(canvas, x, y, color, 230);
+        }
+    }
+
+    // Glyphs are 5×7 with a column between them, scaled to ~60% of the band
+    let glyphs: Vec<&[u8; 7]> = text.chars().map(glyph).collect();
+    if glyphs.is_empty() {
+        return;
+    }
+    let columns = glyphs.len() as u32 * 6 - 1;
+    let scale = (band * 6 / 10 / 7).min(width * 9 / 10 / columns);
+    if scale == 0 {
+        return;
+    }
+    let left = (width - columns * scale) / 2;
+    let text_top = top + (band - 7 * scale) / 2;
+    for (i, rows) in glyphs.iter().enumerate() {
+        let glyph_left = left + i as u32 * 6 * scale;
+        for (row, bits) in rows.iter().enumerate() {
+            for column in 0..5 {
+                if bits & (0b10000 >> column) == 0 {
+                    continue;
+                }
+                for dy in 0..scale {
+                    for dx in 0..scale {
+                        let x = glyph_left + column * scale + dx;
+                        let y = text_top + row as u32 * scale + dy;
+                        blend(canvas, x, y, [255, 255, 255], 255);
+                    }
+                }
+            }
+        }
+    }
+}
+
+fn blend(canvas: &mut RgbaImage, x: u32, y: u32, color: [u8; 3], alpha: u16) {
+    let Rgba([r, g, b, a]) = *canvas.get_pixel(x, y);
+    let mix =
+        |under: u8, over: u8| ((under as u16 * (255 - alpha) + over as u16 * alpha) / 255) as u8;
+    canvas.put_pixel(
+        x,
+        y,
+        Rgba([
+            mix(r, color[0]),
+            mix(g, color[1]),
+            mix(b, color[2]),
+            a.max(alpha as u8),
+        ]),
+    );
+}
+
+/// 5×7 bitmap of an uppercase letter, digit or common punctuation; rows top to
+/// bottom, the low five bits left to right. Anything else draws as a space.
+fn glyph(c: char) -> &'static [u8; 7] {
+    match c.to_ascii_uppercase() {
+        'A' => &[0x0E, 0x11, 0x11, 0x1F, 0x11, 0x11, 0x11],
+        'B' => &[0x1E, 0x11, 0x11, 0x1E, 0x11, 0x11, 0x1E],
+        'C' => &[0x0E, 0x11, 0x10, 0x10, 0x10, 0x11, 0x0E],
+        'D' => &[0x1E, 0x11, 0x11, 0x11, 0x11, 0x11, 0x1E],
+        'E' => &[0x1F, 0x10, 0x10, 0x1E, 0x10, 0x10, 0x1F],
+        'F' => &[0x1F, 0x10, 0x10, 0x1E, 0x10, 0x10, 0x10],
+        'G' => &[0x0E, 0x11, 0x10, 0x17, 0x11, 0x11, 0x0F],
+        'H' => &[0x11, 0x11, 0x11, 0x1F, 0x11, 0x11, 0x11],
+        'I' => &[0x0E, 0x04, 0x04, 0x04, 0x04, 0x04, 0x0E],
+        'J' => &[0x07, 0x02, 0x02, 0x02, 0x02, 0x12, 0x0C],
+        'K' => &[0x11, 0x12, 0x14, 0x18, 0x14, 0x12, 0x11],
+        'L' => &[0x10, 0x10, 0x10, 0x10, 0x10, 0x10, 0x1F],
+        'M' => &[0x11, 0x1B, 0x15, 0x15, 0x11, 0x11, 0x11],
+        'N' => &[0x11, 0x11, 0x19, 0x15, 0x13, 0x11, 0x11],
+        'O' => &[0x0E, 0x11, 0x11, 0x11, 0x11, 0x11, 0x0E],
+        'P' => &[0x1E, 0x11, 0x11, 0x1E, 0x10, 0x10, 0x10],
+        'Q' => &[0x0E, 0x11, 0x11, 0x11, 0x15, 0x12, 0x0D],
+        'R' => &[0x1E, 0x11, 0x11, 0x1E, 0x14, 0x12, 0x11],
+        'S' => &[0x0F, 0x10, 0x10, 0x0E, 0x01, 0x01, 0x1E],
+        'T' => &[0x1F, 0x04, 0x04, 0x04, 0x04, 0x04, 0x04],
+        'U' => &[0x11, 0x11, 0x11, 0x11, 0x11, 0x11, 0x0E],
+        'V' => &[0x11, 0x11, 0x11, 0x11, 0x11, 0x0A, 0x04],
+        'W' => &[0x11, 0x11, 0x11, 0x15, 0x15, 0x15, 0x0A],
+        'X' => &[0x11, 0x11, 0x0A, 0x04, 0x0A, 0x11, 0x11],
+        'Y' => &[0x11, 0x11, 0x11, 0x0A, 0x04, 0x04, 0x04],
+        'Z' => &[0x1F, 0x01, 0x02, 0x04, 0x08, 0x10, 0x1F],
+        '0' => &[0x0E, 0x11, 0x13, 0x15, 0x19, 0x11, 0x0E],
+        '1' => &[0x04, 0x0C, 0x04, 0x04, 0x04, 0x04, 0x0E],
+        '2' => &[0x0E, 0x11, 0x01, 0x02, 0x04, 0x08, 0x1F],
+        '3' => &[0x1F, 0x02, 0x04, 0x02, 0x01, 0x11, 0x0E],
+        '4' => &[0x02, 0x06, 0x0A, 0x12, 0x1F, 0x02, 0x02],
+        '5' => &[0x1F, 0x10, 0x1E, 0x01, 0x01, 0x11, 0x0E],
+        '6' => &[0x06, 0x08, 0x10, 0x1E, 0x11, 0x11, 0x0E],
+        '7' => &[0x1F, 0x01, 0x02, 0x04, 0x08, 0x08, 0x08],
+        '8' => &[0x0E, 0x11, 0x11, 0x0E, 0x11, 0x11, 0x0E],
+        '9' => &[0x0E, 0x11, 0x11, 0x0F, 0x01, 0x02, 0x0C],
+        '.' => &[0x00, 0x00, 0x00, 0x00, 0x00, 0x0C, 0x0C],
+        ',' => &[0x00, 0x00, 0x00, 0x00, 0x0C, 0x04, 0x08],
+        ':' => &[0x00, 0x0C, 0x0C, 0x00, 0x0C, 0x0C, 0x00],
+        '-' => &[0x00, 0x00, 0x00, 0x1F, 0x00, 0x00, 0x00],
+        '+' => &[0x00, 0x04, 0x04, 0x1F, 0x04, 0x04, 0x00],
+        '/' => &[0x00, 0x01, 0x02, 0x04, 0x08, 0x10, 0x00],
+        '#' => &[0x0A, 0x0A, 0x1F, 0x0A, 0x1F, 0x0A, 0x0A],
+        '(' => &[0x02, 0x04, 0x08, 0x08, 0x08, 0x04, 0x02],
+        ')' => &[0x08, 0x04, 0x02, 0x02, 0x02, 0x04, 0x08],
+        _ => &[0; 7],
+    }
+}
@@ -0,0 +1,306 @@
+use crate::config::global::GlobalConfig;
+use crate::config::project::{CacheSettings, ProjectConfig};
+use crate::history;
+use crate::xcode::Xcode;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::path::{Path, PathBuf};
+use thiserror::Error;
+
+const MANIFEST_FILENAME: &str = "manifest.json";
+
+#[derive(Error, Debug)]
+pub enum CacheError {
+    #[error("Could not determine the launchpad config directory")]
+    NoConfigDir,
+
+    #[error("IO error: {0}")]
+    Io(#[from] std::io::Error),
+
+    #[error("Corrupt cache manifest: {0}")]
+    Manifest(#[from] serde_json::Error),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CacheKind {
+    DerivedData,
+    Spm,
+    Cocoapods,
+}
+
+impl CacheKind {
+    fn dir_name(self) -> &'static str {
+        match self {
+            CacheKind::DerivedData => "DerivedData",
+            CacheKind::Spm => "spm",
+            CacheKind::Cocoapods => "cocoapods",
+        }
+    }
+}
+
+impl std::fmt::Display for CacheKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CacheKind::DerivedData => write!(f, "DerivedData"),
+            CacheKind::Spm => write!(f, "SPM packages"),
+            CacheKind::Cocoapods => write!(f, "CocoaPods"),
+        }
+    }
+}
+
+/// Bookkeeping for one cache directory, kept in manifest.json
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CacheEntry {
+    pub kind: CacheKind,
+
+    /// Lockfile hash; DerivedData isn't keyed
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub key: Option<String>,
+
+    /// Unix timestamps (seconds)
+    pub created_at: u64,
+    pub last_used: u64,
+
+    /// Deploys (and `cache warm` runs) that used this directory
+    pub uses: u64,
+}
+
+/// Directories handed to fastlane for one build
+pub struct CachePaths {
+    pub derived_data: PathBuf,
+
+    /// `-clonedSourcePackagesDirPath`; None without a Package.resolved
+    pub spm: Option<PathBuf>,
+
+    /// CP_HOME_DIR; None without a Podfile.lock
+    pub cocoapods: Option<PathBuf>,
+
+    /// Whether each directory already existed before this build
+    pub hits: Vec<(CacheKind, bool)>,
+}
+
+/// Per-project cache directory: DerivedData/, spm/<key>/, cocoapods/<key>/
+pub struct BuildCache {
+    root: PathBuf,
+    keep: usize,
+}
+
+impl BuildCache {
+    /// The project's cache when `[cache]` is present and enabled
+    pub fn for_project(config: &ProjectConfig) -> Result<Option<Self>, CacheError> {
+        match &config.cache {
+            Some(settings) if settings.enabled => Self::open(config, Some(settings)).map(Some),
+            _ => Ok(None),
+        }
+    }
+
+    /// The project's cache location, whether or not caching is enabled
+    pub fn open(
+        config: &ProjectConfig,
+        settings: Option<&CacheSettings>,
+    ) -> Result<Self, CacheError> {
+        let root = match std::env::var("LAUNCHPAD_CACHE_DIR") {
+            Ok(dir) if !dir.is_empty() => PathBuf::from(shellexpand::tilde(&dir).as_ref()),
+            _ => match settings.and_then(|s| s.dir.as_deref()) {
+                Some(dir) => PathBuf::from(shellexpand::tilde(dir).as_ref()),
+                None => default_root()
+                    .ok_or(CacheError::NoConfigDir)?
+                    .join(sanitize(&config.project.bundle_id)),
+            },
+        };
+
+        Ok(Self {
+            root,
+            keep: settings.map(|s| s.keep).unwrap_or(2),
+        })
+    }
+
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    /// Create this build's directories and note which ones were already warm
+    pub fn prepare(&self, ios_path: &str) -> Result<CachePaths, CacheError> {
+        let mut entries = self.entries()?;
+        let mut hits = Vec::new();
+        let mut use_dir = |kind: CacheKind, key: Option<String>| -> std::io::Result<PathBuf> {
+            let dir = match &key {
+                Some(key) => self.root.join(kind.dir_name()).join(key),
+                None => self.root.join(kind.dir_name()),
+            };
+            hits.push((kind, dir.exists()));
+            std::fs::create_dir_all(&dir)?;
+            touch(&mut entries, kind, key);
+            Ok(dir)
+        };
+
+        let derived_data = use_dir(CacheKind::DerivedData, None)?;
+        let spm = match lockfile_key(CacheKind::Spm, ios_path) {
+            Some(key) => Some(use_dir(CacheKind::Spm, Some(key))?),
+            None => None,
+        };
+        let cocoapods = match lockfile_key(CacheKind::Cocoapods, ios_path) {
+            Some(key) => Some(use_dir(CacheKind::Cocoapods, Some(key))?),
+            None => None,
+        };
+
+        self.save_entries(&entries)?;
+        Ok(CachePaths {
+            derived_data,
+            spm,
+            cocoapods,
+            hits,
+        })
+    }
+
+    /// Remove package caches beyond the `keep` most recently used per kind, never the
+    /// ones this build used; returns bytes freed
+    pub fn prune(&self, in_use: &CachePaths) -> Result<u64, CacheError> {
+        let used = |entry: &CacheEntry| {
+            let dir = self.dir(entry);
+            in_use.spm.as_ref() == Some(&dir) || in_use.cocoapods.as_ref() == Some(&dir)
+        };
+        let mut entries = self.entries()?;
+        entries.sort_by(|a, b| {
+            a.kind
+                .cmp(&b.kind)
+                .then(used(b).cmp(&used(a)))
+                .then(b.last_used.cmp(&a.last_used))
+        });
+
+        let mut freed = 0;
+        let mut kept = Vec::new();
+        let mut seen: Vec<CacheKind> = Vec::new();
+        for entry in entries {
+            let rank = seen.iter().filter(|&&kind| kind == entry.kind).count();
+            seen.push(entry.kind);
+
+            if entry.key.is_none() || rank < self.keep {
+                kept.push(entry);
+                continue;
+            }
+            let dir = self.dir(&entry);
+            freed += dir_size(&dir);
+            if dir.exists() {
+                std::fs::remove_dir_all(&dir)?;
+            }
+        }
+
+        self.save_entries(&kept)?;
+        Ok(freed)
+    }
+
+    /// Recorded directories that still exist on disk
+    pub fn entries(&self) -> Result<Vec<CacheEntry>, CacheError> {
+        let path = self.root.join(MANIFEST_FILENAME);
+        if !path.exists() {
+            return Ok(Vec::new());
+        }
+        let entries: Vec<CacheEntry> = serde_json::from_str(&std::fs::read_to_string(path)?)?;
+        Ok(entries
+            .into_iter()
+            .filter(|entry| self.dir(entry).exists())
+            .collect())
+    }
+
+    pub fn dir(&self, entry: &CacheEntry) -> PathBuf {
+        match &entry.key {
+            Some(key) => self.root.join(entry.kind.dir_name()).join(key),
+            None => self.root.join(entry.kind.dir_name()),
+        }
+    }
+
+    fn save_entries(&self, entries: &[CacheEntry]) -> Result<(), CacheError> {
+        std::fs::create_dir_all(&self.root)?;
+        std::fs::write(
+            self.root.join(MANIFEST_FILENAME),
+            serde_json::to_string_pretty(entries)?,
+        )?;
+        Ok(())
+    }
+}
+
+/// ~/.launchpad/cache, holding one directory per bundle ID
+pub fn default_root() -> Option<PathBuf> {
+    GlobalConfig::config_dir().map(|d| d.join("cache"))
+}
+
+/// Hash of the lockfile a package cache is keyed by, if the project has one
+pub fn lockfile_key(kind: CacheKind, ios_path: &str) -> Option<String> {
+    let lockfile = match kind {
+        CacheKind::DerivedData => return None,
+        CacheKind::Spm => spm_lockfile(ios_path)?,
+        CacheKind::Cocoapods => Path::new(ios_path).join("Podfile.lock"),
+    };
+    let content = std::fs::read(lockfile).ok()?;
+    let digest = format!("{:x}", Sha256::digest(&content));
+    Some(digest[..16].to_string())
+}
+
+/// Package.resolved inside the workspace (or the project's embedded workspace)
+fn spm_lockfile(ios_path: &str) -> Option<PathBuf> {
+    let mut candidates = Vec::new();
+    if let Some(container) = Xcode::container(ios_path) {
+        let container = Path::new(ios_path).join(container);
+        let workspace = if container
+            .extension()
+            .is_some_and(|ext| ext == "xcworkspace")
+        {
+            container
+        } else {
+            container.join("project.xcworkspace")
+        };
+        candidates.push(workspace.join("xcshareddata/swiftpm/Package.resolved"));
+    }
+    candidates.push(Path::new(ios_path).join("Package.resolved"));
+
+    candidates.into_iter().find(|path| path.exists())
+}
+
+fn touch(entries: &mut Vec<CacheEntry>, kind: CacheKind, key: Option<String>) {
+    let now = history::now();
+    match entries
+        .iter_mut()
+        .find(|entry| entry.kind == kind && entry.key == key)
+    {
+        Some(entry) => {
+            entry.last_used = now;
+            entry.uses += 1;
+        }
+        None => entries.push(CacheEntry {
+            kind,
+            key,
+            created_at: now,
+            last_used: now,
+            uses: 1,
+        }),
+    }
+}
+
+/// Bundle IDs are safe as directory names once anything unusual is replaced
+fn sanitize(name: &str) -> String {
+    name.chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || c == '.' || c == '-' {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect()
+}
+
+/// Total size of the files below `path`, without following symlinks
+pub fn dir_size(path: &Path) -> u64 {
+    let Ok(metadata) = std::fs::symlink_metadata(path) else {
+        return 0;
+    };
+    if !metadata.is_dir() {
+        return metadata.len();
+    }
+
+    std::fs::read_dir(path)
+        .map(|entries| entries.flatten().map(|entry| dir_size(&entry.path())).sum())
+        .unwrap_or(0)
+}
@@ -0,0 +1,278 @@
+use crate::asc::{AscClient, AscError, ReportRow};
+use crate::config;
+use crate::config::global::GlobalConfig;
+use crate::config::project::ProjectConfig;
+use crate::history;
+use crate::ui;
+use serde::Serialize;
+use std::collections::BTreeMap;
+use thiserror::Error;
+
+const DAY_SECS: u64 = 24 * 60 * 60;
+
+/// Longest period a snapshot covers; the analytics reports don't go back further
+const MAX_DAYS: u64 = 180;
+
+/// Sales report product types that count as a first-time download (iOS, iPad,
+/// universal, Mac and their app bundle and pre-order variants)
+const INSTALL_TYPES: &[&str] = &["1", "1F", "1T", "1E", "1EP", "1EU", "F1"];
+
+#[derive(Error, Debug)]
+pub enum AnalyticsError {
+    #[error("Global config not found. Run 'launchpad setup' first.")]
+    NoGlobalConfig,
+
+    #[error("Project config not found. Run 'launchpad init' first, or pass --bundle-id.")]
+    NoProjectConfig,
+
+    #[error("Invalid period '{0}' (expected days or weeks, like 7d or 4w, up to {MAX_DAYS} days)")]
+    InvalidPeriod(String),
+
+    #[error("{0}")]
+    Asc(#[from] AscError),
+
+    #[error("JSON error: {0}")]
+    Json(#[from] serde_json::Error),
+
+    #[error("Config error: {0}")]
+    Config(String),
+}
+
+/// Totals for one app version over the period
+#[derive(Debug, Default, Serialize)]
+struct VersionStats {
+    version: String,
+    installs: u64,
+    sessions: u64,
+    crashes: u64,
+}
+
+#[derive(Debug, Serialize)]
+struct Snapshot {
+    bundle_id: String,
+    from: String,
+    to: String,
+    /// Newest version first
+    versions: Vec<VersionStats>,
+}
+
+/// Installs, sessions and crashes per version over the last `last` ("7d"), from
+/// Sales and Trends and the App Store Connect analytics reports
+pub async fn run(
+    last: String,
+    json: bool,
+    bundle_id: Option<String>,
+) -> Result<(), AnalyticsError> {
+    let days = parse_period(&last).ok_or_else(|| AnalyticsError::InvalidPeriod(last.clone()))?;
+    // stdout may be piped into a report, so keep it free of anything but JSON
+    if !json {
+        ui::header("App Analytics");
+    }
+
+    let mut global_config = GlobalConfig::load()
+        .map_err(|e| AnalyticsError::Config(e.to_string()))?
+        .ok_or(AnalyticsError::NoGlobalConfig)?;
+    let mut project_config =
+        ProjectConfig::load().map_err(|e| AnalyticsError::Config(e.to_string()))?;
+    config::use_selected_environment(&mut global_config, project_config.as_mut())
+        .map_err(|e| AnalyticsError::Config(e.to_string()))?;
+    let bundle_id = match (bundle_id, &project_config) {
+        (Some(bundle_id), _) => bundle_id,
+        (None, Some(config)) => config.project.bundle_id.clone(),
+        (None, None) => return Err(AnalyticsError::NoProjectConfig),
+    };
+    let client = AscClient::new(&global_config.apple)?;
+    let app_id = client
+        .project_app_id(&bundle_id, project_config.as_ref().map(|c| &c.project))
+        .await?;
+
+    // Yesterday back: today's numbers aren't reported yet
+    let today = history::now() / DAY_SECS * DAY_SECS;
+    let dates: Vec<String> = (1..=days).map(|n| date(today - n * DAY_SECS)).collect();
+    let mut versions: BTreeMap<String, VersionStats> = BTreeMap::new();
+
+    let spinner = ui::spinner("Fetching analytics from App Store Connect...");
+    let sales = match &global_config.apple.vendor_number {
+        Some(vendor_number) => Some(installs(&client, vendor_number, &app_id, &dates).await),
+        None => None,
+    };
+    let (request_id, created) = client.analytics_report_request(&app_id).await?;
+    // A day's numbers are processed the day after
+    let processing_dates: Vec<String> = (0..days).map(|n| date(today - n * DAY_SECS)).collect();
+    let sessions = client
+        .analytics_rows(&request_id, "App Sessions", &processing_dates)
+        .await;
+    let crashes = client
+        .analytics_rows(&request_id, "App Crashes", &processing_dates)
+        .await;
+    spinner.finish_and_clear();
+
+    match sales {
+        Some(rows) => {
+            for (version, units) in rows? {
+                stats(&mut versions, &version).installs += units;
+            }
+        }
+        None if !json => ui::warn("No vendor number, so no installs. Set apple.vendor_number in ~/.launchpad/config.toml (or APPLE_VENDOR_NUMBER); it's under Payments and Financial Reports in App Store Connect."),
+        None => {}
+    }
+    for row in sessions?.iter().filter(|row| in_period(row, &dates)) {
+        stats(&mut versions, &column(row, "App Version")).sessions += count(row, "Sessions");
+    }
+    for row in crashes?.iter().filter(|row| in_period(row, &dates)) {
+        stats(&mut versions, &column(row, "App Version")).crashes += count(row, "Crashes");
+    }
+    if created && !json {
+        ui::warn("Requested analytics reports for this app just now; Apple takes a day or two to produce the first sessions and crashes");
+    }
+
+    let mut versions: Vec<VersionStats> = versions.into_values().collect();
+    versions.sort_by_key(|v| std::cmp::Reverse(version_key(&v.version)));
+    let snapshot = Snapshot {
+        bundle_id,
+        from: dates.last().cloned().unwrap_or_default(),
+        to: dates.first().cloned().unwrap_or_default(),
+        versions,
+    };
+    if json {
+        println!("{}", serde_json::to_string_pretty(&snapshot)?);
+        return Ok(());
+    }
+    print_snapshot(&snapshot);
+    Ok(())
+}
+
+fn print_snapshot(snapshot: &Snapshot) {
+    ui::field("App", &snapshot.bundle_id);
+    ui::field("Period", &format!("{} to {}", snapshot.from, snapshot.to));
+    ui::blank();
+    if snapshot.versions.is_empty() {
+        ui::step("No installs, sessions or crashes reported for the period");
+        return;
+    }
+
+    let mut rows: Vec<Vec<String>> = snapshot.versions.iter().map(row).collect();
+    let total = VersionStats {
+        version: "Total".to_string(),
+        installs: snapshot.versions.iter().map(|v| v.installs).sum(),
+        sessions: snapshot.versions.iter().map(|v| v.sessions).sum(),
+        crashes: snapshot.versions.iter().map(|v| v.crashes).sum(),
+    };
+    rows.push(row(&total));
+    ui::table(
+        &[
+            "Version",
+            "Installs",
+            "Sessions",
+            "Crashes",
+            "Crashes/1k sessions",
+        ],
+        &rows,
+    );
+}
+
+fn row(stats: &VersionStats) -> Vec<String> {
+    let rate = if stats.sessions == 0 {
+        "-".to_string()
+    } else {
+        format!(
+            "{:.1}",
+            stats.crashes as f64 * 1000.0 / stats.sessions as f64
+        )
+    };
+    vec![
+        stats.version.clone(),
+        stats.installs.to_string(),
+        stats.sessions.to_string(),
+        stats.crashes.to_string(),
+        rate,
+    ]
+}
+
+/// First-time downloads per version, summed over `dates`; days Apple hasn't
+/// published yet are skipped
+async fn installs(
+    client: &AscClient,
+    vendor_number: &str,
+    app_id: &str,
+    dates: &[String],
+) -> Result<Vec<(String, u64)>, AscError> {
+    let mut installs = Vec::new();
+    for date in dates {
+        let Some(rows) = client.sales_report(vendor_number, date).await? else {
+            continue;
+        };
+        installs.extend(
+            rows.iter()
+                .filter(|row| column(row, "Apple Identifier") == app_id)
+                .filter(|row| {
+                    INSTALL_TYPES.contains(&column(row, "Product Type Identifier").as_str())
+                })
+                .map(|row| (column(row, "Version"), count(row, "Units"))),
+        );
+    }
+    Ok(installs)
+}
+
+fn stats<'a>(
+    versions: &'a mut BTreeMap<String, VersionStats>,
+    version: &str,
+) -> &'a mut VersionStats {
+    let version = if version.is_empty() {
+        "unknown"
+    } else {
+        version
+    };
+    versions
+        .entry(version.to_string())
+        .or_insert_with(|| VersionStats {
+            version: version.to_string(),
+            ..Default::default()
+        })
+}
+
+fn column(row: &ReportRow, name: &str) -> String {
+    row.get(name).cloned().unwrap_or_default()
+}
+
+/// A count column; reports write large numbers with separators, and negative
+/// units are refunds
+fn count(row: &ReportRow, name: &str) -> u64 {
+    column(row, name)
+        .replace(',', "")
+        .parse::<i64>()
+        .map(|n| n.max(0) as u64)
+        .unwrap_or_default()
+}
+
+/// Analytics rows carry the day they describe; instances can overlap the period
+fn in_period(row: &ReportRow, dates: &[String]) -> bool {
+    dates.contains(&column(row, "Date"))
+}
+
+/// "7d" or "4w" as days
+fn parse_period(value: &str) -> Option<u64> {
+    let value = value.trim();
+    let (number, unit) = value.split_at(value.len().checked_sub(1)?);
+    let number: u64 = number.parse().ok()?;
+    let days = match unit {
+        "d" => number,
+        "w" => number * 7,
+        _ => return None,
+    };
+    (1..=MAX_DAYS).contains(&days).then_some(days)
+}
+
+/// Unix seconds as "2024-05-01" (UTC, like the reports)
+fn date(secs: u64) -> String {
+    let timestamp = std::time::UNIX_EPOCH + std::time::Duration::from_secs(secs);
+    plist::Date::from(timestamp).to_xml_format()[..10].to_string()
+}
+
+/// "1.10.0" after "1.9.2"
+fn version_key(version: &str) -> Vec<u64> {
+    version
+        .split('.')
+        .map(|part| part.parse().unwrap_or_default())
+        .collect()
+}
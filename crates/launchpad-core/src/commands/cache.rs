@@ -0,0 +1,235 @@
+use crate::cache::{self, BuildCache, CacheError};
+use crate::config::project::ProjectConfig;
+use crate::history;
+use crate::templating;
+use crate::ui;
+use crate::xcode::{Xcode, XcodeError};
+use clap::Subcommand;
+use dialoguer::Confirm;
+use std::path::Path;
+use std::process::Command;
+use std::time::Instant;
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+pub enum CacheCommandError {
+    #[error("Project config not found. Run 'launchpad init' first, or use --all.")]
+    NoProjectConfig,
+
+    #[error("The build cache is disabled; add a [cache] section to .launchpad.toml")]
+    Disabled,
+
+    #[error("pod install failed: {0}")]
+    PodInstall(String),
+
+    #[error("{0}")]
+    Cache(#[from] CacheError),
+
+    #[error("{0}")]
+    Xcode(#[from] XcodeError),
+
+    #[error("IO error: {0}")]
+    Io(#[from] std::io::Error),
+
+    #[error("Config error: {0}")]
+    Config(String),
+}
+
+#[derive(Subcommand)]
+pub enum CacheCommand {
+    /// Show what the build cache holds for this project and how often it was reused
+    Stats,
+
+    /// Fill the build cache without deploying: install pods, resolve Swift
+    /// packages and build the scheme unsigned
+    Warm {
+        /// Only install pods and resolve packages; skip the build
+        #[arg(long)]
+        no_build: bool,
+    },
+
+    /// Delete this project's build cache
+    Clean {
+        /// Delete the cache of every project under ~/.launchpad/cache
+        #[arg(long)]
+        all: bool,
+    },
+}
+
+pub async fn run(command: CacheCommand, non_interactive: bool) -> Result<(), CacheCommandError> {
+    match command {
+        CacheCommand::Stats => stats(),
+        CacheCommand::Warm { no_build } => warm(no_build),
+        CacheCommand::Clean { all } => clean(all, non_interactive),
+    }
+}
+
+fn stats() -> Result<(), CacheCommandError> {
+    ui::header("Build Cache");
+
+    let config = load_config()?;
+    let build_cache = BuildCache::open(&config, config.cache.as_ref())?;
+    ui::field("Location", &build_cache.root().display().to_string());
+    ui::field(
+        "Status",
+        match &config.cache {
+            Some(settings) if settings.enabled => "enabled",
+            Some(_) => "disabled (cache.enabled = false)",
+            None => "disabled (add a [cache] section to .launchpad.toml)",
+        },
+    );
+
+    let mut entries = build_cache.entries()?;
+    if entries.is_empty() {
+        ui::blank();
+        ui::warn("Nothing cached yet; the next deploy fills the cache");
+        return Ok(());
+    }
+    entries.sort_by(|a, b| a.kind.cmp(&b.kind).then(b.last_used.cmp(&a.last_used)));
+
+    ui::blank();
+    let ios_path = &config.project.ios_path;
+    let mut total = 0;
+    for entry in &entries {
+        let size = cache::dir_size(&build_cache.dir(entry));
+        total += size;
+
+        let detail = format!(
+            "{}, used by {} deploy{}, last {}",
+            ui::format_size(size),
+            entry.uses,
+            if entry.uses == 1 { "" } else { "s" },
+            history::age(entry.last_used)
+        );
+        match &entry.key {
+            None => ui::check_pass(&entry.kind.to_string(), &detail),
+            Some(key) if cache::lockfile_key(entry.kind, ios_path).as_ref() == Some(key) => {
+                ui::check_pass(&format!("{} ({})", entry.kind, key), &detail)
+            }
+            Some(key) => ui::step(&format!(
+                "{} ({}) — {}, older lockfile",
+                entry.kind, key, detail
+            )),
+        }
+    }
+    ui::blank();
+    ui::field("Total", &ui::format_size(total));
+
+    Ok(())
+}
+
+/// Do what a deploy's build does up to archiving, in the cache's directories,
+/// so the next deploy starts warm; meant for nightly CI jobs
+fn warm(no_build: bool) -> Result<(), CacheCommandError> {
+    let started = Instant::now();
+    ui::header("Build Cache");
+
+    let config = load_config()?;
+    let build_cache = BuildCache::for_project(&config)?.ok_or(CacheCommandError::Disabled)?;
+    let ios_path = &config.project.ios_path;
+    let scheme = &config.project.scheme;
+    let paths = build_cache.prepare(ios_path)?;
+    ui::field("Location", &build_cache.root().display().to_string());
+
+    if Path::new(ios_path).join("Podfile").exists() {
+        let spinner = ui::spinner("Installing pods...");
+        let mut cmd = Command::new("pod");
+        cmd.arg("install").current_dir(ios_path);
+        if let Some(home) = &paths.cocoapods {
+            cmd.env("CP_HOME_DIR", home);
+        }
+        let output = cmd.output();
+        spinner.finish_and_clear();
+        match output {
+            Ok(output) if output.status.success() => ui::success("Pods installed"),
+            Ok(output) => {
+                return Err(CacheCommandError::PodInstall(
+                    String::from_utf8_lossy(&output.stderr).trim().to_string(),
+                ))
+            }
+            Err(e) => return Err(CacheCommandError::PodInstall(e.to_string())),
+        }
+    }
+
+    let spinner = ui::spinner("Resolving Swift packages...");
+    let resolved =
+        Xcode::resolve_packages(ios_path, scheme, &paths.derived_data, paths.spm.as_deref());
+    spinner.finish_and_clear();
+    resolved?;
+    ui::success("Swift packages resolved");
+
+    if !no_build {
+        let spinner = ui::spinner(&format!(
+            "Building {} ({}, unsigned)...",
+            scheme, config.build.configuration
+        ));
+        let built = Xcode::build_unsigned(
+            ios_path,
+            scheme,
+            &config.build,
+            &paths.derived_data,
+            paths.spm.as_deref(),
+        );
+        spinner.finish_and_clear();
+        built?;
+        ui::success("DerivedData primed");
+    }
+
+    let freed = build_cache.prune(&paths)?;
+    if freed > 0 {
+        ui::step(&format!(
+            "Pruned old package caches ({})",
+            ui::format_size(freed)
+        ));
+    }
+    ui::success(&format!(
+        "Cache warm ({}) in {}",
+        ui::format_size(cache::dir_size(build_cache.root())),
+        templating::format_duration(started.elapsed().as_secs())
+    ));
+    Ok(())
+}
+
+fn clean(all: bool, non_interactive: bool) -> Result<(), CacheCommandError> {
+    ui::header("Build Cache");
+
+    let target = if all {
+        cache::default_root().ok_or(CacheError::NoConfigDir)?
+    } else {
+        let config = load_config()?;
+        BuildCache::open(&config, config.cache.as_ref())?
+            .root()
+            .to_path_buf()
+    };
+
+    if !target.exists() {
+        ui::success("Cache is already empty");
+        return Ok(());
+    }
+
+    let size = cache::dir_size(&target);
+    let confirmed = non_interactive
+        || Confirm::new()
+            .with_prompt(format!(
+                "Delete {} ({})?",
+                target.display(),
+                ui::format_size(size)
+            ))
+            .default(true)
+            .interact()
+            .map_err(|e| CacheCommandError::Io(std::io::Error::other(e)))?;
+    if !confirmed {
+        ui::warn("Cache left in place");
+        return Ok(());
+    }
+
+    std::fs::remove_dir_all(&target)?;
+    ui::success(&format!("Freed {}", ui::format_size(size)));
+    Ok(())
+}
+
+fn load_config() -> Result<ProjectConfig, CacheCommandError> {
+    ProjectConfig::load()
+        .map_err(|e| CacheCommandError::Config(e.to_string()))?
+        .ok_or(CacheCommandError::NoProjectConfig)
+}
@@ -0,0 +1,222 @@
+use crate::asc::{AscClient, AscError};
+use crate::config;
+use crate::config::global::GlobalConfig;
+use crate::config::project::{ComplianceSettings, ProjectConfig};
+use crate::history;
+use crate::testflight;
+use crate::ui;
+use clap::Subcommand;
+use std::path::PathBuf;
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+pub enum ComplianceError {
+    #[error("Global config not found. Run 'launchpad setup' first.")]
+    NoGlobalConfig,
+
+    #[error("Project config not found. Run 'launchpad init' first.")]
+    NoProjectConfig,
+
+    #[error("No [compliance] in .launchpad.toml. Add uses_non_exempt_encryption first.")]
+    NoCompliance,
+
+    #[error("No declaration to upload to. Set compliance.declaration_id or pass --declaration.")]
+    NoDeclaration,
+
+    #[error("No document to upload. Set compliance.document or pass a file.")]
+    NoDocument,
+
+    #[error("Document not found: {0}")]
+    DocumentNotFound(PathBuf),
+
+    #[error("No deploy of {0} in the history. Pass --build.")]
+    NoBuild(String),
+
+    #[error("App Store Connect has no build {0}")]
+    BuildNotFound(String),
+
+    #[error("{0}")]
+    Asc(#[from] AscError),
+
+    #[error("Config error: {0}")]
+    Config(String),
+}
+
+#[derive(Subcommand)]
+pub enum ComplianceCommand {
+    /// The app's App Encryption Declarations and the [compliance] answers
+    Status,
+
+    /// Answer export compliance for an uploaded build (default: the last deploy)
+    Apply {
+        /// Build number
+        #[arg(long)]
+        build: Option<String>,
+
+        /// Marketing version, when build numbers repeat across versions
+        #[arg(long)]
+        version: Option<String>,
+    },
+
+    /// Upload compliance documentation to the App Encryption Declaration
+    Upload {
+        /// Document to upload (default: compliance.document)
+        file: Option<PathBuf>,
+
+        /// Declaration ID (default: compliance.declaration_id)
+        #[arg(long)]
+        declaration: Option<String>,
+    },
+}
+
+pub async fn run(command: ComplianceCommand) -> Result<(), ComplianceError> {
+    let mut project_config = ProjectConfig::load()
+        .map_err(|e| ComplianceError::Config(e.to_string()))?
+        .ok_or(ComplianceError::NoProjectConfig)?;
+    let mut global_config = GlobalConfig::load()
+        .map_err(|e| ComplianceError::Config(e.to_string()))?
+        .ok_or(ComplianceError::NoGlobalConfig)?;
+    config::use_selected_environment(&mut global_config, Some(&mut project_config))
+        .map_err(|e| ComplianceError::Config(e.to_string()))?;
+    let client = AscClient::new(&global_config.apple)?;
+
+    match command {
+        ComplianceCommand::Status => status(&client, &project_config).await,
+        ComplianceCommand::Apply { build, version } => {
+            apply(&client, &project_config, build, version).await
+        }
+        ComplianceCommand::Upload { file, declaration } => {
+            upload(&client, &project_config, file, declaration).await
+        }
+    }
+}
+
+async fn status(client: &AscClient, project_config: &ProjectConfig) -> Result<(), ComplianceError> {
+    ui::header("Export Compliance");
+
+    match &project_config.compliance {
+        Some(compliance) => {
+            ui::field(
+                "Encryption",
+                if compliance.uses_non_exempt_encryption {
+                    "non-exempt"
+                } else {
+                    "exempt or none"
+                },
+            );
+            if let Some(id) = &compliance.declaration_id {
+                ui::field("Declaration", id);
+            }
+        }
+        None => ui::warn("No [compliance] in .launchpad.toml; every build waits at Missing Compliance unless its Info.plist sets ITSAppUsesNonExemptEncryption"),
+    }
+
+    let project = &project_config.project;
+    let app_id = client
+        .project_app_id(&project.bundle_id, Some(project))
+        .await?;
+    let declarations = client.encryption_declarations(&app_id).await?;
+    ui::blank();
+    if declarations.is_empty() {
+        ui::step("No App Encryption Declarations on App Store Connect");
+    }
+    for declaration in declarations {
+        let state = declaration.state.as_deref().unwrap_or("UNKNOWN");
+        let detail = [declaration.description, declaration.created_date]
+            .into_iter()
+            .flatten()
+            .collect::<Vec<_>>()
+            .join(", ");
+        let message = if detail.is_empty() {
+            state.to_string()
+        } else {
+            format!("{} ({})", state, detail)
+        };
+        match state {
+            "APPROVED" => ui::check_pass(&declaration.id, &message),
+            "CREATED" | "IN_REVIEW" => ui::check_warn(&declaration.id, &message),
+            _ => ui::check_fail(&declaration.id, &message),
+        }
+    }
+
+    Ok(())
+}
+
+async fn apply(
+    client: &AscClient,
+    project_config: &ProjectConfig,
+    build: Option<String>,
+    version: Option<String>,
+) -> Result<(), ComplianceError> {
+    ui::header("Export Compliance");
+
+    let compliance = compliance(project_config)?;
+    let project = &project_config.project;
+    let (number, version) = match build {
+        Some(number) => (number, version),
+        None => {
+            let records = history::load().map_err(|e| ComplianceError::Config(e.to_string()))?;
+            let record = records
+                .iter()
+                .rev()
+                .find(|r| r.bundle_id == project.bundle_id && r.platform == "ios")
+                .ok_or_else(|| ComplianceError::NoBuild(project.bundle_id.clone()))?;
+            (record.build.clone(), Some(record.version.clone()))
+        }
+    };
+
+    let app_id = client
+        .project_app_id(&project.bundle_id, Some(project))
+        .await?;
+    let build = client
+        .find_build(&app_id, &number, version.as_deref())
+        .await?
+        .ok_or_else(|| ComplianceError::BuildNotFound(number.clone()))?;
+    testflight::answer_compliance(client, &build, compliance).await?;
+    ui::success(&format!("Answered export compliance for build {}", number));
+
+    Ok(())
+}
+
+async fn upload(
+    client: &AscClient,
+    project_config: &ProjectConfig,
+    file: Option<PathBuf>,
+    declaration: Option<String>,
+) -> Result<(), ComplianceError> {
+    ui::header("Export Compliance");
+
+    let compliance = project_config.compliance.as_ref();
+    let declaration = declaration
+        .or_else(|| compliance.and_then(|c| c.declaration_id.clone()))
+        .ok_or(ComplianceError::NoDeclaration)?;
+    let file = file
+        .or_else(|| {
+            compliance
+                .and_then(|c| c.document.as_deref())
+                .map(|path| PathBuf::from(shellexpand::tilde(path).as_ref()))
+        })
+        .ok_or(ComplianceError::NoDocument)?;
+    if !file.is_file() {
+        return Err(ComplianceError::DocumentNotFound(file));
+    }
+
+    let spinner = ui::spinner(&format!("Uploading {}...", file.display()));
+    let result = client.upload_encryption_document(&declaration, &file).await;
+    spinner.finish_and_clear();
+    result?;
+    ui::success(&format!(
+        "Uploaded {} to declaration {}",
+        file.display(),
+        declaration
+    ));
+
+    Ok(())
+}
+
+fn compliance(project_config: &ProjectConfig) -> Result<&ComplianceSettings, ComplianceError> {
+    project_config
+        .compliance
+        .as_ref()
+        .ok_or(ComplianceError::NoCompliance)
+}
@@ -0,0 +1,2414 @@
+use crate::artifact::{self, BuildStamp};
+use crate::asc::{AscClient, AscError};
+use crate::badge;
+use crate::cache::{BuildCache, CachePaths};
+use crate::commands::{init, size, template};
+use crate::config;
+use crate::config::global::{self, GlobalConfig, GooglePlayConfig};
+use crate::config::project::{
+    DeploySettings, DistributionMode, EnvironmentSettings, MetadataSettings, NotificationEvent,
+    ProjectConfig, SigningStyle, SizeSettings,
+};
+use crate::devices::Registry;
+use crate::email;
+use crate::events;
+use crate::fastlane::{self, Fastlane, FastlaneError, LaneResult};
+use crate::git::{self, GitError};
+use crate::gradle::{Gradle, GradleError};
+use crate::history::{self, DeployRecord, PendingAction, PendingOperation};
+use crate::hooks::{self, HookError, HookEvent};
+use crate::integrations;
+use crate::ipa;
+use crate::mock;
+use crate::notifications;
+use crate::play::{PlayClient, PlayError};
+use crate::presets::Preset;
+use crate::profiles;
+use crate::summary::DeploySummary;
+use crate::templating;
+use crate::testflight::{self, DistributionReport};
+use crate::thinning::{self, ThinnedSize};
+use crate::ui;
+use crate::version::{self, VersionBump};
+use crate::xcode::{self, SchemeSharing, TargetVersion, Xcode};
+use dialoguer::Confirm;
+use serde_json::json;
+use std::collections::BTreeMap;
+use std::io::IsTerminal;
+use std::path::{Path, PathBuf};
+use std::time::{Instant, SystemTime};
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+pub enum DeployError {
+    #[error("Global config not found. Run 'launchpad setup' first.")]
+    NoGlobalConfig,
+
+    #[error(
+        "Project config not found. Run 'launchpad init' first, or pass --scheme and --bundle-id."
+    )]
+    NoProjectConfig,
+
+    #[error("Without .launchpad.toml, deploy needs at least --scheme and --bundle-id (or LAUNCHPAD_SCHEME and LAUNCHPAD_BUNDLE_ID)")]
+    IncompleteProjectArgs,
+
+    #[error("Invalid project settings: {0}")]
+    InvalidProjectArgs(String),
+
+    #[error("Apple API key not found at: {0}")]
+    ApiKeyNotFound(String),
+
+    #[error(
+        "Mock mode only covers iOS deploys; an Android deploy would build and release for real"
+    )]
+    MockAndroid,
+
+    #[error("Git working directory is not clean. Commit or stash changes first.")]
+    DirtyWorkingDirectory,
+
+    #[error("Submodules aren't ready: {0}. Run 'git submodule update --init --recursive'.")]
+    SubmodulesNotReady(String),
+
+    #[error("{count} Git LFS file{} still hold{} a pointer instead of the content ({files}). Run 'git lfs pull'.", if *.count == 1 { "" } else { "s" }, if *.count == 1 { "s" } else { "" })]
+    LfsNotPulled { count: usize, files: String },
+
+    #[error("Manual signing needs a provisioning profile for each bundle ID. Add [signing.profiles] to .launchpad.toml.")]
+    MissingProvisioningProfiles,
+
+    #[error("Target versions don't match: {0}. Align them in Xcode before uploading.")]
+    VersionMismatch(String),
+
+    #[error("{0}")]
+    Hook(#[from] HookError),
+
+    #[error("Deploy needs confirmation but stdin isn't a terminal. Pass --yes to skip it.")]
+    ConfirmationRequired,
+
+    #[error("Deploy cancelled")]
+    Cancelled,
+
+    #[error("No [environments.{name}] in .launchpad.toml (defined: {known})")]
+    UnknownEnvironment { name: String, known: String },
+
+    #[error("No [brands.{name}] in .launchpad.toml (defined: {known})")]
+    UnknownBrand { name: String, known: String },
+
+    #[error("Can't reuse the last build: {0}")]
+    NoReusableArtifact(String),
+
+    #[error("The Fastfile always uploads. Run 'launchpad template update' to use --skip-upload.")]
+    SkipUploadUnsupported,
+
+    #[error("The Fastfile has no beta_major lane. Run 'launchpad template update' first.")]
+    MajorLaneMissing,
+
+    #[error("Fastlane failed: {0}")]
+    FastlaneFailed(String),
+
+    #[error("No [android] section in .launchpad.toml. Add one with package_name to deploy to Google Play.")]
+    NoAndroidConfig,
+
+    #[error("Google Play service account not configured. Set GOOGLE_PLAY_SERVICE_ACCOUNT_PATH or [google_play] in ~/.launchpad/config.toml.")]
+    NoPlayServiceAccount,
+
+    #[error("Google Play service account key not found at: {0}")]
+    PlayServiceAccountNotFound(String),
+
+    #[error("{0}")]
+    Gradle(#[from] GradleError),
+
+    #[error("Google Play: {0}")]
+    Play(#[from] PlayError),
+
+    #[error("{0}")]
+    Git(#[from] GitError),
+
+    #[error("IO error: {0}")]
+    Io(#[from] std::io::Error),
+
+    #[error("Config error: {0}")]
+    Config(String),
+}
+
+/// Store to deploy to
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum Platform {
+    /// TestFlight via fastlane
+    #[default]
+    Ios,
+    /// Google Play internal testing via Gradle
+    Android,
+}
+
+impl Platform {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Platform::Ios => "ios",
+            Platform::Android => "android",
+        }
+    }
+
+    /// Release tag for a deploy: "v1.2.0-43", or "android/v1.2.0-43" so the two
+    /// apps' tags don't collide
+    pub fn release_tag(&self, version: &str, build: &str) -> String {
+        match self {
+            Platform::Ios => format!("v{}-{}", version, build),
+            Platform::Android => format!("android/v{}-{}", version, build),
+        }
+    }
+
+    /// Whether `tag` has the form [`release_tag`](Self::release_tag) gives either
+    /// platform's deploys, so `watch` doesn't deploy the tags its deploys push
+    pub fn is_release_tag(tag: &str) -> bool {
+        let tag = tag.strip_prefix("android/").unwrap_or(tag);
+        let Some((version, build)) = tag.strip_prefix('v').and_then(|rest| rest.rsplit_once('-'))
+        else {
+            return false;
+        };
+        // "1.2.0", "43" or a dotted build number like "43.1"
+        let numeric = |s: &str| {
+            s.starts_with(|c: char| c.is_ascii_digit())
+                && s.chars().all(|c| c.is_ascii_digit() || c == '.')
+        };
+        numeric(version) && numeric(build)
+    }
+}
+
+/// Project settings from flags or LAUNCHPAD_* variables; they override .launchpad.toml
+/// and stand in for it on CI checkouts that don't have one
+#[derive(Debug, Default, clap::Args)]
+pub struct ProjectArgs {
+    /// Path to the iOS project (default: .launchpad.toml, else auto-detect)
+    #[arg(long, env = "LAUNCHPAD_IOS_PATH")]
+    pub ios_path: Option<String>,
+
+    /// Xcode scheme
+    #[arg(long, env = "LAUNCHPAD_SCHEME")]
+    pub scheme: Option<String>,
+
+    /// Bundle identifier of the app
+    #[arg(long, env = "LAUNCHPAD_BUNDLE_ID")]
+    pub bundle_id: Option<String>,
+
+    /// Code signing style
+    #[arg(long, value_enum, env = "LAUNCHPAD_SIGNING_STYLE")]
+    pub signing_style: Option<SigningStyle>,
+
+    /// Provisioning profile for manual signing (repeatable; comma-separated in the env var)
+    #[arg(
+        long = "profile",
+        value_name = "BUNDLE_ID=NAME",
+        env = "LAUNCHPAD_PROFILES",
+        value_delimiter = ',',
+        value_parser = parse_profile
+    )]
+    pub profiles: Vec<(String, String)>,
+
+    /// Xcode build configuration
+    #[arg(long, env = "LAUNCHPAD_CONFIGURATION")]
+    pub configuration: Option<String>,
+
+    /// Export method for the .ipa
+    #[arg(long, env = "LAUNCHPAD_EXPORT_METHOD")]
+    pub export_method: Option<String>,
+
+    /// TestFlight group to distribute to (repeatable; comma-separated in the env var)
+    #[arg(
+        long = "group",
+        value_name = "NAME",
+        env = "LAUNCHPAD_GROUPS",
+        value_delimiter = ','
+    )]
+    pub groups: Vec<String>,
+
+    /// White-label brand to build ([brands.<name>] in .launchpad.toml)
+    #[arg(long, value_name = "NAME", env = "LAUNCHPAD_BRAND")]
+    pub brand: Option<String>,
+}
+
+impl ProjectArgs {
+    fn is_empty(&self) -> bool {
+        self.ios_path.is_none()
+            && self.scheme.is_none()
+            && self.bundle_id.is_none()
+            && self.signing_style.is_none()
+            && self.profiles.is_empty()
+            && self.configuration.is_none()
+            && self.export_method.is_none()
+            && self.groups.is_empty()
+            && self.brand.is_none()
+    }
+
+    /// Apply over .launchpad.toml, or build the whole config from the arguments
+    fn resolve(self, file: Option<ProjectConfig>) -> Result<ProjectConfig, DeployError> {
+        if self.is_empty() {
+            return file.ok_or(DeployError::NoProjectConfig);
+        }
+        self.validate()?;
+
+        let mut config = match file {
+            Some(config) => config,
+            None => {
+                let (Some(scheme), Some(bundle_id)) = (&self.scheme, &self.bundle_id) else {
+                    return Err(DeployError::IncompleteProjectArgs);
+                };
+                let ios_path = match &self.ios_path {
+                    Some(path) => path.clone(),
+                    None => init::detect_ios_path(Preset::detect()).ok_or_else(|| {
+                        DeployError::InvalidProjectArgs(
+                            "no Xcode project found; pass --ios-path".to_string(),
+                        )
+                    })?,
+                };
+                ui::step("No .launchpad.toml; using project settings from flags and environment");
+                ProjectConfig::new(ios_path, scheme.clone(), bundle_id.clone())
+            }
+        };
+
+        // The brand's bundle ID, unless --bundle-id names another
+        if let Some(name) = &self.brand {
+            let brand = config
+                .brands
+                .get(name)
+                .ok_or_else(|| DeployError::UnknownBrand {
+                    name: name.clone(),
+                    known: global::known_names(config.brands.keys()),
+                })?;
+            config.project.bundle_id = brand.bundle_id.clone();
+        }
+
+        let check_scheme = self.ios_path.is_some() || self.scheme.is_some();
+        if let Some(ios_path) = self.ios_path {
+            config.project.ios_path = ios_path;
+        }
+        if let Some(scheme) = self.scheme {
+            config.project.scheme = scheme;
+        }
+        if let Some(bundle_id) = self.bundle_id {
+            config.project.bundle_id = bundle_id;
+        }
+        if let Some(style) = self.signing_style {
+            config.signing.style = Some(style);
+        }
+        config.signing.profiles.extend(self.profiles);
+        if let Some(configuration) = self.configuration {
+            config.build.configuration = configuration;
+        }
+        if let Some(export_method) = self.export_method {
+            config.build.export_method = export_method;
+        }
+        if !self.groups.is_empty() {
+            config.deploy.groups = self.groups;
+        }
+
+        // The path and scheme have to exist together
+        let project = &config.project;
+        if Xcode::container(&project.ios_path).is_none() {
+            return Err(DeployError::InvalidProjectArgs(format!(
+                "no Xcode workspace or project in {}",
+                project.ios_path
+            )));
+        }
+        if check_scheme {
+            if let Ok(schemes) = Xcode::list_schemes(&project.ios_path) {
+                if !schemes.contains(&project.scheme) {
+                    return Err(DeployError::InvalidProjectArgs(format!(
+                        "scheme {} not found in {} (available: {})",
+                        project.scheme,
+                        project.ios_path,
+                        schemes.join(", ")
+                    )));
+                }
+            }
+        }
+
+        Ok(config)
+    }
+
+    /// Checks that don't need the Xcode project
+    fn validate(&self) -> Result<(), DeployError> {
+        if let Some(bundle_id) = &self.bundle_id {
+            let valid = !bundle_id.is_empty()
+                && !bundle_id.starts_with('.')
+                && !bundle_id.ends_with('.')
+                && bundle_id
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || c == '.' || c == '-');
+            if !valid {
+                return Err(DeployError::InvalidProjectArgs(format!(
+                    "{} is not a valid bundle identifier",
+                    bundle_id
+                )));
+            }
+        }
+
+        if self.signing_style == Some(SigningStyle::Automatic) && !self.profiles.is_empty() {
+            return Err(DeployError::InvalidProjectArgs(
+                "--profile only applies to manual signing".to_string(),
+            ));
+        }
+
+        Ok(())
+    }
+}
+
+/// Which caches were warm, and a nudge when the Fastfile predates the cache options
+fn report_cache(paths: &CachePaths, ios_path: &str) {
+    let status = paths
+        .hits
+        .iter()
+        .map(|(kind, hit)| format!("{} {}", kind, if *hit { "reused" } else { "cold" }))
+        .collect::<Vec<_>>()
+        .join(", ");
+    ui::step(&format!("Build cache: {}", status));
+
+    let fastfile = template::find_fastfile(ios_path)
+        .and_then(|path| std::fs::read_to_string(path).ok())
+        .unwrap_or_default();
+    if !fastfile.is_empty() && !fastfile.contains("LAUNCHPAD_DERIVED_DATA_PATH") {
+        ui::warn("The Fastfile ignores the build cache; run 'launchpad template update'");
+    }
+}
+
+/// The last build's stamp when its .ipa can be uploaded instead of rebuilding:
+/// always with --reuse-artifact (or an error saying why not), else if the user agrees
+fn reusable_build(
+    config: &ProjectConfig,
+    output_dir: &Path,
+    environment: Option<&str>,
+    requested: bool,
+    bumping: bool,
+    non_interactive: bool,
+) -> Result<Option<BuildStamp>, DeployError> {
+    if bumping {
+        return Ok(None);
+    }
+
+    let stamp = BuildStamp::load(output_dir);
+    let check = match (&stamp, artifact::source_hash(&config.build.output_dir)) {
+        (None, _) => Err(format!("no build recorded in {}", output_dir.display())),
+        (_, None) => {
+            Err("the source tree can only be compared inside a git repository".to_string())
+        }
+        (Some(stamp), Some(hash)) => stamp.check(&hash, environment, &config.project.bundle_id),
+    }
+    .and_then(|()| {
+        let fastfile = template::find_fastfile(&config.project.ios_path)
+            .and_then(|path| std::fs::read_to_string(path).ok())
+            .unwrap_or_default();
+        if fastfile.contains("lane :upload_only") {
+            Ok(())
+        } else {
+            Err("the Fastfile has no upload_only lane; run 'launchpad template update'".to_string())
+        }
+    });
+
+    let Some(stamp) = stamp.filter(|_| check.is_ok()) else {
+        return match check {
+            Err(reason) if requested => Err(DeployError::NoReusableArtifact(reason)),
+            _ => Ok(None),
+        };
+    };
+    if requested {
+        return Ok(Some(stamp));
+    }
+
+    let version = stamp.version.as_deref().unwrap_or("unknown");
+    if non_interactive || !std::io::stdin().is_terminal() {
+        ui::step(&format!(
+            "Sources unchanged since build {} was made; pass --reuse-artifact to upload it without rebuilding",
+            version
+        ));
+        return Ok(None);
+    }
+
+    let reuse = Confirm::new()
+        .with_prompt(format!(
+            "Sources unchanged since build {} was made. Upload it instead of rebuilding?",
+            version
+        ))
+        .default(true)
+        .interact()
+        .map_err(|e| DeployError::Io(std::io::Error::other(e)))?;
+    Ok(reuse.then_some(stamp))
+}
+
+/// Remember the .ipa this run built (or re-uploaded) for a later --reuse-artifact
+fn stamp_build(
+    reused: Option<BuildStamp>,
+    config: &ProjectConfig,
+    output_dir: &Path,
+    environment: Option<&str>,
+    build_started: SystemTime,
+    uploaded: bool,
+) {
+    let stamp = match reused {
+        Some(stamp) => BuildStamp { uploaded, ..stamp },
+        None => {
+            let ios_path = Path::new(&config.project.ios_path);
+            let ipa = find_artifact(&[output_dir, ios_path], ".ipa").filter(|ipa| {
+                std::fs::metadata(ipa)
+                    .and_then(|m| m.modified())
+                    .is_ok_and(|modified| modified >= build_started)
+            });
+            // Without a fresh .ipa the archive step itself failed
+            let (Some(ipa), Some(source_hash)) =
+                (ipa, artifact::source_hash(&config.build.output_dir))
+            else {
+                return;
+            };
+            BuildStamp {
+                source_hash,
+                version: ipa::version(&ipa),
+                ipa: ipa.to_string_lossy().to_string(),
+                built_at: history::now(),
+                environment: environment.map(str::to_string),
+                bundle_id: Some(config.project.bundle_id.clone()),
+                uploaded,
+            }
+        }
+    };
+
+    if let Err(e) = stamp.save(output_dir) {
+        ui::warn(&format!("Failed to record the build: {}", e));
+    }
+}
+
+/// The `[environments]` entry this deploy builds with, named by --env,
+/// LAUNCHPAD_ENVIRONMENT or `notifications.environment`
+fn build_environment(config: &ProjectConfig) -> Result<Option<String>, DeployError> {
+    if config.environments.is_empty() {
+        return Ok(None);
+    }
+    let Some(name) = notifications::environment(config.notifications.as_ref()) else {
+        ui::warn("No environment selected; building without [environments] settings (pass --env)");
+        return Ok(None);
+    };
+    if !config.environments.contains_key(&name) {
+        return Err(DeployError::UnknownEnvironment {
+            name,
+            known: global::known_names(config.environments.keys()),
+        });
+    }
+    Ok(Some(name))
+}
+
+/// CFBundleDisplayName for the environment's builds, so they can sit next to the
+/// other environments' on a tester's phone; None leaves the app's own
+fn environment_display_name(
+    config: &ProjectConfig,
+    brand: Option<&str>,
+    environment: &EnvironmentSettings,
+    bundle_id: &str,
+) -> Option<String> {
+    let suffix = environment
+        .display_name_suffix
+        .as_deref()
+        .unwrap_or_default();
+    let name = environment
+        .display_name
+        .clone()
+        .or_else(|| config.brands.get(brand?)?.display_name.clone());
+    let name = match name {
+        Some(name) => name,
+        None if suffix.is_empty() => return None,
+        None => {
+            let project = &config.project;
+            match Xcode::display_name(&project.ios_path, &project.scheme, bundle_id) {
+                Ok(Some(name)) => name,
+                Ok(None) => {
+                    ui::warn(&format!(
+                        "No display name found for {}; set display_name next to display_name_suffix",
+                        bundle_id
+                    ));
+                    return None;
+                }
+                Err(e) => {
+                    ui::warn(&format!("Display name suffix skipped: {}", e));
+                    return None;
+                }
+            }
+        }
+    };
+    Some(format!("{}{}", name, suffix))
+}
+
+/// `BUNDLE_ID=NAME` from --profile
+fn parse_profile(value: &str) -> Result<(String, String), String> {
+    match value.split_once('=') {
+        Some((bundle_id, name)) if !bundle_id.trim().is_empty() && !name.trim().is_empty() => {
+            Ok((bundle_id.trim().to_string(), name.trim().to_string()))
+        }
+        _ => Err(format!("expected BUNDLE_ID=NAME, got '{}'", value)),
+    }
+}
+
+/// `launchpad deploy` for callers other than the CLI; the defaults are a build
+/// number bump of the project in the current directory
+#[derive(Debug, Default)]
+pub struct DeployOptions {
+    pub bump: Option<VersionBump>,
+    pub no_tag: bool,
+    pub skip_git_check: bool,
+    pub keep_artifacts: bool,
+    pub reuse_artifact: bool,
+    pub skip_upload: bool,
+    pub platform: Platform,
+    pub project: ProjectArgs,
+    pub non_interactive: bool,
+}
+
+impl DeployOptions {
+    pub async fn run(self) -> Result<(), DeployError> {
+        run(self).await
+    }
+}
+
+pub async fn run(options: DeployOptions) -> Result<(), DeployError> {
+    let DeployOptions {
+        bump,
+        no_tag,
+        skip_git_check,
+        keep_artifacts,
+        reuse_artifact,
+        skip_upload,
+        platform,
+        project: project_args,
+        non_interactive,
+    } = options;
+    let started = Instant::now();
+    ui::header("Launchpad Deploy");
+
+    if platform == Platform::Android {
+        return run_android(bump, no_tag, skip_git_check, non_interactive, started).await;
+    }
+
+    // Load configs
+    let global_config = GlobalConfig::load().map_err(|e| DeployError::Config(e.to_string()))?;
+    let mut global_config = global_config.ok_or(DeployError::NoGlobalConfig)?;
+
+    let project_config = ProjectConfig::load().map_err(|e| DeployError::Config(e.to_string()))?;
+    // The scheme still builds the unbranded bundle ID until the build settings apply
+    let brand = project_args.brand.clone();
+    let scheme_bundle_id = project_config.as_ref().map(|c| c.project.bundle_id.clone());
+    let mut project_config = project_args.resolve(project_config)?;
+    // Nothing reaches App Store Connect or the remote; whoever uploads takes it from here
+    if skip_upload {
+        project_config.deploy.verify_upload = false;
+        project_config.deploy.push_tags = false;
+    }
+    // A mock deploy leaves no tag, note or version bump commit behind
+    if mock::enabled() {
+        project_config.deploy.git_tag = false;
+        project_config.deploy.git_notes = false;
+        project_config.deploy.push_version_bump = false;
+    }
+
+    // An environment under another Apple account brings its own key and app
+    let environment = build_environment(&project_config)?;
+    if let Some(name) = &environment {
+        config::apply_environment(&mut global_config, &mut project_config, name)
+            .map_err(|e| DeployError::Config(e.to_string()))?;
+    }
+
+    // Validate API key exists
+    let key_path = shellexpand::tilde(&global_config.apple.key_path).to_string();
+    if !Path::new(&key_path).exists() && !mock::enabled() {
+        return Err(DeployError::ApiKeyNotFound(key_path));
+    }
+
+    // Git checks
+    if !skip_git_check {
+        ui::step("Checking git status...");
+        if !git::is_clean()? {
+            return Err(DeployError::DirtyWorkingDirectory);
+        }
+        ui::success("Working directory clean");
+    }
+    check_checkout()?;
+
+    // Signing style decides how the lanes provision (older configs didn't record it)
+    let project = &project_config.project;
+    let signing_style = match project_config.signing.style {
+        Some(style) => Some(style),
+        None => Xcode::signing_style(&project.ios_path, &project.scheme).ok(),
+    };
+    if signing_style == Some(SigningStyle::Manual) && project_config.signing.profiles.is_empty() {
+        return Err(DeployError::MissingProvisioningProfiles);
+    }
+    project_config.signing.style = signing_style;
+
+    // An older Fastfile would upload anyway
+    if skip_upload {
+        let fastfile = template::find_fastfile(&project.ios_path)
+            .and_then(|path| std::fs::read_to_string(path).ok())
+            .unwrap_or_default();
+        if !fastfile.contains("LAUNCHPAD_SKIP_UPLOAD") {
+            return Err(DeployError::SkipUploadUnsupported);
+        }
+    }
+    // Fastfiles written before --major existed lack its lane
+    if bump == Some(VersionBump::Major) {
+        let fastfile = template::find_fastfile(&project.ios_path)
+            .and_then(|path| std::fs::read_to_string(path).ok());
+        if fastfile.is_some_and(|f| !f.contains("lane :beta_major")) {
+            return Err(DeployError::MajorLaneMissing);
+        }
+    }
+
+    // Builds here, but not on a fresh CI checkout
+    if let SchemeSharing::UserOnly(_) = Xcode::scheme_sharing(
+        &project_config.project.ios_path,
+        &project_config.project.scheme,
+    ) {
+        ui::warn(&format!(
+            "Scheme {} isn't shared, so CI can't build it (run 'launchpad doctor' to share it)",
+            project_config.project.scheme
+        ));
+    }
+
+    // Extensions with a different version than the app get rejected on upload
+    ui::step("Checking target versions...");
+    let current_version = match Xcode::target_versions(&project_config.project.ios_path) {
+        Ok(targets) => {
+            if let Some(mismatch) = xcode::version_mismatch(&targets) {
+                return Err(DeployError::VersionMismatch(mismatch));
+            }
+            ui::success("Target versions consistent");
+            targets.into_iter().next()
+        }
+        Err(e) => {
+            ui::warn(&format!("Could not check target versions: {}", e));
+            None
+        }
+    };
+
+    let version_bump = bump.map(VersionBump::name);
+
+    // An upload that failed after a good build can send the same .ipa again
+    let mut fastlane = Fastlane::new(&global_config, &project_config, non_interactive);
+    if let Some(name) = &environment {
+        fastlane = fastlane.with_apple_account(&project_config.environments[name].apple);
+    }
+    let output_dir = PathBuf::from(fastlane.output_dir());
+    let reused = reusable_build(
+        &project_config,
+        &output_dir,
+        environment.as_deref(),
+        reuse_artifact,
+        version_bump.is_some(),
+        non_interactive,
+    )?;
+
+    let action = match (version_bump, &reused) {
+        (_, Some(_)) => "the last build (no rebuild)",
+        (Some("patch"), _) => "patch version bump",
+        (Some("minor"), _) => "minor version bump",
+        (Some("major"), _) => "major version bump",
+        _ => "build number increment",
+    };
+    let version_change = match &reused {
+        Some(stamp) => format!(
+            "{} (existing .ipa)",
+            stamp.version.as_deref().unwrap_or("unknown")
+        ),
+        None => describe_version_change(current_version.as_ref(), version_bump),
+    };
+
+    // Info.plist lookups go by the bundle ID the scheme itself builds
+    let replaced = brand.is_some()
+        || environment
+            .as_ref()
+            .is_some_and(|name| project_config.environments[name].apple.bundle_id.is_some());
+    let plist_bundle_id = match (replaced, &scheme_bundle_id) {
+        (true, Some(bundle_id)) => bundle_id.clone(),
+        _ => project_config.project.bundle_id.clone(),
+    };
+    let display_name = match (&environment, &reused) {
+        (Some(name), None) => environment_display_name(
+            &project_config,
+            brand.as_deref(),
+            &project_config.environments[name],
+            &plist_bundle_id,
+        ),
+        _ => None,
+    };
+
+    let mut rows = vec![
+        ("App", project_config.project.scheme.clone()),
+        ("Bundle ID", project_config.project.bundle_id.clone()),
+        ("Version", version_change),
+        (
+            "Destination",
+            if skip_upload {
+                "None (--skip-upload)".to_string()
+            } else {
+                describe_testflight_destination(&project_config.deploy)
+            },
+        ),
+    ];
+    if let Some(brand) = &brand {
+        rows.push(("Brand", brand.clone()));
+    }
+    if let Some(name) = &environment {
+        rows.push(("Environment", name.clone()));
+        if let Some(profile) = &project_config.environments[name].apple.key_profile {
+            rows.push((
+                "API key",
+                format!("{} ({})", profile, global_config.apple.key_id),
+            ));
+        }
+    }
+    if let Some(name) = &display_name {
+        rows.push(("Display name", name.clone()));
+    }
+    confirm_deploy(&rows, non_interactive)?;
+
+    // Lifecycle hooks get the same base context; pre-bump hooks can block the deploy
+    let hook_context = json!({
+        "scheme": project_config.project.scheme,
+        "bundle_id": project_config.project.bundle_id,
+        "ios_path": project_config.project.ios_path,
+        "version_bump": version_bump.unwrap_or("build"),
+        "git_sha": git::head_sha(),
+    });
+    if reused.is_none() {
+        hooks::run(
+            &project_config.hooks,
+            HookEvent::PreVersionBump,
+            &hook_context,
+        )?;
+    }
+
+    // "What to Test" is rendered up front, from the version the lane is about to set
+    let planned = match &reused {
+        Some(stamp) => stamp.version.as_deref().map(fastlane::split_version),
+        None => next_version(current_version.as_ref(), version_bump)
+            .map(|(version, build)| (version, Some(build))),
+    };
+    let release_notes = render_message(
+        "release_notes",
+        project_config
+            .templates
+            .as_ref()
+            .and_then(|t| t.release_notes.as_deref()),
+        || {
+            let (version, build) = planned.clone().unwrap_or_default();
+            templating::Variables {
+                version: (!version.is_empty()).then_some(version),
+                build,
+                ..template_variables(&project_config, Platform::Ios)
+            }
+        },
+    );
+    if let Some(notes) = release_notes {
+        fastlane = fastlane.with_release_notes(notes);
+    }
+
+    // [build.metadata] and the environment's display name go in the Info.plist
+    // file for this build only; a literal name there would beat the xcconfig's
+    let mut plist_keys = BTreeMap::new();
+    if let Some(settings) = &project_config.build.metadata {
+        plist_keys.extend(build_metadata(settings));
+    }
+    if let Some(name) = &display_name {
+        plist_keys.insert("CFBundleDisplayName".to_string(), name.clone());
+    }
+    if !plist_keys.is_empty() && reused.is_none() {
+        let project = &project_config.project;
+        match Xcode::info_plist(&project.ios_path, &project.scheme, &plist_bundle_id) {
+            Ok(Some(info_plist)) => {
+                fastlane = fastlane.with_build_metadata(info_plist, &plist_keys);
+            }
+            // A generated Info.plist takes the display name from the xcconfig
+            Ok(None) if project_config.build.metadata.is_none() => {}
+            Ok(None) => ui::warn(&format!(
+                "No Info.plist file for {}; build metadata needs one (set INFOPLIST_FILE)",
+                project.bundle_id
+            )),
+            Err(e) => ui::warn(&format!("Build metadata skipped: {}", e)),
+        }
+    }
+
+    // [brands.<name>] and [environments.<name>.build_env] go in through a
+    // generated xcconfig; the environment's settings win
+    let mut settings = BTreeMap::new();
+    let mut sources = Vec::new();
+    if let Some(name) = &brand {
+        settings.extend(project_config.brands[name].build_settings());
+        sources.push(format!("brand {}", name));
+    }
+    if let Some(name) = &environment {
+        settings.extend(project_config.environments[name].build_env.clone());
+        sources.push(format!("the {} environment", name));
+    }
+    if let Some(name) = &display_name {
+        settings.insert(
+            "INFOPLIST_KEY_CFBundleDisplayName".to_string(),
+            name.clone(),
+        );
+    }
+    let xcconfig = if settings.is_empty() || reused.is_some() {
+        None
+    } else {
+        let name = [brand.as_deref(), environment.as_deref()]
+            .into_iter()
+            .flatten()
+            .collect::<Vec<_>>()
+            .join("-");
+        let path = output_dir.join(format!("launchpad-{}.xcconfig", name));
+        let source = sources.join(" and ");
+        xcode::write_xcconfig(&path, &source, &settings)?;
+        ui::step(&format!(
+            "Build settings for {}: {}",
+            source,
+            settings.keys().cloned().collect::<Vec<_>>().join(", ")
+        ));
+        fastlane = fastlane.with_xcconfig(path.clone());
+        Some(path)
+    };
+
+    ui::step(&format!("Deploying with {}...", action));
+    if skip_upload {
+        fastlane = fastlane.with_skip_upload();
+    }
+
+    // Nothing gets built when an earlier .ipa is reused
+    let build_cache = match reused {
+        Some(_) => Ok(None),
+        None => BuildCache::for_project(&project_config),
+    };
+    let build_cache = build_cache.and_then(|build_cache| {
+        build_cache
+            .map(|build_cache| {
+                let paths = build_cache.prepare(&project_config.project.ios_path)?;
+                Ok((build_cache, paths))
+            })
+            .transpose()
+    });
+    let build_cache = match build_cache {
+        Ok(Some((build_cache, paths))) => {
+            report_cache(&paths, &project_config.project.ios_path);
+            fastlane = fastlane.with_cache(&paths);
+            Some((build_cache, paths))
+        }
+        Ok(None) => None,
+        Err(e) => {
+            ui::warn(&format!("Build cache unavailable: {}", e));
+            None
+        }
+    };
+
+    // [build.icon_badge] marks the icon of this build only
+    let badged = match (&project_config.build.icon_badge, &reused) {
+        (Some(badge), None) if badge.applies_to(environment.as_deref()) => {
+            let icon_set = settings
+                .get("ASSETCATALOG_COMPILER_APPICON_NAME")
+                .map(String::as_str)
+                .unwrap_or("AppIcon");
+            let text = render_message("icon_badge", Some(&badge.text), || {
+                let (version, build) = planned.clone().unwrap_or_default();
+                templating::Variables {
+                    version: (!version.is_empty()).then_some(version),
+                    build,
+                    environment: environment.clone(),
+                    ..template_variables(&project_config, Platform::Ios)
+                }
+            })
+            .unwrap_or_else(|| "BETA".to_string());
+            let ios_path = Path::new(&project_config.project.ios_path);
+            match badge::apply(ios_path, icon_set, &text, &badge.color) {
+                Ok(icons) => {
+                    ui::step(&format!(
+                        "Badged {} {} icons with \"{}\"",
+                        icons.count(),
+                        icon_set,
+                        text
+                    ));
+                    Some(icons)
+                }
+                Err(e) => {
+                    ui::warn(&format!("Icon badge skipped: {}", e));
+                    None
+                }
+            }
+        }
+        _ => None,
+    };
+
+    // Run fastlane
+    let build_started = SystemTime::now();
+    let result = match &reused {
+        Some(stamp) => {
+            events::phase("upload", "Uploading the existing build to TestFlight");
+            let spinner = ui::spinner("Uploading the existing build to TestFlight...");
+            let result = fastlane.upload(Path::new(&stamp.ipa)).await;
+            spinner.finish_and_clear();
+            // Older Fastfiles don't report a version when they only upload
+            result.map(|result| match (&result.version, &stamp.version) {
+                (None, Some(version)) => {
+                    let (version, build) = fastlane::split_version(version);
+                    fastlane::LaneResult {
+                        version: Some(version),
+                        build,
+                        ..result
+                    }
+                }
+                _ => result,
+            })
+        }
+        None if skip_upload => {
+            events::phase("build", "Building");
+            let spinner = ui::spinner("Building...");
+            let result = fastlane.deploy(version_bump).await;
+            spinner.finish_and_clear();
+            result
+        }
+        None => {
+            events::phase("build", "Building and uploading to TestFlight");
+            let spinner = ui::spinner("Building and uploading to TestFlight...");
+            let result = fastlane.deploy(version_bump).await;
+            spinner.finish_and_clear();
+            result
+        }
+    };
+    // Uploading is the lane's last step, so processing time counts from here
+    let uploaded = Instant::now();
+
+    if let Some(path) = &xcconfig {
+        let _ = std::fs::remove_file(path);
+    }
+    if let Some(icons) = badged {
+        if let Err(e) = icons.restore() {
+            ui::warn(&format!("Failed to restore the app icon: {}", e));
+        }
+    }
+
+    // fastlane can exit 0 without the upload having gone through
+    let result = match result {
+        Ok(lane) if project_config.deploy.verify_upload => {
+            verify_upload(&global_config, &project_config, &lane)
+                .await
+                .map(|()| lane)
+        }
+        result => result,
+    };
+
+    if let Some((build_cache, paths)) = &build_cache {
+        match build_cache.prune(paths) {
+            Ok(0) => {}
+            Ok(freed) => ui::step(&format!(
+                "Pruned old package caches ({})",
+                ui::format_size(freed)
+            )),
+            Err(e) => ui::warn(&format!("Failed to prune build cache: {}", e)),
+        }
+    }
+
+    let output_dir = output_dir.as_path();
+    let ios_path = Path::new(&project_config.project.ios_path);
+    stamp_build(
+        reused,
+        &project_config,
+        output_dir,
+        environment.as_deref(),
+        build_started,
+        result.is_ok() && !skip_upload,
+    );
+    let summary_path = match &project_config.deploy.summary_path {
+        Some(path) => PathBuf::from(shellexpand::tilde(path).as_ref()),
+        None => output_dir.join("deploy-summary.json"),
+    };
+
+    match result {
+        Ok(lane) => {
+            let version = lane.label();
+            if skip_upload {
+                ui::success(&format!("Built version {} (not uploaded)", version));
+            } else {
+                ui::success(&format!("Successfully deployed version {}", version));
+            }
+
+            // Before the summary, so the tag and history point at the bump commit
+            if project_config.deploy.push_version_bump {
+                commit_version_bump(&project_config.deploy, ios_path, &version, !skip_upload);
+            }
+
+            // Record artifacts before cleanup removes them
+            let ipa = lane
+                .ipa
+                .filter(|ipa| ipa.exists())
+                .or_else(|| find_artifact(&[output_dir, ios_path], ".ipa"));
+            let dsym = find_artifact(&[output_dir, ios_path], ".dSYM.zip");
+            let thinned_sizes = match &project_config.size {
+                Some(size) => report_thinned_sizes(output_dir, size),
+                None => BTreeMap::new(),
+            };
+            if let (Some(analysis), Some(ipa)) = (&project_config.analysis, &ipa) {
+                if analysis.enabled {
+                    analyze_binary(ipa, &analysis.large_framework);
+                }
+            }
+            let export_method = project_config.build.export_method.as_str();
+            if let (Some(ipa), "ad-hoc" | "development") = (&ipa, export_method) {
+                check_test_devices(ipa);
+            }
+            let version_number = lane.version.unwrap_or_else(|| version.clone());
+            let build_number = lane.build;
+
+            let mut summary = DeploySummary {
+                version: Some(version_number),
+                build: build_number,
+                git_sha: git::head_sha(),
+                ipa_size: ipa
+                    .as_ref()
+                    .and_then(|p| std::fs::metadata(p).ok())
+                    .map(|m| m.len()),
+                ipa_path: ipa.as_ref().map(|p| p.to_string_lossy().to_string()),
+                dsym_path: dsym.map(|p| p.to_string_lossy().to_string()),
+                testflight_status: "processing".to_string(),
+                duration_secs: started.elapsed().as_secs(),
+                aab_path: None,
+                play_track: None,
+                error: None,
+            };
+            let templates = project_config.templates.as_ref();
+
+            // Create git tag if configured and not disabled
+            let should_tag = !no_tag && project_config.deploy.git_tag;
+            let tag = if should_tag {
+                // "1.2.0 (43)" can't be a tag name
+                let tag = match (&summary.version, &summary.build) {
+                    (Some(version), Some(build)) => Platform::Ios.release_tag(version, build),
+                    _ => format!("v{}", version),
+                };
+                let message = render_message(
+                    "tag_message",
+                    templates.and_then(|t| t.tag_message.as_deref()),
+                    || templating::Variables {
+                        tag: Some(tag.clone()),
+                        ..template_variables(&project_config, Platform::Ios).with_summary(&summary)
+                    },
+                );
+                tag_release(&tag, message, &project_config.deploy)
+            } else {
+                None
+            };
+
+            if !skip_upload {
+                notify(
+                    Some(&global_config),
+                    &project_config,
+                    Platform::Ios,
+                    NotificationEvent::Success,
+                    &summary,
+                )
+                .await;
+            }
+
+            // Groups and notifications go through the App Store Connect API once processed
+            let deploy_settings = &project_config.deploy;
+            let needs_distribution = !deploy_settings.groups.is_empty()
+                || !deploy_settings.notify_testers
+                || project_config.compliance.is_some();
+            let mut processing_secs = None;
+            let distributed = if skip_upload {
+                summary.testflight_status = "not uploaded".to_string();
+                false
+            } else if needs_distribution {
+                events::phase(
+                    "processing",
+                    "Waiting for App Store Connect to process the build",
+                );
+                let spinner = ui::spinner("Waiting for App Store Connect to process the build...");
+                let result = distribute_build(&global_config, &project_config, &version).await;
+                spinner.finish_and_clear();
+
+                match result {
+                    Ok(report) => {
+                        processing_secs = Some(uploaded.elapsed().as_secs());
+                        summary.testflight_status = "processed".to_string();
+                        if !report.distributed.is_empty() {
+                            ui::success(&format!(
+                                "Distributed to {}",
+                                report.distributed.join(", ")
+                            ));
+                        }
+                        if !report.deferred.is_empty() {
+                            ui::step(&format!(
+                                "{} will get the build after 'launchpad promote-beta'",
+                                report.deferred.join(", ")
+                            ));
+                        }
+                        !report.distributed.is_empty()
+                    }
+                    Err(e) if e.is_transient() => {
+                        queue_distribution(&project_config, environment.as_deref(), &version, &e);
+                        false
+                    }
+                    Err(e) => {
+                        ui::warn(&format!("Failed to distribute build: {}", e));
+                        false
+                    }
+                }
+            } else if wants_processed(&global_config, &project_config) {
+                // Only the processed notification needs the wait
+                events::phase(
+                    "processing",
+                    "Waiting for App Store Connect to process the build",
+                );
+                let spinner = ui::spinner("Waiting for App Store Connect to process the build...");
+                let result = wait_for_processing(&global_config, &project_config, &version).await;
+                spinner.finish_and_clear();
+
+                if let Err(e) = result {
+                    ui::warn(&format!("Failed to wait for processing: {}", e));
+                } else {
+                    processing_secs = Some(uploaded.elapsed().as_secs());
+                    summary.testflight_status = "processed".to_string();
+                }
+                false
+            } else {
+                false
+            };
+
+            if distributed {
+                summary.testflight_status = "distributed".to_string();
+            }
+            summary.duration_secs = started.elapsed().as_secs();
+            if !skip_upload && summary.testflight_status != "processing" {
+                notify(
+                    Some(&global_config),
+                    &project_config,
+                    Platform::Ios,
+                    NotificationEvent::Processed,
+                    &summary,
+                )
+                .await;
+            }
+            write_summary(&summary_path, &summary);
+
+            if project_config.deploy.git_notes && !skip_upload {
+                let note = render_message(
+                    "git_note",
+                    templates.and_then(|t| t.git_note.as_deref()),
+                    || templating::Variables {
+                        tag: tag.clone(),
+                        ..template_variables(&project_config, Platform::Ios).with_summary(&summary)
+                    },
+                );
+                let note = note.unwrap_or_else(|| {
+                    release_note(
+                        &format!("Deployed {} to TestFlight", version),
+                        &[
+                            ("Platform", Some(Platform::Ios.name().to_string())),
+                            ("Bundle-ID", Some(project_config.project.bundle_id.clone())),
+                            ("Version", summary.version.clone()),
+                            ("Build", summary.build.clone()),
+                            ("Tag", tag.clone()),
+                            (
+                                "TestFlight",
+                                project_config
+                                    .integrations
+                                    .public_link()
+                                    .map(str::to_string),
+                            ),
+                            ("Uploader", uploader()),
+                            ("CI", integrations::ci_run_url()),
+                        ],
+                    )
+                });
+                annotate_release(&project_config.deploy, &note);
+            }
+
+            let contents = ipa.as_deref().map(ipa::inspect).unwrap_or_default();
+            // `launchpad diff` mustn't compare against a build that never existed
+            if !mock::enabled() {
+                record_deploy(&DeployRecord {
+                    bundle_id: project_config.project.bundle_id.clone(),
+                    platform: Platform::Ios.name().to_string(),
+                    version: summary.version.clone().unwrap_or_default(),
+                    build: summary.build.clone().unwrap_or_default(),
+                    deployed_at: history::now(),
+                    git_sha: summary.git_sha.clone(),
+                    tag: tag.clone(),
+                    size: summary.ipa_size,
+                    sha256: ipa.as_deref().and_then(artifact::sha256),
+                    frameworks: contents.frameworks,
+                    entitlements: contents.entitlements,
+                    thinned_sizes,
+                    processing_secs,
+                    upload_skipped: skip_upload,
+                });
+            }
+
+            // Post-upload hooks and the TestFlight comment wait for whoever uploads
+            if !skip_upload {
+                // The upload already happened, so hook failures only warn here
+                let mut upload_context = hook_context.clone();
+                if let (Some(context), Ok(serde_json::Value::Object(fields))) = (
+                    upload_context.as_object_mut(),
+                    serde_json::to_value(&summary),
+                ) {
+                    context.extend(fields);
+                }
+                if let Err(e) = hooks::run(
+                    &project_config.hooks,
+                    HookEvent::PostUpload,
+                    &upload_context,
+                ) {
+                    ui::warn(&e.to_string());
+                }
+
+                // Comment on the pull/merge request when running in CI
+                let comment = render_message(
+                    "pr_comment",
+                    templates.and_then(|t| t.pr_comment.as_deref()),
+                    || templating::Variables {
+                        tag: tag.clone(),
+                        ..template_variables(&project_config, Platform::Ios).with_summary(&summary)
+                    },
+                );
+                match integrations::comment_on_change_request(
+                    &project_config.integrations,
+                    &summary,
+                    comment.as_deref(),
+                )
+                .await
+                {
+                    Ok(Some(target)) => {
+                        ui::success(&format!("Updated TestFlight comment on {}", target))
+                    }
+                    Ok(None) => {}
+                    Err(e) => ui::warn(&format!("Failed to post TestFlight comment: {}", e)),
+                }
+            }
+
+            // Clean up build artifacts if configured and not overridden
+            let should_clean =
+                !keep_artifacts && !skip_upload && project_config.deploy.clean_artifacts;
+            if should_clean {
+                match clean_artifacts(
+                    output_dir,
+                    ios_path,
+                    ipa.as_deref(),
+                    &project_config.project.scheme,
+                ) {
+                    Ok(0) => {}
+                    Ok(n) => ui::success(&format!(
+                        "Cleaned {} build artifact{}",
+                        n,
+                        if n == 1 { "" } else { "s" }
+                    )),
+                    Err(e) => ui::warn(&format!("Failed to clean artifacts: {}", e)),
+                }
+            }
+
+            ui::header(if skip_upload {
+                "Build Complete!"
+            } else {
+                "Deploy Complete!"
+            });
+            ui::blank();
+            ui::field("Version", &version);
+            if !should_clean {
+                ui::field("Artifacts", fastlane.output_dir());
+            }
+            if skip_upload {
+                ui::field(
+                    "TestFlight",
+                    "Not uploaded ('launchpad deploy --reuse-artifact' uploads this build)",
+                );
+            } else if distributed {
+                ui::field("TestFlight", "Processed and distributed");
+            } else {
+                ui::field("TestFlight", "Processing (usually 10-30 minutes)");
+            }
+            ui::blank();
+
+            Ok(())
+        }
+        Err(e) => {
+            let summary = DeploySummary {
+                version: None,
+                build: None,
+                git_sha: git::head_sha(),
+                ipa_path: None,
+                ipa_size: None,
+                dsym_path: None,
+                testflight_status: "failed".to_string(),
+                duration_secs: started.elapsed().as_secs(),
+                aab_path: None,
+                play_track: None,
+                error: Some(e.to_string()),
+            };
+            write_summary(&summary_path, &summary);
+            notify(
+                Some(&global_config),
+                &project_config,
+                Platform::Ios,
+                NotificationEvent::Failure,
+                &summary,
+            )
+            .await;
+
+            Err(DeployError::FastlaneFailed(e.to_string()))
+        }
+    }
+}
+
+/// Build the .aab with Gradle and release it to a Google Play track
+async fn run_android(
+    bump: Option<VersionBump>,
+    no_tag: bool,
+    skip_git_check: bool,
+    non_interactive: bool,
+    started: Instant,
+) -> Result<(), DeployError> {
+    // Gradle and Google Play aren't simulated, so nothing would stop a real release
+    if mock::enabled() {
+        return Err(DeployError::MockAndroid);
+    }
+
+    // Load configs; the Apple key isn't needed for Play
+    let project_config = ProjectConfig::load().map_err(|e| DeployError::Config(e.to_string()))?;
+    let project_config = project_config.ok_or(DeployError::NoProjectConfig)?;
+    let android = project_config
+        .android
+        .as_ref()
+        .ok_or(DeployError::NoAndroidConfig)?;
+
+    let global_config = GlobalConfig::load().ok().flatten();
+    let service_account = GooglePlayConfig::resolve(global_config.as_ref())
+        .ok_or(DeployError::NoPlayServiceAccount)?;
+    if !Path::new(&service_account).exists() {
+        return Err(DeployError::PlayServiceAccountNotFound(service_account));
+    }
+
+    // Git checks
+    if !skip_git_check {
+        ui::step("Checking git status...");
+        if !git::is_clean()? {
+            return Err(DeployError::DirtyWorkingDirectory);
+        }
+        ui::success("Working directory clean");
+    }
+    check_checkout()?;
+
+    // Play rejects reused version codes, so continue from the highest uploaded one
+    ui::step("Checking Google Play version codes...");
+    let play = PlayClient::new(Path::new(&service_account), &android.package_name).await?;
+    let gradle = Gradle::new(android);
+    let version_code = play
+        .latest_version_code()
+        .await?
+        .max(gradle.version_code().unwrap_or(0))
+        + 1;
+
+    let version_bump = bump.map(VersionBump::name);
+
+    let current_name = gradle.version_name();
+    let version_name = match version_bump {
+        Some(bump) => Some(
+            current_name
+                .as_deref()
+                .and_then(|name| version::bump(name, bump))
+                .ok_or_else(|| {
+                    DeployError::Config(format!(
+                        "Could not read a numeric versionName from {}/{}",
+                        android.project_path, android.module
+                    ))
+                })?,
+        ),
+        None => None,
+    };
+    let display_name = version_name
+        .clone()
+        .or_else(|| current_name.clone())
+        .unwrap_or_default();
+    let version = format!("{} ({})", display_name, version_code);
+
+    confirm_deploy(
+        &[
+            ("Package", android.package_name.clone()),
+            (
+                "Version",
+                format!(
+                    "{} ({}) {} {}",
+                    current_name.as_deref().unwrap_or("unknown"),
+                    version_code - 1,
+                    ui::arrow(),
+                    version
+                ),
+            ),
+            (
+                "Destination",
+                format!("Google Play ({} track)", android.track),
+            ),
+        ],
+        non_interactive,
+    )?;
+
+    let hook_context = json!({
+        "platform": "android",
+        "package_name": android.package_name,
+        "version_bump": version_bump.unwrap_or("build"),
+        "version_code": version_code,
+        "git_sha": git::head_sha(),
+    });
+    hooks::run(
+        &project_config.hooks,
+        HookEvent::PreVersionBump,
+        &hook_context,
+    )?;
+
+    let summary_path = match &project_config.deploy.summary_path {
+        Some(path) => PathBuf::from(shellexpand::tilde(path).as_ref()),
+        None => Path::new(&project_config.build.output_dir).join("deploy-summary.json"),
+    };
+
+    // Build, then upload
+    events::phase("build", "Building Android App Bundle");
+    let spinner = ui::spinner("Building Android App Bundle...");
+    let aab = gradle.bundle(version_code, version_name.as_deref()).await;
+    spinner.finish_and_clear();
+
+    let result = match aab {
+        Ok(aab) => {
+            ui::success(&format!("Built {}", aab.display()));
+            let size = std::fs::metadata(&aab).map(|m| m.len()).unwrap_or_default();
+            events::phase("upload", "Uploading to Google Play");
+            let progress = ui::progress_bar(
+                size,
+                &format!("Uploading to Google Play ({})", android.track),
+            );
+            let upload_started = Instant::now();
+            let upload = play
+                .upload_bundle(&aab, &android.track, &version, &progress)
+                .await;
+            progress.finish_and_clear();
+            if upload.is_ok() {
+                let elapsed = upload_started.elapsed().as_secs_f64().max(1.0);
+                ui::success(&format!(
+                    "Uploaded {} in {:.0}s ({}/s)",
+                    ui::format_size(size),
+                    elapsed,
+                    ui::format_size((size as f64 / elapsed) as u64)
+                ));
+            }
+            upload.map(|_| aab).map_err(DeployError::from)
+        }
+        Err(e) => Err(e.into()),
+    };
+
+    let aab = match result {
+        Ok(aab) => aab,
+        Err(e) => {
+            let summary = DeploySummary {
+                version: None,
+                build: None,
+                git_sha: git::head_sha(),
+                ipa_path: None,
+                ipa_size: None,
+                dsym_path: None,
+                testflight_status: "failed".to_string(),
+                duration_secs: started.elapsed().as_secs(),
+                aab_path: None,
+                play_track: Some(android.track.clone()),
+                error: Some(e.to_string()),
+            };
+            write_summary(&summary_path, &summary);
+            notify(
+                global_config.as_ref(),
+                &project_config,
+                Platform::Android,
+                NotificationEvent::Failure,
+                &summary,
+            )
+            .await;
+            return Err(e);
+        }
+    };
+
+    ui::success(&format!(
+        "Successfully released {} to the {} track",
+        version, android.track
+    ));
+
+    let summary = DeploySummary {
+        version: Some(display_name.clone()),
+        build: Some(version_code.to_string()),
+        git_sha: git::head_sha(),
+        ipa_path: None,
+        ipa_size: None,
+        dsym_path: None,
+        testflight_status: "completed".to_string(),
+        duration_secs: started.elapsed().as_secs(),
+        aab_path: Some(aab.to_string_lossy().to_string()),
+        play_track: Some(android.track.clone()),
+        error: None,
+    };
+    let templates = project_config.templates.as_ref();
+
+    let tag = if !no_tag && project_config.deploy.git_tag {
+        let tag = Platform::Android.release_tag(&display_name, &version_code.to_string());
+        let message = render_message(
+            "tag_message",
+            templates.and_then(|t| t.tag_message.as_deref()),
+            || templating::Variables {
+                tag: Some(tag.clone()),
+                ..template_variables(&project_config, Platform::Android).with_summary(&summary)
+            },
+        );
+        tag_release(&tag, message, &project_config.deploy)
+    } else {
+        None
+    };
+
+    write_summary(&summary_path, &summary);
+    notify(
+        global_config.as_ref(),
+        &project_config,
+        Platform::Android,
+        NotificationEvent::Success,
+        &summary,
+    )
+    .await;
+
+    if project_config.deploy.git_notes {
+        let note = render_message(
+            "git_note",
+            templates.and_then(|t| t.git_note.as_deref()),
+            || templating::Variables {
+                tag: tag.clone(),
+                ..template_variables(&project_config, Platform::Android).with_summary(&summary)
+            },
+        );
+        let note = note.unwrap_or_else(|| {
+            release_note(
+                &format!(
+                    "Released {} to Google Play ({} track)",
+                    version, android.track
+                ),
+                &[
+                    ("Platform", Some(Platform::Android.name().to_string())),
+                    ("Package", Some(android.package_name.clone())),
+                    ("Version", summary.version.clone()),
+                    ("Build", summary.build.clone()),
+                    ("Tag", tag.clone()),
+                    ("Uploader", uploader()),
+                    ("CI", integrations::ci_run_url()),
+                ],
+            )
+        });
+        annotate_release(&project_config.deploy, &note);
+    }
+
+    record_deploy(&DeployRecord {
+        bundle_id: android.package_name.clone(),
+        platform: Platform::Android.name().to_string(),
+        version: summary.version.clone().unwrap_or_default(),
+        build: version_code.to_string(),
+        deployed_at: history::now(),
+        git_sha: summary.git_sha.clone(),
+        tag,
+        size: std::fs::metadata(&aab).ok().map(|m| m.len()),
+        sha256: artifact::sha256(&aab),
+        frameworks: Default::default(),
+        entitlements: Default::default(),
+        thinned_sizes: Default::default(),
+        processing_secs: None,
+        upload_skipped: false,
+    });
+
+    let mut upload_context = hook_context.clone();
+    if let (Some(context), Ok(serde_json::Value::Object(fields))) = (
+        upload_context.as_object_mut(),
+        serde_json::to_value(&summary),
+    ) {
+        context.extend(fields);
+    }
+    if let Err(e) = hooks::run(
+        &project_config.hooks,
+        HookEvent::PostUpload,
+        &upload_context,
+    ) {
+        ui::warn(&e.to_string());
+    }
+
+    ui::header("Deploy Complete!");
+    ui::blank();
+    ui::field("Version", &version);
+    ui::field("Google Play", &format!("{} track", android.track));
+    ui::blank();
+
+    Ok(())
+}
+
+/// Create (and optionally push) the release tag, with the `tag_message` template or
+/// "Release <tag>"; returns it if it was created
+fn tag_release(tag: &str, message: Option<String>, deploy: &DeploySettings) -> Option<String> {
+    ui::step(&format!("Creating git tag {}...", tag));
+
+    let message = message.unwrap_or_else(|| format!("Release {}", tag));
+    if let Err(e) = create_git_tag(tag, &message, deploy, None) {
+        ui::warn(&format!("Failed to create tag: {}", e));
+        return None;
+    }
+    ui::success(&format!("Created tag {}", tag));
+
+    // Only this tag: `--tags` would also send every stale local one
+    if deploy.push_tags {
+        let refspec = format!("refs/tags/{}", tag);
+        match git::run(&["push", deploy.remote(), &refspec], &[]) {
+            Ok(()) => ui::success(&format!("Pushed tag to {}", deploy.remote())),
+            Err(e) => ui::warn(&format!("Failed to push tag: {}", e)),
+        }
+    }
+
+    Some(tag.to_string())
+}
+
+/// Commit the version and build number fastlane wrote into the project and, with
+/// `push`, push the current branch. Only files under the iOS project are
+/// committed; failures only warn.
+fn commit_version_bump(deploy: &DeploySettings, ios_path: &Path, version: &str, push: bool) {
+    match git::has_changes(ios_path) {
+        Ok(true) => {}
+        Ok(false) => return,
+        Err(e) => {
+            ui::warn(&format!("Failed to commit the version bump: {}", e));
+            return;
+        }
+    }
+
+    let message = format!("Bump version to {}", version);
+    if let Err(e) = git::run(&["commit", "-m", &message, "--"], &[ios_path]) {
+        ui::warn(&format!("Failed to commit the version bump: {}", e));
+        return;
+    }
+    ui::success(&format!("Committed version bump to {}", version));
+    if !push {
+        return;
+    }
+
+    // A detached checkout (a `watch` worktree) pushes to the branch it was made for
+    let target = match (git::branch(), std::env::var("LAUNCHPAD_WATCH_BRANCH")) {
+        (Some(_), _) => "HEAD".to_string(),
+        (None, Ok(branch)) if !branch.is_empty() => format!("HEAD:refs/heads/{}", branch),
+        (None, _) => {
+            ui::warn("HEAD is detached, so the version bump wasn't pushed; push it to a branch");
+            return;
+        }
+    };
+    match git::run(&["push", deploy.remote(), &target], &[]) {
+        Ok(()) => ui::success(&format!("Pushed version bump to {}", deploy.remote())),
+        Err(e) => ui::warn(&format!("Failed to push the version bump: {}", e)),
+    }
+}
+
+/// The built-in deploy note: a headline and one "Label: value" line per known field
+fn release_note(headline: &str, fields: &[(&str, Option<String>)]) -> String {
+    let mut note = format!("{}\n\n", headline);
+    for (label, value) in fields {
+        if let Some(value) = value {
+            note.push_str(&format!("{}: {}\n", label, value));
+        }
+    }
+    note
+}
+
+/// Attach the release to the deployed commit as a git note, so `git log` shows deploy
+/// history; the notes ref is pushed along with the tags. Failures only warn.
+fn annotate_release(deploy: &DeploySettings, note: &str) {
+    let notes_ref = match deploy.notes_ref.as_deref() {
+        Some(name) if name.starts_with("refs/") => name.to_string(),
+        Some(name) => format!("refs/notes/{}", name),
+        None => "refs/notes/commits".to_string(),
+    };
+
+    // Append, so a commit deployed twice keeps both entries
+    let added = git::run(
+        &[
+            "notes",
+            "--ref",
+            &notes_ref,
+            "append",
+            "-m",
+            note.trim_end(),
+            "HEAD",
+        ],
+        &[],
+    );
+    if let Err(e) = added {
+        ui::warn(&format!("Failed to add git note: {}", e));
+        return;
+    }
+    ui::success(&format!("Added deploy note ({})", notes_ref));
+
+    if deploy.push_tags {
+        match git::run(&["push", deploy.remote(), &notes_ref], &[]) {
+            Ok(()) => ui::success(&format!("Pushed deploy note to {}", deploy.remote())),
+            Err(e) => ui::warn(&format!("Failed to push {}: {}", notes_ref, e)),
+        }
+    }
+}
+
+/// Who ran the deploy: the CI user that triggered it, else the local git identity
+fn uploader() -> Option<String> {
+    let var = |name: &str| std::env::var(name).ok().filter(|v| !v.is_empty());
+    if let Some(actor) = var("GITHUB_ACTOR").or_else(|| var("GITLAB_USER_LOGIN")) {
+        return Some(actor);
+    }
+
+    match (
+        git::config_value("user.name"),
+        git::config_value("user.email"),
+    ) {
+        (Some(name), Some(email)) => Some(format!("{} <{}>", name, email)),
+        (Some(name), None) => Some(name),
+        (None, Some(email)) => Some(email),
+        (None, None) => var("USER"),
+    }
+}
+
+/// Add the deploy to the history used by `launchpad diff`; failures only warn
+fn record_deploy(record: &DeployRecord) {
+    if let Err(e) = history::append(record) {
+        ui::warn(&format!("Failed to record deploy history: {}", e));
+    }
+}
+
+/// Refuse to build from a checkout missing submodules or Git LFS content: the
+/// build can still succeed and upload, without the assets. Runs even with
+/// --skip-git-check; a repository gix can't read only warns.
+fn check_checkout() -> Result<(), DeployError> {
+    match git::submodule_problems() {
+        Ok(Some(problems)) if !problems.is_empty() => {
+            return Err(DeployError::SubmodulesNotReady(problems.join(", ")))
+        }
+        Ok(_) => {}
+        Err(e) => ui::warn(&format!("Couldn't check submodules: {}", e)),
+    }
+
+    match git::lfs_pointers() {
+        Ok(Some(pointers)) if !pointers.is_empty() => {
+            let mut files = pointers[..pointers.len().min(3)].join(", ");
+            if pointers.len() > 3 {
+                files.push_str(", …");
+            }
+            Err(DeployError::LfsNotPulled {
+                count: pointers.len(),
+                files,
+            })
+        }
+        Ok(_) => Ok(()),
+        Err(e) => {
+            ui::warn(&format!("Couldn't check Git LFS files: {}", e));
+            Ok(())
+        }
+    }
+}
+
+/// Show what is about to ship and ask before anything is built
+fn confirm_deploy(rows: &[(&str, String)], non_interactive: bool) -> Result<(), DeployError> {
+    ui::header("Deploy Preview");
+    for (label, value) in rows {
+        ui::field(label, value);
+    }
+    ui::blank();
+
+    if non_interactive {
+        ui::step("Skipping confirmation (--yes)");
+        return Ok(());
+    }
+
+    if !std::io::stdin().is_terminal() {
+        return Err(DeployError::ConfirmationRequired);
+    }
+
+    let proceed = Confirm::new()
+        .with_prompt("Start deploy?")
+        .default(false)
+        .interact()
+        .map_err(|e| DeployError::Io(std::io::Error::other(e)))?;
+
+    if !proceed {
+        return Err(DeployError::Cancelled);
+    }
+
+    Ok(())
+}
+
+fn describe_testflight_destination(deploy: &DeploySettings) -> String {
+    let mut destination = if deploy.groups.is_empty() {
+        "TestFlight".to_string()
+    } else {
+        format!("TestFlight {} {}", ui::arrow(), deploy.groups.join(", "))
+    };
+
+    if deploy.distribution == DistributionMode::Staged {
+        destination.push_str(" (external groups after promote-beta)");
+    }
+    if !deploy.notify_testers {
+        destination.push_str(", testers not notified");
+    }
+
+    destination
+}
+
+/// Wait for the uploaded build to process, then hand it to the configured groups
+async fn distribute_build(
+    global_config: &GlobalConfig,
+    project_config: &ProjectConfig,
+    version: &str,
+) -> Result<DistributionReport, AscError> {
+    let client = AscClient::new(&global_config.apple)?;
+    let app_id = client
+        .project_app_id(
+            &project_config.project.bundle_id,
+            Some(&project_config.project),
+        )
+        .await?;
+
+    let (version_number, build_number) = fastlane::split_version(version);
+    let build = testflight::wait_for_build(
+        &client,
+        &app_id,
+        build_number.as_deref(),
+        Some(version_number.as_str()).filter(|v| *v != "unknown"),
+    )
+    .await?;
+
+    // External groups can't get a build that's missing compliance
+    if let Some(compliance) = &project_config.compliance {
+        match testflight::answer_compliance(&client, &build, compliance).await {
+            Ok(()) => ui::success("Answered export compliance"),
+            Err(e) => ui::warn(&format!("Failed to answer export compliance: {}", e)),
+        }
+    }
+
+    let deploy = &project_config.deploy;
+    testflight::distribute(
+        &client,
+        &app_id,
+        &build,
+        &deploy.groups,
+        deploy.distribution,
+        deploy.notify_testers,
+    )
+    .await
+}
+
+/// Wait for the uploaded build to process without distributing it
+async fn wait_for_processing(
+    global_config: &GlobalConfig,
+    project_config: &ProjectConfig,
+    version: &str,
+) -> Result<(), AscError> {
+    let client = AscClient::new(&global_config.apple)?;
+    let app_id = client
+        .project_app_id(
+            &project_config.project.bundle_id,
+            Some(&project_config.project),
+        )
+        .await?;
+
+    let (version_number, build_number) = fastlane::split_version(version);
+    testflight::wait_for_build(
+        &client,
+        &app_id,
+        build_number.as_deref(),
+        Some(version_number.as_str()).filter(|v| *v != "unknown"),
+    )
+    .await
+    .map(|_| ())
+}
+
+/// Check that App Store Connect lists the build fastlane reported. Only a build
+/// that never shows up fails the deploy; an API error just leaves it unverified.
+async fn verify_upload(
+    global_config: &GlobalConfig,
+    project_config: &ProjectConfig,
+    lane: &LaneResult,
+) -> Result<(), FastlaneError> {
+    let number = match &lane.build {
+        Some(number) => number,
+        None => {
+            ui::warn("Couldn't verify the upload: fastlane didn't report a build number");
+            return Ok(());
+        }
+    };
+
+    events::phase(
+        "verify",
+        "Checking that App Store Connect received the build",
+    );
+    let spinner = ui::spinner("Checking that App Store Connect received the build...");
+    let result = async {
+        let client = AscClient::new(&global_config.apple)?;
+        let app_id = client
+            .project_app_id(
+                &project_config.project.bundle_id,
+                Some(&project_config.project),
+            )
+            .await?;
+        testflight::wait_for_upload(&client, &app_id, number, lane.version.as_deref()).await
+    }
+    .await;
+    spinner.finish_and_clear();
+
+    match result {
+        Ok(Some(build)) => {
+            ui::success(&format!(
+                "App Store Connect received build {}",
+                build.number
+            ));
+            Ok(())
+        }
+        Ok(None) => Err(FastlaneError::NotReceived(lane.label())),
+        Err(e) => {
+            ui::warn(&format!("Couldn't verify the upload: {}", e));
+            Ok(())
+        }
+    }
+}
+
+/// Post a deploy outcome to the matching `[[notifications.rules]]` and email it when
+/// `[email]` asks for it; failures only warn
+pub async fn notify(
+    global_config: Option<&GlobalConfig>,
+    project_config: &ProjectConfig,
+    platform: Platform,
+    event: NotificationEvent,
+    summary: &DeploySummary,
+) {
+    let (app, bundle_id) = app_identity(project_config, platform);
+
+    if let Some(settings) = &project_config.notifications {
+        let template = project_config
+            .templates
+            .as_ref()
+            .and_then(|t| t.notifications.for_event(event));
+        let text = render_message(&format!("notifications.{}", event), template, || {
+            templating::Variables {
+                event: Some(event),
+                ..template_variables(project_config, platform).with_summary(summary)
+            }
+        });
+        for (target, result) in notifications::notify(settings, event, app, summary, text).await {
+            match result {
+                Ok(()) => ui::success(&format!("Posted {} notification to {}", event, target)),
+                Err(e) => ui::warn(&format!("Failed to notify {}: {}", target, e)),
+            }
+        }
+    }
+
+    let Some(settings) =
+        email::settings(global_config, project_config).filter(|s| email::wants(s, event))
+    else {
+        return;
+    };
+    let public_link = project_config.integrations.public_link();
+    let environment = notifications::environment(project_config.notifications.as_ref());
+    let context = email::EmailContext {
+        event,
+        app,
+        bundle_id,
+        platform: platform.name(),
+        environment: environment.as_deref(),
+        public_link,
+        summary,
+    };
+    match email::send(&settings, &context).await {
+        Ok(recipients) => ui::success(&format!(
+            "Emailed the {} summary to {} recipient{}",
+            event,
+            recipients,
+            if recipients == 1 { "" } else { "s" }
+        )),
+        Err(e) => ui::warn(&format!("Failed to send summary email: {}", e)),
+    }
+}
+
+/// Display name and identifier of the app being deployed
+pub fn app_identity(project_config: &ProjectConfig, platform: Platform) -> (&str, &str) {
+    match (platform, &project_config.android) {
+        (Platform::Android, Some(android)) => (&android.package_name, &android.package_name),
+        _ => (
+            &project_config.project.scheme,
+            &project_config.project.bundle_id,
+        ),
+    }
+}
+
+/// `[templates]` variables every message shares; callers add the summary and tag
+fn template_variables(project_config: &ProjectConfig, platform: Platform) -> templating::Variables {
+    let (app, bundle_id) = app_identity(project_config, platform);
+    let sha = git::head_sha();
+    let public_link = match platform {
+        Platform::Ios => project_config
+            .integrations
+            .public_link()
+            .map(str::to_string),
+        Platform::Android => None,
+    };
+    templating::Variables {
+        app: app.to_string(),
+        bundle_id: bundle_id.to_string(),
+        platform: platform.name().to_string(),
+        environment: notifications::environment(project_config.notifications.as_ref()),
+        short_sha: sha.as_ref().map(|sha| sha[..sha.len().min(7)].to_string()),
+        commits: history::commits_since_last_deploy(bundle_id, platform.name(), sha.as_deref()),
+        sha,
+        author: uploader(),
+        store: match platform {
+            Platform::Ios => "TestFlight".to_string(),
+            Platform::Android => "Google Play".to_string(),
+        },
+        public_link,
+        ci_url: integrations::ci_run_url(),
+        ..Default::default()
+    }
+}
+
+/// Render a `[templates]` entry; None keeps the built-in text, when the entry isn't
+/// set or doesn't render. Variables are only gathered for a template.
+fn render_message(
+    name: &str,
+    template: Option<&str>,
+    variables: impl FnOnce() -> templating::Variables,
+) -> Option<String> {
+    match templating::render(name, template?, &variables()) {
+        Ok(text) => Some(text),
+        Err(e) => {
+            ui::warn(&format!("{}; using the built-in text", e));
+            None
+        }
+    }
+}
+
+/// Whether a chat rule or the summary email is waiting for App Store Connect processing
+fn wants_processed(global_config: &GlobalConfig, project_config: &ProjectConfig) -> bool {
+    notifications::wants(
+        project_config.notifications.as_ref(),
+        NotificationEvent::Processed,
+    ) || email::settings(Some(global_config), project_config)
+        .is_some_and(|s| email::wants(&s, NotificationEvent::Processed))
+}
+
+/// Keep a distribution that couldn't reach App Store Connect for `launchpad retry-pending`
+fn queue_distribution(
+    project_config: &ProjectConfig,
+    environment: Option<&str>,
+    version: &str,
+    error: &AscError,
+) {
+    let deploy = &project_config.deploy;
+    let operation = PendingOperation {
+        bundle_id: project_config.project.bundle_id.clone(),
+        version: version.to_string(),
+        queued_at: history::now(),
+        key_profile: environment
+            .and_then(|name| project_config.environments[name].apple.key_profile.clone()),
+        action: PendingAction::Distribute {
+            groups: deploy.groups.clone(),
+            distribution: deploy.distribution,
+            notify_testers: deploy.notify_testers,
+        },
+        attempts: 0,
+        last_error: error.to_string(),
+    };
+
+    match history::queue_pending(operation) {
+        Ok(()) => ui::warn(&format!(
+            "Couldn't reach App Store Connect to distribute the build ({}). Queued; run 'launchpad retry-pending' to finish it",
+            error
+        )),
+        Err(queue_error) => ui::warn(&format!(
+            "Failed to distribute build: {} (and couldn't queue it: {})",
+            error, queue_error
+        )),
+    }
+}
+
+/// "1.2.0 (14) → 1.2.0 (15)", mirroring the lanes: bump the build number, or
+/// bump the version and reset the build number to 1
+fn describe_version_change(current: Option<&TargetVersion>, version_bump: Option<&str>) -> String {
+    let (Some(version), Some(build)) = (
+        current.and_then(|t| t.marketing_version.as_deref()),
+        current.and_then(|t| t.build_number.as_deref()),
+    ) else {
+        return "unknown".to_string();
+    };
+
+    match next_version(current, version_bump) {
+        Some((next, next_build)) => format!(
+            "{} ({}) {} {} ({})",
+            version,
+            build,
+            ui::arrow(),
+            next,
+            next_build
+        ),
+        None => format!("{} ({})", version, build),
+    }
+}
+
+/// Version and build number the lane will give the app
+fn next_version(
+    current: Option<&TargetVersion>,
+    version_bump: Option<&str>,
+) -> Option<(String, String)> {
+    let version = current?.marketing_version.as_deref()?;
+    let build = current?.build_number.as_deref()?;
+    match version_bump {
+        Some(bump) => version::bump(version, bump).map(|next| (next, "1".to_string())),
+        None => build
+            .parse::<u64>()
+            .ok()
+            .map(|build| (version.to_string(), (build + 1).to_string())),
+    }
+}
+
+fn write_summary(path: &Path, summary: &DeploySummary) {
+    events::emit("summary", json!({ "summary": summary }));
+    match summary.write(path) {
+        Ok(()) => ui::success(&format!("Wrote deploy summary to {}", path.display())),
+        Err(e) => ui::warn(&format!("Failed to write deploy summary: {}", e)),
+    }
+}
+
+/// Find the most recently modified file ending in `suffix` across `dirs`
+fn find_artifact(dirs: &[&Path], suffix: &str) -> Option<PathBuf> {
+    dirs.iter()
+        .filter_map(|dir| std::fs::read_dir(dir).ok())
+        .flat_map(|entries| entries.flatten())
+        .filter(|entry| entry.file_name().to_string_lossy().ends_with(suffix))
+        .filter_map(|entry| {
+            let modified = entry.metadata().and_then(|m| m.modified()).ok()?;
+            Some((modified, entry.path()))
+        })
+        .max_by_key(|(modified, _)| *modified)
+        .map(|(_, path)| path)
+}
+
+/// Print the App Thinning sizes per device class. Budgets are enforced by the
+/// lane before uploading, so one exceeded here means the Fastfile predates that check.
+fn report_thinned_sizes(output_dir: &Path, size: &SizeSettings) -> BTreeMap<String, ThinnedSize> {
+    let Ok(report) = std::fs::read_to_string(output_dir.join(thinning::REPORT_FILENAME)) else {
+        ui::warn("No App Thinning size report was exported; run 'launchpad template update' to add it to the Fastfile");
+        return BTreeMap::new();
+    };
+
+    let variants = thinning::parse(&report);
+    let classes = thinning::by_class(&variants);
+    size::print_sizes(&classes);
+
+    match thinning::check_budgets(&variants, size) {
+        Ok(budgets) => {
+            for problem in budgets.exceeded {
+                ui::warn(&format!(
+                    "{}; uploaded anyway (run 'launchpad template update' so the lane checks budgets)",
+                    problem
+                ));
+            }
+        }
+        Err(e) => ui::warn(&e.to_string()),
+    }
+    classes
+}
+
+/// Warn about binary size problems in the built .ipa; never fails the deploy
+fn analyze_binary(ipa: &Path, large_framework: &str) {
+    let Some(threshold) = thinning::parse_size(large_framework) else {
+        ui::warn(&format!(
+            "Invalid analysis.large_framework '{}' (expected e.g. \"50 MB\")",
+            large_framework
+        ));
+        return;
+    };
+
+    let spinner = ui::spinner("Analyzing the binary...");
+    let warnings = ipa::analyze(ipa, threshold);
+    spinner.finish_and_clear();
+    for warning in &warnings {
+        ui::warn(warning);
+    }
+}
+
+/// Warn about devices in devices.toml the ad-hoc or development build won't
+/// install on; never fails the deploy
+fn check_test_devices(ipa: &Path) {
+    let registry = match Registry::load() {
+        Ok(Some(registry)) if !registry.devices.is_empty() => registry,
+        Ok(_) => return,
+        Err(e) => {
+            ui::warn(&format!("Test devices not checked: {}", e));
+            return;
+        }
+    };
+
+    let work = std::env::temp_dir().join(format!("launchpad-devices-{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&work);
+    let provisioning = ipa::extract_app(ipa, &work)
+        .and_then(|app| profiles::read_provisioning(&app.join("embedded.mobileprovision")));
+    let _ = std::fs::remove_dir_all(&work);
+    let Some(provisioning) = provisioning else {
+        return;
+    };
+
+    let missing: Vec<&str> = registry
+        .devices
+        .iter()
+        .filter(|d| !provisioning.allows_device(&d.udid))
+        .map(|d| d.name.as_str())
+        .collect();
+    if !missing.is_empty() {
+        ui::warn(&format!(
+            "{} of the team's test devices can't install this build ({}); run 'launchpad devices sync' and regenerate '{}'",
+            missing.len(),
+            missing.join(", "),
+            provisioning.profile.name
+        ));
+    }
+}
+
+/// Branch being built. CI checkouts are often detached, so ask the CI first.
+fn git_branch() -> Option<String> {
+    let var = |name: &str| std::env::var(name).ok().filter(|v| !v.is_empty());
+    if let Some(branch) = var("GITHUB_HEAD_REF")
+        .or_else(|| var("GITHUB_REF_NAME"))
+        .or_else(|| var("CI_COMMIT_REF_NAME"))
+    {
+        return Some(branch);
+    }
+
+    git::branch()
+}
+
+/// `[build.metadata]` Info.plist keys for this build; unknown values are left out
+fn build_metadata(settings: &MetadataSettings) -> BTreeMap<String, String> {
+    let prefix = &settings.key_prefix;
+    let values = [
+        ("GitSHA", git::head_sha()),
+        ("GitBranch", git_branch()),
+        ("BuildDate", Some(iso8601(history::now()))),
+        ("Version", Some(env!("CARGO_PKG_VERSION").to_string())),
+    ];
+    values
+        .into_iter()
+        .filter_map(|(key, value)| Some((format!("{}{}", prefix, key), value?)))
+        .collect()
+}
+
+/// Unix seconds as "2024-05-01T12:00:00Z"
+fn iso8601(secs: u64) -> String {
+    plist::Date::from(std::time::UNIX_EPOCH + std::time::Duration::from_secs(secs)).to_xml_format()
+}
+
+/// How `git tag` annotates: signed with `deploy.tag_sign`, else a plain annotated tag
+fn tag_flags(deploy: &DeploySettings) -> Vec<&str> {
+    match (deploy.tag_sign, &deploy.tag_signing_key) {
+        (true, Some(key)) => vec!["-u", key],
+        (true, None) => vec!["-s"],
+        (false, _) => vec!["-a"],
+    }
+}
+
+/// Create a release tag on `target` (default HEAD); signed tags need the git binary
+pub fn create_git_tag(
+    tag: &str,
+    message: &str,
+    deploy: &DeploySettings,
+    target: Option<&str>,
+) -> Result<(), GitError> {
+    if !deploy.tag_sign {
+        return git::create_tag(tag, message, target);
+    }
+    let mut args = vec!["tag"];
+    args.extend(tag_flags(deploy));
+    args.extend([tag, "-m", message, target.unwrap_or("HEAD")]);
+    git::run(&args, &[])
+}
+
+/// Remove what this deploy built: the lane's IPA, the scheme's archive, the size
+/// report and fastlane temp files. The dSYM zip stays for symbolicating crashes,
+/// and other builds' outputs sharing the directory are left alone. Returns the
+/// number of entries removed.
+fn clean_artifacts(
+    output_dir: &Path,
+    ios_path: &Path,
+    ipa: Option<&Path>,
+    scheme: &str,
+) -> Result<usize, std::io::Error> {
+    let mut removed = 0;
+
+    let thinning_report = output_dir.join(thinning::REPORT_FILENAME);
+    let files = [ipa.map(Path::to_path_buf), Some(thinning_report)];
+    for file in files.into_iter().flatten() {
+        if file.is_file() {
+            std::fs::remove_file(&file)?;
+            removed += 1;
+        }
+    }
+
+    // The Fastfile archives into output_dir; hand-written lanes fall back to
+    // fastlane's default of writing next to the project
+    for dir in [output_dir, ios_path] {
+        let archive = dir.join(format!("{}.xcarchive", scheme));
+        if archive.is_dir() {
+            std::fs::remove_dir_all(&archive)?;
+            removed += 1;
+        }
+    }
+
+    let report = ios_path.join("fastlane").join("report.xml");
+    if report.exists() {
+        std::fs::remove_file(&report)?;
+        removed += 1;
+    }
+
+    // Nothing left to reuse
+    BuildStamp::remove(output_dir)?;
+
+    // Drop the output directory itself once it's empty
+    if output_dir != ios_path {
+        let _ = std::fs::remove_dir(output_dir);
+    }
+
+    Ok(removed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn release_tags_are_prefixed_per_platform() {
+        assert_eq!(Platform::Ios.release_tag("1.2.0", "43"), "v1.2.0-43");
+        assert_eq!(
+            Platform::Android.release_tag("1.2.0", "43"),
+            "android/v1.2.0-43"
+        );
+    }
+
+    #[test]
+    fn created_release_tags_are_recognized() {
+        for platform in [Platform::Ios, Platform::Android] {
+            let tag = platform.release_tag("1.2.0", "43");
+            assert!(Platform::is_release_tag(&tag), "{}", tag);
+        }
+    }
+
+    #[test]
+    fn release_tags_are_recognized() {
+        assert!(Platform::is_release_tag("v1.2.0-43"));
+        assert!(Platform::is_release_tag("android/v1.2.0-43"));
+        assert!(Platform::is_release_tag("v2.0-43.1"));
+        assert!(!Platform::is_release_tag("release/1.2.0"));
+        assert!(!Platform::is_release_tag("v1.2.0"));
+        assert!(!Platform::is_release_tag("v1.2.0-beta"));
+        assert!(!Platform::is_release_tag("vnext-1"));
+    }
+}
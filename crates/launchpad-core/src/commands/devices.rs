@@ -0,0 +1,276 @@
+use crate::asc::{AscClient, AscError, PortalDevice};
+use crate::config;
+use crate::config::global::GlobalConfig;
+use crate::config::project::ProjectConfig;
+use crate::devices::{self, DevicesError, Registry, TestDevice, REGISTRY_FILENAME};
+use crate::ui;
+use clap::Subcommand;
+use dialoguer::Confirm;
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+pub enum DevicesCommandError {
+    #[error("Global config not found. Run 'launchpad setup' first.")]
+    NoGlobalConfig,
+
+    #[error("No {REGISTRY_FILENAME} here. Add a device with 'launchpad devices add', or run 'launchpad devices sync' to start from the Developer portal.")]
+    NoRegistry,
+
+    #[error("'{0}' isn't a UDID (expected 00008030-001A2B3C4D5E6F70, or 40 hex digits on older devices)")]
+    InvalidUdid(String),
+
+    #[error("{udid} is already in {REGISTRY_FILENAME} as '{name}'")]
+    AlreadyListed { udid: String, name: String },
+
+    #[error("{count} device{} couldn't be registered", if *.count == 1 { "" } else { "s" })]
+    Failed { count: usize },
+
+    #[error("Sync cancelled")]
+    Cancelled,
+
+    #[error("{0}")]
+    Devices(#[from] DevicesError),
+
+    #[error("{0}")]
+    Asc(#[from] AscError),
+
+    #[error("IO error: {0}")]
+    Io(#[from] std::io::Error),
+
+    #[error("Config error: {0}")]
+    Config(String),
+}
+
+#[derive(Subcommand)]
+pub enum DevicesCommand {
+    /// List the team's test devices
+    List,
+
+    /// Add a test device to devices.toml
+    Add {
+        /// The device's UDID (Finder or Xcode > Devices and Simulators shows it)
+        udid: String,
+
+        /// Name on the Developer portal
+        #[arg(long)]
+        name: String,
+
+        /// Who has the device
+        #[arg(long)]
+        owner: Option<String>,
+
+        /// iOS version it runs
+        #[arg(long, value_name = "VERSION")]
+        ios_version: Option<String>,
+    },
+
+    /// Register new devices on the Developer portal and add the ones only registered there
+    Sync {
+        /// Show what would change without changing anything
+        #[arg(long)]
+        dry_run: bool,
+    },
+}
+
+pub async fn run(
+    command: DevicesCommand,
+    non_interactive: bool,
+) -> Result<(), DevicesCommandError> {
+    match command {
+        DevicesCommand::List => list(),
+        DevicesCommand::Add {
+            udid,
+            name,
+            owner,
+            ios_version,
+        } => add(udid, name, owner, ios_version),
+        DevicesCommand::Sync { dry_run } => sync(dry_run, non_interactive).await,
+    }
+}
+
+fn list() -> Result<(), DevicesCommandError> {
+    ui::header("Test Devices");
+
+    let registry = Registry::load()?.ok_or(DevicesCommandError::NoRegistry)?;
+    if registry.devices.is_empty() {
+        ui::step(&format!("No devices in {}", REGISTRY_FILENAME));
+        return Ok(());
+    }
+    let rows: Vec<Vec<String>> = registry
+        .devices
+        .iter()
+        .map(|d| {
+            vec![
+                d.name.clone(),
+                d.udid.clone(),
+                d.owner.clone().unwrap_or_else(|| "-".to_string()),
+                d.ios_version.clone().unwrap_or_else(|| "-".to_string()),
+            ]
+        })
+        .collect();
+    ui::table(&["Name", "UDID", "Owner", "iOS"], &rows);
+    Ok(())
+}
+
+fn add(
+    udid: String,
+    name: String,
+    owner: Option<String>,
+    ios_version: Option<String>,
+) -> Result<(), DevicesCommandError> {
+    ui::header("Add Test Device");
+
+    let udid = udid.trim().to_string();
+    if !devices::valid_udid(&udid) {
+        return Err(DevicesCommandError::InvalidUdid(udid));
+    }
+    let mut registry = Registry::load()?.unwrap_or_default();
+    if let Some(existing) = registry.find(&udid) {
+        return Err(DevicesCommandError::AlreadyListed {
+            udid,
+            name: existing.name.clone(),
+        });
+    }
+
+    registry.devices.push(TestDevice {
+        name: name.clone(),
+        udid,
+        owner,
+        ios_version,
+    });
+    registry.save()?;
+    ui::success(&format!("Added '{}' to {}", name, REGISTRY_FILENAME));
+    ui::step("'launchpad devices sync' registers it on the Developer portal");
+    Ok(())
+}
+
+/// Make devices.toml and the portal list the same devices: register what's only
+/// local, add what's only on the portal
+async fn sync(dry_run: bool, non_interactive: bool) -> Result<(), DevicesCommandError> {
+    ui::header("Sync Test Devices");
+
+    let mut global_config = GlobalConfig::load()
+        .map_err(|e| DevicesCommandError::Config(e.to_string()))?
+        .ok_or(DevicesCommandError::NoGlobalConfig)?;
+    // The devices are the team's whose key the environment uses
+    let mut project_config =
+        ProjectConfig::load().map_err(|e| DevicesCommandError::Config(e.to_string()))?;
+    config::use_selected_environment(&mut global_config, project_config.as_mut())
+        .map_err(|e| DevicesCommandError::Config(e.to_string()))?;
+    let mut registry = Registry::load()?.unwrap_or_default();
+    let client = AscClient::new(&global_config.apple)?;
+
+    let spinner = ui::spinner("Fetching devices from the Developer portal...");
+    let portal = client.devices().await;
+    spinner.finish_and_clear();
+    // Macs register as devices too, but never run the app's ad-hoc builds
+    let portal: Vec<PortalDevice> = portal?
+        .into_iter()
+        .filter(|d| d.platform.as_deref().is_none_or(|p| p == "IOS"))
+        .collect();
+    let on_portal = |udid: &str| portal.iter().find(|d| d.udid.eq_ignore_ascii_case(udid));
+
+    let to_register: Vec<TestDevice> = registry
+        .devices
+        .iter()
+        .filter(|d| on_portal(&d.udid).is_none())
+        .cloned()
+        .collect();
+    let to_import: Vec<&PortalDevice> = portal
+        .iter()
+        .filter(|d| registry.find(&d.udid).is_none())
+        .collect();
+    for device in &registry.devices {
+        if let Some(registered) = on_portal(&device.udid) {
+            if registered.status.as_deref() == Some("DISABLED") {
+                ui::warn(&format!(
+                    "'{}' is disabled on the Developer portal; new profiles leave it out until it's enabled there",
+                    device.name
+                ));
+            }
+        }
+    }
+
+    if to_register.is_empty() && to_import.is_empty() {
+        ui::success(&format!(
+            "{} and the Developer portal list the same {} device{}",
+            REGISTRY_FILENAME,
+            registry.devices.len(),
+            if registry.devices.len() == 1 { "" } else { "s" }
+        ));
+        return Ok(());
+    }
+    for device in &to_register {
+        ui::step(&format!("Register '{}' ({})", device.name, device.udid));
+    }
+    for device in &to_import {
+        let model = device
+            .model
+            .as_deref()
+            .map(|m| format!(", {}", m))
+            .unwrap_or_default();
+        ui::step(&format!(
+            "Add '{}' ({}{}) to {}",
+            device.name, device.udid, model, REGISTRY_FILENAME
+        ));
+    }
+    if dry_run {
+        ui::blank();
+        ui::success(&format!(
+            "Would register {} and add {}",
+            to_register.len(),
+            to_import.len()
+        ));
+        return Ok(());
+    }
+
+    // Registering spends one of the team's yearly device slots
+    if !to_register.is_empty() {
+        let proceed = non_interactive
+            || Confirm::new()
+                .with_prompt(format!(
+                    "Register {} device{}? Each one uses a device slot until the membership renews.",
+                    to_register.len(),
+                    if to_register.len() == 1 { "" } else { "s" }
+                ))
+                .default(true)
+                .interact()
+                .map_err(|e| DevicesCommandError::Io(std::io::Error::other(e)))?;
+        if !proceed {
+            return Err(DevicesCommandError::Cancelled);
+        }
+    }
+
+    let mut failed = 0;
+    for device in &to_register {
+        match client.register_device(&device.name, &device.udid).await {
+            Ok(()) => ui::check_pass(&device.name, "registered"),
+            Err(e) => {
+                failed += 1;
+                ui::check_fail(&device.name, &e.to_string());
+            }
+        }
+    }
+    registry
+        .devices
+        .extend(to_import.iter().map(|device| TestDevice {
+            name: device.name.clone(),
+            udid: device.udid.clone(),
+            owner: None,
+            ios_version: None,
+        }));
+    registry.save()?;
+
+    if failed > 0 {
+        return Err(DevicesCommandError::Failed { count: failed });
+    }
+    ui::blank();
+    ui::success(&format!(
+        "{} matches the Developer portal",
+        REGISTRY_FILENAME
+    ));
+    if !to_register.is_empty() {
+        ui::step("Ad-hoc and development profiles only hold the devices registered when they were made: automatic signing regenerates them on the next deploy; with manual signing, regenerate them on the portal");
+    }
+    Ok(())
+}
@@ -0,0 +1,193 @@
+use crate::commands::deploy::Platform;
+use crate::config::project::ProjectConfig;
+use crate::git;
+use crate::history::{self, DeployRecord, HistoryError};
+use crate::ui;
+use console::style;
+use std::collections::BTreeSet;
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+pub enum DiffError {
+    #[error("Project config not found. Run 'launchpad init' first.")]
+    NoProjectConfig,
+
+    #[error("No [android] section in .launchpad.toml")]
+    NoAndroidConfig,
+
+    #[error("Build {0} is not in the deploy history. Only builds deployed with launchpad can be compared.")]
+    BuildNotRecorded(String),
+
+    #[error("{0}")]
+    History(#[from] HistoryError),
+
+    #[error("Config error: {0}")]
+    Config(String),
+}
+
+/// Compare two recorded deploys of this project
+pub async fn run(build_a: String, build_b: String, platform: Platform) -> Result<(), DiffError> {
+    let project_config = ProjectConfig::load()
+        .map_err(|e| DiffError::Config(e.to_string()))?
+        .ok_or(DiffError::NoProjectConfig)?;
+    let app_id = match platform {
+        Platform::Ios => project_config.project.bundle_id.clone(),
+        Platform::Android => project_config
+            .android
+            .as_ref()
+            .ok_or(DiffError::NoAndroidConfig)?
+            .package_name
+            .clone(),
+    };
+
+    let records = history::load()?;
+    let find = |build: &str| {
+        history::find(&records, &app_id, platform.name(), build)
+            .ok_or_else(|| DiffError::BuildNotRecorded(build.to_string()))
+    };
+    let a = find(&build_a)?;
+    let b = find(&build_b)?;
+
+    ui::header(&format!("Build {} {} {}", a.build, ui::arrow(), b.build));
+    ui::blank();
+    ui::field(
+        "Version",
+        &format!(
+            "{} ({}) {} {} ({})",
+            a.version,
+            a.build,
+            ui::arrow(),
+            b.version,
+            b.build
+        ),
+    );
+    ui::field("Size", &describe_size(a.size, b.size));
+    if let (Some(from), Some(to)) = (a.git_ref(), b.git_ref()) {
+        ui::field("Commits", &format!("{}..{}", from, to));
+    }
+
+    diff_frameworks(a, b);
+    diff_entitlements(a, b);
+    print_commits(a, b);
+    ui::blank();
+
+    Ok(())
+}
+
+fn describe_size(a: Option<u64>, b: Option<u64>) -> String {
+    match (a, b) {
+        (Some(a), Some(b)) => format!(
+            "{} {} {} ({})",
+            ui::format_size(a),
+            ui::arrow(),
+            ui::format_size(b),
+            ui::format_size_diff(b as i64 - a as i64)
+        ),
+        _ => "unknown".to_string(),
+    }
+}
+
+fn diff_frameworks(a: &DeployRecord, b: &DeployRecord) {
+    ui::header("Frameworks");
+    if a.frameworks.is_empty() && b.frameworks.is_empty() {
+        ui::step("Not recorded for these builds");
+        return;
+    }
+
+    let names: BTreeSet<&String> = a.frameworks.keys().chain(b.frameworks.keys()).collect();
+    let mut changed = false;
+    for name in names {
+        match (a.frameworks.get(name), b.frameworks.get(name)) {
+            (None, Some(&size)) => added(name, &ui::format_size(size)),
+            (Some(_), None) => removed(name),
+            (Some(&before), Some(&after)) if before != after => modified(
+                name,
+                &format!(
+                    "{} {} {} ({})",
+                    ui::format_size(before),
+                    ui::arrow(),
+                    ui::format_size(after),
+                    ui::format_size_diff(after as i64 - before as i64)
+                ),
+            ),
+            _ => continue,
+        }
+        changed = true;
+    }
+
+    if !changed {
+        ui::success("No changes");
+    }
+}
+
+fn diff_entitlements(a: &DeployRecord, b: &DeployRecord) {
+    ui::header("Entitlements");
+    if a.entitlements.is_empty() && b.entitlements.is_empty() {
+        ui::step("Not recorded for these builds");
+        return;
+    }
+
+    let keys: BTreeSet<&String> = a.entitlements.keys().chain(b.entitlements.keys()).collect();
+    let mut changed = false;
+    for key in keys {
+        match (a.entitlements.get(key), b.entitlements.get(key)) {
+            (None, Some(value)) => added(key, &value.to_string()),
+            (Some(_), None) => removed(key),
+            (Some(before), Some(after)) if before != after => {
+                modified(key, &format!("{} {} {}", before, ui::arrow(), after))
+            }
+            _ => continue,
+        }
+        changed = true;
+    }
+
+    if !changed {
+        ui::success("No changes");
+    }
+}
+
+fn print_commits(a: &DeployRecord, b: &DeployRecord) {
+    ui::header("Commits");
+    let (Some(from), Some(to)) = (a.git_ref(), b.git_ref()) else {
+        ui::step("No git revision recorded for one of the builds");
+        return;
+    };
+
+    let range = format!("{}..{}", from, to);
+    match git::output(&["log", "--oneline", "--no-decorate", &range]) {
+        Ok(log) => {
+            if log.trim().is_empty() {
+                ui::step("No commits in range");
+            }
+            for line in log.lines() {
+                ui::line(format_args!("  {}", line));
+            }
+        }
+        _ => ui::warn(&format!(
+            "Could not read git log {}..{} (fetch tags first?)",
+            from, to
+        )),
+    }
+}
+
+fn added(name: &str, detail: &str) {
+    ui::line(format_args!(
+        "  {} {} {}",
+        style("+").green(),
+        name,
+        style(detail).dim()
+    ));
+}
+
+fn removed(name: &str) {
+    ui::line(format_args!("  {} {}", style("-").red(), name));
+}
+
+fn modified(name: &str, detail: &str) {
+    ui::line(format_args!(
+        "  {} {} {}",
+        style("~").yellow(),
+        name,
+        style(detail).dim()
+    ));
+}
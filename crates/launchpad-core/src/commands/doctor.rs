@@ -0,0 +1,1399 @@
+use crate::asc::{AscClient, AscError};
+use crate::commands::profiles::DEFAULT_WARN_DAYS;
+use crate::config;
+use crate::config::global::GlobalConfig;
+use crate::config::project::{ProjectConfig, SigningStyle};
+use crate::git;
+use crate::history;
+use crate::http;
+use crate::kmp::{self, FrameworkIntegration};
+use crate::profiles;
+use crate::templating;
+use crate::ui;
+use crate::xcode::{self, SchemeSharing, Xcode};
+use console::style;
+use dialoguer::Confirm;
+use std::io::IsTerminal;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::time::{Duration, Instant};
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+pub enum DoctorError {
+    #[error("Prerequisites check failed")]
+    ChecksFailed,
+
+    #[error("{count} warning{} (--strict)", if *.count == 1 { "" } else { "s" })]
+    Warnings { count: usize },
+
+    #[error("No .launchpad.toml found in {0}")]
+    ProjectNotFound(String),
+
+    #[error("IO error: {0}")]
+    Io(#[from] std::io::Error),
+}
+
+struct CheckResult {
+    name: String,
+    status: Status,
+    message: String,
+}
+
+/// Warnings pass unless `--strict` is given
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Status {
+    Pass,
+    Warn,
+    Fail,
+}
+
+impl CheckResult {
+    fn failed(&self, strict: bool) -> bool {
+        match self.status {
+            Status::Pass => false,
+            Status::Warn => strict,
+            Status::Fail => true,
+        }
+    }
+
+    fn print(&self) {
+        match self.status {
+            Status::Pass => ui::check_pass(&self.name, &self.message),
+            Status::Warn => ui::check_warn(&self.name, &self.message),
+            Status::Fail => ui::check_fail(&self.name, &self.message),
+        }
+    }
+}
+
+/// Oldest fastlane without known problems uploading with current Xcode versions
+const RECOMMENDED_FASTLANE: (u64, u64, u64) = (2, 225, 0);
+
+/// Directories never worth descending into when scanning for projects
+const SCAN_SKIP_DIRS: &[&str] = &[
+    ".git",
+    "node_modules",
+    "Pods",
+    "DerivedData",
+    "build",
+    "target",
+    ".build",
+];
+
+/// How deep `--scan` looks for .launchpad.toml files
+const SCAN_MAX_DEPTH: usize = 6;
+
+/// Path fragments of folders that sync to a cloud service
+const SYNCED_DIRS: &[&str] = &[
+    "Library/Mobile Documents",
+    "Library/CloudStorage",
+    "iCloud Drive",
+    "Dropbox",
+    "OneDrive",
+    "Google Drive",
+    "Box Sync",
+];
+
+/// App Store Connect API, timed for latency by `--network`
+const APPLE_API_URL: &str = "https://api.appstoreconnect.apple.com/v1";
+
+/// Where Transporter and altool send .ipa uploads
+const APPLE_UPLOAD_URL: &str = "https://contentdelivery.itunes.apple.com/WebObjects/MZLabelService.woa/json/MZITunesProducerService";
+
+/// A neutral endpoint to compare Apple's upload speed with
+const REFERENCE_UPLOAD_URL: &str = "https://speed.cloudflare.com/__up";
+
+/// Bytes sent to each endpoint to measure upload throughput
+const PROBE_BYTES: usize = 8 * 1024 * 1024;
+
+/// Longest one probe may take; slower than this is its own answer
+const PROBE_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// Apple uploading slower than this share of the reference speed is Apple's doing
+const SLOW_APPLE_SHARE: f64 = 0.5;
+
+/// Below this (in Mbit/s) the connection itself is slow for .ipa uploads
+const SLOW_CONNECTION_MBITS: f64 = 10.0;
+
+/// A problem with how the API key is stored; warns rather than fails
+struct KeyIssue {
+    message: String,
+    /// File whose permissions `chmod 600` would fix
+    fix_permissions: Option<PathBuf>,
+}
+
+pub async fn run(
+    project: Option<String>,
+    scan: Option<String>,
+    network: bool,
+    strict: bool,
+    non_interactive: bool,
+) -> Result<(), DoctorError> {
+    ui::header("Launchpad Doctor");
+    ui::blank();
+
+    if let Some(root) = scan {
+        return run_scan(&root, strict, non_interactive).await;
+    }
+
+    // Project checks resolve paths relative to the project root
+    if let Some(path) = project {
+        if !Path::new(&path).join(".launchpad.toml").exists() {
+            return Err(DoctorError::ProjectNotFound(path));
+        }
+        std::env::set_current_dir(&path)?;
+    }
+
+    let mut checks = global_checks().await;
+    checks.extend(project_checks());
+    if network {
+        checks.extend(network_checks().await);
+    }
+
+    // Display results
+    for check in &checks {
+        check.print();
+    }
+    let mut failed = checks.iter().filter(|c| c.status == Status::Fail).count();
+    if offer_share_scheme(non_interactive)? {
+        failed -= 1;
+    }
+    let warnings = checks.iter().filter(|c| c.status == Status::Warn).count()
+        + report_key_issues(non_interactive)?;
+    ui::blank();
+
+    if failed > 0 {
+        ui::line(format_args!(
+            "{} issue{} found",
+            failed,
+            if failed == 1 { "" } else { "s" }
+        ));
+        return Err(DoctorError::ChecksFailed);
+    }
+    if warnings > 0 {
+        if strict {
+            return Err(DoctorError::Warnings { count: warnings });
+        }
+        ui::success(&format!(
+            "All checks passed with {} warning{} (--strict fails on them)",
+            warnings,
+            if warnings == 1 { "" } else { "s" }
+        ));
+        return Ok(());
+    }
+
+    ui::success("All checks passed!");
+    Ok(())
+}
+
+/// Checks that apply to the machine rather than a project
+async fn global_checks() -> Vec<CheckResult> {
+    let mut checks = vec![check_xcode(), check_fastlane(), check_global_config()];
+    if let Some(agreements_check) = check_agreements().await {
+        checks.push(agreements_check);
+    }
+    checks
+}
+
+/// Checks for the project in the current directory (empty outside a project)
+fn project_checks() -> Vec<CheckResult> {
+    let mut checks: Vec<CheckResult> = Vec::new();
+
+    // Check project config (if in a project)
+    if let Some(project_check) = check_project_config() {
+        checks.push(project_check);
+    }
+
+    // Check Fastfile (if project config exists)
+    if let Some(fastfile_check) = check_fastfile() {
+        checks.push(fastfile_check);
+    }
+
+    // Check the scheme is shared (if project config exists)
+    if let Some(scheme_check) = check_scheme_shared() {
+        checks.push(scheme_check);
+    }
+
+    // Check signing setup (if project config exists)
+    if let Some(signing_check) = check_signing() {
+        checks.push(signing_check);
+    }
+
+    // Check for the legacy build system and Swift versions Xcode can't compile
+    if let Some(build_system_check) = check_build_system() {
+        checks.push(build_system_check);
+    }
+    if let Some(swift_check) = check_swift_version() {
+        checks.push(swift_check);
+    }
+
+    // Check target versions agree (if project config exists)
+    if let Some(versions_check) = check_target_versions() {
+        checks.push(versions_check);
+    }
+
+    // Check the Kotlin framework is wired in (if a KMP project)
+    if let Some(kmp_check) = check_kmp() {
+        checks.push(kmp_check);
+    }
+
+    // Warnings: expiring profiles, no TestFlight notes
+    if let Some(expiry_check) = check_profile_expiry() {
+        checks.push(expiry_check);
+    }
+    if let Some(notes_check) = check_release_notes() {
+        checks.push(notes_check);
+    }
+
+    // Check [templates] parse (if any are set)
+    if let Some(templates_check) = check_templates() {
+        checks.push(templates_check);
+    }
+
+    // Check submodules and LFS files are there (if the repository has any)
+    if let Some(checkout_check) = check_checkout() {
+        checks.push(checkout_check);
+    }
+
+    // Check tags can be signed (if deploy.tag_sign is set)
+    if let Some(tag_signing_check) = check_tag_signing() {
+        checks.push(tag_signing_check);
+    }
+
+    // Check the Fastfile applies [build.metadata] and [environments] (if configured)
+    if let Some(metadata_check) = check_lane_support(
+        "Build metadata",
+        |config| config.build.metadata.is_some(),
+        "LAUNCHPAD_BUILD_METADATA",
+        "Git SHA, branch and build date go into the Info.plist",
+    ) {
+        checks.push(metadata_check);
+    }
+    if let Some(environments_check) = check_lane_support(
+        "Environments",
+        |config| {
+            config
+                .environments
+                .values()
+                .any(|e| !e.build_env.is_empty())
+        },
+        "LAUNCHPAD_XCCONFIG",
+        "build_env settings are applied through an xcconfig",
+    ) {
+        checks.push(environments_check);
+    }
+
+    checks
+}
+
+/// Run project checks for every .launchpad.toml under `root` and print one table
+async fn run_scan(root: &str, strict: bool, non_interactive: bool) -> Result<(), DoctorError> {
+    let global = global_checks().await;
+    for check in &global {
+        check.print();
+    }
+    let key_issues = report_key_issues(non_interactive)?;
+    ui::blank();
+
+    let mut projects = Vec::new();
+    find_projects(Path::new(root), 0, &mut projects);
+    projects.sort();
+
+    if projects.is_empty() {
+        return Err(DoctorError::ProjectNotFound(root.to_string()));
+    }
+
+    let original_dir = std::env::current_dir()?;
+    let mut rows = Vec::new();
+
+    for project in &projects {
+        std::env::set_current_dir(original_dir.join(project))?;
+        let checks = project_checks();
+        std::env::set_current_dir(&original_dir)?;
+
+        let label = match project.strip_prefix(root) {
+            Ok(relative) if !relative.as_os_str().is_empty() => relative.display().to_string(),
+            _ => project.display().to_string(),
+        };
+        rows.push((label, checks));
+    }
+
+    let width = rows.iter().map(|(label, _)| label.len()).max().unwrap_or(0);
+    let mut failed_projects = 0;
+
+    ui::line(format_args!(
+        "  {}  {}  {}",
+        style(format!("{:width$}", "Project")).bold(),
+        style(format!("{:6}", "Checks")).bold(),
+        style("Issues").bold(),
+    ));
+
+    for (label, checks) in &rows {
+        let passed = checks.iter().filter(|c| c.status == Status::Pass).count();
+        let issues = checks
+            .iter()
+            .filter(|c| c.status != Status::Pass)
+            .map(|c| format!("{}: {}", c.name, c.message))
+            .collect::<Vec<_>>()
+            .join("; ");
+
+        let failed = checks.iter().any(|c| c.failed(strict));
+        if failed {
+            failed_projects += 1;
+        }
+
+        ui::line(format_args!(
+            "{} {:width$}  {:6}  {}",
+            ui::mark(!failed),
+            label,
+            format!("{}/{}", passed, checks.len()),
+            style(issues).dim(),
+        ));
+    }
+
+    ui::blank();
+
+    if failed_projects > 0 {
+        ui::line(format_args!(
+            "{} of {} project{} with issues",
+            failed_projects,
+            rows.len(),
+            if rows.len() == 1 { "" } else { "s" }
+        ));
+        return Err(DoctorError::ChecksFailed);
+    }
+    let global_warnings = global.iter().filter(|c| c.status == Status::Warn).count();
+    if strict && global_warnings + key_issues > 0 {
+        return Err(DoctorError::Warnings {
+            count: global_warnings + key_issues,
+        });
+    }
+
+    ui::success(&format!("All {} projects healthy!", rows.len()));
+    Ok(())
+}
+
+/// Collect directories under `dir` that contain a .launchpad.toml
+fn find_projects(dir: &Path, depth: usize, projects: &mut Vec<std::path::PathBuf>) {
+    if dir.join(".launchpad.toml").exists() {
+        projects.push(dir.to_path_buf());
+    }
+
+    if depth >= SCAN_MAX_DEPTH {
+        return;
+    }
+
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return;
+    };
+
+    for entry in entries.flatten() {
+        let name = entry.file_name();
+        let name_str = name.to_string_lossy();
+        let is_dir = entry.file_type().map(|t| t.is_dir()).unwrap_or(false);
+
+        if is_dir && !SCAN_SKIP_DIRS.contains(&name_str.as_ref()) {
+            find_projects(&entry.path(), depth + 1, projects);
+        }
+    }
+}
+
+fn check_xcode() -> CheckResult {
+    if !Xcode::is_installed() {
+        return CheckResult {
+            name: "Xcode".to_string(),
+            status: Status::Fail,
+            message: "Not installed (run: xcode-select --install)".to_string(),
+        };
+    }
+
+    match Xcode::version() {
+        Some(version) => CheckResult {
+            name: "Xcode".to_string(),
+            status: Status::Pass,
+            message: version,
+        },
+        None => CheckResult {
+            name: "Xcode".to_string(),
+            status: Status::Fail,
+            message: "Command line tools only (install Xcode from the App Store)".to_string(),
+        },
+    }
+}
+
+fn check_fastlane() -> CheckResult {
+    match which::which("fastlane") {
+        Ok(_) => {
+            let output = Command::new("fastlane").arg("--version").output();
+            let version = match output {
+                Ok(out) => {
+                    let stdout = String::from_utf8_lossy(&out.stdout);
+                    // fastlane outputs version in format "fastlane X.Y.Z"
+                    stdout
+                        .lines()
+                        .find(|l| l.contains("fastlane"))
+                        .and_then(|l| l.split_whitespace().last())
+                        .unwrap_or("installed")
+                        .to_string()
+                }
+                Err(_) => "installed".to_string(),
+            };
+            match parse_version(&version) {
+                Some(found) if found < RECOMMENDED_FASTLANE => CheckResult {
+                    name: "fastlane".to_string(),
+                    status: Status::Warn,
+                    message: format!(
+                        "{} is older than {}.{}.{} (run: brew upgrade fastlane or bundle update fastlane)",
+                        version,
+                        RECOMMENDED_FASTLANE.0,
+                        RECOMMENDED_FASTLANE.1,
+                        RECOMMENDED_FASTLANE.2
+                    ),
+                },
+                _ => CheckResult {
+                    name: "fastlane".to_string(),
+                    status: Status::Pass,
+                    message: version,
+                },
+            }
+        }
+        Err(_) => CheckResult {
+            name: "fastlane".to_string(),
+            status: Status::Fail,
+            message: "Not installed (run: brew install fastlane)".to_string(),
+        },
+    }
+}
+
+/// "2.219.0" → (2, 219, 0)
+fn parse_version(version: &str) -> Option<(u64, u64, u64)> {
+    let mut parts = version.trim().split('.').map(|p| p.parse::<u64>().ok());
+    Some((
+        parts.next()??,
+        parts.next()??,
+        parts.next().flatten().unwrap_or(0),
+    ))
+}
+
+fn check_global_config() -> CheckResult {
+    match GlobalConfig::load() {
+        Ok(Some(config)) => {
+            let key_path = shellexpand::tilde(&config.apple.key_path).to_string();
+            if Path::new(&key_path).exists() {
+                CheckResult {
+                    name: "Apple API key".to_string(),
+                    status: Status::Pass,
+                    message: format!("Configured ({})", config.apple.key_id),
+                }
+            } else {
+                CheckResult {
+                    name: "Apple API key".to_string(),
+                    status: Status::Fail,
+                    message: format!("Key file not found: {}", key_path),
+                }
+            }
+        }
+        Ok(None) => CheckResult {
+            name: "Apple API key".to_string(),
+            status: Status::Fail,
+            message: "Not configured (run: launchpad setup)".to_string(),
+        },
+        Err(e) => CheckResult {
+            name: "Apple API key".to_string(),
+            status: Status::Fail,
+            message: format!("Config error: {}", e),
+        },
+    }
+}
+
+/// An unsigned agreement makes App Store Connect refuse uploads and API calls
+/// alike, and fastlane only reports it deep in a failed upload
+async fn check_agreements() -> Option<CheckResult> {
+    // Without a usable key the Apple API key check already failed
+    let mut global_config = GlobalConfig::load().ok()??;
+    let mut project_config = ProjectConfig::load().ok().flatten();
+    config::use_selected_environment(&mut global_config, project_config.as_mut()).ok()?;
+    let client = AscClient::new(&global_config.apple).ok()?.without_retries();
+
+    let spinner = ui::spinner("Checking App Store Connect agreements...");
+    let result = client.verify_access().await;
+    spinner.finish_and_clear();
+
+    Some(match result {
+        Ok(()) => CheckResult {
+            name: "Agreements".to_string(),
+            status: Status::Pass,
+            message: "None pending".to_string(),
+        },
+        Err(e @ AscError::AgreementPending(_)) => CheckResult {
+            name: "Agreements".to_string(),
+            status: Status::Fail,
+            message: e.to_string(),
+        },
+        Err(AscError::Api { status: 401, .. }) => CheckResult {
+            name: "Agreements".to_string(),
+            status: Status::Fail,
+            message: "App Store Connect rejected the API key (revoked, or wrong key/issuer ID)"
+                .to_string(),
+        },
+        Err(e) => CheckResult {
+            name: "Agreements".to_string(),
+            status: Status::Warn,
+            message: format!("Could not reach App Store Connect: {}", e),
+        },
+    })
+}
+
+/// Latency to the App Store Connect API and upload throughput to Apple's upload
+/// endpoint next to a neutral one, to tell a slow Apple from a slow connection
+async fn network_checks() -> Vec<CheckResult> {
+    let client = match http::client_builder().timeout(PROBE_TIMEOUT).build() {
+        Ok(client) => client,
+        Err(e) => {
+            return vec![CheckResult {
+                name: "Network".to_string(),
+                status: Status::Fail,
+                message: e.to_string(),
+            }]
+        }
+    };
+    let mut checks = Vec::new();
+
+    let spinner = ui::spinner("Timing the App Store Connect API...");
+    let started = Instant::now();
+    // Any answer, 401 included, means the API is reachable
+    let api = client.get(APPLE_API_URL).send().await;
+    let latency = started.elapsed();
+    spinner.finish_and_clear();
+    checks.push(match api {
+        Ok(_) => CheckResult {
+            name: "Apple API".to_string(),
+            status: Status::Pass,
+            message: format!("Answered in {} ms", latency.as_millis()),
+        },
+        Err(e) => CheckResult {
+            name: "Apple API".to_string(),
+            status: Status::Fail,
+            message: format!("Unreachable: {}", e),
+        },
+    });
+
+    let spinner = ui::spinner(&format!(
+        "Uploading {} to Apple...",
+        ui::format_size(PROBE_BYTES as u64)
+    ));
+    let apple = upload_speed(&client, APPLE_UPLOAD_URL).await;
+    spinner.set_message(format!(
+        "Uploading {} to a reference endpoint...",
+        ui::format_size(PROBE_BYTES as u64)
+    ));
+    let reference = upload_speed(&client, REFERENCE_UPLOAD_URL).await;
+    spinner.finish_and_clear();
+
+    checks.push(match &apple {
+        Ok(mbits) => CheckResult {
+            name: "Upload to Apple".to_string(),
+            status: Status::Pass,
+            message: format!("{:.1} Mbit/s", mbits),
+        },
+        Err(e) => CheckResult {
+            name: "Upload to Apple".to_string(),
+            status: Status::Fail,
+            message: e.clone(),
+        },
+    });
+    checks.push(match &reference {
+        Ok(mbits) => CheckResult {
+            name: "Upload elsewhere".to_string(),
+            status: Status::Pass,
+            message: format!("{:.1} Mbit/s (speed.cloudflare.com)", mbits),
+        },
+        Err(e) => CheckResult {
+            name: "Upload elsewhere".to_string(),
+            status: Status::Warn,
+            message: e.clone(),
+        },
+    });
+
+    let (Ok(apple), reference) = (apple, reference) else {
+        return checks;
+    };
+    if let Ok(reference) = reference {
+        checks.push(if apple < reference * SLOW_APPLE_SHARE {
+            CheckResult {
+                name: "Bottleneck".to_string(),
+                status: Status::Warn,
+                message: format!(
+                    "Apple: {:.1} Mbit/s is under half of what this connection manages elsewhere; retry later or check developer.apple.com/system-status",
+                    apple
+                ),
+            }
+        } else if reference < SLOW_CONNECTION_MBITS {
+            CheckResult {
+                name: "Bottleneck".to_string(),
+                status: Status::Warn,
+                message: "This connection: slow everywhere, not just to Apple; a VPN or proxy is the usual suspect".to_string(),
+            }
+        } else {
+            CheckResult {
+                name: "Bottleneck".to_string(),
+                status: Status::Pass,
+                message: "None; Apple keeps up with this connection".to_string(),
+            }
+        });
+    }
+    if let Some(size) = last_ipa_size() {
+        let secs = (size as f64 * 8.0 / 1_000_000.0 / apple).ceil() as u64;
+        checks.push(CheckResult {
+            name: "Upload estimate".to_string(),
+            status: Status::Pass,
+            message: format!(
+                "{} for the last .ipa ({})",
+                templating::format_duration(secs),
+                ui::format_size(size)
+            ),
+        });
+    }
+    checks
+}
+
+/// Mbit/s sending PROBE_BYTES to `url`; whatever the endpoint answers, the
+/// body has been sent by then
+async fn upload_speed(client: &reqwest::Client, url: &str) -> Result<f64, String> {
+    let started = Instant::now();
+    client
+        .post(url)
+        .header("Content-Type", "application/octet-stream")
+        .body(vec![0u8; PROBE_BYTES])
+        .send()
+        .await
+        .map_err(|e| {
+            if e.is_timeout() {
+                format!(
+                    "Under {:.1} Mbit/s (no answer in {}s)",
+                    PROBE_BYTES as f64 * 8.0 / 1_000_000.0 / PROBE_TIMEOUT.as_secs_f64(),
+                    PROBE_TIMEOUT.as_secs()
+                )
+            } else {
+                format!("Upload failed: {}", e)
+            }
+        })?;
+    Ok(PROBE_BYTES as f64 * 8.0 / 1_000_000.0 / started.elapsed().as_secs_f64())
+}
+
+/// Size of the project's last deployed .ipa, from the deploy history
+fn last_ipa_size() -> Option<u64> {
+    let config = ProjectConfig::load().ok()??;
+    history::load()
+        .ok()?
+        .iter()
+        .rev()
+        .filter(|r| r.bundle_id == config.project.bundle_id && r.platform == "ios")
+        .find_map(|r| r.size)
+}
+
+/// Print key hygiene warnings and offer to tighten permissions; returns how
+/// many are left unfixed
+fn report_key_issues(non_interactive: bool) -> Result<usize, DoctorError> {
+    let issues = check_key_hygiene();
+    for issue in &issues {
+        ui::warn(&issue.message);
+    }
+
+    let Some(path) = issues.iter().find_map(|i| i.fix_permissions.as_ref()) else {
+        return Ok(issues.len());
+    };
+
+    // Non-interactive runs take the default (fix); without a terminal we can only warn
+    let fix = if non_interactive {
+        true
+    } else if std::io::stdin().is_terminal() {
+        Confirm::new()
+            .with_prompt(format!(
+                "Restrict {} to owner read/write (chmod 600)?",
+                path.display()
+            ))
+            .default(true)
+            .interact()
+            .map_err(std::io::Error::other)?
+    } else {
+        false
+    };
+
+    if fix {
+        set_owner_only(path)?;
+        ui::success(&format!("Set {} to 0600", path.display()));
+        return Ok(issues.len() - 1);
+    }
+
+    Ok(issues.len())
+}
+
+/// The .p8 should be private, local-only and never committed
+fn check_key_hygiene() -> Vec<KeyIssue> {
+    let mut issues = Vec::new();
+
+    if let Ok(Some(config)) = GlobalConfig::load() {
+        let key_path = PathBuf::from(shellexpand::tilde(&config.apple.key_path).as_ref());
+
+        if let Some(mode) = permission_mode(&key_path) {
+            if mode & 0o077 != 0 {
+                issues.push(KeyIssue {
+                    message: format!(
+                        "API key {} is accessible by other users (mode {:o}, expected 600)",
+                        key_path.display(),
+                        mode
+                    ),
+                    fix_permissions: Some(key_path.clone()),
+                });
+            }
+        }
+
+        // Follow symlinks so a ~/.launchpad linked into iCloud Drive is caught too
+        let resolved = key_path.canonicalize().unwrap_or(key_path);
+        let resolved_str = resolved.to_string_lossy();
+        if let Some(service) = SYNCED_DIRS.iter().find(|dir| resolved_str.contains(*dir)) {
+            issues.push(KeyIssue {
+                message: format!(
+                    "API key {} is inside a synced folder ({}); keep it in ~/.launchpad/keys",
+                    resolved.display(),
+                    service
+                ),
+                fix_permissions: None,
+            });
+        }
+    }
+
+    for tracked in tracked_keys() {
+        issues.push(KeyIssue {
+            message: format!(
+                "{} is tracked by git; remove it (git rm --cached) and revoke the key",
+                tracked
+            ),
+            fix_permissions: None,
+        });
+    }
+
+    issues
+}
+
+#[cfg(unix)]
+fn permission_mode(path: &Path) -> Option<u32> {
+    use std::os::unix::fs::PermissionsExt;
+    std::fs::metadata(path)
+        .ok()
+        .map(|m| m.permissions().mode() & 0o777)
+}
+
+#[cfg(not(unix))]
+fn permission_mode(_path: &Path) -> Option<u32> {
+    None
+}
+
+#[cfg(unix)]
+fn set_owner_only(path: &Path) -> Result<(), std::io::Error> {
+    use std::os::unix::fs::PermissionsExt;
+    std::fs::set_permissions(path, std::fs::Permissions::from_mode(0o600))
+}
+
+#[cfg(not(unix))]
+fn set_owner_only(_path: &Path) -> Result<(), std::io::Error> {
+    Ok(())
+}
+
+/// .p8 files committed to the repository in the current directory
+fn tracked_keys() -> Vec<String> {
+    match git::output(&["ls-files", "--", "*.p8"]) {
+        Ok(output) => output.lines().map(|l| l.to_string()).collect(),
+        _ => Vec::new(),
+    }
+}
+
+fn check_project_config() -> Option<CheckResult> {
+    if !Path::new(".launchpad.toml").exists() {
+        return None;
+    }
+
+    match ProjectConfig::load() {
+        Ok(Some(config)) => {
+            let ios_path = Path::new(&config.project.ios_path);
+            if ios_path.exists() {
+                Some(CheckResult {
+                    name: "Project".to_string(),
+                    status: Status::Pass,
+                    message: format!(
+                        "{} (scheme: {})",
+                        config.project.ios_path, config.project.scheme
+                    ),
+                })
+            } else {
+                Some(CheckResult {
+                    name: "Project".to_string(),
+                    status: Status::Fail,
+                    message: format!(
+                        "iOS path not found: {} (run: launchpad refresh)",
+                        config.project.ios_path
+                    ),
+                })
+            }
+        }
+        Ok(None) => None,
+        Err(e) => Some(CheckResult {
+            name: "Project".to_string(),
+            status: Status::Fail,
+            message: format!("Config error: {}", e),
+        }),
+    }
+}
+
+fn check_fastfile() -> Option<CheckResult> {
+    let project_config = ProjectConfig::load().ok()??;
+
+    match find_fastfile(&project_config.project.ios_path) {
+        Some(path) => Some(CheckResult {
+            name: "Fastfile".to_string(),
+            status: Status::Pass,
+            message: path,
+        }),
+        None => Some(CheckResult {
+            name: "Fastfile".to_string(),
+            status: Status::Fail,
+            message: "Not found (run: fastlane init in ios directory)".to_string(),
+        }),
+    }
+}
+
+fn find_fastfile(ios_path: &str) -> Option<String> {
+    let fastfile_paths = [
+        format!("{}/fastlane/Fastfile", ios_path),
+        format!("{}/Fastfile", ios_path),
+        "fastlane/Fastfile".to_string(),
+        "Fastfile".to_string(),
+    ];
+
+    fastfile_paths
+        .into_iter()
+        .find(|path| Path::new(path).exists())
+}
+
+/// Installed profiles for the project that expire soon; a profile never outlives
+/// its signing certificate, so this catches expiring certificates too
+fn check_profile_expiry() -> Option<CheckResult> {
+    let project_config = ProjectConfig::load().ok()??;
+
+    let mut bundle_ids = vec![project_config.project.bundle_id.clone()];
+    bundle_ids.extend(project_config.signing.profiles.keys().cloned());
+
+    // Only the newest profile per bundle ID is used for signing
+    let mut newest: Vec<profiles::Profile> = Vec::new();
+    for profile in profiles::installed()
+        .into_iter()
+        .filter(|p| p.matches(&bundle_ids))
+    {
+        match newest.iter_mut().find(|p| p.bundle_id == profile.bundle_id) {
+            Some(existing) if existing.days_left() < profile.days_left() => *existing = profile,
+            Some(_) => {}
+            None => newest.push(profile),
+        }
+    }
+    if newest.is_empty() {
+        return None;
+    }
+
+    let expiring: Vec<String> = newest
+        .iter()
+        .filter_map(|p| {
+            let days = p.days_left()?;
+            (days < DEFAULT_WARN_DAYS).then(|| match days {
+                d if d < 0 => format!("{} expired", p.name),
+                d => format!("{} in {} day{}", p.name, d, if d == 1 { "" } else { "s" }),
+            })
+        })
+        .collect();
+
+    if expiring.is_empty() {
+        return Some(CheckResult {
+            name: "Profiles".to_string(),
+            status: Status::Pass,
+            message: format!(
+                "{} valid for at least {} days",
+                newest.len(),
+                DEFAULT_WARN_DAYS
+            ),
+        });
+    }
+
+    Some(CheckResult {
+        name: "Profiles".to_string(),
+        status: Status::Warn,
+        message: format!("{} (run: launchpad profiles status)", expiring.join(", ")),
+    })
+}
+
+/// The Fastfile should give testers "What to Test" notes
+fn check_release_notes() -> Option<CheckResult> {
+    let project_config = ProjectConfig::load().ok()??;
+    let path = find_fastfile(&project_config.project.ios_path)?;
+    let fastfile = std::fs::read_to_string(&path).ok()?;
+
+    // Newer Fastfiles take the notes from the release_notes template
+    if fastfile.contains("LAUNCHPAD_CHANGELOG") {
+        let templated = project_config
+            .templates
+            .as_ref()
+            .is_some_and(|t| t.release_notes.is_some());
+        return Some(CheckResult {
+            name: "Release notes".to_string(),
+            status: if templated {
+                Status::Pass
+            } else {
+                Status::Warn
+            },
+            message: if templated {
+                "TestFlight changelog from the release_notes template".to_string()
+            } else {
+                "No release_notes under [templates]; testers get no \"What to Test\"".to_string()
+            },
+        });
+    }
+
+    if fastfile.contains("changelog") {
+        return Some(CheckResult {
+            name: "Release notes".to_string(),
+            status: Status::Pass,
+            message: "TestFlight changelog set in the Fastfile".to_string(),
+        });
+    }
+
+    Some(CheckResult {
+        name: "Release notes".to_string(),
+        status: Status::Warn,
+        message: format!(
+            "No changelog for upload_to_testflight in {}; testers get no \"What to Test\"",
+            path
+        ),
+    })
+}
+
+/// Settings that only work with a Fastfile reading `variable` (one generated
+/// before they existed ignores them)
+fn check_lane_support(
+    name: &str,
+    configured: fn(&ProjectConfig) -> bool,
+    variable: &str,
+    pass_message: &str,
+) -> Option<CheckResult> {
+    let project_config = ProjectConfig::load().ok()??;
+    if !configured(&project_config) {
+        return None;
+    }
+    let path = find_fastfile(&project_config.project.ios_path)?;
+    let fastfile = std::fs::read_to_string(&path).ok()?;
+
+    Some(if fastfile.contains(variable) {
+        CheckResult {
+            name: name.to_string(),
+            status: Status::Pass,
+            message: pass_message.to_string(),
+        }
+    } else {
+        CheckResult {
+            name: name.to_string(),
+            status: Status::Warn,
+            message: format!(
+                "{} doesn't read {} (run: launchpad template update)",
+                path, variable
+            ),
+        }
+    })
+}
+
+/// A template that doesn't parse falls back to the built-in text on every deploy
+fn check_templates() -> Option<CheckResult> {
+    let project_config = ProjectConfig::load().ok()??;
+    let entries = project_config.templates.as_ref()?.entries();
+    if entries.is_empty() {
+        return None;
+    }
+
+    let invalid: Vec<String> = entries
+        .iter()
+        .filter_map(|(name, template)| templating::validate(name, template).err())
+        .map(|e| e.to_string())
+        .collect();
+    if !invalid.is_empty() {
+        return Some(CheckResult {
+            name: "Templates".to_string(),
+            status: Status::Fail,
+            message: invalid.join("; "),
+        });
+    }
+    Some(CheckResult {
+        name: "Templates".to_string(),
+        status: Status::Pass,
+        message: format!(
+            "{} template{} valid",
+            entries.len(),
+            if entries.len() == 1 { "" } else { "s" }
+        ),
+    })
+}
+
+/// Missing submodules or LFS content build fine and upload broken apps
+fn check_checkout() -> Option<CheckResult> {
+    let submodules = git::submodule_problems().ok().flatten();
+    let pointers = git::lfs_pointers().ok().flatten();
+    if submodules.is_none() && pointers.is_none() {
+        return None;
+    }
+
+    let mut problems = submodules.clone().unwrap_or_default();
+    if let Some(pointers) = &pointers {
+        if !pointers.is_empty() {
+            problems.push(format!(
+                "{} Git LFS file{} not pulled",
+                pointers.len(),
+                if pointers.len() == 1 { "" } else { "s" }
+            ));
+        }
+    }
+    if !problems.is_empty() {
+        return Some(CheckResult {
+            name: "Checkout".to_string(),
+            status: Status::Fail,
+            message: problems.join("; "),
+        });
+    }
+
+    let present: Vec<&str> = [
+        submodules.map(|_| "submodules checked out"),
+        pointers.map(|_| "Git LFS files pulled"),
+    ]
+    .into_iter()
+    .flatten()
+    .collect();
+    Some(CheckResult {
+        name: "Checkout".to_string(),
+        status: Status::Pass,
+        message: present.join(", "),
+    })
+}
+
+/// With `tag_sign`, a deploy's tag is only created if git can sign it
+fn check_tag_signing() -> Option<CheckResult> {
+    let project_config = ProjectConfig::load().ok()??;
+    let deploy = &project_config.deploy;
+    if !deploy.tag_sign {
+        return None;
+    }
+    let result = |status, message: String| {
+        Some(CheckResult {
+            name: "Tag signing".to_string(),
+            status,
+            message,
+        })
+    };
+
+    // SSH and X.509 signing are git's to set up; only GnuPG keys are looked up
+    let format = git::config_value("gpg.format").unwrap_or_else(|| "openpgp".to_string());
+    if format != "openpgp" {
+        return result(
+            Status::Pass,
+            format!("Tags are signed with git's {} setup", format),
+        );
+    }
+
+    let key = deploy
+        .tag_signing_key
+        .clone()
+        .or_else(|| git::config_value("user.signingkey"));
+    let program = git::config_value("gpg.program").unwrap_or_else(|| "gpg".to_string());
+    let mut command = Command::new(&program);
+    command.arg("--list-secret-keys");
+    if let Some(key) = &key {
+        command.arg(key);
+    }
+
+    match command.output() {
+        Ok(output) if output.status.success() && !output.stdout.is_empty() => result(
+            Status::Pass,
+            match &key {
+                Some(key) => format!("Tags are signed with key {}", key),
+                None => "Tags are signed with the GPG key for your committer email".to_string(),
+            },
+        ),
+        Ok(_) => result(
+            Status::Fail,
+            match &key {
+                Some(key) => format!("No GPG secret key {}; tags won't be created", key),
+                None => "No GPG secret key; set deploy.tag_signing_key or git's user.signingkey"
+                    .to_string(),
+            },
+        ),
+        Err(_) => result(
+            Status::Fail,
+            format!("{} not found; signed tags need GnuPG", program),
+        ),
+    }
+}
+
+/// xcodebuild only sees shared schemes on a fresh checkout
+fn check_scheme_shared() -> Option<CheckResult> {
+    let project_config = ProjectConfig::load().ok()??;
+    let project = &project_config.project;
+
+    Some(match Xcode::scheme_sharing(&project.ios_path, &project.scheme) {
+        SchemeSharing::Shared => CheckResult {
+            name: "Scheme".to_string(),
+            status: Status::Pass,
+            message: format!("{} (shared)", project.scheme),
+        },
+        SchemeSharing::UserOnly(_) => CheckResult {
+            name: "Scheme".to_string(),
+            status: Status::Fail,
+            message: format!(
+                "{} isn't shared; CI checkouts won't find it (tick Shared in Xcode's scheme manager)",
+                project.scheme
+            ),
+        },
+        SchemeSharing::Missing => CheckResult {
+            name: "Scheme".to_string(),
+            status: Status::Warn,
+            message: format!(
+                "No .xcscheme for {}; Xcode autocreates it locally, but share it so CI gets the same one",
+                project.scheme
+            ),
+        },
+    })
+}
+
+/// Offer to move a user-only scheme into xcshareddata; true if it was shared
+fn offer_share_scheme(non_interactive: bool) -> Result<bool, DoctorError> {
+    let Some(project_config) = ProjectConfig::load().ok().flatten() else {
+        return Ok(false);
+    };
+    let project = &project_config.project;
+    let SchemeSharing::UserOnly(user_scheme) =
+        Xcode::scheme_sharing(&project.ios_path, &project.scheme)
+    else {
+        return Ok(false);
+    };
+
+    // Same defaults as the key permission fix
+    let share = if non_interactive {
+        true
+    } else if std::io::stdin().is_terminal() {
+        Confirm::new()
+            .with_prompt(format!(
+                "Share scheme {} so CI can build it?",
+                project.scheme
+            ))
+            .default(true)
+            .interact()
+            .map_err(std::io::Error::other)?
+    } else {
+        false
+    };
+    if !share {
+        return Ok(false);
+    }
+
+    match Xcode::share_scheme(&user_scheme) {
+        Ok(shared) => {
+            ui::success(&format!(
+                "Shared {} ({}); commit it",
+                project.scheme,
+                shared.display()
+            ));
+            Ok(true)
+        }
+        Err(e) => {
+            ui::warn(&format!("Could not share {}: {}", project.scheme, e));
+            Ok(false)
+        }
+    }
+}
+
+fn check_signing() -> Option<CheckResult> {
+    let project_config = ProjectConfig::load().ok()??;
+    let project = &project_config.project;
+
+    let style = match project_config.signing.style {
+        Some(style) => style,
+        None => match Xcode::signing_style(&project.ios_path, &project.scheme) {
+            Ok(style) => style,
+            Err(e) => {
+                return Some(CheckResult {
+                    name: "Signing".to_string(),
+                    status: Status::Fail,
+                    message: format!("Could not detect signing style: {}", e),
+                })
+            }
+        },
+    };
+
+    let profiles = &project_config.signing.profiles;
+    if style == SigningStyle::Manual && profiles.is_empty() {
+        return Some(CheckResult {
+            name: "Signing".to_string(),
+            status: Status::Fail,
+            message: "Manual signing without [signing.profiles] in .launchpad.toml".to_string(),
+        });
+    }
+
+    if style == SigningStyle::Manual && !installs_profiles(&project.ios_path) {
+        let installed: Vec<String> = profiles::installed().into_iter().map(|p| p.name).collect();
+        let missing: Vec<String> = profiles
+            .iter()
+            .filter(|(_, name)| !installed.contains(name))
+            .map(|(bundle_id, name)| format!("{} ({})", name, bundle_id))
+            .collect();
+        if !missing.is_empty() {
+            return Some(CheckResult {
+                name: "Signing".to_string(),
+                status: Status::Fail,
+                message: format!(
+                    "Profile{} not installed: {}; the archive would fail at signing after the whole build (download in Xcode > Settings > Accounts)",
+                    if missing.len() == 1 { "" } else { "s" },
+                    missing.join(", ")
+                ),
+            });
+        }
+    }
+
+    let message = match style {
+        SigningStyle::Automatic => "Automatic (provisioning via API key)".to_string(),
+        SigningStyle::Manual => format!(
+            "Manual ({} profile{})",
+            profiles.len(),
+            if profiles.len() == 1 { "" } else { "s" }
+        ),
+    };
+
+    Some(CheckResult {
+        name: "Signing".to_string(),
+        status: Status::Pass,
+        message,
+    })
+}
+
+/// Whether the lane installs profiles itself (match), so none need to be installed yet
+fn installs_profiles(ios_path: &str) -> bool {
+    find_fastfile(ios_path)
+        .and_then(|path| std::fs::read_to_string(path).ok())
+        .is_some_and(|fastfile| {
+            fastfile.contains("match(") || fastfile.contains("sync_code_signing")
+        })
+}
+
+/// Xcode 14 removed the legacy build system; a workspace still set to it can't build
+fn check_build_system() -> Option<CheckResult> {
+    let project_config = ProjectConfig::load().ok()??;
+
+    Some(match Xcode::legacy_build_system(&project_config.project.ios_path) {
+        None => CheckResult {
+            name: "Build system".to_string(),
+            status: Status::Pass,
+            message: "New build system".to_string(),
+        },
+        Some(settings) => CheckResult {
+            name: "Build system".to_string(),
+            status: Status::Fail,
+            message: format!(
+                "{} selects the legacy build system, which Xcode 14 removed; builds stop before compiling (delete BuildSystemType or pick New Build System in File > Workspace Settings)",
+                settings.display()
+            ),
+        },
+    })
+}
+
+/// SWIFT_VERSION settings the selected Xcode can't compile fail late, in the compile step
+fn check_swift_version() -> Option<CheckResult> {
+    let project_config = ProjectConfig::load().ok()??;
+    let xcode_version = Xcode::version()?;
+    let targets = Xcode::target_versions(&project_config.project.ios_path).ok()?;
+
+    let mut versions: Vec<&str> = targets
+        .iter()
+        .filter_map(|t| t.swift_version.as_deref())
+        .collect();
+    versions.sort_unstable();
+    versions.dedup();
+    if versions.is_empty() {
+        return None;
+    }
+
+    let problem = xcode::swift_version_problem(&targets, &xcode_version);
+    Some(match problem {
+        None => CheckResult {
+            name: "Swift".to_string(),
+            status: Status::Pass,
+            message: format!("Swift {} with {}", versions.join(", "), xcode_version),
+        },
+        Some(problem) => CheckResult {
+            name: "Swift".to_string(),
+            status: Status::Fail,
+            message: problem,
+        },
+    })
+}
+
+fn check_target_versions() -> Option<CheckResult> {
+    let project_config = ProjectConfig::load().ok()??;
+
+    match Xcode::target_versions(&project_config.project.ios_path) {
+        Ok(targets) => match xcode::version_mismatch(&targets) {
+            None => Some(CheckResult {
+                name: "Versions".to_string(),
+                status: Status::Pass,
+                message: format!(
+                    "Consistent across {} target{}",
+                    targets.len(),
+                    if targets.len() == 1 { "" } else { "s" }
+                ),
+            }),
+            Some(mismatch) => Some(CheckResult {
+                name: "Versions".to_string(),
+                status: Status::Fail,
+                message: mismatch,
+            }),
+        },
+        Err(e) => Some(CheckResult {
+            name: "Versions".to_string(),
+            status: Status::Fail,
+            message: format!("Could not read build settings: {}", e),
+        }),
+    }
+}
+
+fn check_kmp() -> Option<CheckResult> {
+    let project_config = ProjectConfig::load().ok()??;
+    let kmp = project_config.kmp?;
+
+    if !Path::new("gradlew").exists() && which::which("gradle").is_err() {
+        return Some(CheckResult {
+            name: "Kotlin".to_string(),
+            status: Status::Fail,
+            message: "No gradlew in project root and gradle not installed".to_string(),
+        });
+    }
+
+    if kmp.integration == FrameworkIntegration::EmbedAndSign
+        && !kmp::has_embed_phase(&project_config.project.ios_path)
+    {
+        return Some(CheckResult {
+            name: "Kotlin".to_string(),
+            status: Status::Fail,
+            message: format!(
+                "Xcode project doesn't run :{}:embedAndSignAppleFrameworkForXcode",
+                kmp.shared_module
+            ),
+        });
+    }
+
+    Some(CheckResult {
+        name: "Kotlin".to_string(),
+        status: Status::Pass,
+        message: format!(":{} ({})", kmp.shared_module, kmp.integration),
+    })
+}
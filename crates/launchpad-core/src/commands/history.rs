@@ -0,0 +1,186 @@
+use crate::artifact::{self, BuildStamp};
+use crate::commands::deploy::Platform;
+use crate::config::project::ProjectConfig;
+use crate::fastlane;
+use crate::history::{self, DeployRecord, HistoryError};
+use crate::ipa;
+use crate::notifications;
+use crate::ui;
+use clap::Subcommand;
+use std::path::{Path, PathBuf};
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+pub enum HistoryCommandError {
+    #[error("Project config not found. Run 'launchpad init' first, or pass --bundle-id.")]
+    NoProjectConfig,
+
+    #[error(".ipa not found: {0}")]
+    IpaNotFound(PathBuf),
+
+    #[error("Couldn't read the version from {0}. Pass --version and --build.")]
+    NoVersion(PathBuf),
+
+    #[error("Build {build} of {bundle_id} is already in the deploy history")]
+    AlreadyRecorded { bundle_id: String, build: String },
+
+    #[error("--pending-upload needs .launchpad.toml and a git repository, to record the build for 'launchpad deploy --reuse-artifact'")]
+    NoSourceHash,
+
+    #[error("{0}")]
+    History(#[from] HistoryError),
+
+    #[error("IO error: {0}")]
+    Io(#[from] std::io::Error),
+
+    #[error("Config error: {0}")]
+    Config(String),
+}
+
+#[derive(Subcommand)]
+pub enum HistoryCommand {
+    /// Record an .ipa built outside launchpad, so stats, diff and tag know about it
+    Import {
+        /// The .ipa to record
+        ipa: PathBuf,
+
+        /// Marketing version (default: CFBundleShortVersionString in the .ipa)
+        #[arg(long)]
+        version: Option<String>,
+
+        /// Build number (default: CFBundleVersion in the .ipa)
+        #[arg(long)]
+        build: Option<String>,
+
+        /// App the build is for (default: the bundle ID in .launchpad.toml)
+        #[arg(long)]
+        bundle_id: Option<String>,
+
+        /// Commit the build was made from, for `launchpad diff` and `launchpad tag`
+        #[arg(long, value_name = "SHA")]
+        commit: Option<String>,
+
+        /// The .ipa isn't on TestFlight yet: let `launchpad deploy --reuse-artifact` upload it
+        #[arg(long)]
+        pending_upload: bool,
+    },
+}
+
+pub async fn run(command: HistoryCommand) -> Result<(), HistoryCommandError> {
+    match command {
+        HistoryCommand::Import {
+            ipa,
+            version,
+            build,
+            bundle_id,
+            commit,
+            pending_upload,
+        } => import(ipa, version, build, bundle_id, commit, pending_upload).await,
+    }
+}
+
+async fn import(
+    path: PathBuf,
+    version: Option<String>,
+    build: Option<String>,
+    bundle_id: Option<String>,
+    commit: Option<String>,
+    pending_upload: bool,
+) -> Result<(), HistoryCommandError> {
+    ui::header("Import Build");
+
+    if !path.is_file() {
+        return Err(HistoryCommandError::IpaNotFound(path));
+    }
+    let path = std::path::absolute(&path)?;
+    let project_config =
+        ProjectConfig::load().map_err(|e| HistoryCommandError::Config(e.to_string()))?;
+    let bundle_id = match (bundle_id, &project_config) {
+        (Some(bundle_id), _) => bundle_id,
+        (None, Some(config)) => config.project.bundle_id.clone(),
+        (None, None) => return Err(HistoryCommandError::NoProjectConfig),
+    };
+
+    // Whatever isn't given comes from the app's Info.plist
+    let (version, build) = match (version, build) {
+        (Some(version), Some(build)) => (version, build),
+        (version, build) => {
+            let (read_version, read_build) = ipa::version(&path)
+                .map(|label| fastlane::split_version(&label))
+                .ok_or_else(|| HistoryCommandError::NoVersion(path.clone()))?;
+            let build = build
+                .or(read_build)
+                .ok_or_else(|| HistoryCommandError::NoVersion(path.clone()))?;
+            (version.unwrap_or(read_version), build)
+        }
+    };
+
+    let records = history::load()?;
+    if history::find(&records, &bundle_id, Platform::Ios.name(), &build).is_some() {
+        return Err(HistoryCommandError::AlreadyRecorded { bundle_id, build });
+    }
+
+    if pending_upload {
+        let config = project_config
+            .as_ref()
+            .ok_or(HistoryCommandError::NoSourceHash)?;
+        stamp(config, &path, &bundle_id, &version, &build)?;
+    }
+
+    let contents = ipa::inspect(&path);
+    history::append(&DeployRecord {
+        bundle_id: bundle_id.clone(),
+        platform: Platform::Ios.name().to_string(),
+        version: version.clone(),
+        build: build.clone(),
+        deployed_at: history::now(),
+        git_sha: commit,
+        tag: None,
+        size: std::fs::metadata(&path).ok().map(|m| m.len()),
+        sha256: artifact::sha256(&path),
+        frameworks: contents.frameworks,
+        entitlements: contents.entitlements,
+        thinned_sizes: Default::default(),
+        processing_secs: None,
+        upload_skipped: pending_upload,
+    })?;
+
+    ui::success(&format!(
+        "Recorded {} ({}) of {} in the deploy history",
+        version, build, bundle_id
+    ));
+    if pending_upload {
+        ui::step("'launchpad deploy --reuse-artifact' uploads it while the sources stay unchanged");
+    }
+    Ok(())
+}
+
+/// Record the .ipa as the last build, the way a deploy with --skip-upload does
+fn stamp(
+    config: &ProjectConfig,
+    ipa: &Path,
+    bundle_id: &str,
+    version: &str,
+    build: &str,
+) -> Result<(), HistoryCommandError> {
+    // Deploy only reuses a build made for the environment it's deploying
+    let environment = if config.environments.is_empty() {
+        None
+    } else {
+        notifications::environment(config.notifications.as_ref())
+    };
+    let output_dir = &config.build.output_dir;
+    let source_hash = artifact::source_hash(output_dir).ok_or(HistoryCommandError::NoSourceHash)?;
+    let stamp = BuildStamp {
+        source_hash,
+        ipa: ipa.to_string_lossy().to_string(),
+        version: Some(format!("{} ({})", version, build)),
+        built_at: history::now(),
+        environment,
+        bundle_id: Some(bundle_id.to_string()),
+        uploaded: false,
+    };
+    let output_dir = PathBuf::from(shellexpand::tilde(output_dir).as_ref());
+    stamp.save(&output_dir)?;
+    Ok(())
+}
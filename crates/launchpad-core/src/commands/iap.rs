@@ -0,0 +1,614 @@
+use crate::asc::{AscClient, AscError, ProductKind, ProductLocalization, StoreProduct};
+use crate::commands::{connect, ConnectError};
+use crate::ui;
+use clap::Subcommand;
+use dialoguer::Confirm;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::path::{Path, PathBuf};
+use thiserror::Error;
+
+/// Storefront `price` is given in; App Store Connect equalizes the others from it
+const PRICE_TERRITORY: &str = "USA";
+
+const IAP_TYPES: &[&str] = &["CONSUMABLE", "NON_CONSUMABLE", "NON_RENEWING_SUBSCRIPTION"];
+
+const FILE_HEADER: &str = "# In-app purchases and subscriptions for `launchpad iap push`.\n# `launchpad iap pull` rewrites this file from App Store Connect.\n\n";
+
+#[derive(Error, Debug)]
+pub enum IapError {
+    #[error("{0} not found. Run 'launchpad iap pull' to create it.")]
+    NoFile(PathBuf),
+
+    #[error("{path}: {message}")]
+    Invalid { path: PathBuf, message: String },
+
+    #[error("{territory} has no price point of {price}; pick a price App Store Connect offers")]
+    NoPricePoint { territory: String, price: String },
+
+    #[error("{count} product{} couldn't be pushed", if *.count == 1 { "" } else { "s" })]
+    Failed { count: usize },
+
+    #[error("Push cancelled")]
+    Cancelled,
+
+    #[error("TOML parse error: {0}")]
+    TomlParse(#[from] toml::de::Error),
+
+    #[error("TOML serialize error: {0}")]
+    TomlSerialize(#[from] toml::ser::Error),
+
+    #[error("{0}")]
+    Asc(#[from] AscError),
+
+    #[error("IO error: {0}")]
+    Io(#[from] std::io::Error),
+
+    #[error("{0}")]
+    Connect(#[from] ConnectError),
+}
+
+#[derive(Subcommand)]
+pub enum IapCommand {
+    /// Write the app's in-app purchases and subscriptions to a local file
+    Pull {
+        /// File to write
+        #[arg(long, default_value = "iap.toml")]
+        file: PathBuf,
+
+        /// App the products are for (default: the bundle ID in .launchpad.toml)
+        #[arg(long)]
+        bundle_id: Option<String>,
+    },
+
+    /// Create and update products on App Store Connect to match the local file
+    Push {
+        /// File to read
+        #[arg(long, default_value = "iap.toml")]
+        file: PathBuf,
+
+        /// App the products are for (default: the bundle ID in .launchpad.toml)
+        #[arg(long)]
+        bundle_id: Option<String>,
+
+        /// Show what would change without changing anything
+        #[arg(long)]
+        dry_run: bool,
+    },
+}
+
+/// iap.toml: the products as App Store Connect should have them
+#[derive(Debug, Default, PartialEq, Serialize, Deserialize)]
+struct IapFile {
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    products: Vec<ProductEntry>,
+
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    subscriptions: Vec<SubscriptionEntry>,
+}
+
+/// `[[products]]`: a consumable, non-consumable or non-renewing subscription
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+struct ProductEntry {
+    product_id: String,
+    reference_name: String,
+
+    /// CONSUMABLE, NON_CONSUMABLE or NON_RENEWING_SUBSCRIPTION
+    #[serde(rename = "type")]
+    iap_type: String,
+
+    /// Customer price in the US storefront ("0.99")
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    price: Option<String>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    review_note: Option<String>,
+
+    /// Locale ("en-US") → display name and description
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    localizations: BTreeMap<String, LocalizationEntry>,
+}
+
+/// `[[subscriptions]]`: an auto-renewable subscription
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+struct SubscriptionEntry {
+    product_id: String,
+    reference_name: String,
+
+    /// Reference name of the subscription group, created if missing
+    group: String,
+
+    /// ONE_WEEK, ONE_MONTH, TWO_MONTHS, THREE_MONTHS, SIX_MONTHS or ONE_YEAR
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    period: Option<String>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    review_note: Option<String>,
+
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    localizations: BTreeMap<String, LocalizationEntry>,
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+struct LocalizationEntry {
+    name: String,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    description: Option<String>,
+}
+
+/// What a push changes for one product
+struct Plan<'a> {
+    kind: ProductKind,
+    product_id: &'a str,
+    reference_name: &'a str,
+    review_note: Option<&'a str>,
+    existing: Option<&'a StoreProduct>,
+    /// Reference name or review note differ
+    update: bool,
+    /// Locale, the existing localization's ID, and what it should say
+    localizations: Vec<(&'a str, Option<String>, &'a LocalizationEntry)>,
+    price: Option<&'a str>,
+    /// Creating an in-app purchase: its type
+    iap_type: Option<&'a str>,
+    /// Creating a subscription: its group and period
+    group: Option<&'a str>,
+    period: Option<&'a str>,
+}
+
+impl Plan<'_> {
+    fn is_empty(&self) -> bool {
+        self.existing.is_some()
+            && !self.update
+            && self.localizations.is_empty()
+            && self.price.is_none()
+    }
+
+    fn describe(&self) -> String {
+        let mut changes = Vec::new();
+        if self.existing.is_none() {
+            changes.push("create".to_string());
+        } else if self.update {
+            changes.push("reference name / review note".to_string());
+        }
+        if !self.localizations.is_empty() {
+            let locales: Vec<&str> = self.localizations.iter().map(|(l, _, _)| *l).collect();
+            changes.push(format!("localizations ({})", locales.join(", ")));
+        }
+        if let Some(price) = self.price {
+            changes.push(format!("price {} in {}", price, PRICE_TERRITORY));
+        }
+        format!("{} {} {}", self.product_id, ui::arrow(), changes.join(", "))
+    }
+}
+
+pub async fn run(command: IapCommand, non_interactive: bool) -> Result<(), IapError> {
+    match command {
+        IapCommand::Pull { file, bundle_id } => pull(file, bundle_id, non_interactive).await,
+        IapCommand::Push {
+            file,
+            bundle_id,
+            dry_run,
+        } => push(file, bundle_id, dry_run, non_interactive).await,
+    }
+}
+
+async fn pull(
+    path: PathBuf,
+    bundle_id: Option<String>,
+    non_interactive: bool,
+) -> Result<(), IapError> {
+    ui::header("Pull In-App Purchases");
+
+    let (client, app_id) = connect(bundle_id).await?;
+    let spinner = ui::spinner("Loading products from App Store Connect...");
+    let remote = load_file(&client, &app_id).await;
+    spinner.finish_and_clear();
+    let remote = remote?;
+
+    let content = format!("{}{}", FILE_HEADER, toml::to_string_pretty(&remote)?);
+    if path.exists() {
+        if std::fs::read_to_string(&path)? == content {
+            ui::success(&format!("{} is up to date", path.display()));
+            return Ok(());
+        }
+        let overwrite = non_interactive
+            || Confirm::new()
+                .with_prompt(format!("Overwrite {}?", path.display()))
+                .default(true)
+                .interact()
+                .map_err(|e| IapError::Io(std::io::Error::other(e)))?;
+        if !overwrite {
+            ui::warn(&format!("{} left unchanged", path.display()));
+            return Ok(());
+        }
+    }
+
+    std::fs::write(&path, content)?;
+    ui::success(&format!(
+        "Wrote {} in-app purchase{} and {} subscription{} to {}",
+        remote.products.len(),
+        if remote.products.len() == 1 { "" } else { "s" },
+        remote.subscriptions.len(),
+        if remote.subscriptions.len() == 1 {
+            ""
+        } else {
+            "s"
+        },
+        path.display()
+    ));
+    Ok(())
+}
+
+async fn push(
+    path: PathBuf,
+    bundle_id: Option<String>,
+    dry_run: bool,
+    non_interactive: bool,
+) -> Result<(), IapError> {
+    ui::header("Push In-App Purchases");
+
+    let wanted = read_file(&path)?;
+    let (client, app_id) = connect(bundle_id).await?;
+
+    let spinner = ui::spinner("Comparing with App Store Connect...");
+    let loaded = load_remote(&client, &app_id).await;
+    spinner.finish_and_clear();
+    let (products, groups, subscriptions) = loaded?;
+    let subscriptions: Vec<StoreProduct> = subscriptions.into_iter().map(|(_, s)| s).collect();
+
+    let mut plans = Vec::new();
+    for entry in &wanted.products {
+        let existing = products.iter().find(|p| p.product_id == entry.product_id);
+        let mut plan = Plan {
+            kind: ProductKind::InAppPurchase,
+            product_id: &entry.product_id,
+            reference_name: &entry.reference_name,
+            review_note: entry.review_note.as_deref(),
+            existing,
+            update: false,
+            localizations: Vec::new(),
+            price: None,
+            iap_type: Some(&entry.iap_type),
+            group: None,
+            period: None,
+        };
+        plan.price = entry.price.as_deref();
+        if let Some(existing) = existing {
+            plan.update = existing.reference_name != entry.reference_name
+                || existing.review_note != entry.review_note;
+            let price = client.iap_price(&existing.id, PRICE_TERRITORY).await?;
+            if same_price(price.as_deref(), plan.price) {
+                plan.price = None;
+            }
+        }
+        plan.localizations =
+            localization_changes(&client, plan.kind, existing, &entry.localizations).await?;
+        plans.push(plan);
+    }
+    for entry in &wanted.subscriptions {
+        let existing = subscriptions
+            .iter()
+            .find(|p| p.product_id == entry.product_id);
+        let update = existing.is_some_and(|existing| {
+            existing.reference_name != entry.reference_name
+                || existing.review_note != entry.review_note
+        });
+        let localizations = localization_changes(
+            &client,
+            ProductKind::Subscription,
+            existing,
+            &entry.localizations,
+        )
+        .await?;
+        plans.push(Plan {
+            kind: ProductKind::Subscription,
+            product_id: &entry.product_id,
+            reference_name: &entry.reference_name,
+            review_note: entry.review_note.as_deref(),
+            existing,
+            update,
+            localizations,
+            price: None,
+            iap_type: None,
+            group: Some(&entry.group),
+            period: entry.period.as_deref(),
+        });
+    }
+
+    let listed: Vec<&str> = plans.iter().map(|p| p.product_id).collect();
+    for product in products.iter().chain(&subscriptions) {
+        if !listed.contains(&product.product_id.as_str()) {
+            ui::warn(&format!(
+                "{} is on App Store Connect but not in {}; left alone",
+                product.product_id,
+                path.display()
+            ));
+        }
+    }
+
+    plans.retain(|plan| !plan.is_empty());
+    if plans.is_empty() {
+        ui::success("App Store Connect already matches");
+        return Ok(());
+    }
+    for plan in &plans {
+        ui::step(&plan.describe());
+    }
+    if dry_run {
+        ui::blank();
+        ui::success(&format!(
+            "Would change {} product{}",
+            plans.len(),
+            if plans.len() == 1 { "" } else { "s" }
+        ));
+        return Ok(());
+    }
+
+    let proceed = non_interactive
+        || Confirm::new()
+            .with_prompt(format!("Push {} changed products?", plans.len()))
+            .default(false)
+            .interact()
+            .map_err(|e| IapError::Io(std::io::Error::other(e)))?;
+    if !proceed {
+        return Err(IapError::Cancelled);
+    }
+
+    let mut group_ids: HashMap<String, String> = groups
+        .into_iter()
+        .map(|g| (g.reference_name, g.id))
+        .collect();
+    let mut failed = 0;
+    for plan in &plans {
+        match apply(&client, &app_id, plan, &mut group_ids).await {
+            Ok(()) => ui::check_pass(plan.product_id, "updated"),
+            Err(e) => {
+                failed += 1;
+                ui::check_fail(plan.product_id, &e.to_string());
+            }
+        }
+    }
+
+    if failed > 0 {
+        return Err(IapError::Failed { count: failed });
+    }
+    ui::blank();
+    ui::success("Pushed; new products still need review screenshots before they can be submitted");
+    Ok(())
+}
+
+/// Create the product if it's new, then bring its metadata and price in line
+async fn apply(
+    client: &AscClient,
+    app_id: &str,
+    plan: &Plan<'_>,
+    group_ids: &mut HashMap<String, String>,
+) -> Result<(), IapError> {
+    let id = match (plan.existing, plan.kind) {
+        (Some(existing), _) => existing.id.clone(),
+        (None, ProductKind::InAppPurchase) => {
+            client
+                .create_in_app_purchase(
+                    app_id,
+                    plan.product_id,
+                    plan.reference_name,
+                    plan.iap_type.unwrap_or_default(),
+                    plan.review_note,
+                )
+                .await?
+        }
+        (None, ProductKind::Subscription) => {
+            let group = plan.group.unwrap_or_default();
+            let group_id = match group_ids.get(group) {
+                Some(id) => id.clone(),
+                None => {
+                    let id = client.create_subscription_group(app_id, group).await?;
+                    group_ids.insert(group.to_string(), id.clone());
+                    id
+                }
+            };
+            client
+                .create_subscription(
+                    &group_id,
+                    plan.product_id,
+                    plan.reference_name,
+                    plan.period,
+                    plan.review_note,
+                )
+                .await?
+        }
+    };
+
+    if plan.update {
+        client
+            .update_product(plan.kind, &id, plan.reference_name, plan.review_note)
+            .await?;
+    }
+    for (locale, existing, entry) in &plan.localizations {
+        let description = entry.description.as_deref();
+        match existing {
+            Some(localization_id) => {
+                client
+                    .update_product_localization(
+                        plan.kind,
+                        localization_id,
+                        &entry.name,
+                        description,
+                    )
+                    .await?
+            }
+            None => {
+                client
+                    .create_product_localization(plan.kind, &id, locale, &entry.name, description)
+                    .await?
+            }
+        }
+    }
+    if let Some(price) = plan.price {
+        if !client.set_iap_price(&id, PRICE_TERRITORY, price).await? {
+            return Err(IapError::NoPricePoint {
+                territory: PRICE_TERRITORY.to_string(),
+                price: price.to_string(),
+            });
+        }
+    }
+    Ok(())
+}
+
+/// Locales whose name or description differ from App Store Connect's, or that it lacks
+async fn localization_changes<'a>(
+    client: &AscClient,
+    kind: ProductKind,
+    existing: Option<&StoreProduct>,
+    wanted: &'a BTreeMap<String, LocalizationEntry>,
+) -> Result<Vec<(&'a str, Option<String>, &'a LocalizationEntry)>, AscError> {
+    let current: Vec<ProductLocalization> = match existing {
+        Some(product) => client.product_localizations(kind, &product.id).await?,
+        None => Vec::new(),
+    };
+    Ok(wanted
+        .iter()
+        .filter_map(
+            |(locale, entry)| match current.iter().find(|l| l.locale == *locale) {
+                Some(l) if l.name == entry.name && l.description == entry.description => None,
+                Some(l) => Some((locale.as_str(), Some(l.id.clone()), entry)),
+                None => Some((locale.as_str(), None, entry)),
+            },
+        )
+        .collect())
+}
+
+/// Everything on App Store Connect, as iap.toml would describe it
+async fn load_file(client: &AscClient, app_id: &str) -> Result<IapFile, IapError> {
+    let (products, groups, subscriptions) = load_remote(client, app_id).await?;
+
+    let mut file = IapFile::default();
+    for product in products {
+        let localizations = client
+            .product_localizations(ProductKind::InAppPurchase, &product.id)
+            .await?;
+        file.products.push(ProductEntry {
+            price: client.iap_price(&product.id, PRICE_TERRITORY).await?,
+            product_id: product.product_id,
+            reference_name: product.reference_name,
+            iap_type: product.iap_type.unwrap_or_default(),
+            review_note: product.review_note,
+            localizations: localization_entries(localizations),
+        });
+    }
+    for (group_id, product) in subscriptions {
+        let localizations = client
+            .product_localizations(ProductKind::Subscription, &product.id)
+            .await?;
+        let group = groups
+            .iter()
+            .find(|g| g.id == group_id)
+            .map(|g| g.reference_name.clone())
+            .unwrap_or_default();
+        file.subscriptions.push(SubscriptionEntry {
+            product_id: product.product_id,
+            reference_name: product.reference_name,
+            group,
+            period: product.period,
+            review_note: product.review_note,
+            localizations: localization_entries(localizations),
+        });
+    }
+
+    file.products
+        .sort_by(|a, b| a.product_id.cmp(&b.product_id));
+    file.subscriptions
+        .sort_by(|a, b| (&a.group, &a.product_id).cmp(&(&b.group, &b.product_id)));
+    Ok(file)
+}
+
+/// In-app purchases, subscription groups, and subscriptions with their group's ID
+async fn load_remote(
+    client: &AscClient,
+    app_id: &str,
+) -> Result<
+    (
+        Vec<StoreProduct>,
+        Vec<crate::asc::SubscriptionGroup>,
+        Vec<(String, StoreProduct)>,
+    ),
+    AscError,
+> {
+    let products = client.in_app_purchases(app_id).await?;
+    let groups = client.subscription_groups(app_id).await?;
+    let mut subscriptions = Vec::new();
+    for group in &groups {
+        for subscription in client.subscriptions(&group.id).await? {
+            subscriptions.push((group.id.clone(), subscription));
+        }
+    }
+    Ok((products, groups, subscriptions))
+}
+
+fn localization_entries(
+    localizations: Vec<ProductLocalization>,
+) -> BTreeMap<String, LocalizationEntry> {
+    localizations
+        .into_iter()
+        .map(|l| {
+            (
+                l.locale,
+                LocalizationEntry {
+                    name: l.name,
+                    description: l.description,
+                },
+            )
+        })
+        .collect()
+}
+
+/// Parse and check the file before anything is sent
+fn read_file(path: &Path) -> Result<IapFile, IapError> {
+    if !path.exists() {
+        return Err(IapError::NoFile(path.to_path_buf()));
+    }
+    let file: IapFile = toml::from_str(&std::fs::read_to_string(path)?)?;
+    let invalid = |message: String| IapError::Invalid {
+        path: path.to_path_buf(),
+        message,
+    };
+
+    let mut seen = Vec::new();
+    let ids = file
+        .products
+        .iter()
+        .map(|p| &p.product_id)
+        .chain(file.subscriptions.iter().map(|s| &s.product_id));
+    for id in ids {
+        if seen.contains(&id) {
+            return Err(invalid(format!("product_id '{}' is listed twice", id)));
+        }
+        seen.push(id);
+    }
+    for product in &file.products {
+        if !IAP_TYPES.contains(&product.iap_type.as_str()) {
+            return Err(invalid(format!(
+                "{} has type '{}' (expected {})",
+                product.product_id,
+                product.iap_type,
+                IAP_TYPES.join(", ")
+            )));
+        }
+        if let Some(price) = &product.price {
+            if price.parse::<f64>().is_err() {
+                return Err(invalid(format!(
+                    "{} has price '{}' (expected a number like \"0.99\")",
+                    product.product_id, price
+                )));
+            }
+        }
+    }
+    Ok(file)
+}
+
+/// Prices compare as numbers: App Store Connect may write "1.0" for "1.00"
+fn same_price(current: Option<&str>, wanted: Option<&str>) -> bool {
+    let parse = |price: Option<&str>| price.and_then(|p| p.parse::<f64>().ok());
+    wanted.is_none() || parse(current) == parse(wanted)
+}
@@ -0,0 +1,681 @@
+use crate::asc::AscClient;
+use crate::config::global::GlobalConfig;
+use crate::config::project::{KmpSettings, ProjectConfig, SigningSettings, SigningStyle};
+use crate::i18n::t;
+use crate::kmp::{self, FrameworkIntegration, KmpProject};
+use crate::presets::Preset;
+use crate::templates::{self, FastfileContext, MANAGED_BEGIN, MANAGED_END};
+use crate::ui;
+use crate::xcode::Xcode;
+use dialoguer::{Confirm, Input, Select};
+use std::path::Path;
+use std::process::Command;
+use thiserror::Error;
+
+/// How many directories below the project root to look for Xcode projects
+const MAX_SEARCH_DEPTH: usize = 3;
+
+/// Dependency and build directories that contain Xcode projects nobody deploys
+const SKIPPED_DIRS: &[&str] = &[
+    "Pods",
+    "DerivedData",
+    "node_modules",
+    "Carthage",
+    "build",
+    "vendor",
+];
+
+#[derive(Error, Debug)]
+pub enum InitError {
+    #[error("No iOS project found in current directory")]
+    NoIosProject,
+
+    #[error("Could not detect Xcode scheme. Use --scheme to specify.")]
+    NoSchemeDetected,
+
+    #[error(".launchpad.toml already exists. Run 'launchpad reconfigure' to change it.")]
+    AlreadyInitialized,
+
+    #[error("IO error: {0}")]
+    Io(#[from] std::io::Error),
+
+    #[error("Xcode error: {0}")]
+    Xcode(String),
+
+    #[error("fastlane installation failed")]
+    FastlaneInstallFailed,
+
+    #[error("User cancelled")]
+    UserCancelled,
+}
+
+pub async fn run(
+    ios_path: Option<String>,
+    scheme: Option<String>,
+    bundle_id: Option<String>,
+    template: Option<Preset>,
+    non_interactive: bool,
+) -> Result<(), InitError> {
+    ui::header(&t!("init-header"));
+
+    // Check if already initialized
+    if Path::new(".launchpad.toml").exists() {
+        return Err(InitError::AlreadyInitialized);
+    }
+
+    // 1. Check and install fastlane
+    check_and_install_fastlane(non_interactive)?;
+
+    // 2. Pick project preset
+    let preset = select_preset(template, non_interactive)?;
+
+    // 3. Detect Kotlin Multiplatform module
+    let kmp_project = if preset == Preset::Kmp {
+        detect_kmp()
+    } else {
+        None
+    };
+
+    // 4. Detect iOS project path
+    let known_path = ios_path.or_else(|| kmp_project.as_ref().map(|k| k.ios_path.clone()));
+    let detected_ios_path = match known_path {
+        Some(path) => path,
+        None => select_ios_path(preset, non_interactive)?,
+    };
+
+    ui::success(&t!("init-found-project", path = detected_ios_path));
+
+    // 5. Detect or prompt for scheme
+    let schemes =
+        Xcode::list_schemes(&detected_ios_path).map_err(|e| InitError::Xcode(e.to_string()))?;
+
+    let selected_scheme = if let Some(s) = scheme {
+        s
+    } else if schemes.is_empty() {
+        return Err(InitError::NoSchemeDetected);
+    } else if schemes.len() == 1 {
+        ui::success(&t!("init-detected-scheme", scheme = schemes[0]));
+        schemes[0].clone()
+    } else if non_interactive {
+        // In non-interactive mode, pick the first scheme
+        ui::success(&t!(
+            "init-using-scheme-first",
+            scheme = schemes[0],
+            count = schemes.len()
+        ));
+        schemes[0].clone()
+    } else {
+        ui::step(&t!("init-select-scheme"));
+        let selection = Select::new()
+            .items(&schemes)
+            .default(0)
+            .interact()
+            .map_err(|e| InitError::Io(std::io::Error::other(e)))?;
+        schemes[selection].clone()
+    };
+
+    // 6. Detect bundle ID; a scheme that also builds extensions lists several
+    let targets = Xcode::scheme_targets(&detected_ios_path, &selected_scheme).unwrap_or_default();
+    let app_index = targets.iter().position(|t| t.is_app()).unwrap_or(0);
+    let bundle_ids: Vec<String> = targets.iter().filter_map(|t| t.bundle_id.clone()).collect();
+
+    let final_bundle_id = if let Some(b) = bundle_id {
+        b
+    } else if bundle_ids.len() > 1 {
+        if non_interactive {
+            // In non-interactive mode, pick the app target
+            ui::success(&t!(
+                "init-using-bundle-id-app",
+                bundle_id = bundle_ids[app_index],
+                count = bundle_ids.len()
+            ));
+            bundle_ids[app_index].clone()
+        } else {
+            let items: Vec<String> = bundle_ids
+                .iter()
+                .zip(&targets)
+                .map(|(id, t)| format!("{} ({})", id, t.target))
+                .collect();
+            ui::step(&t!("init-select-bundle-id"));
+            let selection = Select::new()
+                .items(&items)
+                .default(app_index)
+                .interact()
+                .map_err(|e| InitError::Io(std::io::Error::other(e)))?;
+            bundle_ids[selection].clone()
+        }
+    } else {
+        let detected_bundle_id = match bundle_ids.first() {
+            Some(bundle_id) => bundle_id.clone(),
+            None => Xcode::get_bundle_id(&detected_ios_path, &selected_scheme)
+                .unwrap_or_else(|_| "com.example.app".to_string()),
+        };
+
+        if non_interactive {
+            ui::success(&t!("init-using-bundle-id", bundle_id = detected_bundle_id));
+            detected_bundle_id
+        } else {
+            Input::new()
+                .with_prompt(t!("init-prompt-bundle-id"))
+                .default(detected_bundle_id)
+                .interact_text()
+                .map_err(|e| InitError::Io(std::io::Error::other(e)))?
+        }
+    };
+
+    // Manual signing needs a profile for each of them, the app's first
+    let mut signed_ids = vec![final_bundle_id.clone()];
+    signed_ids.extend(bundle_ids.into_iter().filter(|id| *id != final_bundle_id));
+
+    // Recorded so later API calls skip the lookup; needs `launchpad setup` first
+    let asc_app_id = lookup_app_id(&final_bundle_id).await;
+
+    // 7. Detect signing style
+    let signing = match Xcode::signing_style(&detected_ios_path, &selected_scheme) {
+        Ok(style) => {
+            ui::success(&t!("init-signing", style = style));
+            detect_signing(style, &signed_ids, non_interactive)?
+        }
+        Err(_) => {
+            ui::warn(&t!("init-signing-unknown"));
+            SigningSettings::default()
+        }
+    };
+
+    // 8. Git tag options
+    let (git_tag, push_tags) = if non_interactive {
+        ui::success(&t!("init-git-tag-default"));
+        (true, true)
+    } else {
+        let git_tag = Confirm::new()
+            .with_prompt(t!("init-prompt-git-tag"))
+            .default(true)
+            .interact()
+            .map_err(|e| InitError::Io(std::io::Error::other(e)))?;
+
+        let push_tags = if git_tag {
+            Confirm::new()
+                .with_prompt(t!("init-prompt-push-tags"))
+                .default(true)
+                .interact()
+                .map_err(|e| InitError::Io(std::io::Error::other(e)))?
+        } else {
+            false
+        };
+
+        (git_tag, push_tags)
+    };
+
+    // 9. Create config
+    let kmp = kmp_project.map(|k| KmpSettings {
+        shared_module: k.shared_module,
+        integration: k.integration,
+    });
+    let mut pre_version_bump = preset.default_hooks();
+    if let Some(kmp) = &kmp {
+        pre_version_bump.push(kmp::prebuild_command(&kmp.shared_module, kmp.integration));
+    }
+
+    let config = ProjectConfig {
+        project: crate::config::project::ProjectSettings {
+            ios_path: detected_ios_path.clone(),
+            scheme: selected_scheme.clone(),
+            bundle_id: final_bundle_id,
+            asc_app_id,
+            preset: Some(preset),
+        },
+        deploy: crate::config::project::DeploySettings {
+            git_tag,
+            push_tags,
+            ..Default::default()
+        },
+        build: crate::config::project::BuildSettings::default(),
+        signing,
+        environments: Default::default(),
+        integrations: crate::config::project::IntegrationsSettings::default(),
+        hooks: crate::config::project::HooksSettings {
+            pre_version_bump,
+            ..Default::default()
+        },
+        kmp,
+        android: None,
+        symbols: None,
+        cache: None,
+        notifications: None,
+        size: None,
+        analysis: None,
+        templates: None,
+        brands: Default::default(),
+        matrix: None,
+        compliance: None,
+    };
+
+    // 10. Write config
+    config
+        .save()
+        .map_err(|e| InitError::Io(std::io::Error::other(e)))?;
+
+    ui::success(&t!("init-created-config"));
+
+    // 11. Create example config for team reference
+    if !Path::new(".launchpad.toml.example").exists() {
+        std::fs::write(".launchpad.toml.example", templates::LAUNCHPAD_TOML_EXAMPLE)?;
+        ui::success(&t!("init-created-example"));
+    }
+
+    // 12. Check and create Fastfile
+    check_and_create_fastfile(&config, non_interactive)?;
+
+    // 13. Offer to keep build outputs (and optionally .launchpad.toml) out of git
+    if Path::new(".gitignore").exists() || Path::new(".git").exists() {
+        offer_gitignore(&config, non_interactive)?;
+    }
+
+    ui::blank();
+    ui::header(&t!("init-complete"));
+    ui::blank();
+    ui::line(format_args!("  {}", t!("next-steps")));
+    ui::line(format_args!("    {}", t!("init-next-doctor")));
+    ui::line(format_args!("    {}", t!("init-next-deploy")));
+    ui::blank();
+
+    Ok(())
+}
+
+/// Write launchpad's entries into a managed .gitignore block, replaced on re-runs
+fn offer_gitignore(config: &ProjectConfig, non_interactive: bool) -> Result<(), InitError> {
+    if non_interactive {
+        return Ok(()); // Don't modify gitignore in non-interactive mode
+    }
+
+    let add_outputs = Confirm::new()
+        .with_prompt(t!("init-prompt-gitignore-outputs"))
+        .default(true)
+        .interact()
+        .map_err(|e| InitError::Io(std::io::Error::other(e)))?;
+    let add_config = Confirm::new()
+        .with_prompt(t!("init-prompt-gitignore"))
+        .default(false)
+        .interact()
+        .map_err(|e| InitError::Io(std::io::Error::other(e)))?;
+
+    let mut entries = Vec::new();
+    if add_outputs {
+        entries.extend(gitignore_entries(config));
+    }
+    if add_config {
+        entries.push(".launchpad.toml".to_string());
+    }
+    if entries.is_empty() {
+        return Ok(());
+    }
+
+    let gitignore = std::fs::read_to_string(".gitignore").unwrap_or_default();
+    let updated = with_managed_block(&gitignore, &entries);
+    if updated != gitignore {
+        std::fs::write(".gitignore", updated)?;
+        ui::success(&t!("init-added-gitignore"));
+    }
+
+    Ok(())
+}
+
+/// Build artifacts, fastlane's report and the local config override
+fn gitignore_entries(config: &ProjectConfig) -> Vec<String> {
+    let mut entries = Vec::new();
+
+    // Anchored to the repo root; skip directories outside it
+    let output_dir = config.build.output_dir.trim_matches('/');
+    if !output_dir.is_empty()
+        && output_dir != "."
+        && !output_dir.starts_with("..")
+        && !output_dir.starts_with('~')
+        && !Path::new(output_dir).is_absolute()
+    {
+        entries.push(format!("/{}/", output_dir));
+    }
+
+    let ios_path = config.project.ios_path.trim_matches('/');
+    entries.push(match ios_path {
+        "" | "." => "/fastlane/report.xml".to_string(),
+        _ => format!("/{}/fastlane/report.xml", ios_path),
+    });
+    entries.push("*.ipa".to_string());
+    entries.push("*.dSYM.zip".to_string());
+    entries.push(".launchpad.local.toml".to_string());
+
+    entries
+}
+
+/// Replace the launchpad block in a .gitignore (or append one), leaving other lines alone
+fn with_managed_block(gitignore: &str, entries: &[String]) -> String {
+    let lines: Vec<&str> = gitignore.lines().collect();
+    let begin = lines.iter().position(|l| l.trim() == MANAGED_BEGIN);
+    let end = lines.iter().position(|l| l.trim() == MANAGED_END);
+
+    let (before, after, replacing) = match (begin, end) {
+        (Some(begin), Some(end)) if begin < end => (&lines[..begin], &lines[end + 1..], true),
+        _ => (&lines[..], &lines[..0], false),
+    };
+
+    // Entries the user already ignores outside the block stay where they are
+    let mut block = vec![MANAGED_BEGIN.to_string()];
+    block.extend(
+        entries
+            .iter()
+            .filter(|entry| !before.contains(&entry.as_str()) && !after.contains(&entry.as_str()))
+            .cloned(),
+    );
+    block.push(MANAGED_END.to_string());
+
+    let mut result: Vec<String> = before.iter().map(|l| l.to_string()).collect();
+    if !replacing && result.last().is_some_and(|l| !l.trim().is_empty()) {
+        result.push(String::new());
+    }
+    result.extend(block);
+    result.extend(after.iter().map(|l| l.to_string()));
+
+    let mut content = result.join("\n");
+    content.push('\n');
+    content
+}
+
+/// Record the signing style; manual signing needs a provisioning profile per bundle ID
+/// App Store Connect app ID of `bundle_id`, when there are credentials to ask with
+pub async fn lookup_app_id(bundle_id: &str) -> Option<String> {
+    let global_config = GlobalConfig::load().ok()??;
+    let client = AscClient::new(&global_config.apple).ok()?.without_retries();
+
+    match client.app_id(bundle_id).await {
+        Ok(app_id) => {
+            ui::success(&t!("init-asc-app-id", app_id = app_id));
+            Some(app_id)
+        }
+        Err(e) => {
+            ui::warn(&t!("init-asc-app-id-unknown", error = e.to_string()));
+            None
+        }
+    }
+}
+
+fn detect_signing(
+    style: SigningStyle,
+    bundle_ids: &[String],
+    non_interactive: bool,
+) -> Result<SigningSettings, InitError> {
+    let mut signing = SigningSettings {
+        style: Some(style),
+        ..Default::default()
+    };
+
+    if style == SigningStyle::Manual {
+        if non_interactive {
+            ui::warn(&t!("init-signing-manual-todo"));
+        } else {
+            for bundle_id in bundle_ids {
+                let profile: String = Input::new()
+                    .with_prompt(t!("init-prompt-profile", bundle_id = bundle_id))
+                    .allow_empty(true)
+                    .interact_text()
+                    .map_err(|e| InitError::Io(std::io::Error::other(e)))?;
+
+                if !profile.is_empty() {
+                    signing.profiles.insert(bundle_id.clone(), profile);
+                }
+            }
+        }
+    }
+
+    Ok(signing)
+}
+
+fn check_and_install_fastlane(non_interactive: bool) -> Result<(), InitError> {
+    if which::which("fastlane").is_ok() {
+        ui::success(&t!("init-fastlane-found"));
+        return Ok(());
+    }
+
+    ui::error(&t!("init-fastlane-missing"));
+
+    let install = if non_interactive {
+        ui::step(&t!("init-fastlane-installing-yes"));
+        true
+    } else {
+        Confirm::new()
+            .with_prompt(t!("init-prompt-install-fastlane"))
+            .default(true)
+            .interact()
+            .map_err(|e| InitError::Io(std::io::Error::other(e)))?
+    };
+
+    if !install {
+        return Err(InitError::UserCancelled);
+    }
+
+    ui::step(&t!("init-fastlane-running-brew"));
+
+    let spinner = ui::spinner(&t!("init-fastlane-installing"));
+
+    let status = Command::new("brew")
+        .args(["install", "fastlane"])
+        .stdout(std::process::Stdio::null())
+        .stderr(std::process::Stdio::null())
+        .status()?;
+
+    spinner.finish_and_clear();
+
+    if !status.success() {
+        ui::error(&t!("init-fastlane-install-failed"));
+        ui::step(&t!("init-fastlane-try-manual"));
+        return Err(InitError::FastlaneInstallFailed);
+    }
+
+    ui::success(&t!("init-fastlane-installed"));
+    Ok(())
+}
+
+fn check_and_create_fastfile(
+    config: &ProjectConfig,
+    non_interactive: bool,
+) -> Result<(), InitError> {
+    let ios_path = &config.project.ios_path;
+    let fastfile_paths = [
+        format!("{}/fastlane/Fastfile", ios_path),
+        format!("{}/Fastfile", ios_path),
+        "fastlane/Fastfile".to_string(),
+        "Fastfile".to_string(),
+    ];
+
+    for path in &fastfile_paths {
+        if Path::new(path).exists() {
+            ui::success(&t!("init-fastfile-found", path = path));
+            return Ok(());
+        }
+    }
+
+    ui::warn(&t!("init-fastfile-missing", path = ios_path));
+
+    let create = if non_interactive {
+        ui::step(&t!("init-fastfile-creating-yes"));
+        true
+    } else {
+        Confirm::new()
+            .with_prompt(t!("init-prompt-create-fastfile"))
+            .default(true)
+            .interact()
+            .map_err(|e| InitError::Io(std::io::Error::other(e)))?
+    };
+
+    if !create {
+        ui::warn(&t!("init-fastfile-skipped"));
+        return Ok(());
+    }
+
+    // Create fastlane directory if it doesn't exist
+    let fastlane_dir = format!("{}/fastlane", ios_path);
+    std::fs::create_dir_all(&fastlane_dir)?;
+
+    // Generate and write Fastfile
+    let fastfile_content = templates::generate_fastfile(&FastfileContext::new(config));
+    let fastfile_path = format!("{}/Fastfile", fastlane_dir);
+    std::fs::write(&fastfile_path, fastfile_content)?;
+
+    ui::success(&t!("init-created-file", path = fastfile_path));
+
+    Ok(())
+}
+
+/// Use --template, or confirm the detected preset interactively
+fn select_preset(template: Option<Preset>, non_interactive: bool) -> Result<Preset, InitError> {
+    if let Some(preset) = template {
+        return Ok(preset);
+    }
+
+    let detected = Preset::detect();
+    if non_interactive {
+        ui::success(&t!("init-detected-preset", preset = detected));
+        return Ok(detected);
+    }
+
+    let default = Preset::ALL.iter().position(|p| *p == detected).unwrap_or(0);
+    let selection = Select::new()
+        .with_prompt(t!("init-prompt-preset"))
+        .items(&Preset::ALL)
+        .default(default)
+        .interact()
+        .map_err(|e| InitError::Io(std::io::Error::other(e)))?;
+
+    Ok(Preset::ALL[selection])
+}
+
+/// Find the shared Gradle module and the app that embeds it
+fn detect_kmp() -> Option<KmpProject> {
+    let Some(project) = kmp::detect(Path::new(".")) else {
+        ui::warn(&t!("init-kmp-not-found"));
+        return None;
+    };
+
+    ui::success(&t!(
+        "init-detected-kmp",
+        module = project.shared_module,
+        integration = project.integration
+    ));
+
+    if project.integration == FrameworkIntegration::EmbedAndSign
+        && !kmp::has_embed_phase(&project.ios_path)
+    {
+        ui::warn(&t!(
+            "init-kmp-no-embed-phase",
+            module = project.shared_module
+        ));
+    }
+
+    Some(project)
+}
+
+/// Best-ranked directory with an Xcode workspace or project
+pub fn detect_ios_path(preset: Preset) -> Option<String> {
+    find_ios_paths(preset).into_iter().next()
+}
+
+/// Pick among the detected project directories; the best-ranked one without a prompt
+fn select_ios_path(preset: Preset, non_interactive: bool) -> Result<String, InitError> {
+    let paths = find_ios_paths(preset);
+
+    match paths.len() {
+        0 => Err(InitError::NoIosProject),
+        1 => Ok(paths[0].clone()),
+        _ if non_interactive => {
+            ui::success(&t!(
+                "init-using-project-first",
+                path = paths[0],
+                count = paths.len()
+            ));
+            Ok(paths[0].clone())
+        }
+        _ => {
+            let items: Vec<String> = paths
+                .iter()
+                .map(|path| match Xcode::container(path) {
+                    Some(container) => format!("{} ({})", path, container),
+                    None => path.clone(),
+                })
+                .collect();
+
+            ui::step(&t!("init-select-project"));
+            let selection = Select::new()
+                .items(&items)
+                .default(0)
+                .interact()
+                .map_err(|e| InitError::Io(std::io::Error::other(e)))?;
+            Ok(paths[selection].clone())
+        }
+    }
+}
+
+/// A directory holding an Xcode workspace or project
+struct ProjectDir {
+    path: String,
+    workspace: bool,
+    depth: usize,
+}
+
+/// Every directory (up to MAX_SEARCH_DEPTH deep) with an Xcode workspace or project,
+/// workspaces first, then the preset's usual locations, then the shallowest
+fn find_ios_paths(preset: Preset) -> Vec<String> {
+    let mut found = Vec::new();
+    search_project_dirs(Path::new("."), 0, &mut found);
+
+    let usual = preset.ios_path_candidates();
+    found.sort_by_key(|dir| {
+        let rank = usual
+            .iter()
+            .position(|&candidate| candidate == dir.path)
+            .unwrap_or(usual.len());
+        (!dir.workspace, rank, dir.depth, dir.path.clone())
+    });
+
+    found.into_iter().map(|dir| dir.path).collect()
+}
+
+fn search_project_dirs(dir: &Path, depth: usize, found: &mut Vec<ProjectDir>) {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return;
+    };
+
+    let mut workspace = false;
+    let mut project = false;
+    let mut subdirs = Vec::new();
+    for entry in entries.flatten() {
+        let name = entry.file_name().to_string_lossy().to_string();
+        if name.ends_with(".xcworkspace") {
+            workspace = true;
+        } else if name.ends_with(".xcodeproj") {
+            project = true;
+        } else if depth < MAX_SEARCH_DEPTH
+            && !name.starts_with('.')
+            && !SKIPPED_DIRS.contains(&name.as_str())
+            && entry.file_type().is_ok_and(|t| t.is_dir())
+        {
+            subdirs.push(entry.path());
+        }
+    }
+
+    if workspace || project {
+        let path = dir
+            .strip_prefix(".")
+            .ok()
+            .filter(|p| !p.as_os_str().is_empty())
+            .map(|p| p.to_string_lossy().to_string())
+            .unwrap_or_else(|| ".".to_string());
+        found.push(ProjectDir {
+            path,
+            workspace,
+            depth,
+        });
+    }
+
+    for subdir in subdirs {
+        search_project_dirs(&subdir, depth + 1, found);
+    }
+}
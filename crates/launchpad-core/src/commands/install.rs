@@ -0,0 +1,339 @@
+use crate::artifact::BuildStamp;
+use crate::config::project::ProjectConfig;
+use crate::ipa;
+use crate::profiles;
+use crate::ui;
+use dialoguer::Select;
+use serde::Deserialize;
+use std::io::IsTerminal;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use thiserror::Error;
+
+/// Seconds ios-deploy listens for devices before listing them
+const DETECT_TIMEOUT: &str = "3";
+
+#[derive(Error, Debug)]
+pub enum InstallError {
+    #[error("Project config not found. Run 'launchpad init' first, or pass --ipa.")]
+    NoProjectConfig,
+
+    #[error("No build in {0}. Build one with an ad-hoc or development export method ('launchpad deploy --skip-upload'), or pass --ipa.")]
+    NoBuild(PathBuf),
+
+    #[error("IPA not found: {0}")]
+    IpaNotFound(PathBuf),
+
+    #[error("No app found in {0}")]
+    InvalidIpa(PathBuf),
+
+    #[error("Neither devicectl (Xcode 15 or later) nor ios-deploy is available; installing needs macOS with one of them")]
+    NoInstaller,
+
+    #[error("No connected device. Plug one in over USB, or pair it for Wi-Fi in Xcode > Devices and Simulators.")]
+    NoDevice,
+
+    #[error("Several devices are connected ({0}). Pass --device with a UDID.")]
+    DeviceRequired(String),
+
+    #[error("{0} is built for the App Store; it installs through TestFlight, not directly")]
+    AppStoreBuild(String),
+
+    #[error("Device {udid} isn't in the profile '{profile}'. Register it on the Developer portal and rebuild.")]
+    DeviceNotProvisioned { udid: String, profile: String },
+
+    #[error("{command} failed: {message}")]
+    Command { command: String, message: String },
+
+    #[error("IO error: {0}")]
+    Io(#[from] std::io::Error),
+
+    #[error("Config error: {0}")]
+    Config(String),
+}
+
+/// What installs the app: Xcode's devicectl, or ios-deploy on older Xcodes
+#[derive(Debug, Clone, Copy)]
+enum Installer {
+    Devicectl,
+    IosDeploy,
+}
+
+impl Installer {
+    fn detect() -> Option<Self> {
+        let devicectl = Command::new("xcrun")
+            .args(["--find", "devicectl"])
+            .output()
+            .is_ok_and(|output| output.status.success());
+        if devicectl {
+            Some(Installer::Devicectl)
+        } else if which::which("ios-deploy").is_ok() {
+            Some(Installer::IosDeploy)
+        } else {
+            None
+        }
+    }
+
+    fn name(self) -> &'static str {
+        match self {
+            Installer::Devicectl => "devicectl",
+            Installer::IosDeploy => "ios-deploy",
+        }
+    }
+}
+
+struct Device {
+    udid: String,
+    name: String,
+}
+
+#[derive(Deserialize)]
+struct DevicectlOutput {
+    result: DevicectlResult,
+}
+
+#[derive(Deserialize)]
+struct DevicectlResult {
+    devices: Vec<DevicectlDevice>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct DevicectlDevice {
+    device_properties: DevicectlDeviceProperties,
+    hardware_properties: DevicectlHardwareProperties,
+    connection_properties: DevicectlConnectionProperties,
+}
+
+#[derive(Deserialize)]
+struct DevicectlDeviceProperties {
+    name: String,
+}
+
+#[derive(Deserialize)]
+struct DevicectlHardwareProperties {
+    udid: String,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct DevicectlConnectionProperties {
+    /// "connected" once the device is reachable over USB or the network
+    tunnel_state: Option<String>,
+}
+
+/// ios-deploy --detect --json prints one event object per device
+#[derive(Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct IosDeployEvent {
+    device: Option<IosDeployDevice>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct IosDeployDevice {
+    device_identifier: String,
+    device_name: Option<String>,
+}
+
+/// Install the last build (or `ipa`) on a connected device, for checking an
+/// ad-hoc or development build before it goes to TestFlight
+pub async fn run(
+    device: Option<String>,
+    ipa: Option<PathBuf>,
+    non_interactive: bool,
+) -> Result<(), InstallError> {
+    ui::header("Install on Device");
+
+    let ipa = match ipa {
+        Some(ipa) => ipa,
+        None => last_build()?,
+    };
+    if !ipa.is_file() {
+        return Err(InstallError::IpaNotFound(ipa));
+    }
+    let installer = Installer::detect().ok_or(InstallError::NoInstaller)?;
+
+    let device = match device {
+        Some(udid) => Device {
+            name: udid.clone(),
+            udid,
+        },
+        None => pick_device(installer, non_interactive)?,
+    };
+
+    let work = std::env::temp_dir().join(format!("launchpad-install-{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&work);
+    let result = install(installer, &ipa, &device, &work);
+    let _ = std::fs::remove_dir_all(&work);
+    result?;
+
+    ui::success(&format!("Installed {} on {}", ipa.display(), device.name));
+    Ok(())
+}
+
+/// The .ipa the last deploy built, from `last-build.json` in build.output_dir
+fn last_build() -> Result<PathBuf, InstallError> {
+    let project_config = ProjectConfig::load()
+        .map_err(|e| InstallError::Config(e.to_string()))?
+        .ok_or(InstallError::NoProjectConfig)?;
+    let output_dir = PathBuf::from(shellexpand::tilde(&project_config.build.output_dir).as_ref());
+    let stamp = BuildStamp::load(&output_dir).ok_or(InstallError::NoBuild(output_dir))?;
+    if let Some(version) = &stamp.version {
+        ui::field("Build", version);
+    }
+    Ok(PathBuf::from(stamp.ipa))
+}
+
+/// The only connected device, or the one the user picks
+fn pick_device(installer: Installer, non_interactive: bool) -> Result<Device, InstallError> {
+    let spinner = ui::spinner("Looking for connected devices...");
+    let devices = connected_devices(installer);
+    spinner.finish_and_clear();
+    let mut devices = devices?;
+
+    match devices.len() {
+        0 => Err(InstallError::NoDevice),
+        1 => Ok(devices.remove(0)),
+        _ if non_interactive || !std::io::stdin().is_terminal() => {
+            let names: Vec<String> = devices
+                .iter()
+                .map(|d| format!("{} {}", d.name, d.udid))
+                .collect();
+            Err(InstallError::DeviceRequired(names.join(", ")))
+        }
+        _ => {
+            let items: Vec<String> = devices
+                .iter()
+                .map(|d| format!("{} ({})", d.name, d.udid))
+                .collect();
+            let selection = Select::new()
+                .with_prompt("Device")
+                .items(&items)
+                .default(0)
+                .interact()
+                .map_err(|e| InstallError::Io(std::io::Error::other(e)))?;
+            Ok(devices.remove(selection))
+        }
+    }
+}
+
+fn connected_devices(installer: Installer) -> Result<Vec<Device>, InstallError> {
+    match installer {
+        Installer::Devicectl => {
+            // devicectl only writes JSON to a file
+            let json =
+                std::env::temp_dir().join(format!("launchpad-devices-{}.json", std::process::id()));
+            let mut list = Command::new("xcrun");
+            list.args(["devicectl", "list", "devices", "--quiet", "--json-output"])
+                .arg(&json);
+            let result = run_tool(&mut list, "devicectl list devices")
+                .and_then(|_| Ok(std::fs::read_to_string(&json)?));
+            let _ = std::fs::remove_file(&json);
+            let output: DevicectlOutput =
+                serde_json::from_str(&result?).map_err(|e| InstallError::Command {
+                    command: "devicectl list devices".to_string(),
+                    message: e.to_string(),
+                })?;
+            Ok(output
+                .result
+                .devices
+                .into_iter()
+                .filter(|d| d.connection_properties.tunnel_state.as_deref() == Some("connected"))
+                .map(|d| Device {
+                    udid: d.hardware_properties.udid,
+                    name: d.device_properties.name,
+                })
+                .collect())
+        }
+        Installer::IosDeploy => {
+            let mut detect = Command::new("ios-deploy");
+            detect.args(["--detect", "--json", "--timeout", DETECT_TIMEOUT]);
+            let stdout = run_tool(&mut detect, "ios-deploy --detect")?;
+            let mut devices: Vec<Device> = Vec::new();
+            for event in serde_json::Deserializer::from_str(&stdout).into_iter::<IosDeployEvent>() {
+                let Some(device) = event.ok().and_then(|e| e.device) else {
+                    continue;
+                };
+                if devices.iter().all(|d| d.udid != device.device_identifier) {
+                    devices.push(Device {
+                        name: device
+                            .device_name
+                            .unwrap_or_else(|| device.device_identifier.clone()),
+                        udid: device.device_identifier,
+                    });
+                }
+            }
+            Ok(devices)
+        }
+    }
+}
+
+/// Check the build's profile allows the device, then install the extracted app
+fn install(
+    installer: Installer,
+    ipa: &Path,
+    device: &Device,
+    work: &Path,
+) -> Result<(), InstallError> {
+    let spinner = ui::spinner("Extracting...");
+    let app = ipa::extract_app(ipa, work);
+    spinner.finish_and_clear();
+    let app = app.ok_or_else(|| InstallError::InvalidIpa(ipa.to_path_buf()))?;
+
+    // Fail with the reason rather than the installer's opaque error
+    if let Some(provisioning) = profiles::read_provisioning(&app.join("embedded.mobileprovision")) {
+        ui::field("Profile", &provisioning.profile.name);
+        ui::field("Method", provisioning.method());
+        if provisioning.method() == "app-store" {
+            return Err(InstallError::AppStoreBuild(ipa.display().to_string()));
+        }
+        if !provisioning.allows_device(&device.udid) {
+            return Err(InstallError::DeviceNotProvisioned {
+                udid: device.udid.clone(),
+                profile: provisioning.profile.name,
+            });
+        }
+    }
+    ui::field("Device", &format!("{} ({})", device.name, device.udid));
+
+    let spinner = ui::spinner(&format!("Installing with {}...", installer.name()));
+    let result = match installer {
+        Installer::Devicectl => {
+            let mut command = Command::new("xcrun");
+            command
+                .args(["devicectl", "device", "install", "app", "--device"])
+                .arg(&device.udid)
+                .arg(&app);
+            run_tool(&mut command, "devicectl device install app")
+        }
+        Installer::IosDeploy => {
+            let mut command = Command::new("ios-deploy");
+            command.args(["--id", &device.udid, "--bundle"]).arg(&app);
+            run_tool(&mut command, "ios-deploy")
+        }
+    };
+    spinner.finish_and_clear();
+    result.map(|_| ())
+}
+
+/// Run a tool and return its stdout, or its last error line
+fn run_tool(command: &mut Command, name: &str) -> Result<String, InstallError> {
+    let output = command.output()?;
+    if output.status.success() {
+        return Ok(String::from_utf8_lossy(&output.stdout).to_string());
+    }
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let message = stdout
+        .lines()
+        .chain(stderr.lines())
+        .rfind(|line| !line.trim().is_empty())
+        .unwrap_or("no output")
+        .trim()
+        .to_string();
+    Err(InstallError::Command {
+        command: name.to_string(),
+        message,
+    })
+}
@@ -0,0 +1,112 @@
+use crate::commands::template;
+use crate::config;
+use crate::config::global::GlobalConfig;
+use crate::config::project::ProjectConfig;
+use crate::events;
+use crate::fastlane::{self, Fastlane, FastlaneError};
+use crate::templating;
+use crate::ui;
+use std::path::{Path, PathBuf};
+use std::time::Instant;
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+pub enum LaneError {
+    #[error("Global config not found. Run 'launchpad setup' first.")]
+    NoGlobalConfig,
+
+    #[error("Project config not found. Run 'launchpad init' first.")]
+    NoProjectConfig,
+
+    #[error("Apple API key not found at: {0}")]
+    ApiKeyNotFound(String),
+
+    #[error("No Fastfile in {0}. Run 'launchpad template update' to create one.")]
+    NoFastfile(String),
+
+    #[error("No lane '{name}' in {path} (lanes: {available})")]
+    NotFound {
+        name: String,
+        path: String,
+        available: String,
+    },
+
+    #[error("Invalid lane option '{0}' (expected KEY:VALUE)")]
+    InvalidOption(String),
+
+    #[error("Lane '{lane}' failed (fastlane's output is above{})", .log.as_ref().map(|log| format!("; full log: {}", log.display())).unwrap_or_default())]
+    Failed { lane: String, log: Option<PathBuf> },
+
+    #[error("{0}")]
+    Fastlane(#[from] FastlaneError),
+
+    #[error("IO error: {0}")]
+    Io(#[from] std::io::Error),
+
+    #[error("Config error: {0}")]
+    Config(String),
+}
+
+/// Run a lane of the project's Fastfile with the App Store Connect key and
+/// `[build]` settings a deploy passes, showing fastlane's output as it goes
+pub async fn run(
+    name: String,
+    options: Vec<String>,
+    non_interactive: bool,
+) -> Result<(), LaneError> {
+    let started = Instant::now();
+    ui::header("Launchpad Lane");
+
+    let mut global_config = GlobalConfig::load()
+        .map_err(|e| LaneError::Config(e.to_string()))?
+        .ok_or(LaneError::NoGlobalConfig)?;
+    let mut project_config = ProjectConfig::load()
+        .map_err(|e| LaneError::Config(e.to_string()))?
+        .ok_or(LaneError::NoProjectConfig)?;
+    let environment =
+        config::use_selected_environment(&mut global_config, Some(&mut project_config))
+            .map_err(|e| LaneError::Config(e.to_string()))?;
+
+    let key_path = shellexpand::tilde(&global_config.apple.key_path).to_string();
+    if !Path::new(&key_path).exists() {
+        return Err(LaneError::ApiKeyNotFound(key_path));
+    }
+
+    // Catch a typo before fastlane spends a minute starting up
+    let ios_path = &project_config.project.ios_path;
+    let path =
+        template::find_fastfile(ios_path).ok_or_else(|| LaneError::NoFastfile(ios_path.clone()))?;
+    let lanes = fastlane::lanes(&std::fs::read_to_string(&path)?);
+    if !lanes.contains(&name) {
+        return Err(LaneError::NotFound {
+            name,
+            path,
+            available: lanes.join(", "),
+        });
+    }
+    if let Some(option) = options.iter().find(|o| !o.contains(':')) {
+        return Err(LaneError::InvalidOption(option.clone()));
+    }
+
+    ui::step(&format!("Running lane {}...", name));
+    events::phase("lane", &format!("Running fastlane lane {}", name));
+    let mut fastlane =
+        Fastlane::new(&global_config, &project_config, non_interactive).with_output_streaming();
+    if let Some(environment) = &environment {
+        fastlane = fastlane.with_apple_account(&project_config.environments[environment].apple);
+    }
+    let result = fastlane.lane(&name, &options).await;
+
+    match result {
+        Ok(()) => {
+            ui::success(&format!(
+                "Lane {} finished in {}",
+                name,
+                templating::format_duration(started.elapsed().as_secs())
+            ));
+            Ok(())
+        }
+        Err(FastlaneError::CommandFailed { log, .. }) => Err(LaneError::Failed { lane: name, log }),
+        Err(e) => Err(e.into()),
+    }
+}
@@ -0,0 +1,340 @@
+use crate::commands::deploy::ProjectArgs;
+use crate::config::project::{MatrixTarget, ProjectConfig};
+use crate::git::{self, GitError};
+use crate::history;
+use crate::templating;
+use crate::ui;
+use crate::version::VersionBump;
+use dialoguer::Confirm;
+use std::io::IsTerminal;
+use std::time::Instant;
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+pub enum MatrixError {
+    #[error("Project config not found. Run 'launchpad init' first.")]
+    NoProjectConfig,
+
+    #[error("No [matrix] in .launchpad.toml. Add environments and [[matrix.targets]] to deploy with --matrix.")]
+    NoMatrix,
+
+    #[error("[matrix] lists no environments or targets, so there's nothing to combine")]
+    EmptyMatrix,
+
+    #[error("[matrix] names environment '{name}', but .launchpad.toml defines {known}")]
+    UnknownEnvironment { name: String, known: String },
+
+    #[error("[matrix.targets] names brand '{0}', which has no [brands.{0}] in .launchpad.toml")]
+    UnknownBrand(String),
+
+    #[error("Git working directory is not clean. Commit or stash changes first.")]
+    DirtyWorkingDirectory,
+
+    #[error("Deploy needs confirmation but stdin isn't a terminal. Pass --yes to skip it.")]
+    ConfirmationRequired,
+
+    #[error("Deploy cancelled")]
+    Cancelled,
+
+    #[error("{failed} of {total} matrix deploys failed")]
+    Failed { failed: usize, total: usize },
+
+    #[error("{0}")]
+    Git(#[from] GitError),
+
+    #[error("IO error: {0}")]
+    Io(#[from] std::io::Error),
+
+    #[error("Config error: {0}")]
+    Config(String),
+}
+
+/// One deploy of the matrix
+struct Combination<'a> {
+    environment: Option<&'a str>,
+    target: Option<&'a MatrixTarget>,
+}
+
+impl Combination<'_> {
+    fn label(&self) -> String {
+        match (self.target, self.environment) {
+            (Some(target), Some(environment)) => format!("{} / {}", target.name, environment),
+            (Some(target), None) => target.name.clone(),
+            (None, Some(environment)) => environment.to_string(),
+            (None, None) => String::new(),
+        }
+    }
+
+    /// Scheme and bundle ID the deploy will use, resolved the way deploy does:
+    /// the target's own, the matrix run's flags, its brand, then `[project]`
+    fn app<'a>(&'a self, config: &'a ProjectConfig, args: &'a ProjectArgs) -> (&'a str, &'a str) {
+        let target = self.target;
+        let brand = target
+            .and_then(|t| t.brand.as_ref())
+            .or(args.brand.as_ref())
+            .and_then(|name| config.brands.get(name));
+        let scheme = target
+            .and_then(|t| t.scheme.as_deref())
+            .or(args.scheme.as_deref())
+            .unwrap_or(&config.project.scheme);
+        let bundle_id = target
+            .and_then(|t| t.bundle_id.as_deref())
+            .or(args.bundle_id.as_deref())
+            .or(brand.map(|b| b.bundle_id.as_str()))
+            .unwrap_or(&config.project.bundle_id);
+        (scheme, bundle_id)
+    }
+}
+
+/// Flags of the matrix run that every deploy in it gets
+pub struct MatrixFlags {
+    pub bump: Option<VersionBump>,
+    pub no_tag: bool,
+    pub skip_git_check: bool,
+    pub keep_artifacts: bool,
+}
+
+/// Run `launchpad deploy` once per environment × target in `[matrix]`, one after
+/// the other, and report how each went
+pub async fn run(
+    flags: MatrixFlags,
+    project_args: ProjectArgs,
+    non_interactive: bool,
+) -> Result<(), MatrixError> {
+    let started = Instant::now();
+    ui::header("Launchpad Deploy Matrix");
+
+    let config = ProjectConfig::load()
+        .map_err(|e| MatrixError::Config(e.to_string()))?
+        .ok_or(MatrixError::NoProjectConfig)?;
+    let matrix = config.matrix.as_ref().ok_or(MatrixError::NoMatrix)?;
+
+    let environments: Vec<&str> = if matrix.environments.is_empty() {
+        config.environments.keys().map(String::as_str).collect()
+    } else {
+        matrix.environments.iter().map(String::as_str).collect()
+    };
+    if let Some(name) = environments
+        .iter()
+        .find(|name| !config.environments.contains_key(**name))
+    {
+        return Err(MatrixError::UnknownEnvironment {
+            name: name.to_string(),
+            known: if config.environments.is_empty() {
+                "no [environments]".to_string()
+            } else {
+                config
+                    .environments
+                    .keys()
+                    .cloned()
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            },
+        });
+    }
+    if let Some(name) = matrix
+        .targets
+        .iter()
+        .filter_map(|t| t.brand.as_ref())
+        .find(|name| !config.brands.contains_key(*name))
+    {
+        return Err(MatrixError::UnknownBrand(name.clone()));
+    }
+    if environments.is_empty() && matrix.targets.is_empty() {
+        return Err(MatrixError::EmptyMatrix);
+    }
+
+    // Targets outside, environments inside: a target's later deploys reuse its bump
+    let targets: Vec<Option<&MatrixTarget>> = if matrix.targets.is_empty() {
+        vec![None]
+    } else {
+        matrix.targets.iter().map(Some).collect()
+    };
+    let environments: Vec<Option<&str>> = if environments.is_empty() {
+        vec![None]
+    } else {
+        environments.into_iter().map(Some).collect()
+    };
+    let combinations: Vec<Combination> = targets
+        .iter()
+        .flat_map(|target| {
+            environments.iter().map(move |environment| Combination {
+                environment: *environment,
+                target: *target,
+            })
+        })
+        .collect();
+
+    // Each deploy leaves a version bump behind, so only the first state is checked
+    if !flags.skip_git_check {
+        ui::step("Checking git status...");
+        if !git::is_clean()? {
+            return Err(MatrixError::DirtyWorkingDirectory);
+        }
+        ui::success("Working directory clean");
+    }
+
+    confirm(&config, &project_args, &combinations, non_interactive)?;
+
+    let launchpad = std::env::current_exe()?;
+    let total = combinations.len();
+    let mut results = Vec::new();
+    for (index, combination) in combinations.iter().enumerate() {
+        let label = combination.label();
+        ui::header(&format!("[{}/{}] {}", index + 1, total, label));
+
+        let first_of_target = index % environments.len() == 0;
+        let mut cmd = tokio::process::Command::new(&launchpad);
+        cmd.args(["deploy", "--yes", "--skip-git-check"]);
+        if let Some(bump) = flags.bump.filter(|_| first_of_target) {
+            cmd.arg(format!("--{}", bump.name()));
+        }
+        if flags.no_tag {
+            cmd.arg("--no-tag");
+        }
+        if flags.keep_artifacts {
+            cmd.arg("--keep-artifacts");
+        }
+        cmd.envs(project_env(&project_args));
+        if let Some(environment) = combination.environment {
+            cmd.env("LAUNCHPAD_ENVIRONMENT", environment);
+        }
+        if let Some(target) = combination.target {
+            for (key, value) in [
+                ("LAUNCHPAD_BRAND", &target.brand),
+                ("LAUNCHPAD_SCHEME", &target.scheme),
+                ("LAUNCHPAD_BUNDLE_ID", &target.bundle_id),
+                ("LAUNCHPAD_IOS_PATH", &target.ios_path),
+            ] {
+                if let Some(value) = value {
+                    cmd.env(key, value);
+                }
+            }
+        }
+
+        let deploy_started = Instant::now();
+        let outcome = match cmd.status().await {
+            Ok(status) if status.success() => Ok(()),
+            Ok(status) => Err(status.to_string()),
+            Err(e) => Err(e.to_string()),
+        };
+        let duration = templating::format_duration(deploy_started.elapsed().as_secs());
+        let failed = outcome.is_err();
+
+        let (_, bundle_id) = combination.app(&config, &project_args);
+        results.push((label, deployed_version(bundle_id, outcome), duration));
+
+        if failed && matrix.fail_fast {
+            ui::warn("Stopping after the first failure (matrix.fail_fast)");
+            break;
+        }
+    }
+
+    ui::blank();
+    ui::header("Matrix Results");
+    let mut failed = 0;
+    for (label, outcome, duration) in &results {
+        match outcome {
+            Ok(version) => ui::check_pass(label, &format!("{} in {}", version, duration)),
+            Err(reason) => {
+                failed += 1;
+                ui::check_fail(label, &format!("failed ({}) after {}", reason, duration))
+            }
+        }
+    }
+    for combination in &combinations[results.len()..] {
+        ui::check_warn(&combination.label(), "not run");
+    }
+    ui::blank();
+    ui::field(
+        "Duration",
+        &templating::format_duration(started.elapsed().as_secs()),
+    );
+
+    if failed > 0 {
+        return Err(MatrixError::Failed { failed, total });
+    }
+    Ok(())
+}
+
+/// One preview and confirmation for the whole matrix instead of one per deploy
+fn confirm(
+    config: &ProjectConfig,
+    project_args: &ProjectArgs,
+    combinations: &[Combination],
+    non_interactive: bool,
+) -> Result<(), MatrixError> {
+    ui::header("Deploy Preview");
+    for combination in combinations {
+        let (scheme, bundle_id) = combination.app(config, project_args);
+        ui::field(&combination.label(), &format!("{} ({})", scheme, bundle_id));
+    }
+    ui::blank();
+
+    if non_interactive {
+        ui::step("Skipping confirmation (--yes)");
+        return Ok(());
+    }
+    if !std::io::stdin().is_terminal() {
+        return Err(MatrixError::ConfirmationRequired);
+    }
+
+    let proceed = Confirm::new()
+        .with_prompt(format!("Start {} deploys?", combinations.len()))
+        .default(false)
+        .interact()
+        .map_err(|e| MatrixError::Io(std::io::Error::other(e)))?;
+    if !proceed {
+        return Err(MatrixError::Cancelled);
+    }
+    Ok(())
+}
+
+/// "1.2.0 (43)" from the deploy history once a deploy succeeded
+fn deployed_version(bundle_id: &str, outcome: Result<(), String>) -> Result<String, String> {
+    outcome?;
+    let version = history::load().ok().and_then(|records| {
+        records
+            .iter()
+            .rev()
+            .find(|r| r.bundle_id == bundle_id && r.platform == "ios")
+            .map(|r| format!("{} ({})", r.version, r.build))
+    });
+    Ok(version.unwrap_or_else(|| "deployed".to_string()))
+}
+
+/// The project flags of the matrix run as the LAUNCHPAD_* variables deploy reads,
+/// so every deploy gets them; a target's own settings go on top
+fn project_env(args: &ProjectArgs) -> Vec<(&'static str, String)> {
+    let mut env = Vec::new();
+    let values = [
+        ("LAUNCHPAD_IOS_PATH", args.ios_path.clone()),
+        ("LAUNCHPAD_SCHEME", args.scheme.clone()),
+        ("LAUNCHPAD_BUNDLE_ID", args.bundle_id.clone()),
+        (
+            "LAUNCHPAD_SIGNING_STYLE",
+            args.signing_style.map(|style| style.to_string()),
+        ),
+        ("LAUNCHPAD_CONFIGURATION", args.configuration.clone()),
+        ("LAUNCHPAD_EXPORT_METHOD", args.export_method.clone()),
+        ("LAUNCHPAD_BRAND", args.brand.clone()),
+    ];
+    for (key, value) in values {
+        if let Some(value) = value {
+            env.push((key, value));
+        }
+    }
+    if !args.profiles.is_empty() {
+        let profiles = args
+            .profiles
+            .iter()
+            .map(|(bundle_id, name)| format!("{}={}", bundle_id, name))
+            .collect::<Vec<_>>()
+            .join(",");
+        env.push(("LAUNCHPAD_PROFILES", profiles));
+    }
+    if !args.groups.is_empty() {
+        env.push(("LAUNCHPAD_GROUPS", args.groups.join(",")));
+    }
+    env
+}
@@ -0,0 +1,88 @@
+pub mod analytics;
+pub mod cache;
+pub mod compliance;
+pub mod deploy;
+pub mod devices;
+pub mod diff;
+pub mod doctor;
+pub mod history;
+pub mod iap;
+pub mod init;
+pub mod install;
+pub mod lane;
+pub mod matrix;
+pub mod notarize;
+pub mod pending;
+pub mod plugin;
+pub mod profiles;
+pub mod promote;
+pub mod reconfigure;
+pub mod refresh;
+pub mod resign;
+pub mod review;
+pub mod reviews;
+pub mod run;
+pub mod serve;
+pub mod setup;
+pub mod size;
+pub mod stats;
+pub mod status;
+pub mod symbols;
+pub mod tag;
+pub mod template;
+pub mod testers;
+pub mod verify;
+pub mod verify_ipa;
+pub mod watch;
+
+use crate::asc::{AscClient, AscError};
+use crate::config;
+use crate::config::global::GlobalConfig;
+use crate::config::project::ProjectConfig;
+use thiserror::Error;
+
+/// Why [`connect`] couldn't get to the app in App Store Connect
+#[derive(Error, Debug)]
+pub enum ConnectError {
+    #[error("Global config not found. Run 'launchpad setup' first.")]
+    NoGlobalConfig,
+
+    #[error("Project config not found. Run 'launchpad init' first, or pass --bundle-id.")]
+    NoProjectConfig,
+
+    #[error("Config error: {0}")]
+    Config(String),
+
+    #[error("{0}")]
+    Asc(#[from] AscError),
+}
+
+/// Both configs, switched to the account of the environment picked with --env
+pub(crate) fn load_configs() -> Result<(GlobalConfig, Option<ProjectConfig>), ConnectError> {
+    let mut global_config = GlobalConfig::load()
+        .map_err(|e| ConnectError::Config(e.to_string()))?
+        .ok_or(ConnectError::NoGlobalConfig)?;
+    let mut project_config =
+        ProjectConfig::load().map_err(|e| ConnectError::Config(e.to_string()))?;
+    config::use_selected_environment(&mut global_config, project_config.as_mut())
+        .map_err(|e| ConnectError::Config(e.to_string()))?;
+    Ok((global_config, project_config))
+}
+
+/// API client and App Store Connect app ID for `--bundle-id` or the project's app
+pub(crate) async fn connect(
+    bundle_id: Option<String>,
+) -> Result<(AscClient, String), ConnectError> {
+    let (global_config, project_config) = load_configs()?;
+    let bundle_id = match (bundle_id, &project_config) {
+        (Some(bundle_id), _) => bundle_id,
+        (None, Some(config)) => config.project.bundle_id.clone(),
+        (None, None) => return Err(ConnectError::NoProjectConfig),
+    };
+
+    let client = AscClient::new(&global_config.apple)?;
+    let app_id = client
+        .project_app_id(&bundle_id, project_config.as_ref().map(|c| &c.project))
+        .await?;
+    Ok((client, app_id))
+}
@@ -0,0 +1,176 @@
+use crate::config::global::GlobalConfig;
+use crate::events;
+use crate::notary::{Notary, NotaryError, Status};
+use crate::ui;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+use thiserror::Error;
+
+/// How often the submission status is checked
+const POLL_INTERVAL: Duration = Duration::from_secs(20);
+
+#[derive(Error, Debug)]
+pub enum NotarizeError {
+    #[error("Global config not found. Run 'launchpad setup' first.")]
+    NoGlobalConfig,
+
+    #[error("Not found: {0}")]
+    NotFound(PathBuf),
+
+    #[error("Can't notarize {0}: expected a .app, .dmg, .pkg or .zip")]
+    Unsupported(PathBuf),
+
+    #[error("Notarization {status} (submission {id})")]
+    NotAccepted { id: String, status: &'static str },
+
+    #[error(
+        "Still in progress after {minutes} minutes; check again with: xcrun notarytool info {id}"
+    )]
+    Timeout { id: String, minutes: u64 },
+
+    #[error("Could not zip the app: {0}")]
+    Zip(String),
+
+    #[error("{0}")]
+    Notary(#[from] NotaryError),
+
+    #[error("IO error: {0}")]
+    Io(#[from] std::io::Error),
+
+    #[error("Config error: {0}")]
+    Config(String),
+}
+
+/// Submit a Developer ID signed app, disk image or installer to Apple's notary
+/// service, wait for the verdict, and staple the ticket to it
+pub async fn run(path: PathBuf, staple: bool, timeout_minutes: u64) -> Result<(), NotarizeError> {
+    ui::header("Notarize");
+
+    if !path.exists() {
+        return Err(NotarizeError::NotFound(path));
+    }
+    let extension = path
+        .extension()
+        .map(|ext| ext.to_string_lossy().to_lowercase())
+        .unwrap_or_default();
+    if !matches!(extension.as_str(), "app" | "dmg" | "pkg" | "zip") {
+        return Err(NotarizeError::Unsupported(path));
+    }
+
+    let global_config = GlobalConfig::load()
+        .map_err(|e| NotarizeError::Config(e.to_string()))?
+        .ok_or(NotarizeError::NoGlobalConfig)?;
+    if !Notary::is_installed().await {
+        return Err(NotaryError::NotInstalled.into());
+    }
+    let notary = Notary::new(&global_config.apple);
+
+    // The service takes archives, so an .app goes up zipped
+    let zipped =
+        std::env::temp_dir().join(format!("launchpad-notarize-{}.zip", std::process::id()));
+    let upload = if extension == "app" {
+        zip_app(&path, &zipped)?;
+        zipped.as_path()
+    } else {
+        path.as_path()
+    };
+
+    events::phase("upload", "Uploading to the notary service");
+    let spinner = ui::spinner("Uploading to the notary service...");
+    let submitted = notary.submit(upload).await;
+    spinner.finish_and_clear();
+    let _ = std::fs::remove_file(&zipped);
+    let id = submitted?;
+    ui::success(&format!("Submitted ({})", id));
+
+    events::phase("notarize", "Waiting for notarization");
+    let status = wait(&notary, &id, timeout_minutes).await?;
+    match status {
+        Status::Accepted => ui::success("Accepted"),
+        Status::Invalid | Status::Rejected => {
+            report_issues(&notary, &id).await;
+            return Err(NotarizeError::NotAccepted {
+                id,
+                status: if status == Status::Invalid {
+                    "invalid"
+                } else {
+                    "rejected"
+                },
+            });
+        }
+        Status::InProgress => unreachable!("wait only returns a final status"),
+    }
+
+    if !staple {
+        return Ok(());
+    }
+    // Tickets attach to apps, disk images and installers, not to zip files
+    if extension == "zip" {
+        ui::step("Zip archives can't be stapled; staple the app inside and zip it again");
+        return Ok(());
+    }
+    let spinner = ui::spinner("Stapling the ticket...");
+    let stapled = Notary::staple(&path).await;
+    spinner.finish_and_clear();
+    stapled?;
+    ui::success(&format!("Stapled {}", path.display()));
+
+    Ok(())
+}
+
+/// Poll until the submission is accepted, invalid or rejected
+async fn wait(notary: &Notary, id: &str, timeout_minutes: u64) -> Result<Status, NotarizeError> {
+    let started = Instant::now();
+    let spinner = ui::spinner("Waiting for notarization...");
+    let result = loop {
+        match notary.status(id).await {
+            Ok(Status::InProgress) => {}
+            Ok(status) => break Ok(status),
+            Err(e) => break Err(e.into()),
+        }
+        if started.elapsed() >= Duration::from_secs(timeout_minutes * 60) {
+            break Err(NotarizeError::Timeout {
+                id: id.to_string(),
+                minutes: timeout_minutes,
+            });
+        }
+        tokio::time::sleep(POLL_INTERVAL).await;
+        spinner.set_message(format!(
+            "Waiting for notarization... ({}s)",
+            started.elapsed().as_secs()
+        ));
+    };
+    spinner.finish_and_clear();
+    result
+}
+
+/// Print what the notary log found; the log itself is only a hint, so errors just warn
+async fn report_issues(notary: &Notary, id: &str) {
+    match notary.issues(id).await {
+        Ok(issues) if issues.is_empty() => {}
+        Ok(issues) => {
+            for issue in issues {
+                match issue.path {
+                    Some(path) => ui::error(&format!("{}: {}", path, issue.message)),
+                    None => ui::error(&issue.message),
+                }
+            }
+        }
+        Err(e) => ui::warn(&format!("Could not fetch the notary log: {}", e)),
+    }
+}
+
+/// `ditto` keeps the extended attributes and symlinks the signature depends on
+fn zip_app(app: &Path, zip: &Path) -> Result<(), NotarizeError> {
+    let output = std::process::Command::new("ditto")
+        .args(["-c", "-k", "--keepParent"])
+        .arg(app)
+        .arg(zip)
+        .output()?;
+    if !output.status.success() {
+        return Err(NotarizeError::Zip(
+            String::from_utf8_lossy(&output.stderr).trim().to_string(),
+        ));
+    }
+    Ok(())
+}
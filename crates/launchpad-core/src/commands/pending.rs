@@ -0,0 +1,175 @@
+use crate::asc::{AscClient, AscError};
+use crate::config::global::GlobalConfig;
+use crate::fastlane;
+use crate::history::{self, HistoryError, PendingAction, PendingOperation};
+use crate::testflight;
+use crate::ui;
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+pub enum PendingError {
+    #[error("Global config not found. Run 'launchpad setup' first.")]
+    NoGlobalConfig,
+
+    #[error("{count} queued operation{} still pending. Run 'launchpad retry-pending' again later.", if *.count == 1 { "" } else { "s" })]
+    StillPending { count: usize },
+
+    #[error("{0}")]
+    Asc(#[from] AscError),
+
+    #[error("{0}")]
+    History(#[from] HistoryError),
+
+    #[error("Config error: {0}")]
+    Config(String),
+}
+
+/// Why an operation stays in the queue or leaves it
+enum Outcome {
+    Done(String),
+    Retry(String),
+    Failed(String),
+}
+
+/// Finish post-upload steps that were queued when App Store Connect was unreachable
+pub async fn run() -> Result<(), PendingError> {
+    ui::header("Retry Pending Operations");
+
+    let operations = history::load_pending()?;
+    if operations.is_empty() {
+        ui::success("Nothing pending");
+        return Ok(());
+    }
+
+    let global_config = GlobalConfig::load()
+        .map_err(|e| PendingError::Config(e.to_string()))?
+        .ok_or(PendingError::NoGlobalConfig)?;
+    let client = AscClient::new(&global_config.apple)?;
+
+    let tried = operations.clone();
+    let mut remaining = Vec::new();
+    for mut operation in operations {
+        let label = format!(
+            "{} {}: {}",
+            operation.bundle_id, operation.version, operation.action
+        );
+
+        // A build uploaded with another account's key is distributed with it too
+        let spinner = ui::spinner(&format!("Retrying {}...", label));
+        let outcome = match operation.key_profile.as_deref().map(profile_client) {
+            None => retry(&client, &operation).await,
+            Some(Ok(profile_client)) => retry(&profile_client, &operation).await,
+            Some(Err(e)) => Outcome::Retry(e.to_string()),
+        };
+        spinner.finish_and_clear();
+
+        match outcome {
+            Outcome::Done(message) => ui::check_pass(&label, &message),
+            Outcome::Failed(message) => ui::check_fail(&label, &message),
+            Outcome::Retry(message) => {
+                ui::warn(&format!("{} — {}", label, message));
+                operation.attempts += 1;
+                operation.last_error = message;
+                remaining.push(operation);
+            }
+        }
+    }
+
+    // Deploys may have queued more while these were retried
+    history::update_pending(|queue| {
+        let queued_since = queue.drain(..).filter(|op| !tried.contains(op));
+        *queue = remaining.iter().cloned().chain(queued_since).collect();
+    })?;
+
+    if remaining.is_empty() {
+        ui::blank();
+        ui::success("All pending operations finished");
+        Ok(())
+    } else {
+        Err(PendingError::StillPending {
+            count: remaining.len(),
+        })
+    }
+}
+
+/// A client with `[key_profiles.<name>]` instead of `[apple]`
+fn profile_client(name: &str) -> Result<AscClient, PendingError> {
+    let mut global_config = GlobalConfig::load()
+        .map_err(|e| PendingError::Config(e.to_string()))?
+        .ok_or(PendingError::NoGlobalConfig)?;
+    global_config
+        .use_key_profile(name)
+        .map_err(|e| PendingError::Config(e.to_string()))?;
+    Ok(AscClient::new(&global_config.apple)?)
+}
+
+async fn retry(client: &AscClient, operation: &PendingOperation) -> Outcome {
+    match distribute(client, operation).await {
+        Ok(outcome) => outcome,
+        Err(e) if e.is_transient() => Outcome::Retry(e.to_string()),
+        // Retrying won't fix it (deleted group, revoked key...), so stop queueing it
+        Err(e) => Outcome::Failed(e.to_string()),
+    }
+}
+
+async fn distribute(client: &AscClient, operation: &PendingOperation) -> Result<Outcome, AscError> {
+    let PendingAction::Distribute {
+        groups,
+        distribution,
+        notify_testers,
+    } = &operation.action;
+
+    let app_id = client.app_id(&operation.bundle_id).await?;
+    let (version, number) = fastlane::split_version(&operation.version);
+    let found = match &number {
+        Some(number) => {
+            client
+                .find_build(
+                    &app_id,
+                    number,
+                    Some(version.as_str()).filter(|v| *v != "unknown"),
+                )
+                .await?
+        }
+        None => client.latest_build(&app_id).await?,
+    };
+
+    let build = match found {
+        Some(build) if build.is_valid() => build,
+        Some(build) if build.is_failed() => {
+            return Ok(Outcome::Failed(format!(
+                "build {} failed processing on App Store Connect",
+                build.number
+            )))
+        }
+        Some(_) => return Ok(Outcome::Retry("build still processing".to_string())),
+        None => {
+            return Ok(Outcome::Retry(
+                "build not on App Store Connect yet".to_string(),
+            ))
+        }
+    };
+
+    let report = testflight::distribute(
+        client,
+        &app_id,
+        &build,
+        groups,
+        *distribution,
+        *notify_testers,
+    )
+    .await?;
+
+    let mut message = if report.distributed.is_empty() {
+        "settings applied".to_string()
+    } else {
+        format!("distributed to {}", report.distributed.join(", "))
+    };
+    if !report.deferred.is_empty() {
+        message.push_str(&format!(
+            "; {} wait for promote-beta",
+            report.deferred.join(", ")
+        ));
+    }
+    Ok(Outcome::Done(message))
+}
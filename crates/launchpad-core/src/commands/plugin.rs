@@ -0,0 +1,75 @@
+use crate::config::global::{AppleConfig, GlobalConfig};
+use crate::config::project::ProjectConfig;
+use serde::Serialize;
+use std::io::Write;
+use std::process::{Command, ExitCode, Stdio};
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+pub enum PluginError {
+    #[error("Unknown command '{0}' (no launchpad-{0} found on PATH)")]
+    NotFound(String),
+
+    #[error("Config error: {0}")]
+    Config(String),
+
+    #[error("IO error: {0}")]
+    Io(#[from] std::io::Error),
+}
+
+/// Everything a plugin receives as JSON on stdin
+#[derive(Serialize)]
+struct PluginContext {
+    launchpad_version: &'static str,
+    project_dir: String,
+    project: Option<ProjectConfig>,
+    apple: Option<AppleConfig>,
+    non_interactive: bool,
+}
+
+/// Run `launchpad-<name>` from PATH with the remaining args, cargo-style.
+/// The plugin's exit code becomes launchpad's.
+pub async fn run(args: Vec<String>, non_interactive: bool) -> Result<ExitCode, PluginError> {
+    let Some((name, plugin_args)) = args.split_first() else {
+        return Err(PluginError::NotFound(String::new()));
+    };
+
+    let executable = which::which(format!("launchpad-{}", name))
+        .map_err(|_| PluginError::NotFound(name.clone()))?;
+
+    let project = ProjectConfig::load().map_err(|e| PluginError::Config(e.to_string()))?;
+    let apple = GlobalConfig::load()
+        .map_err(|e| PluginError::Config(e.to_string()))?
+        .map(|config| AppleConfig {
+            key_path: shellexpand::tilde(&config.apple.key_path).to_string(),
+            ..config.apple
+        });
+
+    let context = PluginContext {
+        launchpad_version: env!("CARGO_PKG_VERSION"),
+        project_dir: std::env::current_dir()?.to_string_lossy().to_string(),
+        project,
+        apple,
+        non_interactive,
+    };
+    let payload = serde_json::to_vec(&context).map_err(std::io::Error::other)?;
+
+    let mut child = Command::new(executable)
+        .args(plugin_args)
+        .stdin(Stdio::piped())
+        .spawn()?;
+
+    if let Some(mut stdin) = child.stdin.take() {
+        // Plugins that ignore stdin may exit before reading it
+        if let Err(e) = stdin.write_all(&payload) {
+            if e.kind() != std::io::ErrorKind::BrokenPipe {
+                return Err(e.into());
+            }
+        }
+    }
+
+    let status = child.wait()?;
+    let code = status.code().unwrap_or(1);
+
+    Ok(ExitCode::from(code.clamp(0, 255) as u8))
+}
@@ -0,0 +1,383 @@
+use crate::asc::{AscClient, AscError};
+use crate::config;
+use crate::config::global::GlobalConfig;
+use crate::config::project::ProjectConfig;
+use crate::ipa;
+use crate::profiles::{self, Profile, Provisioning, Source};
+use crate::ui;
+use clap::Subcommand;
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+use thiserror::Error;
+
+/// Highlight profiles expiring within this many days when --warn-days isn't given
+pub const DEFAULT_WARN_DAYS: i64 = 30;
+
+#[derive(Error, Debug)]
+pub enum ProfilesError {
+    #[error("Project config not found. Run 'launchpad init' first.")]
+    NoProjectConfig,
+
+    #[error("{count} provisioning profile{} expired, invalid or expiring within {days} days", if *.count == 1 { "" } else { "s" })]
+    Expiring { count: usize, days: i64 },
+
+    #[error("IPA not found: {0}")]
+    IpaNotFound(PathBuf),
+
+    #[error("No app found in {0}")]
+    InvalidIpa(PathBuf),
+
+    #[error("{count} problem{} would stop the build from installing", if *.count == 1 { "" } else { "s" })]
+    WontInstall { count: usize },
+
+    #[error("{0}")]
+    Asc(#[from] AscError),
+
+    #[error("Config error: {0}")]
+    Config(String),
+}
+
+#[derive(Subcommand)]
+pub enum ProfilesCommand {
+    /// List installed and portal profiles for this project with their expiry dates
+    Status {
+        /// Exit non-zero if any profile expires within this many days (for scheduled CI)
+        #[arg(long, value_name = "DAYS")]
+        warn_days: Option<i64>,
+    },
+
+    /// Why an .ipa will or won't install: distribution method, provisioned devices, expiry
+    Ipa {
+        /// The .ipa to inspect
+        path: PathBuf,
+
+        /// Check that this device can install it (repeatable)
+        #[arg(long = "device", value_name = "UDID")]
+        devices: Vec<String>,
+    },
+}
+
+pub async fn run(command: ProfilesCommand) -> Result<(), ProfilesError> {
+    match command {
+        ProfilesCommand::Status { warn_days } => status(warn_days).await,
+        ProfilesCommand::Ipa { path, devices } => inspect_ipa(path, devices).await,
+    }
+}
+
+async fn status(warn_days: Option<i64>) -> Result<(), ProfilesError> {
+    ui::header("Provisioning Profiles");
+
+    let mut project_config = ProjectConfig::load()
+        .map_err(|e| ProfilesError::Config(e.to_string()))?
+        .ok_or(ProfilesError::NoProjectConfig)?;
+    let mut global_config = GlobalConfig::load().ok().flatten();
+    if let Some(global_config) = &mut global_config {
+        config::use_selected_environment(global_config, Some(&mut project_config))
+            .map_err(|e| ProfilesError::Config(e.to_string()))?;
+    }
+
+    // The app, plus anything mapped for manual signing (extensions)
+    let mut bundle_ids = vec![project_config.project.bundle_id.clone()];
+    for id in project_config.signing.profiles.keys() {
+        if !bundle_ids.contains(id) {
+            bundle_ids.push(id.clone());
+        }
+    }
+
+    let mut found: Vec<Profile> = profiles::installed()
+        .into_iter()
+        .filter(|p| p.matches(&bundle_ids))
+        .collect();
+
+    // Portal profiles need the API key; without it, installed ones still get checked
+    match global_config {
+        Some(global_config) => {
+            let client = AscClient::new(&global_config.apple)?;
+            let spinner = ui::spinner("Fetching profiles from the Developer portal...");
+            let portal = client.profiles().await;
+            spinner.finish_and_clear();
+
+            match portal {
+                Ok(portal) => found.extend(
+                    portal
+                        .into_iter()
+                        .map(Profile::from)
+                        .filter(|p| p.matches(&bundle_ids)),
+                ),
+                Err(e) => ui::warn(&format!("Could not list portal profiles: {}", e)),
+            }
+        }
+        None => ui::warn("No global config; only installed profiles are checked"),
+    }
+
+    if found.is_empty() {
+        ui::warn(&format!("No profiles found for {}", bundle_ids.join(", ")));
+        return Ok(());
+    }
+
+    found.sort_by_key(|p| {
+        (
+            p.source,
+            p.bundle_id.clone(),
+            p.expires.map(SystemTime::from),
+        )
+    });
+
+    // Only the newest valid profile per bundle ID counts; older ones have been replaced
+    let newest: BTreeMap<(Source, &str), usize> = found
+        .iter()
+        .enumerate()
+        .filter(|(_, p)| !p.invalid)
+        .map(|(i, p)| ((p.source, p.bundle_id.as_str()), i))
+        .collect();
+
+    let threshold = warn_days.unwrap_or(DEFAULT_WARN_DAYS);
+    let mut at_risk = 0;
+    let mut section = None;
+
+    for (i, profile) in found.iter().enumerate() {
+        if section != Some(profile.source) {
+            section = Some(profile.source);
+            ui::blank();
+            ui::line(format_args!("  {}:", profile.source));
+        }
+
+        let label = format!(
+            "{}{} ({})",
+            profile.name,
+            profile
+                .kind
+                .as_deref()
+                .map(|k| format!(" [{}]", k))
+                .unwrap_or_default(),
+            profile.bundle_id
+        );
+        let days = profile.days_left();
+        let expiry = match (profile.expiry_date(), days) {
+            (Some(date), Some(days)) if days < 0 => {
+                format!("expired {} ({} days ago)", date, -days)
+            }
+            (Some(date), Some(days)) => format!("expires {} ({} days)", date, days),
+            _ => "expiry unknown".to_string(),
+        };
+
+        let replaced = newest
+            .get(&(profile.source, profile.bundle_id.as_str()))
+            .is_some_and(|&n| n != i);
+        if replaced {
+            ui::step(&format!("{} — {}, superseded", label, expiry));
+        } else if profile.invalid {
+            ui::check_fail(&label, "invalid on the portal");
+            at_risk += 1;
+        } else if days.is_some_and(|d| d < 0) {
+            ui::check_fail(&label, &expiry);
+            at_risk += 1;
+        } else if days.is_some_and(|d| d <= threshold) {
+            ui::warn(&format!("{} — {}", label, expiry));
+            at_risk += 1;
+        } else {
+            ui::check_pass(&label, &expiry);
+        }
+    }
+    ui::blank();
+
+    match warn_days {
+        Some(days) if at_risk > 0 => Err(ProfilesError::Expiring {
+            count: at_risk,
+            days,
+        }),
+        _ => Ok(()),
+    }
+}
+
+async fn inspect_ipa(path: PathBuf, devices: Vec<String>) -> Result<(), ProfilesError> {
+    ui::header("IPA Provisioning");
+
+    if !path.is_file() {
+        return Err(ProfilesError::IpaNotFound(path));
+    }
+    let work = std::env::temp_dir().join(format!("launchpad-ipa-profile-{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&work);
+    let spinner = ui::spinner("Extracting...");
+    let app = ipa::extract_app(&path, &work);
+    spinner.finish_and_clear();
+    let result = match app {
+        Some(app) => Ok(report_app(&app, &devices)),
+        None => Err(ProfilesError::InvalidIpa(path)),
+    };
+    let _ = std::fs::remove_dir_all(&work);
+
+    match result? {
+        0 => {
+            ui::success(if devices.is_empty() {
+                "Profiles are current"
+            } else {
+                "Installs on every given device"
+            });
+            Ok(())
+        }
+        count => Err(ProfilesError::WontInstall { count }),
+    }
+}
+
+/// Print the app's and its extensions' profiles; returns how many problems
+/// would stop an install
+fn report_app(app: &Path, devices: &[String]) -> usize {
+    let info = plist::Value::from_file(app.join("Info.plist")).ok();
+    let info = info.as_ref().and_then(|info| info.as_dictionary());
+    let string = |key: &str| {
+        info.and_then(|info| info.get(key))
+            .and_then(|value| value.as_string())
+            .unwrap_or("unknown")
+            .to_string()
+    };
+    let families: Vec<&str> = info
+        .and_then(|info| info.get("UIDeviceFamily"))
+        .and_then(|value| value.as_array())
+        .map(|families| {
+            families
+                .iter()
+                .filter_map(|family| match family.as_unsigned_integer() {
+                    Some(1) => Some("iPhone"),
+                    Some(2) => Some("iPad"),
+                    Some(3) => Some("Apple TV"),
+                    Some(4) => Some("Apple Watch"),
+                    Some(6) => Some("Mac"),
+                    Some(7) => Some("Apple Vision"),
+                    _ => None,
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+
+    ui::field("App", &string("CFBundleIdentifier"));
+    ui::field(
+        "Version",
+        &format!(
+            "{} ({})",
+            string("CFBundleShortVersionString"),
+            string("CFBundleVersion")
+        ),
+    );
+    ui::field("Minimum OS", &string("MinimumOSVersion"));
+    if !families.is_empty() {
+        ui::field("Devices", &families.join(", "));
+    }
+
+    // Every bundle with its own profile has to allow the device, or the install fails
+    let mut bundles = vec![app.to_path_buf()];
+    if let Ok(entries) = std::fs::read_dir(app.join("PlugIns")) {
+        let mut extensions: Vec<PathBuf> = entries
+            .filter_map(|entry| entry.ok())
+            .map(|entry| entry.path())
+            .filter(|path| path.extension().is_some_and(|ext| ext == "appex"))
+            .collect();
+        extensions.sort();
+        bundles.extend(extensions);
+    }
+
+    let mut problems = 0;
+    for (i, bundle) in bundles.iter().enumerate() {
+        let name = bundle
+            .file_name()
+            .unwrap_or_default()
+            .to_string_lossy()
+            .to_string();
+        let bundle_id = plist::Value::from_file(bundle.join("Info.plist"))
+            .ok()
+            .and_then(|info| {
+                info.as_dictionary()?
+                    .get("CFBundleIdentifier")?
+                    .as_string()
+                    .map(str::to_string)
+            })
+            .unwrap_or_default();
+
+        ui::blank();
+        ui::line(format_args!("  {}:", name));
+        let Some(provisioning) =
+            profiles::read_provisioning(&bundle.join("embedded.mobileprovision"))
+        else {
+            ui::check_fail("Profile", "no readable embedded.mobileprovision");
+            problems += 1;
+            continue;
+        };
+        problems += report_provisioning(&provisioning, &bundle_id, devices, i == 0);
+    }
+    ui::blank();
+    problems
+}
+
+fn report_provisioning(
+    provisioning: &Provisioning,
+    bundle_id: &str,
+    devices: &[String],
+    list_devices: bool,
+) -> usize {
+    let profile = &provisioning.profile;
+    let mut problems = 0;
+
+    ui::field("Profile", &profile.name);
+    if let Some(team) = &provisioning.team {
+        ui::field("Team", team);
+    }
+    ui::field("Method", provisioning.method());
+    let provisioned = match provisioning.method() {
+        "enterprise" => "any device".to_string(),
+        "app-store" => "none (installs through TestFlight or the App Store)".to_string(),
+        _ => format!(
+            "{} device{}",
+            provisioning.devices.len(),
+            if provisioning.devices.len() == 1 {
+                ""
+            } else {
+                "s"
+            }
+        ),
+    };
+    ui::field("Provisioned", &provisioned);
+    if list_devices && devices.is_empty() {
+        for udid in &provisioning.devices {
+            ui::output(udid);
+        }
+    }
+
+    if !bundle_id.is_empty() && !profile.covers(bundle_id) {
+        ui::check_fail(
+            "Bundle ID",
+            &format!(
+                "profile is for {}, bundle is {}",
+                profile.bundle_id, bundle_id
+            ),
+        );
+        problems += 1;
+    }
+    let expiry = profile
+        .expiry_date()
+        .unwrap_or_else(|| "unknown".to_string());
+    match profile.days_left() {
+        Some(days) if days < 0 => {
+            ui::check_fail("Expiry", &format!("expired on {}", expiry));
+            problems += 1;
+        }
+        Some(days) if days < DEFAULT_WARN_DAYS => {
+            ui::check_warn("Expiry", &format!("{} ({} days left)", expiry, days))
+        }
+        Some(days) => ui::check_pass("Expiry", &format!("{} ({} days left)", expiry, days)),
+        None => ui::check_warn("Expiry", "unknown"),
+    }
+
+    for udid in devices {
+        if provisioning.allows_device(udid) {
+            ui::check_pass(udid, "can install");
+        } else if provisioning.method() == "app-store" {
+            ui::check_fail(udid, "App Store builds only install through TestFlight");
+            problems += 1;
+        } else {
+            ui::check_fail(udid, "not in the profile; register it and rebuild");
+            problems += 1;
+        }
+    }
+    problems
+}
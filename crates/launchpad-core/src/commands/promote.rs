@@ -0,0 +1,119 @@
+use crate::asc::{AscClient, AscError};
+use crate::config;
+use crate::config::global::GlobalConfig;
+use crate::config::project::ProjectConfig;
+use crate::testflight;
+use crate::ui;
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+pub enum PromoteError {
+    #[error("Global config not found. Run 'launchpad setup' first.")]
+    NoGlobalConfig,
+
+    #[error("Project config not found. Run 'launchpad init' first, or pass --bundle-id.")]
+    NoProjectConfig,
+
+    #[error("No TestFlight groups to promote to. Pass --group or set deploy.groups.")]
+    NoGroups,
+
+    #[error("Build {0} not found on App Store Connect")]
+    BuildNotFound(String),
+
+    #[error("Build {0} is still processing. Try again once it's done, or pass --wait.")]
+    BuildProcessing(String),
+
+    #[error("Build {0} failed processing on App Store Connect")]
+    BuildFailed(String),
+
+    #[error("{0}")]
+    Asc(#[from] AscError),
+
+    #[error("Config error: {0}")]
+    Config(String),
+}
+
+/// Give a processed build to external TestFlight groups and submit it for beta app
+/// review. Independent of the deploy that uploaded it: the build, groups and app can
+/// all come from the command line.
+pub async fn run(
+    build: Option<String>,
+    version: Option<String>,
+    groups: Vec<String>,
+    bundle_id: Option<String>,
+    wait: bool,
+) -> Result<(), PromoteError> {
+    ui::header("Launchpad Promote");
+
+    let mut global_config = GlobalConfig::load()
+        .map_err(|e| PromoteError::Config(e.to_string()))?
+        .ok_or(PromoteError::NoGlobalConfig)?;
+    let mut project_config =
+        ProjectConfig::load().map_err(|e| PromoteError::Config(e.to_string()))?;
+    config::use_selected_environment(&mut global_config, project_config.as_mut())
+        .map_err(|e| PromoteError::Config(e.to_string()))?;
+
+    let bundle_id = match (bundle_id, &project_config) {
+        (Some(bundle_id), _) => bundle_id,
+        (None, Some(config)) => config.project.bundle_id.clone(),
+        (None, None) => return Err(PromoteError::NoProjectConfig),
+    };
+    // Groups named on the command line may include internal ones; deploy.groups
+    // internal groups already got the build with the upload
+    let include_internal = !groups.is_empty();
+    let groups = match (groups, &project_config) {
+        (groups, _) if !groups.is_empty() => groups,
+        (_, Some(config)) if !config.deploy.groups.is_empty() => config.deploy.groups.clone(),
+        _ => return Err(PromoteError::NoGroups),
+    };
+
+    let client = AscClient::new(&global_config.apple)?;
+    let app_id = client
+        .project_app_id(&bundle_id, project_config.as_ref().map(|c| &c.project))
+        .await?;
+    let label = build.clone().unwrap_or_else(|| "latest".to_string());
+
+    let build = if wait {
+        let spinner = ui::spinner("Waiting for App Store Connect to process the build...");
+        let result =
+            testflight::wait_for_build(&client, &app_id, build.as_deref(), version.as_deref())
+                .await;
+        spinner.finish_and_clear();
+        result?
+    } else {
+        // Default to the newest upload
+        let found = match &build {
+            Some(number) => {
+                client
+                    .find_build(&app_id, number, version.as_deref())
+                    .await?
+            }
+            None => client.latest_build(&app_id).await?,
+        };
+        let build = found.ok_or(PromoteError::BuildNotFound(label))?;
+        if build.is_failed() {
+            return Err(PromoteError::BuildFailed(build.number));
+        }
+        if !build.is_valid() {
+            return Err(PromoteError::BuildProcessing(build.number));
+        }
+        build
+    };
+
+    ui::success(&format!(
+        "Build {}{}",
+        build.number,
+        build
+            .uploaded_date
+            .as_deref()
+            .map(|date| format!(" (uploaded {})", date))
+            .unwrap_or_default()
+    ));
+
+    let promoted = testflight::promote(&client, &app_id, &build, &groups, include_internal).await?;
+
+    ui::success(&format!("Added to {}", promoted.join(", ")));
+    ui::step("External testers get the build once beta app review approves it");
+
+    Ok(())
+}
@@ -0,0 +1,237 @@
+use crate::commands::init;
+use crate::config::project::{ProjectConfig, ProjectConfigError, ProjectSettings, SigningStyle};
+use crate::i18n::t;
+use crate::ui;
+use crate::xcode::Xcode;
+use dialoguer::{Confirm, Input, Select};
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+pub enum ReconfigureError {
+    #[error("Project config not found. Run 'launchpad init' first.")]
+    NoProjectConfig,
+
+    #[error("IO error: {0}")]
+    Io(#[from] std::io::Error),
+
+    #[error("Config error: {0}")]
+    Config(String),
+}
+
+/// A setting whose new value differs from .launchpad.toml
+pub struct Change {
+    path: Vec<String>,
+    old: String,
+    new: String,
+    value: toml_edit::Value,
+}
+
+/// Re-run the init questions with the current values as defaults
+pub async fn run(
+    ios_path: Option<String>,
+    scheme: Option<String>,
+    bundle_id: Option<String>,
+    non_interactive: bool,
+) -> Result<(), ReconfigureError> {
+    ui::header(&t!("reconfigure-header"));
+
+    let config = ProjectConfig::load()
+        .map_err(|e| ReconfigureError::Config(e.to_string()))?
+        .ok_or(ReconfigureError::NoProjectConfig)?;
+    let current = &config.project;
+    let mut changes = Vec::new();
+
+    // 1. iOS project path
+    let new_ios_path = match ios_path {
+        Some(path) => path,
+        None if non_interactive => current.ios_path.clone(),
+        None => prompt_text(&t!("reconfigure-prompt-ios-path"), &current.ios_path)?,
+    };
+    compare(
+        &mut changes,
+        &["project", "ios_path"],
+        &current.ios_path,
+        &new_ios_path,
+    );
+
+    // 2. Scheme, from the schemes Xcode knows about when it can list them
+    let new_scheme = match scheme {
+        Some(scheme) => scheme,
+        None if non_interactive => current.scheme.clone(),
+        None => select_scheme(&new_ios_path, &current.scheme)?,
+    };
+    compare(
+        &mut changes,
+        &["project", "scheme"],
+        &current.scheme,
+        &new_scheme,
+    );
+
+    // 3. Bundle ID
+    let new_bundle_id = match bundle_id {
+        Some(bundle_id) => bundle_id,
+        None if non_interactive => current.bundle_id.clone(),
+        None => prompt_text(&t!("init-prompt-bundle-id"), &current.bundle_id)?,
+    };
+    compare(
+        &mut changes,
+        &["project", "bundle_id"],
+        &current.bundle_id,
+        &new_bundle_id,
+    );
+    compare_app_id(&mut changes, current, &new_bundle_id).await;
+
+    // 4. Provisioning profile for manual signing
+    if config.signing.style == Some(SigningStyle::Manual) && !non_interactive {
+        let old = config
+            .signing
+            .profiles
+            .get(&new_bundle_id)
+            .cloned()
+            .unwrap_or_default();
+        let profile: String = Input::new()
+            .with_prompt(t!("init-prompt-profile", bundle_id = new_bundle_id))
+            .with_initial_text(old.clone())
+            .allow_empty(true)
+            .interact_text()
+            .map_err(|e| ReconfigureError::Io(std::io::Error::other(e)))?;
+
+        // An empty answer keeps whatever is there
+        if !profile.is_empty() {
+            compare(
+                &mut changes,
+                &["signing", "profiles", &new_bundle_id],
+                &old,
+                &profile,
+            );
+        }
+    }
+
+    // 5. Git tag options
+    if !non_interactive {
+        let git_tag = Confirm::new()
+            .with_prompt(t!("init-prompt-git-tag"))
+            .default(config.deploy.git_tag)
+            .interact()
+            .map_err(|e| ReconfigureError::Io(std::io::Error::other(e)))?;
+        compare(
+            &mut changes,
+            &["deploy", "git_tag"],
+            &config.deploy.git_tag,
+            &git_tag,
+        );
+
+        let push_tags = if git_tag {
+            Confirm::new()
+                .with_prompt(t!("init-prompt-push-tags"))
+                .default(config.deploy.push_tags)
+                .interact()
+                .map_err(|e| ReconfigureError::Io(std::io::Error::other(e)))?
+        } else {
+            false
+        };
+        compare(
+            &mut changes,
+            &["deploy", "push_tags"],
+            &config.deploy.push_tags,
+            &push_tags,
+        );
+    }
+
+    // 6. Write back only the keys that changed
+    ui::blank();
+    if changes.is_empty() {
+        ui::success(&t!("reconfigure-unchanged"));
+        return Ok(());
+    }
+
+    show_changes(&changes);
+    save_changes(changes).map_err(|e| ReconfigureError::Config(e.to_string()))?;
+    Ok(())
+}
+
+/// One line per changed key: old → new
+pub fn show_changes(changes: &[Change]) {
+    for change in changes {
+        ui::step(&t!(
+            "reconfigure-changed",
+            key = change.path.join("."),
+            old = change.old,
+            new = change.new
+        ));
+    }
+}
+
+/// Write only the changed keys back to .launchpad.toml
+pub fn save_changes(changes: Vec<Change>) -> Result<(), ProjectConfigError> {
+    let values: Vec<_> = changes
+        .into_iter()
+        .map(|change| (change.path, change.value))
+        .collect();
+    ProjectConfig::update(&values)?;
+
+    ui::success(&t!("reconfigure-saved"));
+    Ok(())
+}
+
+/// Record a change when `new` differs from `old`
+pub fn compare<T>(changes: &mut Vec<Change>, path: &[&str], old: &T, new: &T)
+where
+    T: PartialEq + ToString + Clone + Into<toml_edit::Value>,
+{
+    if old != new {
+        changes.push(Change {
+            path: path.iter().map(|s| s.to_string()).collect(),
+            old: old.to_string(),
+            new: new.to_string(),
+            value: new.clone().into(),
+        });
+    }
+}
+
+/// Look up the App Store Connect app ID again when the bundle ID changes, since
+/// the recorded one belongs to the old app
+pub async fn compare_app_id(
+    changes: &mut Vec<Change>,
+    current: &ProjectSettings,
+    new_bundle_id: &str,
+) {
+    if new_bundle_id == current.bundle_id {
+        return;
+    }
+    let old = current.asc_app_id.clone().unwrap_or_default();
+    match init::lookup_app_id(new_bundle_id).await {
+        Some(app_id) => compare(changes, &["project", "asc_app_id"], &old, &app_id),
+        None if !old.is_empty() => ui::warn(&t!("reconfigure-asc-app-id-stale", app_id = old)),
+        None => {}
+    }
+}
+
+fn prompt_text(prompt: &str, current: &str) -> Result<String, ReconfigureError> {
+    Input::new()
+        .with_prompt(prompt)
+        .default(current.to_string())
+        .interact_text()
+        .map_err(|e| ReconfigureError::Io(std::io::Error::other(e)))
+}
+
+/// Pick from `xcodebuild -list`, falling back to free text when listing fails
+fn select_scheme(ios_path: &str, current: &str) -> Result<String, ReconfigureError> {
+    let schemes = match Xcode::list_schemes(ios_path) {
+        Ok(schemes) if !schemes.is_empty() => schemes,
+        _ => {
+            ui::warn(&t!("reconfigure-schemes-unavailable", path = ios_path));
+            return prompt_text(&t!("reconfigure-prompt-scheme"), current);
+        }
+    };
+
+    let default = schemes.iter().position(|s| s == current).unwrap_or(0);
+    let selection = Select::new()
+        .with_prompt(t!("reconfigure-prompt-scheme"))
+        .items(&schemes)
+        .default(default)
+        .interact()
+        .map_err(|e| ReconfigureError::Io(std::io::Error::other(e)))?;
+
+    Ok(schemes[selection].clone())
+}
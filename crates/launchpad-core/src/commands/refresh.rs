@@ -0,0 +1,238 @@
+use crate::commands::init;
+use crate::commands::reconfigure::{self, Change};
+use crate::config::project::{ProjectConfig, SigningStyle};
+use crate::i18n::t;
+use crate::presets::Preset;
+use crate::ui;
+use crate::xcode::{Xcode, XcodeError};
+use dialoguer::{Confirm, Input, Select};
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+pub enum RefreshError {
+    #[error("Project config not found. Run 'launchpad init' first.")]
+    NoProjectConfig,
+
+    #[error("No iOS project found in current directory")]
+    NoIosProject,
+
+    #[error("The Xcode project has no schemes")]
+    NoSchemeDetected,
+
+    #[error("Xcode error: {0}")]
+    Xcode(String),
+
+    #[error("IO error: {0}")]
+    Io(#[from] std::io::Error),
+
+    #[error("Config error: {0}")]
+    Config(String),
+}
+
+/// Re-run Xcode detection and offer to update settings the project no longer matches
+pub async fn run(non_interactive: bool) -> Result<(), RefreshError> {
+    ui::header(&t!("refresh-header"));
+
+    let config = ProjectConfig::load()
+        .map_err(|e| RefreshError::Config(e.to_string()))?
+        .ok_or(RefreshError::NoProjectConfig)?;
+    let project = &config.project;
+    let mut changes = Vec::new();
+
+    // 1. iOS project path, detected again when the configured one has no Xcode project
+    let (ios_path, schemes) = match Xcode::list_schemes(&project.ios_path) {
+        Ok(schemes) => {
+            ui::success(&t!("init-found-project", path = project.ios_path));
+            (project.ios_path.clone(), schemes)
+        }
+        Err(XcodeError::NoProjectFound(_)) => {
+            ui::warn(&t!("refresh-ios-path-missing", path = project.ios_path));
+            let preset = project.preset.unwrap_or_else(Preset::detect);
+            let detected = init::detect_ios_path(preset).ok_or(RefreshError::NoIosProject)?;
+            ui::success(&t!("init-found-project", path = detected));
+            reconfigure::compare(
+                &mut changes,
+                &["project", "ios_path"],
+                &project.ios_path,
+                &detected,
+            );
+
+            let schemes =
+                Xcode::list_schemes(&detected).map_err(|e| RefreshError::Xcode(e.to_string()))?;
+            (detected, schemes)
+        }
+        Err(e) => return Err(RefreshError::Xcode(e.to_string())),
+    };
+
+    // 2. Scheme
+    let scheme = if schemes.contains(&project.scheme) {
+        ui::success(&t!("refresh-scheme-found", scheme = project.scheme));
+        project.scheme.clone()
+    } else {
+        ui::warn(&t!("refresh-scheme-missing", scheme = project.scheme));
+        let scheme = pick_scheme(&schemes, non_interactive)?;
+        reconfigure::compare(
+            &mut changes,
+            &["project", "scheme"],
+            &project.scheme,
+            &scheme,
+        );
+        scheme
+    };
+
+    // 3. Bundle ID of the scheme's app
+    match Xcode::get_bundle_id(&ios_path, &scheme) {
+        Ok(bundle_id) => {
+            ui::success(&t!("init-using-bundle-id", bundle_id = bundle_id));
+            reconfigure::compare(
+                &mut changes,
+                &["project", "bundle_id"],
+                &project.bundle_id,
+                &bundle_id,
+            );
+            reconfigure::compare_app_id(&mut changes, project, &bundle_id).await;
+        }
+        Err(_) => ui::warn(&t!("refresh-bundle-id-unknown", scheme = scheme)),
+    }
+
+    // 4. Signing style (left unset if it was, so deploy keeps detecting it)
+    let mut style = config.signing.style;
+    match Xcode::signing_style(&ios_path, &scheme) {
+        Ok(detected) => {
+            ui::success(&t!("init-signing", style = detected));
+            if let Some(old) = style.filter(|old| *old != detected) {
+                reconfigure::compare(
+                    &mut changes,
+                    &["signing", "style"],
+                    &old.to_string(),
+                    &detected.to_string(),
+                );
+                style = Some(detected);
+            }
+        }
+        Err(_) => ui::warn(&t!("init-signing-unknown")),
+    }
+
+    // 5. Shipped targets; manual signing needs a profile for each of them
+    match Xcode::target_versions(&ios_path) {
+        Ok(targets) => {
+            let bundle_ids: Vec<&str> = targets
+                .iter()
+                .filter_map(|target| target.bundle_id.as_deref())
+                .collect();
+            for target in &targets {
+                ui::success(&t!(
+                    "refresh-target",
+                    target = target.target,
+                    bundle_id = target.bundle_id.as_deref().unwrap_or("?")
+                ));
+            }
+
+            if style == Some(SigningStyle::Manual) {
+                check_profiles(&config, &bundle_ids, &mut changes, non_interactive)?;
+            }
+        }
+        Err(e) => ui::warn(&t!("refresh-targets-unknown", error = e)),
+    }
+
+    // 6. Confirm, then write back only what changed
+    ui::blank();
+    if changes.is_empty() {
+        ui::success(&t!("refresh-up-to-date"));
+        return Ok(());
+    }
+
+    reconfigure::show_changes(&changes);
+    let apply = if non_interactive {
+        true
+    } else {
+        Confirm::new()
+            .with_prompt(t!("refresh-prompt-apply"))
+            .default(true)
+            .interact()
+            .map_err(|e| RefreshError::Io(std::io::Error::other(e)))?
+    };
+
+    if !apply {
+        ui::warn(&t!("refresh-not-applied"));
+        return Ok(());
+    }
+
+    reconfigure::save_changes(changes).map_err(|e| RefreshError::Config(e.to_string()))?;
+    Ok(())
+}
+
+/// The configured scheme is gone: pick another like init does
+fn pick_scheme(schemes: &[String], non_interactive: bool) -> Result<String, RefreshError> {
+    if schemes.is_empty() {
+        return Err(RefreshError::NoSchemeDetected);
+    }
+
+    if schemes.len() == 1 {
+        ui::success(&t!("init-detected-scheme", scheme = schemes[0]));
+        return Ok(schemes[0].clone());
+    }
+
+    if non_interactive {
+        ui::success(&t!(
+            "init-using-scheme-first",
+            scheme = schemes[0],
+            count = schemes.len()
+        ));
+        return Ok(schemes[0].clone());
+    }
+
+    ui::step(&t!("init-select-scheme"));
+    let selection = Select::new()
+        .items(schemes)
+        .default(0)
+        .interact()
+        .map_err(|e| RefreshError::Io(std::io::Error::other(e)))?;
+    Ok(schemes[selection].clone())
+}
+
+/// Ask for profiles of new targets and point out ones for targets that are gone
+fn check_profiles(
+    config: &ProjectConfig,
+    bundle_ids: &[&str],
+    changes: &mut Vec<Change>,
+    non_interactive: bool,
+) -> Result<(), RefreshError> {
+    let profiles = &config.signing.profiles;
+
+    for &bundle_id in bundle_ids {
+        if profiles.contains_key(bundle_id) {
+            continue;
+        }
+        if non_interactive {
+            ui::warn(&t!("refresh-profile-missing", bundle_id = bundle_id));
+            continue;
+        }
+
+        let profile: String = Input::new()
+            .with_prompt(t!("init-prompt-profile", bundle_id = bundle_id))
+            .allow_empty(true)
+            .interact_text()
+            .map_err(|e| RefreshError::Io(std::io::Error::other(e)))?;
+        if !profile.is_empty() {
+            reconfigure::compare(
+                changes,
+                &["signing", "profiles", bundle_id],
+                &String::new(),
+                &profile,
+            );
+        }
+    }
+
+    // Without any bundle IDs from Xcode every profile would look stale
+    if bundle_ids.is_empty() {
+        return Ok(());
+    }
+    for bundle_id in profiles.keys() {
+        if !bundle_ids.contains(&bundle_id.as_str()) {
+            ui::warn(&t!("refresh-profile-stale", bundle_id = bundle_id));
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,240 @@
+use crate::ipa;
+use crate::profiles::{self, Profile};
+use crate::ui;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+pub enum ResignError {
+    #[error("IPA not found: {0}")]
+    IpaNotFound(PathBuf),
+
+    #[error("{0} not found; re-signing needs macOS with the Xcode command line tools")]
+    ToolMissing(&'static str),
+
+    #[error("No installed provisioning profile named '{0}' (pass a .mobileprovision path, or download it in Xcode > Settings > Accounts)")]
+    ProfileNotFound(String),
+
+    #[error("Could not read provisioning profile {0}")]
+    InvalidProfile(PathBuf),
+
+    #[error("Provisioning profile '{0}' has expired")]
+    ProfileExpired(String),
+
+    #[error("No app found in {0}")]
+    InvalidIpa(PathBuf),
+
+    #[error("Profile '{profile}' is for {profile_bundle_id}, but the app is {app_bundle_id}")]
+    BundleMismatch {
+        profile: String,
+        profile_bundle_id: String,
+        app_bundle_id: String,
+    },
+
+    #[error("{command} failed: {message}")]
+    Command { command: String, message: String },
+
+    #[error("Could not write entitlements: {0}")]
+    Plist(#[from] plist::Error),
+
+    #[error("IO error: {0}")]
+    Io(#[from] std::io::Error),
+}
+
+/// Replace the embedded provisioning profile of an existing .ipa and re-sign its
+/// frameworks and app with `identity`, so a build can go to other devices
+/// (another team's ad-hoc profile) without rebuilding
+pub async fn run(
+    ipa: PathBuf,
+    profile: String,
+    identity: String,
+    output: Option<PathBuf>,
+) -> Result<(), ResignError> {
+    ui::header("Re-sign IPA");
+
+    if !ipa.is_file() {
+        return Err(ResignError::IpaNotFound(ipa));
+    }
+    for tool in ["codesign", "unzip", "zip"] {
+        which::which(tool).map_err(|_| ResignError::ToolMissing(tool))?;
+    }
+
+    let (profile_path, profile) = resolve_profile(&profile)?;
+    if profile.days_left().is_some_and(|days| days < 0) {
+        return Err(ResignError::ProfileExpired(profile.name));
+    }
+
+    let output = match output {
+        Some(output) => output,
+        None => {
+            let stem = ipa.file_stem().unwrap_or_default().to_string_lossy();
+            ipa.with_file_name(format!("{}-resigned.ipa", stem))
+        }
+    };
+    // zip runs inside the work directory
+    let output = std::env::current_dir()?.join(output);
+
+    let work = std::env::temp_dir().join(format!("launchpad-resign-{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&work);
+    let result = resign(&ipa, &profile_path, &profile, &identity, &output, &work);
+    let _ = std::fs::remove_dir_all(&work);
+    let app_bundle_id = result?;
+
+    ui::blank();
+    ui::field("App", &app_bundle_id);
+    ui::field(
+        "Profile",
+        &match profile.expiry_date() {
+            Some(date) => format!("{} (expires {})", profile.name, date),
+            None => profile.name.clone(),
+        },
+    );
+    ui::field("Identity", &identity);
+    ui::field("Output", &output.display().to_string());
+    ui::blank();
+    ui::success("Re-signed");
+
+    Ok(())
+}
+
+/// An installed profile's name, or the path of a .mobileprovision file
+fn resolve_profile(profile: &str) -> Result<(PathBuf, Profile), ResignError> {
+    let path = Path::new(profile);
+    if path.is_file() {
+        let parsed =
+            profiles::read(path).ok_or_else(|| ResignError::InvalidProfile(path.to_path_buf()))?;
+        return Ok((path.to_path_buf(), parsed));
+    }
+    profiles::find_installed(profile)
+        .ok_or_else(|| ResignError::ProfileNotFound(profile.to_string()))
+}
+
+/// Extract, re-sign and re-zip; returns the app's bundle ID
+fn resign(
+    ipa: &Path,
+    profile_path: &Path,
+    profile: &Profile,
+    identity: &str,
+    output: &Path,
+    work: &Path,
+) -> Result<String, ResignError> {
+    let contents = work.join("ipa");
+    std::fs::create_dir_all(&contents)?;
+    let spinner = ui::spinner("Extracting...");
+    let app = ipa::extract_app(ipa, &contents);
+    spinner.finish_and_clear();
+    let app = app.ok_or_else(|| ResignError::InvalidIpa(ipa.to_path_buf()))?;
+
+    let app_bundle_id = plist::Value::from_file(app.join("Info.plist"))
+        .ok()
+        .and_then(|info| {
+            info.as_dictionary()?
+                .get("CFBundleIdentifier")?
+                .as_string()
+                .map(str::to_string)
+        })
+        .ok_or_else(|| ResignError::InvalidIpa(ipa.to_path_buf()))?;
+    if !profile.covers(&app_bundle_id) {
+        return Err(ResignError::BundleMismatch {
+            profile: profile.name.clone(),
+            profile_bundle_id: profile.bundle_id.clone(),
+            app_bundle_id,
+        });
+    }
+
+    // The app is signed with exactly what the new profile grants
+    let entitlements = profiles::entitlements(profile_path)
+        .ok_or_else(|| ResignError::InvalidProfile(profile_path.to_path_buf()))?;
+    let entitlements_path = work.join("entitlements.plist");
+    plist::to_file_xml(&entitlements_path, &plist::Value::Dictionary(entitlements))?;
+
+    std::fs::copy(profile_path, app.join("embedded.mobileprovision"))?;
+    // Left over from the original signature; codesign doesn't replace it
+    let _ = std::fs::remove_file(app.join("archived-expanded-entitlements.xcent"));
+
+    let extensions = nested(&app.join("PlugIns"), &["appex"]);
+    if !extensions.is_empty() {
+        ui::warn(&format!(
+            "{} app extension{} keep{} the original signature and profile",
+            extensions.len(),
+            if extensions.len() == 1 { "" } else { "s" },
+            if extensions.len() == 1 { "s" } else { "" }
+        ));
+    }
+
+    // Inside out: frameworks first, then the app that seals them
+    let frameworks = nested(&app.join("Frameworks"), &["framework", "dylib"]);
+    let spinner = ui::spinner(&format!(
+        "Signing {} framework{} and the app...",
+        frameworks.len(),
+        if frameworks.len() == 1 { "" } else { "s" }
+    ));
+    let signed = frameworks
+        .iter()
+        .try_for_each(|framework| {
+            run_tool(
+                Command::new("codesign")
+                    .args(["--force", "--sign", identity])
+                    .arg(framework),
+            )
+        })
+        .and_then(|_| {
+            run_tool(
+                Command::new("codesign")
+                    .args(["--force", "--sign", identity, "--entitlements"])
+                    .arg(&entitlements_path)
+                    .arg(&app),
+            )
+        })
+        .and_then(|_| {
+            run_tool(
+                Command::new("codesign")
+                    .args(["--verify", "--deep", "--strict"])
+                    .arg(&app),
+            )
+        });
+    spinner.finish_and_clear();
+    signed?;
+
+    if output.exists() {
+        std::fs::remove_file(output)?;
+    }
+    run_tool(
+        Command::new("zip")
+            .args(["-qry"])
+            .arg(output)
+            .arg(".")
+            .current_dir(&contents),
+    )?;
+
+    Ok(app_bundle_id)
+}
+
+/// Bundles in `dir` with one of the given extensions
+fn nested(dir: &Path, extensions: &[&str]) -> Vec<PathBuf> {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return Vec::new();
+    };
+    let mut found: Vec<PathBuf> = entries
+        .filter_map(|e| e.ok())
+        .map(|e| e.path())
+        .filter(|p| {
+            p.extension()
+                .is_some_and(|ext| extensions.iter().any(|e| ext == *e))
+        })
+        .collect();
+    found.sort();
+    found
+}
+
+fn run_tool(command: &mut Command) -> Result<(), ResignError> {
+    let output = command.output()?;
+    if output.status.success() {
+        return Ok(());
+    }
+    Err(ResignError::Command {
+        command: command.get_program().to_string_lossy().to_string(),
+        message: String::from_utf8_lossy(&output.stderr).trim().to_string(),
+    })
+}
@@ -0,0 +1,164 @@
+use crate::asc::{AppStoreVersion, AscClient, AscError};
+use crate::commands::deploy::{self, Platform};
+use crate::config;
+use crate::config::global::GlobalConfig;
+use crate::config::project::{NotificationEvent, ProjectConfig};
+use crate::summary::DeploySummary;
+use crate::ui;
+use clap::Subcommand;
+use std::time::Duration;
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+pub enum ReviewError {
+    #[error("Global config not found. Run 'launchpad setup' first.")]
+    NoGlobalConfig,
+
+    #[error("Project config not found. Run 'launchpad init' first.")]
+    NoProjectConfig,
+
+    #[error("App Store Connect has no App Store version of {0} yet")]
+    NoVersion(String),
+
+    #[error("App Review: {version} is {state}")]
+    Rejected { version: String, state: String },
+
+    #[error("{0}")]
+    Asc(#[from] AscError),
+
+    #[error("Config error: {0}")]
+    Config(String),
+}
+
+#[derive(Subcommand)]
+pub enum ReviewCommand {
+    /// Where the latest App Store version is in App Review
+    Status {
+        /// Keep checking and notify on every change until the version is accepted or rejected
+        #[arg(long)]
+        watch: bool,
+
+        /// Seconds between checks with --watch
+        #[arg(long, default_value_t = 300)]
+        interval: u64,
+    },
+}
+
+pub async fn run(command: ReviewCommand) -> Result<(), ReviewError> {
+    match command {
+        ReviewCommand::Status { watch, interval } => status(watch, interval).await,
+    }
+}
+
+async fn status(watch: bool, interval: u64) -> Result<(), ReviewError> {
+    ui::header("App Review");
+
+    let mut project_config = ProjectConfig::load()
+        .map_err(|e| ReviewError::Config(e.to_string()))?
+        .ok_or(ReviewError::NoProjectConfig)?;
+    let mut global_config = GlobalConfig::load()
+        .map_err(|e| ReviewError::Config(e.to_string()))?
+        .ok_or(ReviewError::NoGlobalConfig)?;
+    config::use_selected_environment(&mut global_config, Some(&mut project_config))
+        .map_err(|e| ReviewError::Config(e.to_string()))?;
+    let client = AscClient::new(&global_config.apple)?;
+    let project = &project_config.project;
+    let app_id = client
+        .project_app_id(&project.bundle_id, Some(project))
+        .await?;
+
+    let mut current = client
+        .latest_app_store_version(&app_id)
+        .await?
+        .ok_or_else(|| ReviewError::NoVersion(project.bundle_id.clone()))?;
+    ui::field("App", &project.bundle_id);
+    ui::field("Version", &version_label(&current));
+    ui::field("State", &current.state_label());
+    if let Some(reason) = current.rejection_reason() {
+        ui::warn(reason);
+    }
+    if !watch {
+        return Ok(());
+    }
+
+    ui::blank();
+    ui::step(&format!(
+        "Checking every {}s until App Review accepts or rejects the version (Ctrl-C to stop)",
+        interval
+    ));
+    while !current.is_accepted() && !current.is_rejected() {
+        tokio::time::sleep(Duration::from_secs(interval)).await;
+
+        // A watch can run for days; a flaky connection shouldn't end it
+        let latest = match client.latest_app_store_version(&app_id).await {
+            Ok(Some(latest)) => latest,
+            Ok(None) => continue,
+            Err(e) if e.is_transient() => {
+                ui::warn(&format!("Couldn't check App Review ({}); trying again", e));
+                continue;
+            }
+            Err(e) => return Err(e.into()),
+        };
+        if latest.id == current.id && latest.state == current.state {
+            continue;
+        }
+
+        ui::step(&format!(
+            "{}: {} → {}",
+            version_label(&latest),
+            current.state_label(),
+            latest.state_label()
+        ));
+        if let Some(reason) = latest.rejection_reason() {
+            ui::warn(reason);
+        }
+        deploy::notify(
+            Some(&global_config),
+            &project_config,
+            Platform::Ios,
+            NotificationEvent::Review,
+            &summary(&latest),
+        )
+        .await;
+        current = latest;
+    }
+
+    if current.is_rejected() {
+        return Err(ReviewError::Rejected {
+            version: version_label(&current),
+            state: current.state_label(),
+        });
+    }
+    ui::success(&format!(
+        "{} passed App Review ({})",
+        version_label(&current),
+        current.state_label()
+    ));
+    Ok(())
+}
+
+/// "1.2.0 (43)", or just the version before a build is attached
+fn version_label(version: &AppStoreVersion) -> String {
+    match &version.build {
+        Some(build) => format!("{} ({})", version.version, build),
+        None => version.version.clone(),
+    }
+}
+
+/// Notifications take a deploy summary; a review update fills in the version,
+/// its state and, for rejections, what the rejection means
+fn summary(version: &AppStoreVersion) -> DeploySummary {
+    DeploySummary {
+        version: Some(version.version.clone()),
+        build: version.build.clone(),
+        git_sha: None,
+        ipa_path: None,
+        ipa_size: None,
+        dsym_path: None,
+        testflight_status: version.state_label(),
+        duration_secs: 0,
+        aab_path: None,
+        play_track: None,
+        error: version.rejection_reason().map(str::to_string),
+    }
+}
@@ -0,0 +1,226 @@
+use crate::asc::{AscClient, AscError, CustomerReview, ReviewFilter};
+use crate::commands::{connect, load_configs, ConnectError};
+use crate::ui;
+use clap::Subcommand;
+use std::io::Read;
+use std::path::PathBuf;
+use thiserror::Error;
+
+/// Longest developer response App Store Connect accepts
+const MAX_RESPONSE_CHARS: usize = 5970;
+
+#[derive(Error, Debug)]
+pub enum ReviewsError {
+    #[error("Invalid rating {0} (expected 1 to 5)")]
+    InvalidRating(u8),
+
+    #[error("No response text. Pass it as an argument or with --file.")]
+    EmptyResponse,
+
+    #[error("Response is {0} characters; App Store Connect takes at most {MAX_RESPONSE_CHARS}")]
+    ResponseTooLong(usize),
+
+    #[error("{0}")]
+    Asc(#[from] AscError),
+
+    #[error("JSON error: {0}")]
+    Json(#[from] serde_json::Error),
+
+    #[error("IO error: {0}")]
+    Io(#[from] std::io::Error),
+
+    #[error("{0}")]
+    Connect(#[from] ConnectError),
+}
+
+#[derive(Subcommand)]
+pub enum ReviewsCommand {
+    /// Latest App Store customer reviews and their responses
+    List {
+        /// Only reviews with this many stars (repeatable)
+        #[arg(long = "rating", value_name = "STARS")]
+        ratings: Vec<u8>,
+
+        /// Only reviews from this storefront ("USA", "GBR", ...)
+        #[arg(long)]
+        territory: Option<String>,
+
+        /// Only reviews without a response
+        #[arg(long)]
+        unanswered: bool,
+
+        /// Most reviews to list
+        #[arg(long, default_value_t = 20)]
+        limit: usize,
+
+        /// Print the reviews as JSON, for scripts
+        #[arg(long)]
+        json: bool,
+
+        /// App the reviews are for (default: the bundle ID in .launchpad.toml)
+        #[arg(long)]
+        bundle_id: Option<String>,
+    },
+
+    /// Respond to a customer review (replaces an earlier response)
+    Reply {
+        /// Review ID, as `reviews list` shows it
+        review: String,
+
+        /// Response text
+        #[arg(conflicts_with = "file")]
+        message: Option<String>,
+
+        /// Read the response from a file ("-" for stdin)
+        #[arg(long)]
+        file: Option<PathBuf>,
+    },
+}
+
+pub async fn run(command: ReviewsCommand) -> Result<(), ReviewsError> {
+    match command {
+        ReviewsCommand::List {
+            ratings,
+            territory,
+            unanswered,
+            limit,
+            json,
+            bundle_id,
+        } => {
+            let filter = ReviewFilter {
+                ratings,
+                territory,
+                unanswered,
+                limit,
+            };
+            list(filter, json, bundle_id).await
+        }
+        ReviewsCommand::Reply {
+            review,
+            message,
+            file,
+        } => reply(review, message, file).await,
+    }
+}
+
+async fn list(
+    filter: ReviewFilter,
+    json: bool,
+    bundle_id: Option<String>,
+) -> Result<(), ReviewsError> {
+    if let Some(rating) = filter.ratings.iter().find(|r| !(1..=5).contains(*r)) {
+        return Err(ReviewsError::InvalidRating(*rating));
+    }
+    // stdout may be piped into a script, so keep it free of anything but JSON
+    if !json {
+        ui::header("Customer Reviews");
+    }
+
+    let (client, app_id) = connect(bundle_id).await?;
+    let reviews = client.customer_reviews(&app_id, &filter).await?;
+    if json {
+        println!("{}", serde_json::to_string_pretty(&reviews)?);
+        return Ok(());
+    }
+
+    if reviews.is_empty() {
+        ui::step("No reviews match");
+        return Ok(());
+    }
+    for review in &reviews {
+        print_review(review);
+    }
+    let unanswered = reviews.iter().filter(|r| r.response.is_none()).count();
+    ui::success(&format!(
+        "{} review{}, {} without a response",
+        reviews.len(),
+        if reviews.len() == 1 { "" } else { "s" },
+        unanswered
+    ));
+    Ok(())
+}
+
+async fn reply(
+    review: String,
+    message: Option<String>,
+    file: Option<PathBuf>,
+) -> Result<(), ReviewsError> {
+    ui::header("Reply to Review");
+
+    let text = match (message, file) {
+        (Some(message), _) => message,
+        (None, Some(path)) if path.as_os_str() == "-" => {
+            let mut text = String::new();
+            std::io::stdin().read_to_string(&mut text)?;
+            text
+        }
+        (None, Some(path)) => std::fs::read_to_string(path)?,
+        (None, None) => String::new(),
+    };
+    let text = text.trim();
+    if text.is_empty() {
+        return Err(ReviewsError::EmptyResponse);
+    }
+    let length = text.chars().count();
+    if length > MAX_RESPONSE_CHARS {
+        return Err(ReviewsError::ResponseTooLong(length));
+    }
+
+    let client = client()?;
+    let response = client.respond_to_review(&review, text).await?;
+    ui::success(&format!(
+        "Responded to review {} ({})",
+        review,
+        response.state.as_deref().unwrap_or("PENDING_PUBLISH")
+    ));
+    ui::step("Apple shows the response on the App Store once it's published, usually within a day");
+    Ok(())
+}
+
+fn print_review(review: &CustomerReview) {
+    let title = review.title.as_deref().unwrap_or("(no title)");
+    ui::step(&format!("{}/5  {}", review.rating, title));
+    if let Some(body) = &review.body {
+        for line in body.lines().filter(|line| !line.trim().is_empty()) {
+            ui::output(line.trim());
+        }
+    }
+    let from = [
+        review.reviewer.as_deref(),
+        review.territory.as_deref(),
+        review.created_date.as_deref(),
+    ]
+    .into_iter()
+    .flatten()
+    .collect::<Vec<_>>()
+    .join(", ");
+    ui::field("From", &from);
+    match &review.response {
+        Some(response) => ui::field(
+            "Response",
+            &format!(
+                "{}: {}",
+                response.state.as_deref().unwrap_or("UNKNOWN"),
+                excerpt(&response.body)
+            ),
+        ),
+        None => ui::field("Response", "none"),
+    }
+    ui::field("ID", &review.id);
+    ui::blank();
+}
+
+/// First line of a response, cut to fit the terminal
+fn excerpt(text: &str) -> String {
+    let line = text.lines().next().unwrap_or_default();
+    if line.chars().count() > 80 || text.lines().nth(1).is_some() {
+        format!("{}...", line.chars().take(80).collect::<String>())
+    } else {
+        line.to_string()
+    }
+}
+
+fn client() -> Result<AscClient, ReviewsError> {
+    let (global_config, _) = load_configs()?;
+    Ok(AscClient::new(&global_config.apple)?)
+}
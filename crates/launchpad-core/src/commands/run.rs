@@ -0,0 +1,236 @@
+use crate::config::project::ProjectConfig;
+use crate::ui;
+use crate::xcode::{Xcode, XcodeError};
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+pub enum RunError {
+    #[error("Project config not found. Run 'launchpad init' first.")]
+    NoProjectConfig,
+
+    #[error("Running on a simulator needs macOS with Xcode")]
+    NoXcode,
+
+    #[error("No simulator named '{name}'. Available: {available}")]
+    UnknownSimulator { name: String, available: String },
+
+    #[error("No simulator is booted. Pass --simulator with one of: {0}")]
+    SimulatorRequired(String),
+
+    #[error("Several simulators are booted ({0}). Pass --simulator with a name or UDID.")]
+    SeveralBooted(String),
+
+    #[error("Couldn't read the bundle ID from {0}")]
+    NoBundleId(PathBuf),
+
+    #[error("{command} failed: {message}")]
+    Command { command: String, message: String },
+
+    #[error("{0}")]
+    Xcode(#[from] XcodeError),
+
+    #[error("IO error: {0}")]
+    Io(#[from] std::io::Error),
+
+    #[error("Config error: {0}")]
+    Config(String),
+}
+
+/// `xcrun simctl list devices available --json`
+#[derive(Deserialize)]
+struct SimctlDevices {
+    /// Devices by runtime identifier ("com.apple.CoreSimulator.SimRuntime.iOS-17-2")
+    devices: BTreeMap<String, Vec<SimctlDevice>>,
+}
+
+#[derive(Deserialize)]
+struct SimctlDevice {
+    udid: String,
+    name: String,
+    /// "Booted" or "Shutdown"
+    state: String,
+}
+
+#[derive(Clone)]
+struct Simulator {
+    udid: String,
+    name: String,
+    /// "iOS 17.2"
+    runtime: String,
+    booted: bool,
+    /// Runtime version, for preferring the newest of same-named simulators
+    version: Vec<u64>,
+}
+
+/// Build the scheme for a simulator, install it there and launch it with its
+/// console attached: the quick look before a deploy
+pub async fn run(simulator: Option<String>, configuration: String) -> Result<(), RunError> {
+    ui::header("Run on Simulator");
+
+    let config = ProjectConfig::load()
+        .map_err(|e| RunError::Config(e.to_string()))?
+        .ok_or(RunError::NoProjectConfig)?;
+    if !Xcode::is_installed() {
+        return Err(RunError::NoXcode);
+    }
+
+    let simulator = pick_simulator(simulator.as_deref())?;
+    ui::field(
+        "Simulator",
+        &format!(
+            "{} ({}, {})",
+            simulator.name, simulator.runtime, simulator.udid
+        ),
+    );
+
+    let spinner = ui::spinner(&format!("Booting {}...", simulator.name));
+    // bootstatus -b boots a shut down simulator and waits until it's usable
+    let booted = simctl(&["bootstatus", &simulator.udid, "-b"]);
+    spinner.finish_and_clear();
+    booted?;
+    // Bring up the window; the app runs either way
+    let _ = Command::new("open").args(["-a", "Simulator"]).output();
+
+    // Kept between runs so the next build is incremental
+    let derived_data =
+        Path::new(shellexpand::tilde(&config.build.output_dir).as_ref()).join("simulator");
+    let scheme = &config.project.scheme;
+    let spinner = ui::spinner(&format!("Building {} ({})...", scheme, configuration));
+    let app = Xcode::build_for_simulator(
+        &config.project.ios_path,
+        scheme,
+        &configuration,
+        &simulator.udid,
+        &derived_data,
+    );
+    spinner.finish_and_clear();
+    let app = app?;
+    ui::success(&format!("Built {}", app.display()));
+
+    let bundle_id = bundle_id(&app).ok_or_else(|| RunError::NoBundleId(app.clone()))?;
+    let spinner = ui::spinner("Installing...");
+    let installed = simctl(&["install", &simulator.udid, &app.to_string_lossy()]);
+    spinner.finish_and_clear();
+    installed?;
+    ui::success(&format!("Installed {} on {}", bundle_id, simulator.name));
+
+    ui::step("Launching; the app's output follows (Ctrl-C to stop)");
+    ui::blank();
+    // --console-pty keeps simctl attached to the app's stdout and stderr
+    let status = Command::new("xcrun")
+        .args([
+            "simctl",
+            "launch",
+            "--console-pty",
+            "--terminate-running-process",
+            &simulator.udid,
+            &bundle_id,
+        ])
+        .status()?;
+    if !status.success() {
+        return Err(RunError::Command {
+            command: "simctl launch".to_string(),
+            message: format!("exited with {}", status),
+        });
+    }
+    Ok(())
+}
+
+/// The simulator named (or with the UDID) `wanted`, else the one that's booted
+fn pick_simulator(wanted: Option<&str>) -> Result<Simulator, RunError> {
+    let simulators = available_simulators()?;
+    let names = || {
+        let mut names: Vec<&str> = simulators.iter().map(|s| s.name.as_str()).collect();
+        names.dedup();
+        names.join(", ")
+    };
+
+    let mut candidates: Vec<&Simulator> = match wanted {
+        Some(wanted) => simulators
+            .iter()
+            .filter(|s| s.udid == wanted || s.name.eq_ignore_ascii_case(wanted))
+            .collect(),
+        None => simulators.iter().filter(|s| s.booted).collect(),
+    };
+    match (wanted, candidates.len()) {
+        (Some(name), 0) => {
+            return Err(RunError::UnknownSimulator {
+                name: name.to_string(),
+                available: names(),
+            })
+        }
+        (None, 0) => return Err(RunError::SimulatorRequired(names())),
+        (None, 2..) => {
+            let booted: Vec<&str> = candidates.iter().map(|s| s.name.as_str()).collect();
+            return Err(RunError::SeveralBooted(booted.join(", ")));
+        }
+        _ => {}
+    }
+
+    // The same name exists once per installed runtime: a booted one, else the newest
+    candidates.sort_by_key(|s| std::cmp::Reverse((s.booted, s.version.clone())));
+    Ok(candidates[0].clone())
+}
+
+/// Available iOS simulators, by name
+fn available_simulators() -> Result<Vec<Simulator>, RunError> {
+    let json = simctl(&["list", "devices", "available", "--json"])?;
+    let list: SimctlDevices = serde_json::from_str(&json).map_err(|e| RunError::Command {
+        command: "simctl list devices".to_string(),
+        message: e.to_string(),
+    })?;
+
+    let mut simulators = Vec::new();
+    for (runtime, devices) in list.devices {
+        // "com.apple.CoreSimulator.SimRuntime.iOS-17-2"; tvOS and watchOS can't run the app
+        let Some(version) = runtime
+            .rsplit('.')
+            .next()
+            .and_then(|r| r.strip_prefix("iOS-"))
+        else {
+            continue;
+        };
+        let numbers: Vec<u64> = version.split('-').filter_map(|n| n.parse().ok()).collect();
+        for device in devices {
+            simulators.push(Simulator {
+                udid: device.udid,
+                name: device.name,
+                runtime: format!("iOS {}", version.replace('-', ".")),
+                booted: device.state == "Booted",
+                version: numbers.clone(),
+            });
+        }
+    }
+    simulators.sort_by(|a, b| a.name.cmp(&b.name));
+    Ok(simulators)
+}
+
+fn bundle_id(app: &Path) -> Option<String> {
+    let info = plist::Value::from_file(app.join("Info.plist")).ok()?;
+    info.as_dictionary()?
+        .get("CFBundleIdentifier")?
+        .as_string()
+        .map(str::to_string)
+}
+
+/// Run `xcrun simctl` and return its stdout
+fn simctl(args: &[&str]) -> Result<String, RunError> {
+    let output = Command::new("xcrun").arg("simctl").args(args).output()?;
+    if output.status.success() {
+        return Ok(String::from_utf8_lossy(&output.stdout).to_string());
+    }
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    Err(RunError::Command {
+        command: format!("simctl {}", args[0]),
+        message: stderr
+            .lines()
+            .rfind(|line| !line.trim().is_empty())
+            .unwrap_or("no output")
+            .trim()
+            .to_string(),
+    })
+}
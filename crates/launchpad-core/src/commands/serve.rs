@@ -0,0 +1,332 @@
+use crate::events;
+use crate::ui;
+use serde::Deserialize;
+use serde_json::{json, Value};
+use std::path::{Path, PathBuf};
+use std::process::Stdio;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use thiserror::Error;
+use tokio::io::{AsyncBufReadExt, AsyncRead, AsyncWrite, AsyncWriteExt, BufReader};
+use tokio::sync::mpsc::{self, UnboundedSender};
+
+/// JSON-RPC 2.0 error codes
+const PARSE_ERROR: i64 = -32700;
+const METHOD_NOT_FOUND: i64 = -32601;
+const INVALID_PARAMS: i64 = -32602;
+const BUSY: i64 = -32000;
+const SPAWN_FAILED: i64 = -32001;
+
+const METHODS: &[&str] = &["initialize", "status", "doctor", "deploy", "shutdown"];
+
+#[derive(Error, Debug)]
+pub enum ServeError {
+    #[error("IO error: {0}")]
+    Io(#[from] std::io::Error),
+
+    #[error("{0} exists and isn't a socket; pass a path that doesn't exist yet")]
+    NotASocket(PathBuf),
+
+    #[cfg(not(unix))]
+    #[error("--socket needs Unix domain sockets, which this platform doesn't have")]
+    SocketUnsupported,
+}
+
+#[derive(Deserialize)]
+struct Request {
+    /// Absent for notifications, which get no response
+    id: Option<Value>,
+    method: String,
+    #[serde(default)]
+    params: Value,
+}
+
+#[derive(Deserialize, Default)]
+struct DoctorParams {
+    /// Project directory; the server's working directory by default
+    project: Option<PathBuf>,
+    /// Fail on warnings too
+    #[serde(default)]
+    strict: bool,
+}
+
+#[derive(Deserialize, Default)]
+struct DeployParams {
+    project: Option<PathBuf>,
+    /// "ios" (default) or "android"
+    platform: Option<String>,
+    /// "patch", "minor" or "major"; a build number bump otherwise
+    bump: Option<String>,
+    #[serde(default)]
+    skip_git_check: bool,
+    #[serde(default)]
+    no_tag: bool,
+}
+
+/// Shared by every connection: only one deploy runs at a time
+#[derive(Default)]
+struct Server {
+    deploying: AtomicBool,
+}
+
+/// Answer JSON-RPC requests from editors, one JSON object per line, on stdio or
+/// a Unix socket. `deploy` and `doctor` run launchpad itself and forward its
+/// `--event-stream` as `event` notifications.
+pub async fn run(socket: Option<PathBuf>) -> Result<(), ServeError> {
+    let server = Arc::new(Server::default());
+    match socket {
+        Some(path) => listen(&path, server).await,
+        None => {
+            // stdout carries the protocol; anything printed goes to stderr
+            events::claim_stdout();
+            connection(tokio::io::stdin(), tokio::io::stdout(), server).await
+        }
+    }
+}
+
+#[cfg(unix)]
+async fn listen(path: &Path, server: Arc<Server>) -> Result<(), ServeError> {
+    use std::os::unix::fs::FileTypeExt;
+    use tokio::net::UnixListener;
+
+    // A socket left behind by a server that didn't shut down cleanly; anything
+    // else at the path is the user's, and stays
+    match std::fs::symlink_metadata(path) {
+        Ok(metadata) if metadata.file_type().is_socket() => std::fs::remove_file(path)?,
+        Ok(_) => return Err(ServeError::NotASocket(path.to_path_buf())),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+        Err(e) => return Err(e.into()),
+    }
+    let listener = UnixListener::bind(path)?;
+
+    ui::header("Launchpad Server");
+    ui::field("Socket", &path.display().to_string());
+    ui::field("Methods", &METHODS.join(", "));
+
+    let result = loop {
+        tokio::select! {
+            accepted = listener.accept() => match accepted {
+                Ok((stream, _)) => {
+                    let server = server.clone();
+                    tokio::spawn(async move {
+                        let (reader, writer) = stream.into_split();
+                        if let Err(e) = connection(reader, writer, server).await {
+                            ui::warn(&format!("Connection closed: {}", e));
+                        }
+                    });
+                }
+                Err(e) => break Err(e.into()),
+            },
+            _ = tokio::signal::ctrl_c() => {
+                ui::blank();
+                ui::success("Server stopped");
+                break Ok(());
+            }
+        }
+    };
+
+    let _ = std::fs::remove_file(path);
+    result
+}
+
+#[cfg(not(unix))]
+async fn listen(_path: &Path, _server: Arc<Server>) -> Result<(), ServeError> {
+    Err(ServeError::SocketUnsupported)
+}
+
+/// Read requests until EOF or `shutdown`; each request runs on its own task so
+/// `status` answers while a deploy is in progress
+async fn connection<R, W>(reader: R, writer: W, server: Arc<Server>) -> Result<(), ServeError>
+where
+    R: AsyncRead + Unpin,
+    W: AsyncWrite + Unpin + Send + 'static,
+{
+    let (tx, mut rx) = mpsc::unbounded_channel::<Value>();
+    let writer = tokio::spawn(async move {
+        let mut writer = writer;
+        while let Some(message) = rx.recv().await {
+            let line = format!("{}\n", message);
+            if writer.write_all(line.as_bytes()).await.is_err() {
+                break;
+            }
+            let _ = writer.flush().await;
+        }
+    });
+
+    let mut lines = BufReader::new(reader).lines();
+    while let Some(line) = lines.next_line().await? {
+        if line.trim().is_empty() {
+            continue;
+        }
+        let request: Request = match serde_json::from_str(&line) {
+            Ok(request) => request,
+            Err(e) => {
+                let _ = tx.send(error(Value::Null, PARSE_ERROR, &e.to_string()));
+                continue;
+            }
+        };
+
+        if request.method == "shutdown" {
+            if let Some(id) = request.id {
+                let _ = tx.send(result(id, Value::Null));
+            }
+            break;
+        }
+
+        let tx = tx.clone();
+        let server = server.clone();
+        tokio::spawn(async move {
+            let id = request.id.clone();
+            let response = handle(request, &server, &tx).await;
+            if let Some(id) = id {
+                let _ = tx.send(match response {
+                    Ok(value) => result(id, value),
+                    Err((code, message)) => error(id, code, &message),
+                });
+            }
+        });
+    }
+
+    // Deploys still running finish and report before the connection closes
+    drop(tx);
+    let _ = writer.await;
+    Ok(())
+}
+
+async fn handle(
+    request: Request,
+    server: &Server,
+    tx: &UnboundedSender<Value>,
+) -> Result<Value, (i64, String)> {
+    let id = request.id.clone().unwrap_or(Value::Null);
+    match request.method.as_str() {
+        "initialize" => Ok(json!({
+            "name": "launchpad",
+            "version": env!("CARGO_PKG_VERSION"),
+            "methods": METHODS,
+        })),
+        "status" => Ok(json!({ "deploying": server.deploying.load(Ordering::SeqCst) })),
+        "doctor" => {
+            let params: DoctorParams = params(request.params)?;
+            let project = params.project.unwrap_or_else(|| PathBuf::from("."));
+            let args: &[&str] = if params.strict {
+                &["doctor", "--strict"]
+            } else {
+                &["doctor"]
+            };
+            run_launchpad(args, &project, &id, tx).await
+        }
+        "deploy" => {
+            let params: DeployParams = params(request.params)?;
+            if server.deploying.swap(true, Ordering::SeqCst) {
+                return Err((BUSY, "A deploy is already running".to_string()));
+            }
+            let outcome = deploy(params, &id, tx).await;
+            server.deploying.store(false, Ordering::SeqCst);
+            outcome
+        }
+        method => Err((METHOD_NOT_FOUND, format!("Unknown method '{}'", method))),
+    }
+}
+
+async fn deploy(
+    params: DeployParams,
+    id: &Value,
+    tx: &UnboundedSender<Value>,
+) -> Result<Value, (i64, String)> {
+    let mut args = vec!["deploy", "--yes"];
+    match params.platform.as_deref() {
+        None | Some("ios") => {}
+        Some("android") => args.extend(["--platform", "android"]),
+        Some(other) => return Err((INVALID_PARAMS, format!("Unknown platform '{}'", other))),
+    }
+    match params.bump.as_deref() {
+        None => {}
+        Some("patch") => args.push("--patch"),
+        Some("minor") => args.push("--minor"),
+        Some("major") => args.push("--major"),
+        Some(other) => return Err((INVALID_PARAMS, format!("Unknown bump '{}'", other))),
+    }
+    if params.skip_git_check {
+        args.push("--skip-git-check");
+    }
+    if params.no_tag {
+        args.push("--no-tag");
+    }
+
+    let project = params.project.unwrap_or_else(|| PathBuf::from("."));
+    run_launchpad(&args, &project, id, tx).await
+}
+
+/// Run a launchpad command in `project`, forwarding each event it streams as an
+/// `event` notification tagged with the request id. The result carries the exit
+/// status, the deploy summary if one was written, and the log lines.
+async fn run_launchpad(
+    args: &[&str],
+    project: &Path,
+    id: &Value,
+    tx: &UnboundedSender<Value>,
+) -> Result<Value, (i64, String)> {
+    let spawn_error = |e: std::io::Error| (SPAWN_FAILED, format!("Could not run launchpad: {}", e));
+    let launchpad = std::env::current_exe().map_err(spawn_error)?;
+    let mut child = tokio::process::Command::new(launchpad)
+        .args(args)
+        .args(["--event-stream", "-"])
+        .current_dir(project)
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null())
+        .kill_on_drop(true)
+        .spawn()
+        .map_err(spawn_error)?;
+
+    let mut summary = Value::Null;
+    let mut log = Vec::new();
+    if let Some(stdout) = child.stdout.take() {
+        let mut lines = BufReader::new(stdout).lines();
+        while let Ok(Some(line)) = lines.next_line().await {
+            let Ok(event) = serde_json::from_str::<Value>(&line) else {
+                continue;
+            };
+            match event["event"].as_str() {
+                Some("summary") => summary = event["summary"].clone(),
+                Some("log") => log.push(json!({
+                    "level": event["level"],
+                    "message": event["message"],
+                })),
+                _ => {}
+            }
+            let _ = tx.send(json!({
+                "jsonrpc": "2.0",
+                "method": "event",
+                "params": { "request": id, "event": event },
+            }));
+        }
+    }
+
+    let status = child.wait().await.map_err(spawn_error)?;
+    Ok(json!({
+        "success": status.success(),
+        "summary": summary,
+        "log": log,
+    }))
+}
+
+fn params<T: for<'de> Deserialize<'de> + Default>(params: Value) -> Result<T, (i64, String)> {
+    if params.is_null() {
+        return Ok(T::default());
+    }
+    serde_json::from_value(params).map_err(|e| (INVALID_PARAMS, e.to_string()))
+}
+
+fn result(id: Value, result: Value) -> Value {
+    json!({ "jsonrpc": "2.0", "id": id, "result": result })
+}
+
+fn error(id: Value, code: i64, message: &str) -> Value {
+    json!({
+        "jsonrpc": "2.0",
+        "id": id,
+        "error": { "code": code, "message": message },
+    })
+}
@@ -0,0 +1,636 @@
+use crate::config::encryption::{self, EncryptionError};
+use crate::config::global::{AppleConfig, GlobalConfig, GooglePlayConfig};
+use crate::i18n::t;
+use crate::ui;
+use clap::Subcommand;
+use dialoguer::{Confirm, Input, Password};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+pub enum SetupError {
+    #[error("IO error: {0}")]
+    Io(#[from] std::io::Error),
+
+    #[error("Config error: {0}")]
+    Config(String),
+
+    #[error("Setup cancelled")]
+    Cancelled,
+
+    #[error("{0} must be set for non-interactive setup")]
+    MissingEnv(&'static str),
+
+    #[error("Invalid setup payload {path}: {message}")]
+    Payload { path: String, message: String },
+
+    #[error("No global config yet. Run 'launchpad setup' first.")]
+    NotConfigured,
+
+    #[error("Launchpad config is already encrypted")]
+    AlreadyEncrypted,
+
+    #[error("Launchpad config isn't encrypted")]
+    NotEncrypted,
+
+    #[error("{0}")]
+    Encryption(#[from] EncryptionError),
+}
+
+#[derive(Subcommand)]
+pub enum SetupAction {
+    /// Encrypt ~/.launchpad/config.toml and the keys directory at rest
+    Encrypt {
+        /// Use a generated passphrase stored in the macOS keychain instead of your own
+        #[arg(long)]
+        keychain: bool,
+    },
+
+    /// Turn encryption off again (writes plaintext files)
+    Decrypt,
+
+    /// Write the global config to a passphrase-encrypted bundle for another machine
+    Export {
+        /// Bundle file to write
+        #[arg(long, short, default_value = DEFAULT_BUNDLE)]
+        output: String,
+
+        /// Include the .p8 key itself (otherwise the importer needs it at the same path)
+        #[arg(long)]
+        include_key: bool,
+    },
+
+    /// Load a bundle made by `setup export` (new machine or CI runner)
+    Import {
+        /// Bundle file to read
+        bundle: String,
+    },
+}
+
+const DEFAULT_BUNDLE: &str = "launchpad-onboarding.age";
+
+/// Passphrase for export/import, separate from the at-rest one
+const BUNDLE_PASSPHRASE_ENV: &str = "LAUNCHPAD_BUNDLE_PASSPHRASE";
+
+/// Contents of an onboarding bundle (TOML, then age-encrypted)
+#[derive(Serialize, Deserialize)]
+struct Bundle {
+    config: GlobalConfig,
+
+    /// The .p8 key, installed to ~/.launchpad/keys on import
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    key: Option<String>,
+
+    /// The `[key_profiles]` keys by profile name, installed the same way
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    profile_keys: BTreeMap<String, String>,
+}
+
+pub async fn run(
+    action: Option<SetupAction>,
+    from_env: bool,
+    from_json: Option<String>,
+    non_interactive: bool,
+) -> Result<(), SetupError> {
+    match action {
+        None => configure(from_env, from_json, non_interactive).await,
+        Some(SetupAction::Encrypt { keychain }) => encrypt(keychain, non_interactive),
+        Some(SetupAction::Decrypt) => decrypt(),
+        Some(SetupAction::Export {
+            output,
+            include_key,
+        }) => export(&output, include_key, non_interactive),
+        Some(SetupAction::Import { bundle }) => import(&bundle, non_interactive).await,
+    }
+}
+
+/// First-time configuration: prompted, from APPLE_API_* variables, or from a payload file
+async fn configure(
+    from_env: bool,
+    from_json: Option<String>,
+    non_interactive: bool,
+) -> Result<(), SetupError> {
+    // Either flag means no prompts at all, like --yes
+    let payload = from_json
+        .as_deref()
+        .map(|path| read_payload(Path::new(path)))
+        .transpose()?;
+    let non_interactive = non_interactive || from_env || payload.is_some();
+
+    ui::header(&t!("setup-header"));
+    ui::blank();
+    ui::line(t!("setup-intro"));
+    ui::line(t!(
+        "setup-intro-key-url",
+        url = "https://appstoreconnect.apple.com/access/api"
+    ));
+    ui::blank();
+
+    // Check for existing config
+    let existing = GlobalConfig::load_file().map_err(|e| SetupError::Config(e.to_string()))?;
+    if existing.is_some() {
+        let overwrite = if non_interactive {
+            ui::step(&t!("setup-overwrite-yes"));
+            true
+        } else {
+            Confirm::new()
+                .with_prompt(t!("setup-prompt-overwrite"))
+                .default(false)
+                .interact()
+                .map_err(|e| SetupError::Io(std::io::Error::other(e)))?
+        };
+
+        if !overwrite {
+            return Err(SetupError::Cancelled);
+        }
+    }
+
+    // Get API key details (from the payload, or the APPLE_API_* variables in non-interactive mode)
+    let (key_id, issuer_id, key_path) = match &payload {
+        Some(payload) => {
+            let path = from_json.as_deref().unwrap_or("-");
+            ui::success(&t!("setup-using-payload", path = path));
+            (
+                payload.apple.key_id.clone(),
+                payload.apple.issuer_id.clone(),
+                payload.apple.key_path.clone(),
+            )
+        }
+        None => (
+            prompt_or_env(
+                t!("setup-prompt-key-id"),
+                "APPLE_API_KEY_ID",
+                non_interactive,
+            )?,
+            prompt_or_env(
+                t!("setup-prompt-issuer-id"),
+                "APPLE_API_ISSUER_ID",
+                non_interactive,
+            )?,
+            prompt_or_env(
+                t!("setup-prompt-key-path"),
+                "APPLE_API_KEY_PATH",
+                non_interactive,
+            )?,
+        ),
+    };
+
+    // Expand and validate key path
+    let expanded_path = shellexpand::tilde(&key_path).to_string();
+    if !Path::new(&expanded_path).exists() {
+        ui::warn(&t!("setup-key-not-found", path = expanded_path));
+        let proceed = if non_interactive {
+            ui::step(&t!("setup-key-not-found-yes"));
+            false
+        } else {
+            Confirm::new()
+                .with_prompt(t!("setup-prompt-continue"))
+                .default(false)
+                .interact()
+                .map_err(|e| SetupError::Io(std::io::Error::other(e)))?
+        };
+
+        if !proceed {
+            return Err(SetupError::Cancelled);
+        }
+    }
+
+    // Create config directory
+    let config_dir = GlobalConfig::config_dir()
+        .ok_or_else(|| SetupError::Config("Could not determine config directory".to_string()))?;
+    std::fs::create_dir_all(&config_dir)?;
+
+    // Copy key file to config directory
+    let keys_dir = config_dir.join("keys");
+    std::fs::create_dir_all(&keys_dir)?;
+
+    let key_filename = format!("AuthKey_{}.p8", key_id);
+    let dest_key_path = keys_dir.join(&key_filename);
+
+    if Path::new(&expanded_path).exists() {
+        std::fs::copy(&expanded_path, &dest_key_path)?;
+        #[cfg(unix)]
+        {
+            // Private to the user, whatever the source file's mode was
+            use std::os::unix::fs::PermissionsExt;
+            std::fs::set_permissions(&dest_key_path, std::fs::Permissions::from_mode(0o600))?;
+        }
+        ui::success(&t!("setup-copied-key", path = dest_key_path.display()));
+    }
+
+    // Determine final key path (use copied location if it exists, otherwise original)
+    let final_key_path = if dest_key_path.exists() {
+        installed_key_path(&key_id)
+    } else {
+        key_path
+    };
+
+    // Create and save config (keeping sections setup doesn't ask about unless given)
+    let vendor_number = payload
+        .as_ref()
+        .and_then(|p| p.apple.vendor_number.clone())
+        .or_else(|| {
+            std::env::var("APPLE_VENDOR_NUMBER")
+                .ok()
+                .filter(|_| from_env)
+        })
+        .or_else(|| {
+            existing
+                .as_ref()
+                .and_then(|c| c.apple.vendor_number.clone())
+        });
+    let (mut key_profiles, mut google_play, mut ui_config, mut email, mut network, mut history) =
+        match existing {
+            Some(c) => (
+                c.key_profiles,
+                c.google_play,
+                c.ui,
+                c.email,
+                c.network,
+                c.history,
+            ),
+            None => Default::default(),
+        };
+    match payload {
+        Some(payload) => {
+            key_profiles.extend(payload.key_profiles);
+            google_play = payload.google_play.or(google_play);
+            email = payload.email.or(email);
+            if !payload.ui.is_default() {
+                ui_config = payload.ui;
+            }
+            if !payload.network.is_default() {
+                network = payload.network;
+            }
+            if !payload.history.is_default() {
+                history = payload.history;
+            }
+        }
+        None if from_env => {
+            if let Ok(path) = std::env::var("GOOGLE_PLAY_SERVICE_ACCOUNT_PATH") {
+                google_play = Some(GooglePlayConfig {
+                    service_account_path: path,
+                });
+            }
+        }
+        None => {}
+    }
+    let config = GlobalConfig {
+        apple: AppleConfig {
+            key_id,
+            issuer_id,
+            key_path: final_key_path,
+            vendor_number,
+        },
+        key_profiles,
+        google_play,
+        ui: ui_config,
+        email,
+        network,
+        history,
+    };
+
+    config
+        .save()
+        .map_err(|e| SetupError::Config(e.to_string()))?;
+
+    // Keep the keys directory encrypted too
+    if GlobalConfig::is_encrypted() && dest_key_path.exists() {
+        encryption::write(&dest_key_path, &std::fs::read(&dest_key_path)?)?;
+        std::fs::remove_file(&dest_key_path)?;
+    }
+
+    ui::success(&t!("setup-saved"));
+    ui::blank();
+
+    // Run doctor
+    ui::step(&t!("setup-running-doctor"));
+    ui::blank();
+
+    if let Err(e) = crate::commands::doctor::run(None, None, false, false, non_interactive).await {
+        ui::warn(&t!("setup-checks-failed", error = e));
+    }
+
+    ui::blank();
+    ui::header(&t!("setup-complete"));
+    ui::blank();
+    ui::line(format_args!("  {}", t!("next-steps")));
+    ui::line(format_args!("    {}", t!("setup-next-cd")));
+    ui::line(format_args!("    {}", t!("setup-next-init")));
+    ui::line(format_args!("    {}", t!("setup-next-deploy")));
+    ui::blank();
+
+    Ok(())
+}
+
+fn encrypt(keychain: bool, non_interactive: bool) -> Result<(), SetupError> {
+    ui::header(&t!("setup-encrypt-header"));
+    ui::blank();
+
+    if GlobalConfig::is_encrypted() {
+        return Err(SetupError::AlreadyEncrypted);
+    }
+    let config = GlobalConfig::read_file()
+        .map_err(|e| SetupError::Config(e.to_string()))?
+        .ok_or(SetupError::NotConfigured)?;
+    let (config_path, keys_dir) = config_paths()?;
+
+    let passphrase = if keychain {
+        let secret = encryption::store_keychain_passphrase()?;
+        ui::success(&t!("setup-encrypt-keychain"));
+        secret
+    } else {
+        new_passphrase(encryption::PASSPHRASE_ENV, non_interactive)?
+    };
+    encryption::set_passphrase(passphrase);
+
+    let mut files = vec![config_path];
+    files.extend(files_in(&keys_dir, |name| !name.ends_with(".age")));
+
+    for file in &files {
+        let plaintext = std::fs::read(file)?;
+        encryption::write(file, &plaintext)?;
+        std::fs::remove_file(file)?;
+        ui::success(&t!("setup-encrypted-file", path = file.display()));
+    }
+    // Nor should the backup of the plaintext config outlive it
+    let backup = crate::files::backup_path(&files[0]);
+    if backup.exists() {
+        std::fs::remove_file(backup)?;
+    }
+
+    // Keys kept elsewhere stay as they are
+    for apple in std::iter::once(&config.apple).chain(config.key_profiles.values()) {
+        let key_path = PathBuf::from(shellexpand::tilde(&apple.key_path).as_ref());
+        if key_path.exists() && !key_path.starts_with(&keys_dir) {
+            ui::warn(&t!("setup-encrypt-key-outside", path = key_path.display()));
+        }
+    }
+
+    ui::blank();
+    ui::step(&t!(
+        "setup-encrypt-unlock",
+        env = encryption::PASSPHRASE_ENV
+    ));
+    Ok(())
+}
+
+fn decrypt() -> Result<(), SetupError> {
+    ui::header(&t!("setup-decrypt-header"));
+    ui::blank();
+
+    if !GlobalConfig::is_encrypted() {
+        return Err(SetupError::NotEncrypted);
+    }
+    let (config_path, keys_dir) = config_paths()?;
+
+    let mut files = vec![config_path];
+    files.extend(
+        files_in(&keys_dir, |name| name.ends_with(".age"))
+            .into_iter()
+            .map(|path| path.with_extension("")),
+    );
+
+    for file in &files {
+        let plaintext = encryption::read(file)?;
+        encryption::write_private(file, &plaintext)?;
+        std::fs::remove_file(encryption::encrypted_path(file))?;
+        ui::success(&t!("setup-decrypted-file", path = file.display()));
+    }
+
+    encryption::delete_keychain_passphrase();
+    Ok(())
+}
+
+fn export(output: &str, include_key: bool, non_interactive: bool) -> Result<(), SetupError> {
+    ui::header(&t!("setup-export-header"));
+    ui::blank();
+
+    let mut config = GlobalConfig::read_file()
+        .map_err(|e| SetupError::Config(e.to_string()))?
+        .ok_or(SetupError::NotConfigured)?;
+
+    let mut profile_keys = BTreeMap::new();
+    let key = if include_key {
+        for (name, profile) in &mut config.key_profiles {
+            profile_keys.insert(name.clone(), bundle_key(profile)?);
+        }
+        Some(bundle_key(&mut config.apple)?)
+    } else {
+        ui::warn(&t!(
+            "setup-export-no-key",
+            path = config.apple.key_path.as_str()
+        ));
+        None
+    };
+
+    let bundle = toml::to_string_pretty(&Bundle {
+        config,
+        key,
+        profile_keys,
+    })
+    .map_err(|e| SetupError::Config(e.to_string()))?;
+    let passphrase = new_passphrase(BUNDLE_PASSPHRASE_ENV, non_interactive)?;
+    encryption::write_with(Path::new(output), bundle.as_bytes(), &passphrase)?;
+
+    ui::success(&t!("setup-exported", path = output));
+    ui::step(&t!(
+        "setup-export-next",
+        path = output,
+        env = BUNDLE_PASSPHRASE_ENV
+    ));
+    Ok(())
+}
+
+async fn import(bundle_path: &str, non_interactive: bool) -> Result<(), SetupError> {
+    ui::header(&t!("setup-import-header"));
+    ui::blank();
+
+    let passphrase = match std::env::var(BUNDLE_PASSPHRASE_ENV) {
+        Ok(passphrase) => passphrase,
+        Err(_) if non_interactive => return Err(SetupError::MissingEnv(BUNDLE_PASSPHRASE_ENV)),
+        Err(_) => Password::new()
+            .with_prompt(t!("setup-prompt-bundle-passphrase"))
+            .interact()
+            .map_err(|e| SetupError::Io(std::io::Error::other(e)))?,
+    };
+    let plaintext = encryption::read_with(Path::new(bundle_path), &passphrase)?;
+    let Bundle {
+        mut config,
+        key,
+        profile_keys,
+    } = String::from_utf8(plaintext)
+        .map_err(|e| e.to_string())
+        .and_then(|content| toml::from_str(&content).map_err(|e| e.to_string()))
+        .map_err(|message| SetupError::Payload {
+            path: bundle_path.to_string(),
+            message,
+        })?;
+
+    // Reading only checks for the file, so this doesn't ask for the at-rest passphrase
+    if GlobalConfig::config_path().is_some_and(|p| p.exists()) || GlobalConfig::is_encrypted() {
+        let overwrite = if non_interactive {
+            ui::step(&t!("setup-overwrite-yes"));
+            true
+        } else {
+            Confirm::new()
+                .with_prompt(t!("setup-prompt-overwrite"))
+                .default(false)
+                .interact()
+                .map_err(|e| SetupError::Io(std::io::Error::other(e)))?
+        };
+
+        if !overwrite {
+            return Err(SetupError::Cancelled);
+        }
+    }
+
+    let (_, keys_dir) = config_paths()?;
+    for (name, key) in &profile_keys {
+        if let Some(profile) = config.key_profiles.get_mut(name) {
+            install_key(&keys_dir, profile, key)?;
+        }
+    }
+    if let Some(key) = key {
+        install_key(&keys_dir, &mut config.apple, &key)?;
+    } else if !Path::new(shellexpand::tilde(&config.apple.key_path).as_ref()).exists() {
+        ui::warn(&t!(
+            "setup-key-not-found",
+            path = config.apple.key_path.as_str()
+        ));
+    }
+
+    config
+        .save()
+        .map_err(|e| SetupError::Config(e.to_string()))?;
+    ui::success(&t!("setup-saved"));
+    ui::blank();
+
+    ui::step(&t!("setup-running-doctor"));
+    ui::blank();
+    if let Err(e) = crate::commands::doctor::run(None, None, false, false, non_interactive).await {
+        ui::warn(&t!("setup-checks-failed", error = e));
+    }
+
+    Ok(())
+}
+
+/// The key's contents for a bundle, with `key_path` pointing where import installs it
+fn bundle_key(apple: &mut AppleConfig) -> Result<String, SetupError> {
+    // Decrypts the key first if the config is encrypted at rest
+    let key_path = encryption::resolve_key_path(&apple.key_path)?;
+    let key = std::fs::read_to_string(shellexpand::tilde(&key_path).as_ref())?;
+    apple.key_path = installed_key_path(&apple.key_id);
+    Ok(key)
+}
+
+/// Install a bundled key in ~/.launchpad/keys, encrypted when the config is
+fn install_key(keys_dir: &Path, apple: &mut AppleConfig, key: &str) -> Result<(), SetupError> {
+    std::fs::create_dir_all(keys_dir)?;
+    let dest_key_path = keys_dir.join(format!("AuthKey_{}.p8", apple.key_id));
+    if GlobalConfig::is_encrypted() {
+        encryption::write(&dest_key_path, key.as_bytes())?;
+    } else {
+        encryption::write_private(&dest_key_path, key.as_bytes())?;
+    }
+    apple.key_path = installed_key_path(&apple.key_id);
+    ui::success(&t!("setup-copied-key", path = dest_key_path.display()));
+    Ok(())
+}
+
+/// Where setup puts a copied key, as written to config.toml: in the config
+/// directory's keys/ (LAUNCHPAD_CONFIG_DIR included), `~`-relative under home
+fn installed_key_path(key_id: &str) -> String {
+    let file = format!("AuthKey_{}.p8", key_id);
+    let Some(keys_dir) = GlobalConfig::config_dir().map(|dir| dir.join("keys")) else {
+        return format!("~/.launchpad/keys/{}", file);
+    };
+    let under_home =
+        dirs::home_dir().and_then(|home| keys_dir.strip_prefix(home).ok().map(Path::to_path_buf));
+    match under_home {
+        Some(relative) => format!("~/{}", relative.join(file).display()),
+        None => keys_dir.join(file).display().to_string(),
+    }
+}
+
+/// ~/.launchpad/config.toml and ~/.launchpad/keys
+fn config_paths() -> Result<(PathBuf, PathBuf), SetupError> {
+    let config_dir = GlobalConfig::config_dir()
+        .ok_or_else(|| SetupError::Config("Could not determine config directory".to_string()))?;
+    Ok((config_dir.join("config.toml"), config_dir.join("keys")))
+}
+
+/// Regular files in `dir` whose name passes `filter`
+fn files_in(dir: &Path, filter: impl Fn(&str) -> bool) -> Vec<PathBuf> {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return Vec::new();
+    };
+
+    entries
+        .flatten()
+        .filter(|e| e.file_type().is_ok_and(|t| t.is_file()))
+        .filter(|e| filter(&e.file_name().to_string_lossy()))
+        .map(|e| e.path())
+        .collect()
+}
+
+/// A new passphrase from `env`, or asked for twice
+fn new_passphrase(env: &'static str, non_interactive: bool) -> Result<String, SetupError> {
+    if let Ok(passphrase) = std::env::var(env) {
+        return Ok(passphrase);
+    }
+    if non_interactive {
+        return Err(SetupError::MissingEnv(env));
+    }
+
+    Password::new()
+        .with_prompt(t!("setup-prompt-passphrase"))
+        .with_confirmation(
+            t!("setup-prompt-passphrase-confirm"),
+            t!("setup-passphrase-mismatch"),
+        )
+        .interact()
+        .map_err(|e| SetupError::Io(std::io::Error::other(e)))
+}
+
+/// A global config document as JSON or TOML, from a file or stdin (`-`)
+fn read_payload(path: &Path) -> Result<GlobalConfig, SetupError> {
+    let content = if path == Path::new("-") {
+        let mut content = String::new();
+        std::io::stdin().read_to_string(&mut content)?;
+        content
+    } else {
+        std::fs::read_to_string(path)?
+    };
+
+    let parsed = if content.trim_start().starts_with('{') {
+        serde_json::from_str(&content).map_err(|e| e.to_string())
+    } else {
+        toml::from_str(&content).map_err(|e| e.to_string())
+    };
+    parsed.map_err(|message| SetupError::Payload {
+        path: path.display().to_string(),
+        message,
+    })
+}
+
+/// Prompt for a value, or read it from `env` without prompting
+fn prompt_or_env(
+    prompt: String,
+    env: &'static str,
+    non_interactive: bool,
+) -> Result<String, SetupError> {
+    if non_interactive {
+        let value = std::env::var(env).map_err(|_| SetupError::MissingEnv(env))?;
+        ui::success(&t!("setup-using-env", name = prompt, env = env));
+        return Ok(value);
+    }
+
+    Input::new()
+        .with_prompt(prompt)
+        .interact_text()
+        .map_err(|e| SetupError::Io(std::io::Error::other(e)))
+}
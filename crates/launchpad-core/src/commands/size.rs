@@ -0,0 +1,101 @@
+use crate::config::project::{ProjectConfig, SizeSettings};
+use crate::thinning::{self, ThinnedSize, ThinningError};
+use crate::ui;
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+use thiserror::Error;
+
+/// `[size]` as JSON, set by `launchpad deploy` for the check the lane runs
+pub const BUDGETS_ENV: &str = "LAUNCHPAD_SIZE_BUDGETS";
+
+#[derive(Error, Debug)]
+pub enum SizeError {
+    #[error("No App Thinning size report at {0}. Add a [size] section to .launchpad.toml so deploys export one.")]
+    NoReport(PathBuf),
+
+    #[error("{0} has no variants")]
+    EmptyReport(PathBuf),
+
+    #[error("{count} size budget{} exceeded", if *.count == 1 { "" } else { "s" })]
+    OverBudget { count: usize },
+
+    #[error("{0}")]
+    Thinning(#[from] ThinningError),
+
+    #[error("Invalid {BUDGETS_ENV}: {0}")]
+    Budgets(#[from] serde_json::Error),
+
+    #[error("IO error: {0}")]
+    Io(#[from] std::io::Error),
+
+    #[error("Config error: {0}")]
+    Config(String),
+}
+
+/// Show download and install sizes per device class from an App Thinning size
+/// report; with `check`, fail when a `[size]` budget is exceeded
+pub async fn run(report: Option<PathBuf>, check: bool) -> Result<(), SizeError> {
+    ui::header("App Size");
+
+    let project_config = ProjectConfig::load().map_err(|e| SizeError::Config(e.to_string()))?;
+    let path = match report {
+        Some(path) => path,
+        None => {
+            let output_dir = project_config
+                .as_ref()
+                .map(|c| c.build.output_dir.clone())
+                .unwrap_or_else(|| "build".to_string());
+            PathBuf::from(shellexpand::tilde(&output_dir).as_ref()).join(thinning::REPORT_FILENAME)
+        }
+    };
+    if !path.exists() {
+        return Err(SizeError::NoReport(path));
+    }
+
+    let variants = thinning::parse(&std::fs::read_to_string(&path)?);
+    if variants.is_empty() {
+        return Err(SizeError::EmptyReport(path));
+    }
+    print_sizes(&thinning::by_class(&variants));
+
+    if !check {
+        return Ok(());
+    }
+    // The lane passes the deploy's settings; a checkout without .launchpad.toml has no file to read
+    let settings = match std::env::var(BUDGETS_ENV) {
+        Ok(json) if !json.is_empty() => Some(serde_json::from_str::<SizeSettings>(&json)?),
+        _ => project_config.and_then(|c| c.size),
+    };
+    let Some(settings) = settings else {
+        return Ok(());
+    };
+
+    let budgets = thinning::check_budgets(&variants, &settings)?;
+    for device in &budgets.unmatched {
+        ui::warn(&format!("No variant for the {} size budget", device));
+    }
+    if budgets.exceeded.is_empty() {
+        ui::success("Within size budgets");
+        return Ok(());
+    }
+    for problem in &budgets.exceeded {
+        ui::error(problem);
+    }
+    Err(SizeError::OverBudget {
+        count: budgets.exceeded.len(),
+    })
+}
+
+/// One line per device class: largest download and install size
+pub fn print_sizes(classes: &BTreeMap<String, ThinnedSize>) {
+    for (class, size) in classes {
+        ui::field(
+            class,
+            &format!(
+                "{} download, {} installed",
+                thinning::format_size(size.download),
+                thinning::format_size(size.install)
+            ),
+        );
+    }
+}
@@ -0,0 +1,123 @@
+use crate::config::project::ProjectConfig;
+use crate::history::{self, DeployRecord, HistoryError};
+use crate::templating;
+use crate::ui;
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+pub enum StatsError {
+    #[error("Project config not found. Run 'launchpad init' first, or pass --bundle-id.")]
+    NoProjectConfig,
+
+    #[error("{0}")]
+    History(#[from] HistoryError),
+
+    #[error("Config error: {0}")]
+    Config(String),
+}
+
+/// How many of the latest builds are listed and compared against the rest
+const RECENT: usize = 5;
+
+/// Recent builds processing this much slower than the median (in percent) point at Apple
+const SLOWDOWN_PERCENT: u64 = 150;
+
+/// Deploy counts and App Store Connect processing times from the deploy history
+pub async fn run(bundle_id: Option<String>) -> Result<(), StatsError> {
+    ui::header("Launchpad Stats");
+
+    let bundle_id = match bundle_id {
+        Some(bundle_id) => bundle_id,
+        None => {
+            ProjectConfig::load()
+                .map_err(|e| StatsError::Config(e.to_string()))?
+                .ok_or(StatsError::NoProjectConfig)?
+                .project
+                .bundle_id
+        }
+    };
+    let records: Vec<DeployRecord> = history::load()?
+        .into_iter()
+        .filter(|r| r.bundle_id == bundle_id && r.platform == "ios")
+        .collect();
+
+    ui::field("App", &bundle_id);
+    ui::field("Deploys", &records.len().to_string());
+    if let Some(last) = records.last() {
+        ui::field(
+            "Last deploy",
+            &format!(
+                "{} ({}), {}",
+                last.version,
+                last.build,
+                history::age(last.deployed_at)
+            ),
+        );
+    }
+
+    ui::header("TestFlight Processing");
+    let measured: Vec<(&DeployRecord, u64)> = records
+        .iter()
+        .filter_map(|r| Some((r, r.processing_secs?)))
+        .collect();
+    if measured.is_empty() {
+        ui::step("No processing times recorded yet. Deploys record them when they wait for processing (TestFlight groups, or a \"processed\" notification).");
+        return Ok(());
+    }
+
+    let times: Vec<u64> = measured.iter().map(|(_, secs)| *secs).collect();
+    let overall = median(&times);
+    ui::field(
+        "Median",
+        &format!(
+            "{} over {} builds",
+            templating::format_duration(overall),
+            times.len()
+        ),
+    );
+    ui::field(
+        "Fastest",
+        &templating::format_duration(*times.iter().min().unwrap_or(&0)),
+    );
+    ui::field(
+        "Slowest",
+        &templating::format_duration(*times.iter().max().unwrap_or(&0)),
+    );
+
+    let recent = &measured[measured.len().saturating_sub(RECENT)..];
+    if measured.len() > RECENT {
+        let recent_times: Vec<u64> = recent.iter().map(|(_, secs)| *secs).collect();
+        let recent_median = median(&recent_times);
+        ui::field(
+            &format!("Last {}", RECENT),
+            &format!("{} median", templating::format_duration(recent_median)),
+        );
+        if recent_median * 100 > overall * SLOWDOWN_PERCENT {
+            ui::warn("Recent builds are processing much slower than usual; App Store Connect may be backed up (https://developer.apple.com/system-status/)");
+        }
+    }
+
+    ui::blank();
+    for (record, secs) in recent.iter().rev() {
+        ui::field(
+            &format!("{} ({})", record.version, record.build),
+            &format!(
+                "{}, {}",
+                templating::format_duration(*secs),
+                history::age(record.deployed_at)
+            ),
+        );
+    }
+
+    Ok(())
+}
+
+fn median(values: &[u64]) -> u64 {
+    let mut sorted = values.to_vec();
+    sorted.sort_unstable();
+    match sorted.len() {
+        0 => 0,
+        n if n % 2 == 0 => (sorted[n / 2 - 1] + sorted[n / 2]) / 2,
+        n => sorted[n / 2],
+    }
+}
@@ -0,0 +1,95 @@
+use crate::asc::{AscClient, AscError, BuildStatus};
+use crate::config;
+use crate::config::global::GlobalConfig;
+use crate::config::project::ProjectConfig;
+use crate::ui;
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+pub enum StatusError {
+    #[error("Global config not found. Run 'launchpad setup' first.")]
+    NoGlobalConfig,
+
+    #[error("Project config not found. Run 'launchpad init' first, or pass --bundle-id.")]
+    NoProjectConfig,
+
+    #[error("App Store Connect has no builds of {0} yet")]
+    NoBuilds(String),
+
+    #[error("{0}")]
+    Asc(#[from] AscError),
+
+    #[error("Config error: {0}")]
+    Config(String),
+}
+
+/// TestFlight state of the latest build, or with `all` of every build still
+/// processing or missing export compliance, across versions
+pub async fn run(all: bool, bundle_id: Option<String>) -> Result<(), StatusError> {
+    ui::header("Launchpad Status");
+
+    let mut global_config = GlobalConfig::load()
+        .map_err(|e| StatusError::Config(e.to_string()))?
+        .ok_or(StatusError::NoGlobalConfig)?;
+    let mut project_config =
+        ProjectConfig::load().map_err(|e| StatusError::Config(e.to_string()))?;
+    config::use_selected_environment(&mut global_config, project_config.as_mut())
+        .map_err(|e| StatusError::Config(e.to_string()))?;
+    let bundle_id = match (bundle_id, &project_config) {
+        (Some(bundle_id), _) => bundle_id,
+        (None, Some(config)) => config.project.bundle_id.clone(),
+        (None, None) => return Err(StatusError::NoProjectConfig),
+    };
+
+    let client = AscClient::new(&global_config.apple)?;
+    let app_id = client
+        .project_app_id(&bundle_id, project_config.as_ref().map(|c| &c.project))
+        .await?;
+    let spinner = ui::spinner("Fetching builds from App Store Connect...");
+    let statuses = client.build_statuses(&app_id).await;
+    spinner.finish_and_clear();
+    let statuses = statuses?;
+    ui::field("App", &bundle_id);
+
+    if !all {
+        let latest = statuses
+            .first()
+            .ok_or_else(|| StatusError::NoBuilds(bundle_id.clone()))?;
+        ui::field("Build", &build_label(latest));
+        ui::field("State", &latest.state_label());
+        if let Some(date) = &latest.build.uploaded_date {
+            ui::field("Uploaded", date);
+        }
+        return Ok(());
+    }
+
+    let pending: Vec<&BuildStatus> = statuses.iter().filter(|s| s.is_pending()).collect();
+    if pending.is_empty() {
+        ui::success("No builds processing or missing compliance");
+        return Ok(());
+    }
+    ui::field("Pending", &pending.len().to_string());
+    ui::blank();
+    for status in pending {
+        let uploaded = status
+            .build
+            .uploaded_date
+            .as_deref()
+            .map(|date| format!(", uploaded {}", date))
+            .unwrap_or_default();
+        ui::field(
+            &build_label(status),
+            &format!("{}{}", status.state_label(), uploaded),
+        );
+    }
+
+    Ok(())
+}
+
+/// "1.2.0 (43)", or just the build number when the version wasn't included
+fn build_label(status: &BuildStatus) -> String {
+    match &status.version {
+        Some(version) => format!("{} ({})", version, status.build.number),
+        None => status.build.number.clone(),
+    }
+}
@@ -0,0 +1,162 @@
+use crate::asc::{AscClient, AscError};
+use crate::config;
+use crate::config::global::GlobalConfig;
+use crate::config::project::ProjectConfig;
+use crate::symbols::{self, SymbolsError};
+use crate::ui;
+use clap::Subcommand;
+use std::path::PathBuf;
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+pub enum SymbolsCommandError {
+    #[error("Global config not found. Run 'launchpad setup' first.")]
+    NoGlobalConfig,
+
+    #[error("Project config not found. Run 'launchpad init' first.")]
+    NoProjectConfig,
+
+    #[error("Build {0} not found on App Store Connect")]
+    BuildNotFound(String),
+
+    #[error("Build {0} is still processing. Try again once it's done.")]
+    BuildProcessing(String),
+
+    #[error("App Store Connect has no dSYMs for build {0}. Use the dSYMs from the local archive instead.")]
+    NoSymbols(String),
+
+    #[error("No crash reporter configured. Add a [symbols] section to .launchpad.toml or pass --no-upload.")]
+    NoReporter,
+
+    #[error("{0}")]
+    Asc(#[from] AscError),
+
+    #[error("{0}")]
+    Symbols(#[from] SymbolsError),
+
+    #[error("IO error: {0}")]
+    Io(#[from] std::io::Error),
+
+    #[error("Config error: {0}")]
+    Config(String),
+}
+
+#[derive(Subcommand)]
+pub enum SymbolsCommand {
+    /// Download Apple-processed dSYMs and forward them to the crash reporter
+    Fetch {
+        /// Build number (CFBundleVersion)
+        #[arg(long)]
+        build: String,
+
+        /// Marketing version, when the build number is reused across versions
+        #[arg(long)]
+        version: Option<String>,
+
+        /// Where to put the dSYMs (default: <output_dir>/dsyms/<build>)
+        #[arg(long)]
+        output: Option<String>,
+
+        /// Only download; don't upload to the crash reporter
+        #[arg(long)]
+        no_upload: bool,
+    },
+}
+
+pub async fn run(command: SymbolsCommand) -> Result<(), SymbolsCommandError> {
+    match command {
+        SymbolsCommand::Fetch {
+            build,
+            version,
+            output,
+            no_upload,
+        } => fetch(build, version, output, no_upload).await,
+    }
+}
+
+async fn fetch(
+    number: String,
+    version: Option<String>,
+    output: Option<String>,
+    no_upload: bool,
+) -> Result<(), SymbolsCommandError> {
+    ui::header("Launchpad Symbols");
+
+    let mut global_config = GlobalConfig::load()
+        .map_err(|e| SymbolsCommandError::Config(e.to_string()))?
+        .ok_or(SymbolsCommandError::NoGlobalConfig)?;
+    let mut project_config = ProjectConfig::load()
+        .map_err(|e| SymbolsCommandError::Config(e.to_string()))?
+        .ok_or(SymbolsCommandError::NoProjectConfig)?;
+    config::use_selected_environment(&mut global_config, Some(&mut project_config))
+        .map_err(|e| SymbolsCommandError::Config(e.to_string()))?;
+
+    // Fail before downloading anything
+    let reporter = match (&project_config.symbols, no_upload) {
+        (_, true) => None,
+        (Some(reporter), false) => Some(reporter),
+        (None, false) => return Err(SymbolsCommandError::NoReporter),
+    };
+
+    let client = AscClient::new(&global_config.apple)?;
+    let app_id = client
+        .project_app_id(
+            &project_config.project.bundle_id,
+            Some(&project_config.project),
+        )
+        .await?;
+
+    let build = client
+        .find_build(&app_id, &number, version.as_deref())
+        .await?
+        .ok_or_else(|| SymbolsCommandError::BuildNotFound(number.clone()))?;
+
+    // Apple only generates dSYMs once processing is done
+    if !build.is_valid() {
+        return Err(SymbolsCommandError::BuildProcessing(build.number));
+    }
+
+    // (bundle name, dSYM download) for the app and each extension
+    let bundles: Vec<(String, String)> = client
+        .build_bundles(&build.id)
+        .await?
+        .into_iter()
+        .filter_map(|b| {
+            let url = b.dsym_url?;
+            Some((b.bundle_id.unwrap_or(b.id), url))
+        })
+        .collect();
+    if bundles.is_empty() {
+        return Err(SymbolsCommandError::NoSymbols(build.number));
+    }
+
+    let dir = output.map(PathBuf::from).unwrap_or_else(|| {
+        PathBuf::from(&project_config.build.output_dir)
+            .join("dsyms")
+            .join(&build.number)
+    });
+    std::fs::create_dir_all(&dir)?;
+
+    for (name, url) in &bundles {
+        let archive = dir.join(format!("{}.dSYM.zip", name));
+
+        let spinner = ui::spinner(&format!("Downloading dSYMs for {}...", name));
+        let downloaded = client.download(url, &archive).await;
+        spinner.finish_and_clear();
+        downloaded?;
+
+        symbols::extract(&archive, &dir)?;
+        std::fs::remove_file(&archive)?;
+        ui::success(&format!("dSYMs for {}", name));
+    }
+
+    ui::field("Saved to", &dir.display().to_string());
+
+    if let Some(reporter) = reporter {
+        ui::step(&format!("Uploading to {}...", reporter));
+        symbols::upload(reporter, &project_config.project.ios_path, &dir)?;
+        ui::success(&format!("Uploaded to {}", reporter));
+    }
+
+    Ok(())
+}
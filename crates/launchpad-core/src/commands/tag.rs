@@ -0,0 +1,133 @@
+use crate::commands::deploy::{self, Platform};
+use crate::config::project::ProjectConfig;
+use crate::git::{self, GitError};
+use crate::history::{self, HistoryError};
+use crate::templating;
+use crate::ui;
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+pub enum TagError {
+    #[error("Project config not found. Run 'launchpad init' first.")]
+    NoProjectConfig,
+
+    #[error("No [android] section in .launchpad.toml")]
+    NoAndroidConfig,
+
+    #[error("Build {0} is not in the deploy history. Only builds deployed with launchpad can be tagged.")]
+    BuildNotRecorded(String),
+
+    #[error("The deploy of build {0} didn't record a commit")]
+    NoCommit(String),
+
+    #[error("Commit {0} isn't in this clone. Fetch it first.")]
+    UnknownCommit(String),
+
+    #[error("Failed to create git tag: {0}")]
+    Git(#[from] GitError),
+
+    #[error("{0}")]
+    History(#[from] HistoryError),
+
+    #[error("IO error: {0}")]
+    Io(#[from] std::io::Error),
+
+    #[error("Config error: {0}")]
+    Config(String),
+}
+
+/// Create the release tag for a recorded deploy, on the commit it was built from
+/// rather than HEAD; for deploys whose tagging was skipped or failed
+pub async fn run(build: String, platform: Platform, no_push: bool) -> Result<(), TagError> {
+    ui::header("Launchpad Tag");
+
+    let project_config = ProjectConfig::load()
+        .map_err(|e| TagError::Config(e.to_string()))?
+        .ok_or(TagError::NoProjectConfig)?;
+    if platform == Platform::Android && project_config.android.is_none() {
+        return Err(TagError::NoAndroidConfig);
+    }
+    let (app, bundle_id) = deploy::app_identity(&project_config, platform);
+
+    let records = history::load()?;
+    let index = records
+        .iter()
+        .rposition(|r| {
+            r.bundle_id == bundle_id && r.platform == platform.name() && r.build == build
+        })
+        .ok_or_else(|| TagError::BuildNotRecorded(build.clone()))?;
+    let record = &records[index];
+    let sha = record
+        .git_sha
+        .clone()
+        .ok_or_else(|| TagError::NoCommit(build.clone()))?;
+
+    if !git::has_commit(&sha) {
+        return Err(TagError::UnknownCommit(sha));
+    }
+
+    let tag = platform.release_tag(&record.version, &record.build);
+    let template = project_config
+        .templates
+        .as_ref()
+        .and_then(|t| t.tag_message.as_deref());
+    let message = match template {
+        Some(template) => {
+            let variables = templating::Variables {
+                app: app.to_string(),
+                bundle_id: bundle_id.to_string(),
+                platform: platform.name().to_string(),
+                version: Some(record.version.clone()),
+                build: Some(record.build.clone()),
+                tag: Some(tag.clone()),
+                short_sha: Some(sha[..sha.len().min(7)].to_string()),
+                sha: Some(sha.clone()),
+                ..Default::default()
+            };
+            match templating::render("tag_message", template, &variables) {
+                Ok(text) => Some(text),
+                Err(e) => {
+                    ui::warn(&format!("{}; using the built-in text", e));
+                    None
+                }
+            }
+        }
+        None => None,
+    };
+    let message = message.unwrap_or_else(|| format!("Release {}", tag));
+
+    ui::step(&format!(
+        "Tagging {} ({}) at {}...",
+        record.version,
+        record.build,
+        &sha[..sha.len().min(7)]
+    ));
+    deploy::create_git_tag(&tag, &message, &project_config.deploy, Some(&sha))?;
+    ui::success(&format!("Created tag {}", tag));
+
+    // Keep the history in step, so `diff` and later runs see the tag
+    let recorded = history::update(|records| {
+        let deployed = records.iter_mut().rev().find(|r| {
+            r.bundle_id == bundle_id && r.platform == platform.name() && r.build == build
+        });
+        if let Some(deployed) = deployed {
+            deployed.tag = Some(tag.clone());
+        }
+    });
+    if let Err(e) = recorded {
+        ui::warn(&format!(
+            "Failed to record the tag in the deploy history: {}",
+            e
+        ));
+    }
+
+    if !no_push && project_config.deploy.push_tags {
+        let remote = project_config.deploy.remote();
+        match git::run(&["push", remote, &format!("refs/tags/{}", tag)], &[]) {
+            Ok(()) => ui::success(&format!("Pushed tag to {}", remote)),
+            Err(e) => ui::warn(&format!("Failed to push tag: {}", e)),
+        }
+    }
+
+    Ok(())
+}
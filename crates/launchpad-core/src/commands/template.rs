@@ -0,0 +1,193 @@
+use crate::config::project::ProjectConfig;
+use crate::templates::{self, FastfileContext, MANAGED_BEGIN, MANAGED_END};
+use crate::ui;
+use clap::Subcommand;
+use console::style;
+use dialoguer::Confirm;
+use std::path::Path;
+use thiserror::Error;
+
+/// Unchanged lines shown around each change
+const DIFF_CONTEXT: usize = 2;
+
+#[derive(Error, Debug)]
+pub enum TemplateError {
+    #[error("Project config not found. Run 'launchpad init' first.")]
+    NoProjectConfig,
+
+    #[error("No Fastfile found in {0}/fastlane/. Run 'launchpad init' to create one.")]
+    NoFastfile(String),
+
+    #[error("{0} has no launchpad-managed section. Put the generated lanes between '{MANAGED_BEGIN}' and '{MANAGED_END}' lines, or move the file away and run 'launchpad init'.")]
+    NoManagedSection(String),
+
+    #[error("The managed section of {0} was edited by hand. Re-run with --force to replace it.")]
+    LocalEdits(String),
+
+    #[error("IO error: {0}")]
+    Io(#[from] std::io::Error),
+
+    #[error("Config error: {0}")]
+    Config(String),
+}
+
+#[derive(Subcommand)]
+pub enum TemplateCommand {
+    /// Regenerate the launchpad-managed part of the Fastfile, keeping your own lanes
+    Update {
+        /// Replace the managed section even if it was edited by hand
+        #[arg(long)]
+        force: bool,
+    },
+}
+
+pub async fn run(command: TemplateCommand, non_interactive: bool) -> Result<(), TemplateError> {
+    match command {
+        TemplateCommand::Update { force } => update(force, non_interactive),
+    }
+}
+
+/// Three-way update: the checksum in the begin marker stands in for the base,
+/// so hand edits inside the section are noticed before they're overwritten
+fn update(force: bool, non_interactive: bool) -> Result<(), TemplateError> {
+    ui::header("Fastfile Template Update");
+
+    let config = ProjectConfig::load()
+        .map_err(|e| TemplateError::Config(e.to_string()))?
+        .ok_or(TemplateError::NoProjectConfig)?;
+    let ios_path = &config.project.ios_path;
+    let path =
+        find_fastfile(ios_path).ok_or_else(|| TemplateError::NoFastfile(ios_path.clone()))?;
+
+    let content = std::fs::read_to_string(&path)?;
+    let current = templates::split_managed(&content)
+        .ok_or_else(|| TemplateError::NoManagedSection(path.clone()))?;
+
+    let generated = templates::generate_fastfile(&FastfileContext::new(&config));
+    let new_body = templates::split_managed(&generated)
+        .map(|section| section.body)
+        .unwrap_or_default();
+
+    if current.body == new_body {
+        ui::success(&format!("{} is up to date", path));
+        return Ok(());
+    }
+
+    ui::blank();
+    print_diff(current.body, new_body);
+    ui::blank();
+
+    if current.is_edited() {
+        ui::warn("The managed section was edited by hand; those edits will be replaced");
+        ui::step(&format!(
+            "Keep custom lanes below the '{}' line so updates leave them alone",
+            MANAGED_END
+        ));
+        if non_interactive && !force {
+            return Err(TemplateError::LocalEdits(path));
+        }
+    }
+
+    let write = if non_interactive {
+        true
+    } else {
+        Confirm::new()
+            .with_prompt(format!("Update {}?", path))
+            .default(!current.is_edited() || force)
+            .interact()
+            .map_err(|e| TemplateError::Io(std::io::Error::other(e)))?
+    };
+
+    if !write {
+        ui::warn("Fastfile left unchanged");
+        return Ok(());
+    }
+
+    std::fs::write(&path, current.replace_body(new_body))?;
+    ui::success(&format!("Updated {}", path));
+    Ok(())
+}
+
+pub fn find_fastfile(ios_path: &str) -> Option<String> {
+    [
+        format!("{}/fastlane/Fastfile", ios_path),
+        format!("{}/Fastfile", ios_path),
+        "fastlane/Fastfile".to_string(),
+        "Fastfile".to_string(),
+    ]
+    .into_iter()
+    .find(|path| Path::new(path).exists())
+}
+
+enum DiffLine<'a> {
+    Same(&'a str),
+    Removed(&'a str),
+    Added(&'a str),
+}
+
+/// Line diff of `old` → `new` with a little context around each change
+fn print_diff(old: &str, new: &str) {
+    let lines = diff_lines(
+        &old.lines().collect::<Vec<_>>(),
+        &new.lines().collect::<Vec<_>>(),
+    );
+    let changed: Vec<usize> = lines
+        .iter()
+        .enumerate()
+        .filter(|(_, line)| !matches!(line, DiffLine::Same(_)))
+        .map(|(i, _)| i)
+        .collect();
+
+    let mut last_shown = None;
+    for (i, line) in lines.iter().enumerate() {
+        let near_change = changed.iter().any(|&c| c.abs_diff(i) <= DIFF_CONTEXT);
+        if !near_change {
+            continue;
+        }
+        if last_shown.is_some_and(|last| last + 1 < i) {
+            ui::line(format_args!("  {}", style("...").dim()));
+        }
+        last_shown = Some(i);
+
+        let styled = match line {
+            DiffLine::Same(text) => style(format!("  {}", text)).dim(),
+            DiffLine::Removed(text) => style(format!("- {}", text)).red(),
+            DiffLine::Added(text) => style(format!("+ {}", text)).green(),
+        };
+        ui::line(format_args!("  {}", styled));
+    }
+}
+
+/// Longest-common-subsequence diff; Fastfiles are small enough for the O(n·m) table
+fn diff_lines<'a>(old: &[&'a str], new: &[&'a str]) -> Vec<DiffLine<'a>> {
+    let mut lcs = vec![vec![0usize; new.len() + 1]; old.len() + 1];
+    for i in (0..old.len()).rev() {
+        for j in (0..new.len()).rev() {
+            lcs[i][j] = if old[i] == new[j] {
+                lcs[i + 1][j + 1] + 1
+            } else {
+                lcs[i + 1][j].max(lcs[i][j + 1])
+            };
+        }
+    }
+
+    let (mut i, mut j) = (0, 0);
+    let mut lines = Vec::new();
+    while i < old.len() && j < new.len() {
+        if old[i] == new[j] {
+            lines.push(DiffLine::Same(old[i]));
+            i += 1;
+            j += 1;
+        } else if lcs[i + 1][j] >= lcs[i][j + 1] {
+            lines.push(DiffLine::Removed(old[i]));
+            i += 1;
+        } else {
+            lines.push(DiffLine::Added(new[j]));
+            j += 1;
+        }
+    }
+    lines.extend(old[i..].iter().map(|line| DiffLine::Removed(line)));
+    lines.extend(new[j..].iter().map(|line| DiffLine::Added(line)));
+
+    lines
+}
@@ -0,0 +1,368 @@
+use crate::asc::{AscClient, AscError, BetaGroup, BetaTester};
+use crate::commands::{connect, ConnectError};
+use crate::ui;
+use clap::Subcommand;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+pub enum TestersError {
+    #[error("{path}, line {line}: {message}")]
+    InvalidRow {
+        path: PathBuf,
+        line: u64,
+        message: String,
+    },
+
+    #[error("{0} has no testers")]
+    Empty(PathBuf),
+
+    #[error("TestFlight group '{0}' not found")]
+    GroupNotFound(String),
+
+    #[error("'{0}' is an internal group; internal testers are App Store Connect users, invited under Users and Access")]
+    InternalGroup(String),
+
+    #[error("{count} tester{} couldn't be imported", if *.count == 1 { "" } else { "s" })]
+    Failed { count: usize },
+
+    #[error("CSV error: {0}")]
+    Csv(#[from] csv::Error),
+
+    #[error("{0}")]
+    Asc(#[from] AscError),
+
+    #[error("IO error: {0}")]
+    Io(#[from] std::io::Error),
+
+    #[error("{0}")]
+    Connect(#[from] ConnectError),
+}
+
+#[derive(Subcommand)]
+pub enum TestersCommand {
+    /// Invite external testers from a CSV file (first name, last name, email, groups)
+    Import {
+        /// CSV in fastlane pilot's format; groups are separated by ';'
+        file: PathBuf,
+
+        /// Group for rows without one (repeatable)
+        #[arg(long = "group", value_name = "NAME")]
+        groups: Vec<String>,
+
+        /// App the testers are for (default: the bundle ID in .launchpad.toml)
+        #[arg(long)]
+        bundle_id: Option<String>,
+
+        /// Show what would change without inviting anyone
+        #[arg(long)]
+        dry_run: bool,
+    },
+
+    /// Write the app's testers and their groups as CSV
+    Export {
+        /// Output file (default: stdout)
+        file: Option<PathBuf>,
+
+        /// Only testers in this group (repeatable)
+        #[arg(long = "group", value_name = "NAME")]
+        groups: Vec<String>,
+
+        /// App the testers are for (default: the bundle ID in .launchpad.toml)
+        #[arg(long)]
+        bundle_id: Option<String>,
+    },
+}
+
+/// One tester from the import file
+struct Row {
+    line: u64,
+    first_name: String,
+    last_name: String,
+    email: String,
+    groups: Vec<String>,
+}
+
+pub async fn run(command: TestersCommand) -> Result<(), TestersError> {
+    match command {
+        TestersCommand::Import {
+            file,
+            groups,
+            bundle_id,
+            dry_run,
+        } => import(file, groups, bundle_id, dry_run).await,
+        TestersCommand::Export {
+            file,
+            groups,
+            bundle_id,
+        } => export(file, groups, bundle_id).await,
+    }
+}
+
+async fn import(
+    path: PathBuf,
+    default_groups: Vec<String>,
+    bundle_id: Option<String>,
+    dry_run: bool,
+) -> Result<(), TestersError> {
+    ui::header("Import Testers");
+
+    let rows = read_rows(&path, &default_groups)?;
+    let (client, app_id) = connect(bundle_id).await?;
+
+    // Check every group before inviting anyone, so a typo doesn't leave half an import
+    let spinner = ui::spinner("Loading groups and testers...");
+    let loaded = tokio::try_join!(client.beta_groups(&app_id), client.beta_testers(&app_id));
+    spinner.finish_and_clear();
+    let (groups, testers) = loaded?;
+    let mut wanted: Vec<&str> = rows
+        .iter()
+        .flat_map(|row| row.groups.iter().map(String::as_str))
+        .collect();
+    wanted.sort_unstable();
+    wanted.dedup();
+    let group_ids = resolve_external(&groups, &wanted)?;
+
+    let existing: HashMap<String, &BetaTester> = testers
+        .iter()
+        .map(|t| (t.email.to_lowercase(), t))
+        .collect();
+
+    let mut invited = 0;
+    let mut added = 0;
+    let mut unchanged = 0;
+    let mut failed = 0;
+    for row in &rows {
+        let ids: Vec<String> = row
+            .groups
+            .iter()
+            .map(|name| group_ids[name.as_str()].clone())
+            .collect();
+
+        let result = match existing.get(&row.email.to_lowercase()) {
+            Some(tester) => {
+                let missing: Vec<&String> = ids
+                    .iter()
+                    .filter(|id| !tester.group_ids.contains(id))
+                    .collect();
+                if missing.is_empty() {
+                    unchanged += 1;
+                    continue;
+                }
+                ui::step(&format!(
+                    "{} → {}",
+                    row.email,
+                    group_names(&groups, missing.iter().copied())
+                ));
+                let result = if dry_run {
+                    Ok(())
+                } else {
+                    add_to_groups(&client, tester, &missing).await
+                };
+                result.map(|()| &mut added)
+            }
+            None => {
+                ui::step(&format!("{} (new) → {}", row.email, row.groups.join(", ")));
+                let result = if dry_run {
+                    Ok(())
+                } else {
+                    client
+                        .create_beta_tester(&row.email, &row.first_name, &row.last_name, &ids)
+                        .await
+                };
+                result.map(|()| &mut invited)
+            }
+        };
+
+        match result {
+            Ok(count) => *count += 1,
+            Err(e) => {
+                ui::error(&format!("Line {} ({}): {}", row.line, row.email, e));
+                failed += 1;
+            }
+        }
+    }
+
+    ui::blank();
+    let verb = if dry_run { "Would invite" } else { "Invited" };
+    ui::success(&format!(
+        "{} {} new tester{}, {} added to groups, {} already up to date",
+        verb,
+        invited,
+        if invited == 1 { "" } else { "s" },
+        added,
+        unchanged
+    ));
+    if failed > 0 {
+        return Err(TestersError::Failed { count: failed });
+    }
+    Ok(())
+}
+
+async fn export(
+    path: Option<PathBuf>,
+    only_groups: Vec<String>,
+    bundle_id: Option<String>,
+) -> Result<(), TestersError> {
+    // stdout may be piped into a file, so keep it free of anything but CSV
+    if path.is_some() {
+        ui::header("Export Testers");
+    }
+
+    let (client, app_id) = connect(bundle_id).await?;
+    let (groups, testers) =
+        tokio::try_join!(client.beta_groups(&app_id), client.beta_testers(&app_id))?;
+
+    let mut filter = Vec::new();
+    for name in &only_groups {
+        let group = groups
+            .iter()
+            .find(|g| g.name == *name)
+            .ok_or_else(|| TestersError::GroupNotFound(name.clone()))?;
+        filter.push(group.id.as_str());
+    }
+
+    let mut writer = match &path {
+        Some(path) => csv::Writer::from_writer(
+            Box::new(std::fs::File::create(path)?) as Box<dyn std::io::Write>
+        ),
+        None => csv::Writer::from_writer(Box::new(std::io::stdout()) as Box<dyn std::io::Write>),
+    };
+    writer.write_record(["First", "Last", "Email", "Groups", "State"])?;
+
+    let mut count = 0;
+    for tester in &testers {
+        if !filter.is_empty()
+            && !tester
+                .group_ids
+                .iter()
+                .any(|id| filter.contains(&id.as_str()))
+        {
+            continue;
+        }
+        // Tester groups span every app on the team; keep this app's
+        let names: Vec<&str> = groups
+            .iter()
+            .filter(|g| tester.group_ids.contains(&g.id))
+            .map(|g| g.name.as_str())
+            .collect();
+        writer.write_record([
+            tester.first_name.as_str(),
+            tester.last_name.as_str(),
+            tester.email.as_str(),
+            &names.join(";"),
+            tester.state.as_deref().unwrap_or_default(),
+        ])?;
+        count += 1;
+    }
+    writer.flush()?;
+
+    if let Some(path) = path {
+        ui::success(&format!(
+            "Exported {} tester{} to {}",
+            count,
+            if count == 1 { "" } else { "s" },
+            path.display()
+        ));
+    }
+    Ok(())
+}
+
+/// Parse the import file. Rows are `first,last,email[,groups]` like pilot's; a
+/// header row is skipped, and rows without groups get `default_groups`.
+fn read_rows(path: &Path, default_groups: &[String]) -> Result<Vec<Row>, TestersError> {
+    let mut reader = csv::ReaderBuilder::new()
+        .has_headers(false)
+        .flexible(true)
+        .trim(csv::Trim::All)
+        .from_path(path)?;
+
+    let mut rows = Vec::new();
+    for record in reader.records() {
+        let record = record?;
+        let line = record.position().map(|p| p.line()).unwrap_or_default();
+        let invalid = |message: &str| TestersError::InvalidRow {
+            path: path.to_path_buf(),
+            line,
+            message: message.to_string(),
+        };
+
+        if record.iter().all(str::is_empty) {
+            continue;
+        }
+        let email = record.get(2).unwrap_or_default();
+        if !email.contains('@') {
+            if rows.is_empty() && line == 1 {
+                continue;
+            }
+            return Err(invalid("expected first name, last name, email[, groups]"));
+        }
+
+        let mut groups: Vec<String> = record
+            .get(3)
+            .unwrap_or_default()
+            .split(';')
+            .map(str::trim)
+            .filter(|g| !g.is_empty())
+            .map(str::to_string)
+            .collect();
+        if groups.is_empty() {
+            groups = default_groups.to_vec();
+        }
+        if groups.is_empty() {
+            return Err(invalid("no group; add one to the row or pass --group"));
+        }
+
+        rows.push(Row {
+            line,
+            first_name: record.get(0).unwrap_or_default().to_string(),
+            last_name: record.get(1).unwrap_or_default().to_string(),
+            email: email.to_string(),
+            groups,
+        });
+    }
+
+    if rows.is_empty() {
+        return Err(TestersError::Empty(path.to_path_buf()));
+    }
+    Ok(rows)
+}
+
+/// Group IDs by name; only external groups take testers by email
+fn resolve_external<'a>(
+    groups: &[BetaGroup],
+    names: &[&'a str],
+) -> Result<HashMap<&'a str, String>, TestersError> {
+    names
+        .iter()
+        .map(|&name| {
+            let group = groups
+                .iter()
+                .find(|g| g.name == name)
+                .ok_or_else(|| TestersError::GroupNotFound(name.to_string()))?;
+            if group.is_internal {
+                return Err(TestersError::InternalGroup(name.to_string()));
+            }
+            Ok((name, group.id.clone()))
+        })
+        .collect()
+}
+
+async fn add_to_groups(
+    client: &AscClient,
+    tester: &BetaTester,
+    group_ids: &[&String],
+) -> Result<(), AscError> {
+    for group_id in group_ids {
+        client.add_tester_to_group(group_id, &tester.id).await?;
+    }
+    Ok(())
+}
+
+fn group_names<'a>(groups: &[BetaGroup], ids: impl Iterator<Item = &'a String>) -> String {
+    ids.filter_map(|id| groups.iter().find(|g| g.id == *id))
+        .map(|g| g.name.as_str())
+        .collect::<Vec<_>>()
+        .join(", ")
+}
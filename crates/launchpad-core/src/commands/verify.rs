@@ -0,0 +1,117 @@
+use crate::asc::{AscClient, AscError};
+use crate::config;
+use crate::config::global::GlobalConfig;
+use crate::config::project::ProjectConfig;
+use crate::history;
+use crate::testflight;
+use crate::ui;
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+pub enum VerifyError {
+    #[error("Global config not found. Run 'launchpad setup' first.")]
+    NoGlobalConfig,
+
+    #[error("Project config not found. Run 'launchpad init' first, or pass --bundle-id.")]
+    NoProjectConfig,
+
+    #[error("No deploy of {0} in the history. Pass --build.")]
+    NoBuild(String),
+
+    #[error("App Store Connect has no build {0}. The upload didn't go through.")]
+    NotReceived(String),
+
+    #[error("Build {0} was received but failed processing on App Store Connect")]
+    BuildFailed(String),
+
+    #[error("{0}")]
+    Asc(#[from] AscError),
+
+    #[error("Config error: {0}")]
+    Config(String),
+}
+
+/// Confirm that App Store Connect has an uploaded build, catching uploads that
+/// fastlane reported but that never arrived. Defaults to the last iOS deploy.
+pub async fn run(
+    build: Option<String>,
+    version: Option<String>,
+    bundle_id: Option<String>,
+    wait: bool,
+) -> Result<(), VerifyError> {
+    ui::header("Launchpad Verify");
+
+    let mut global_config = GlobalConfig::load()
+        .map_err(|e| VerifyError::Config(e.to_string()))?
+        .ok_or(VerifyError::NoGlobalConfig)?;
+    let mut project_config =
+        ProjectConfig::load().map_err(|e| VerifyError::Config(e.to_string()))?;
+    config::use_selected_environment(&mut global_config, project_config.as_mut())
+        .map_err(|e| VerifyError::Config(e.to_string()))?;
+    let bundle_id = match (bundle_id, &project_config) {
+        (Some(bundle_id), _) => bundle_id,
+        (None, Some(config)) => config.project.bundle_id.clone(),
+        (None, None) => return Err(VerifyError::NoProjectConfig),
+    };
+
+    let (number, version) = match build {
+        Some(number) => (number, version),
+        None => {
+            let records = history::load().map_err(|e| VerifyError::Config(e.to_string()))?;
+            let record = records
+                .iter()
+                .rev()
+                .find(|r| r.bundle_id == bundle_id && r.platform == "ios")
+                .ok_or_else(|| VerifyError::NoBuild(bundle_id.clone()))?;
+            (record.build.clone(), Some(record.version.clone()))
+        }
+    };
+    let label = match &version {
+        Some(version) => format!("{} ({})", version, number),
+        None => number.clone(),
+    };
+
+    let client = AscClient::new(&global_config.apple)?;
+    let app_id = client
+        .project_app_id(&bundle_id, project_config.as_ref().map(|c| &c.project))
+        .await?;
+
+    let found = if wait {
+        let spinner = ui::spinner(&format!(
+            "Waiting for App Store Connect to list {}...",
+            label
+        ));
+        let result =
+            testflight::wait_for_upload(&client, &app_id, &number, version.as_deref()).await;
+        spinner.finish_and_clear();
+        result?
+    } else {
+        client
+            .find_build(&app_id, &number, version.as_deref())
+            .await?
+    };
+    let build = found.ok_or(VerifyError::NotReceived(label.clone()))?;
+
+    if build.is_failed() {
+        return Err(VerifyError::BuildFailed(label));
+    }
+    ui::success(&format!(
+        "App Store Connect received {}{}",
+        label,
+        build
+            .uploaded_date
+            .as_deref()
+            .map(|date| format!(" (uploaded {})", date))
+            .unwrap_or_default()
+    ));
+    ui::field(
+        "TestFlight",
+        if build.is_valid() {
+            "Processed"
+        } else {
+            "Processing (usually 10-30 minutes)"
+        },
+    );
+
+    Ok(())
+}
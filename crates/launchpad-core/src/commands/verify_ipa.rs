@@ -0,0 +1,258 @@
+use crate::artifact;
+use crate::commands::deploy::Platform;
+use crate::commands::profiles::DEFAULT_WARN_DAYS;
+use crate::history;
+use crate::ipa;
+use crate::profiles;
+use crate::ui;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use thiserror::Error;
+
+/// Root every App Store signing chain ends in
+const APPLE_ROOT_CA: &str = "Apple Root CA";
+
+#[derive(Error, Debug)]
+pub enum VerifyIpaError {
+    #[error("IPA not found: {0}")]
+    IpaNotFound(PathBuf),
+
+    #[error("No app found in {0}")]
+    InvalidIpa(PathBuf),
+
+    #[error("{count} check{} failed; don't upload this .ipa", if *.count == 1 { "" } else { "s" })]
+    Failed { count: usize },
+}
+
+/// What the .ipa's Info.plist says it is
+struct AppInfo {
+    bundle_id: String,
+    version: String,
+    build: String,
+}
+
+/// Check an .ipa before it's uploaded: the code signature and its certificate
+/// chain, the embedded provisioning profile, and the file's checksum against the
+/// one recorded in the deploy history
+pub async fn run(ipa: PathBuf) -> Result<(), VerifyIpaError> {
+    ui::header("Verify IPA");
+
+    if !ipa.is_file() {
+        return Err(VerifyIpaError::IpaNotFound(ipa));
+    }
+
+    let work = std::env::temp_dir().join(format!("launchpad-verify-ipa-{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&work);
+    let result = verify(&ipa, &work);
+    let _ = std::fs::remove_dir_all(&work);
+    let failed = result?;
+
+    ui::blank();
+    if failed > 0 {
+        return Err(VerifyIpaError::Failed { count: failed });
+    }
+    ui::success(&format!("{} checks out", ipa.display()));
+    Ok(())
+}
+
+/// Run every check; returns how many failed
+fn verify(ipa: &Path, work: &Path) -> Result<usize, VerifyIpaError> {
+    let spinner = ui::spinner("Extracting...");
+    let app = ipa::extract_app(ipa, work);
+    spinner.finish_and_clear();
+    let app = app.ok_or_else(|| VerifyIpaError::InvalidIpa(ipa.to_path_buf()))?;
+    let info = app_info(&app).ok_or_else(|| VerifyIpaError::InvalidIpa(ipa.to_path_buf()))?;
+    let checksum = artifact::sha256(ipa);
+
+    ui::field("App", &info.bundle_id);
+    ui::field("Version", &format!("{} ({})", info.version, info.build));
+    if let Some(checksum) = &checksum {
+        ui::field("SHA-256", checksum);
+    }
+    ui::blank();
+
+    let checks = [
+        check_signature(&app),
+        check_profile(&app, &info.bundle_id),
+        check_checksum(checksum.as_deref(), &info),
+    ];
+    Ok(checks.into_iter().filter(|passed| !passed).count())
+}
+
+fn app_info(app: &Path) -> Option<AppInfo> {
+    let info = plist::Value::from_file(app.join("Info.plist")).ok()?;
+    let info = info.as_dictionary()?;
+    let string = |key: &str| {
+        info.get(key)
+            .and_then(|value| value.as_string())
+            .map(str::to_string)
+    };
+    Some(AppInfo {
+        bundle_id: string("CFBundleIdentifier")?,
+        version: string("CFBundleShortVersionString")?,
+        build: string("CFBundleVersion")?,
+    })
+}
+
+/// `codesign --verify` over the app and everything nested in it, then the
+/// certificate chain it was signed with (macOS only)
+fn check_signature(app: &Path) -> bool {
+    if which::which("codesign").is_err() {
+        ui::check_warn(
+            "Signature",
+            "codesign not found; signatures can only be checked on macOS",
+        );
+        return true;
+    }
+
+    let verified = Command::new("codesign")
+        .args(["--verify", "--deep", "--strict", "--verbose=2"])
+        .arg(app)
+        .output();
+    match verified {
+        Ok(output) if output.status.success() => {}
+        Ok(output) => {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            let reason = stderr.lines().last().unwrap_or("invalid signature");
+            ui::check_fail("Signature", reason.trim());
+            return false;
+        }
+        Err(e) => {
+            ui::check_fail("Signature", &e.to_string());
+            return false;
+        }
+    }
+
+    // `codesign -d` writes "Authority=..." lines, leaf certificate first
+    let details = Command::new("codesign")
+        .args(["-d", "--verbose=2"])
+        .arg(app)
+        .output()
+        .map(|output| String::from_utf8_lossy(&output.stderr).to_string())
+        .unwrap_or_default();
+    let chain: Vec<&str> = details
+        .lines()
+        .filter_map(|line| line.strip_prefix("Authority="))
+        .collect();
+    let Some(leaf) = chain.first() else {
+        ui::check_fail("Signature", "valid, but ad-hoc signed (no certificate)");
+        return false;
+    };
+    let chain_label = chain.join(&format!(" {} ", ui::arrow()));
+    if chain.last() != Some(&APPLE_ROOT_CA) {
+        ui::check_fail(
+            "Signature",
+            &format!("chain doesn't end in {}: {}", APPLE_ROOT_CA, chain_label),
+        );
+        return false;
+    }
+
+    ui::check_pass("Signature", &chain_label);
+    if leaf.starts_with("Apple Development") || leaf.starts_with("iPhone Developer") {
+        ui::check_warn(
+            "Certificate",
+            "development certificate; App Store Connect only takes distribution builds",
+        );
+    }
+    true
+}
+
+/// The embedded profile is readable, current and made for the app
+fn check_profile(app: &Path, bundle_id: &str) -> bool {
+    let path = app.join("embedded.mobileprovision");
+    if !path.is_file() {
+        ui::check_fail("Profile", "no embedded.mobileprovision");
+        return false;
+    }
+    let Some(profile) = profiles::read(&path) else {
+        ui::check_fail("Profile", "embedded.mobileprovision can't be read");
+        return false;
+    };
+
+    if !profile.covers(bundle_id) {
+        ui::check_fail(
+            "Profile",
+            &format!(
+                "'{}' is for {}, but the app is {}",
+                profile.name, profile.bundle_id, bundle_id
+            ),
+        );
+        return false;
+    }
+    let expiry = profile
+        .expiry_date()
+        .unwrap_or_else(|| "unknown".to_string());
+    match profile.days_left() {
+        Some(days) if days < 0 => {
+            ui::check_fail(
+                "Profile",
+                &format!("'{}' expired on {}", profile.name, expiry),
+            );
+            return false;
+        }
+        Some(days) if days < DEFAULT_WARN_DAYS => ui::check_warn(
+            "Profile",
+            &format!(
+                "'{}' expires in {} day{} ({})",
+                profile.name,
+                days,
+                if days == 1 { "" } else { "s" },
+                expiry
+            ),
+        ),
+        _ => ui::check_pass(
+            "Profile",
+            &format!("'{}', expires {}", profile.name, expiry),
+        ),
+    }
+    true
+}
+
+/// The .ipa is byte for byte the one the deploy history recorded for its build
+fn check_checksum(checksum: Option<&str>, info: &AppInfo) -> bool {
+    let Some(checksum) = checksum else {
+        ui::check_fail("Checksum", "the .ipa can't be read");
+        return false;
+    };
+    let records = match history::load() {
+        Ok(records) => records,
+        Err(e) => {
+            ui::check_warn("Checksum", &format!("deploy history unreadable: {}", e));
+            return true;
+        }
+    };
+    let Some(record) = history::find(&records, &info.bundle_id, Platform::Ios.name(), &info.build)
+    else {
+        ui::check_warn(
+            "Checksum",
+            &format!("build {} isn't in the deploy history", info.build),
+        );
+        return true;
+    };
+
+    let recorded = history::age(record.deployed_at);
+    match &record.sha256 {
+        None => ui::check_warn(
+            "Checksum",
+            &format!(
+                "build {} was recorded {} without a checksum",
+                info.build, recorded
+            ),
+        ),
+        Some(expected) if expected == checksum => ui::check_pass(
+            "Checksum",
+            &format!("matches build {} recorded {}", info.build, recorded),
+        ),
+        Some(_) => {
+            ui::check_fail(
+                "Checksum",
+                &format!(
+                    "differs from build {} recorded {}; this isn't the .ipa that was built",
+                    info.build, recorded
+                ),
+            );
+            return false;
+        }
+    }
+    true
+}
@@ -0,0 +1,297 @@
+use crate::commands::deploy::Platform;
+use crate::config::global::GlobalConfig;
+use crate::files;
+use crate::git::{self, GitError};
+use crate::ui;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use thiserror::Error;
+
+const STATE_FILENAME: &str = "watch.json";
+
+#[derive(Error, Debug)]
+pub enum WatchError {
+    #[error("Not inside a git repository")]
+    NotARepo,
+
+    #[error("Could not determine the launchpad config directory")]
+    NoConfigDir,
+
+    #[error("{0}")]
+    Git(#[from] GitError),
+
+    #[error("IO error: {0}")]
+    Io(#[from] std::io::Error),
+
+    #[error("Corrupt watch state: {0}")]
+    State(#[from] serde_json::Error),
+}
+
+/// Revisions already handled, per repository and watched ref, in ~/.launchpad/watch.json
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct WatchState {
+    /// "<repo root>#tags:<pattern>" or "<repo root>#branch:<name>" → tag names or commit SHAs
+    #[serde(default)]
+    seen: BTreeMap<String, Vec<String>>,
+}
+
+/// What the daemon deploys
+enum Trigger {
+    /// Every new tag matching a glob
+    Tags(String),
+    /// Every new commit at the tip of a remote branch
+    Branch(String),
+}
+
+impl std::fmt::Display for Trigger {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Trigger::Tags(pattern) => write!(f, "tags:{}", pattern),
+            Trigger::Branch(branch) => write!(f, "branch:{}", branch),
+        }
+    }
+}
+
+/// One deployable revision: `label` for humans and the state file, `rev` for git
+struct Candidate {
+    label: String,
+    rev: String,
+}
+
+/// Poll the remote and deploy each new release tag (or branch commit) from a
+/// throwaway worktree, so the checkout `watch` runs in is never modified
+pub async fn run(
+    pattern: String,
+    branch: Option<String>,
+    remote: String,
+    interval: u64,
+    platform: Platform,
+    once: bool,
+    deploy_existing: bool,
+) -> Result<(), WatchError> {
+    ui::header("Watch");
+
+    let root =
+        PathBuf::from(git(&["rev-parse", "--show-toplevel"]).map_err(|_| WatchError::NotARepo)?);
+    // Where .launchpad.toml lives relative to the repository root
+    let prefix = git(&["rev-parse", "--show-prefix"])?;
+
+    let trigger = match branch {
+        Some(branch) => Trigger::Branch(branch),
+        None => Trigger::Tags(pattern),
+    };
+    let key = format!("{}#{}", root.display(), trigger);
+    // Where deploys push their version bump: the watched branch, or the remote's default
+    let bump_branch = match &trigger {
+        Trigger::Branch(branch) => Some(branch.clone()),
+        Trigger::Tags(_) => default_branch(&remote),
+    };
+
+    // Worktrees left behind by an interrupted deploy
+    let _ = git(&["worktree", "prune"]);
+
+    ui::field("Repository", &root.display().to_string());
+    ui::field("Watching", &format!("{} on {}", trigger, remote));
+    ui::field("Interval", &format!("{}s", interval));
+    ui::blank();
+
+    let mut first = !load_state()?.seen.contains_key(&key);
+    loop {
+        match poll(&trigger, &remote) {
+            Ok(candidates) => {
+                let mut state = load_state()?;
+                let seen = state.seen.entry(key.clone()).or_default();
+
+                // Without history, only what appears from now on is deployed
+                if first && !deploy_existing {
+                    seen.extend(candidates.iter().map(|c| c.label.clone()));
+                    save_state(&state)?;
+                    ui::success(&format!(
+                        "Tracking {} existing revision{}; waiting for new ones",
+                        candidates.len(),
+                        if candidates.len() == 1 { "" } else { "s" }
+                    ));
+                } else {
+                    let new: Vec<Candidate> = candidates
+                        .into_iter()
+                        .filter(|c| !seen.contains(&c.label))
+                        .collect();
+                    for candidate in new {
+                        let bump =
+                            deploy(&root, &prefix, &candidate, bump_branch.as_deref(), platform)
+                                .await;
+
+                        // Failed deploys aren't retried every interval; push a new tag instead.
+                        // The version bump commit a deploy pushed isn't deployed again either.
+                        let mut state = load_state()?;
+                        let seen = state.seen.entry(key.clone()).or_default();
+                        seen.push(candidate.label);
+                        seen.extend(bump);
+                        save_state(&state)?;
+                    }
+                }
+                first = false;
+            }
+            Err(e) => ui::warn(&format!("Poll failed: {}", e)),
+        }
+
+        if once {
+            return Ok(());
+        }
+        tokio::select! {
+            _ = tokio::time::sleep(Duration::from_secs(interval)) => {}
+            _ = tokio::signal::ctrl_c() => {
+                ui::blank();
+                ui::success("Stopped watching");
+                return Ok(());
+            }
+        }
+    }
+}
+
+/// Fetch and list the revisions the trigger currently points at, oldest first
+fn poll(trigger: &Trigger, remote: &str) -> Result<Vec<Candidate>, WatchError> {
+    match trigger {
+        Trigger::Tags(pattern) => {
+            git(&["fetch", "--quiet", "--tags", "--force", remote])?;
+            let tags = git(&["tag", "--list", pattern, "--sort=creatordate"])?;
+            // The release tags deploys push would each start another deploy
+            Ok(tags
+                .lines()
+                .filter(|tag| !tag.is_empty() && !Platform::is_release_tag(tag))
+                .map(|tag| Candidate {
+                    label: tag.to_string(),
+                    rev: format!("refs/tags/{}", tag),
+                })
+                .collect())
+        }
+        Trigger::Branch(branch) => {
+            git(&["fetch", "--quiet", remote, branch])?;
+            let sha = git(&["rev-parse", "FETCH_HEAD"])?;
+            Ok(vec![Candidate {
+                label: sha.clone(),
+                rev: sha,
+            }])
+        }
+    }
+}
+
+/// The branch `<remote>/HEAD` points at, e.g. "main"
+fn default_branch(remote: &str) -> Option<String> {
+    let head = git(&[
+        "symbolic-ref",
+        "--short",
+        &format!("refs/remotes/{}/HEAD", remote),
+    ])
+    .ok()?;
+    head.strip_prefix(&format!("{}/", remote))
+        .map(str::to_string)
+}
+
+/// Check the revision out into a temporary worktree and run `launchpad deploy` there.
+/// Returns the version bump commit the deploy made on top of it, if any.
+async fn deploy(
+    root: &Path,
+    prefix: &str,
+    candidate: &Candidate,
+    bump_branch: Option<&str>,
+    platform: Platform,
+) -> Option<String> {
+    ui::header(&format!("Deploying {}", candidate.label));
+
+    let worktree = std::env::temp_dir().join(format!("launchpad-watch-{}", std::process::id()));
+    let worktree_arg = worktree.to_string_lossy().to_string();
+    if let Err(e) = git(&[
+        "worktree",
+        "add",
+        "--force",
+        "--detach",
+        &worktree_arg,
+        &candidate.rev,
+    ]) {
+        ui::error(&format!("Could not check out {}: {}", candidate.label, e));
+        return None;
+    }
+    let checked_out = git(&["-C", &worktree_arg, "rev-parse", "HEAD"]).ok();
+
+    let project_dir = worktree.join(prefix);
+    // An untracked .launchpad.toml only exists in the main checkout
+    let config = root.join(prefix).join(".launchpad.toml");
+    if config.exists() && !project_dir.join(".launchpad.toml").exists() {
+        if let Err(e) = std::fs::copy(&config, project_dir.join(".launchpad.toml")) {
+            ui::warn(&format!("Could not copy .launchpad.toml: {}", e));
+        }
+    }
+
+    let status = match std::env::current_exe() {
+        Ok(launchpad) => {
+            let mut cmd = tokio::process::Command::new(launchpad);
+            cmd.args([
+                "deploy",
+                "--yes",
+                "--skip-git-check",
+                "--platform",
+                platform.name(),
+            ])
+            .current_dir(&project_dir)
+            .env("LAUNCHPAD_WATCH_REF", &candidate.label);
+            // The worktree is detached; the bump commit goes back to this branch
+            if let Some(branch) = bump_branch {
+                cmd.env("LAUNCHPAD_WATCH_BRANCH", branch);
+            }
+            cmd.status().await
+        }
+        Err(e) => Err(e),
+    };
+
+    match status {
+        Ok(status) if status.success() => ui::success(&format!("Deployed {}", candidate.label)),
+        Ok(status) => ui::error(&format!(
+            "Deploy of {} failed ({})",
+            candidate.label, status
+        )),
+        Err(e) => ui::error(&format!("Could not run deploy: {}", e)),
+    }
+
+    let head = git(&["-C", &worktree_arg, "rev-parse", "HEAD"]).ok();
+    let bump = head.filter(|head| checked_out.as_ref() != Some(head));
+
+    if let Err(e) = git(&["worktree", "remove", "--force", &worktree_arg]) {
+        ui::warn(&format!(
+            "Could not remove worktree {}: {}",
+            worktree.display(),
+            e
+        ));
+    }
+    bump
+}
+
+fn git(args: &[&str]) -> Result<String, WatchError> {
+    Ok(git::output(args)?.trim().to_string())
+}
+
+fn state_path() -> Result<PathBuf, WatchError> {
+    GlobalConfig::config_dir()
+        .map(|dir| dir.join(STATE_FILENAME))
+        .ok_or(WatchError::NoConfigDir)
+}
+
+fn load_state() -> Result<WatchState, WatchError> {
+    let state = files::read_recovering(&state_path()?, |content| {
+        serde_json::from_str(content).map_err(WatchError::from)
+    })?;
+    Ok(state.unwrap_or_default())
+}
+
+/// Replaced atomically, so a daemon killed mid-write doesn't forget what it deployed
+fn save_state(state: &WatchState) -> Result<(), WatchError> {
+    let path = state_path()?;
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    let content = serde_json::to_string_pretty(state)? + "\n";
+    files::write_atomic(&path, content.as_bytes())?;
+    Ok(())
+}
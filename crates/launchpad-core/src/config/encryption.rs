@@ -0,0 +1,320 @@
+use crate::files;
+use age::secrecy::SecretString;
+use std::collections::HashMap;
+use std::io::{IsTerminal, Write};
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::sync::Mutex;
+use thiserror::Error;
+
+// Optional at-rest encryption of ~/.launchpad. Encrypted files sit where the
+// plaintext would be, with an `.age` suffix. The passphrase comes from
+// LAUNCHPAD_PASSPHRASE, the macOS keychain, or a prompt, at most once per run.
+
+/// Keychain item holding a generated passphrase (`setup encrypt --keychain`)
+const KEYCHAIN_SERVICE: &str = "launchpad";
+const KEYCHAIN_ACCOUNT: &str = "config-encryption";
+
+pub const PASSPHRASE_ENV: &str = "LAUNCHPAD_PASSPHRASE";
+
+static PASSPHRASE: Mutex<Option<String>> = Mutex::new(None);
+
+/// Decrypted key copies for this run, removed with their directory on drop
+static RUNTIME_KEYS: Mutex<Option<RuntimeKeys>> = Mutex::new(None);
+
+struct RuntimeKeys {
+    /// Private (0700), unpredictably named directory under the temp dir
+    dir: PathBuf,
+    /// Encrypted key → decrypted copy
+    copies: HashMap<PathBuf, PathBuf>,
+}
+
+impl Drop for RuntimeKeys {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_dir_all(&self.dir);
+    }
+}
+
+/// Removes the decrypted key copies when dropped, however the command ends
+pub struct KeyCleanup(());
+
+impl Drop for KeyCleanup {
+    fn drop(&mut self) {
+        cleanup();
+    }
+}
+
+#[derive(Error, Debug)]
+pub enum EncryptionError {
+    #[error("Launchpad config is encrypted: set {PASSPHRASE_ENV} or run interactively")]
+    NoPassphrase,
+
+    #[error("Could not decrypt {path}: {message}")]
+    Decrypt { path: String, message: String },
+
+    #[error("Could not encrypt {path}: {message}")]
+    Encrypt { path: String, message: String },
+
+    #[error("Keychain error: {0}")]
+    Keychain(String),
+
+    #[error("IO error: {0}")]
+    Io(#[from] std::io::Error),
+}
+
+/// Where the encrypted form of `path` lives
+pub fn encrypted_path(path: &Path) -> PathBuf {
+    let mut name = path.as_os_str().to_owned();
+    name.push(".age");
+    PathBuf::from(name)
+}
+
+/// Read an encrypted file given its plaintext path
+pub fn read(path: &Path) -> Result<Vec<u8>, EncryptionError> {
+    decrypt(&encrypted_path(path), passphrase()?).inspect_err(|_| {
+        // A wrong passphrase shouldn't stick for the rest of the run
+        forget_passphrase();
+    })
+}
+
+/// Encrypt `plaintext` to the `.age` file for `path`
+pub fn write(path: &Path, plaintext: &[u8]) -> Result<(), EncryptionError> {
+    encrypt(&encrypted_path(path), plaintext, passphrase()?)
+}
+
+/// Decrypt a file with an explicit passphrase (onboarding bundles)
+pub fn read_with(path: &Path, passphrase: &str) -> Result<Vec<u8>, EncryptionError> {
+    decrypt(path, SecretString::from(passphrase.to_string()))
+}
+
+/// Encrypt to `path` with an explicit passphrase (onboarding bundles)
+pub fn write_with(path: &Path, plaintext: &[u8], passphrase: &str) -> Result<(), EncryptionError> {
+    encrypt(path, plaintext, SecretString::from(passphrase.to_string()))
+}
+
+fn decrypt(path: &Path, passphrase: SecretString) -> Result<Vec<u8>, EncryptionError> {
+    let ciphertext = std::fs::read(path)?;
+    let identity = age::scrypt::Identity::new(passphrase);
+
+    age::decrypt(&identity, &ciphertext).map_err(|e| EncryptionError::Decrypt {
+        path: path.display().to_string(),
+        message: e.to_string(),
+    })
+}
+
+fn encrypt(path: &Path, plaintext: &[u8], passphrase: SecretString) -> Result<(), EncryptionError> {
+    let recipient = age::scrypt::Recipient::new(passphrase);
+
+    let ciphertext = age::encrypt(&recipient, plaintext).map_err(|e| EncryptionError::Encrypt {
+        path: path.display().to_string(),
+        message: e.to_string(),
+    })?;
+    write_private(path, &ciphertext)?;
+    Ok(())
+}
+
+/// Path to a usable key file: the plaintext one if present, otherwise a
+/// decrypted copy (mode 0600) that lives until [`cleanup`]
+pub fn resolve_key_path(key_path: &str) -> Result<String, EncryptionError> {
+    let path = PathBuf::from(shellexpand::tilde(key_path).as_ref());
+    if path.exists() || !encrypted_path(&path).exists() {
+        return Ok(key_path.to_string());
+    }
+
+    let mut keys = RUNTIME_KEYS.lock().unwrap_or_else(|e| e.into_inner());
+    if let Some(copy) = keys.as_ref().and_then(|keys| keys.copies.get(&path)) {
+        return Ok(copy.to_string_lossy().to_string());
+    }
+
+    let plaintext = read(&path)?;
+    if keys.is_none() {
+        *keys = Some(RuntimeKeys {
+            dir: create_runtime_dir()?,
+            copies: HashMap::new(),
+        });
+        remove_on_signal();
+    }
+    let keys = keys
+        .as_mut()
+        .expect("runtime key directory was just created");
+    let copy = keys.dir.join(path.file_name().unwrap_or_default());
+    write_private(&copy, &plaintext)?;
+
+    keys.copies.insert(path, copy.clone());
+    Ok(copy.to_string_lossy().to_string())
+}
+
+/// Remove decrypted key copies made during this run
+pub fn cleanup() {
+    drop(
+        RUNTIME_KEYS
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .take(),
+    );
+}
+
+/// Hold for the whole command: decrypted key copies are removed when it's
+/// dropped, including on an early return or a panic
+pub fn cleanup_on_exit() -> KeyCleanup {
+    KeyCleanup(())
+}
+
+/// Ctrl-C and SIGTERM end the process without running destructors, so the
+/// copies are removed there too before exiting with the usual 128 + signal code
+fn remove_on_signal() {
+    let Ok(runtime) = tokio::runtime::Handle::try_current() else {
+        return;
+    };
+    runtime.spawn(async {
+        let code = interrupted().await;
+        cleanup();
+        std::process::exit(code);
+    });
+}
+
+async fn interrupted() -> i32 {
+    #[cfg(unix)]
+    let terminate = async {
+        use tokio::signal::unix::{signal, SignalKind};
+        match signal(SignalKind::terminate()) {
+            Ok(mut terminate) => {
+                terminate.recv().await;
+            }
+            Err(_) => std::future::pending::<()>().await,
+        }
+    };
+    #[cfg(not(unix))]
+    let terminate = std::future::pending::<()>();
+
+    tokio::select! {
+        Ok(()) = tokio::signal::ctrl_c() => 130,
+        () = terminate => 143,
+    }
+}
+
+/// Use this passphrase for the rest of the run (encrypting for the first time)
+pub fn set_passphrase(passphrase: String) {
+    *PASSPHRASE.lock().unwrap_or_else(|e| e.into_inner()) = Some(passphrase);
+}
+
+fn forget_passphrase() {
+    *PASSPHRASE.lock().unwrap_or_else(|e| e.into_inner()) = None;
+}
+
+/// Env var, then keychain, then a prompt
+fn passphrase() -> Result<SecretString, EncryptionError> {
+    let mut cached = PASSPHRASE.lock().unwrap_or_else(|e| e.into_inner());
+    if cached.is_none() {
+        *cached = std::env::var(PASSPHRASE_ENV)
+            .ok()
+            .or_else(keychain_passphrase)
+            .or_else(prompt_passphrase);
+    }
+
+    cached
+        .clone()
+        .map(SecretString::from)
+        .ok_or(EncryptionError::NoPassphrase)
+}
+
+fn prompt_passphrase() -> Option<String> {
+    let non_interactive = std::env::var("LAUNCHPAD_NON_INTERACTIVE").is_ok_and(|v| v == "1");
+    if non_interactive || !std::io::stdin().is_terminal() {
+        return None;
+    }
+
+    dialoguer::Password::new()
+        .with_prompt("Launchpad config passphrase")
+        .interact()
+        .ok()
+}
+
+fn keychain_passphrase() -> Option<String> {
+    let output = Command::new("security")
+        .args(["find-generic-password", "-s", KEYCHAIN_SERVICE])
+        .args(["-a", KEYCHAIN_ACCOUNT, "-w"])
+        .output()
+        .ok()?;
+    if !output.status.success() {
+        return None;
+    }
+
+    let secret = String::from_utf8(output.stdout).ok()?;
+    Some(secret.trim_end().to_string()).filter(|s| !s.is_empty())
+}
+
+/// Generate a random passphrase and keep it in the login keychain
+pub fn store_keychain_passphrase() -> Result<String, EncryptionError> {
+    let secret = random_hex(32)?;
+
+    // Given to `security -i` on stdin: as an argument it would show up in `ps`
+    let mut child = Command::new("security")
+        .arg("-i")
+        .stdin(Stdio::piped())
+        .stdout(Stdio::null())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|e| EncryptionError::Keychain(e.to_string()))?;
+    let command = format!(
+        "add-generic-password -U -s {} -a {} -w {}\n",
+        KEYCHAIN_SERVICE, KEYCHAIN_ACCOUNT, secret
+    );
+    if let Some(mut stdin) = child.stdin.take() {
+        stdin.write_all(command.as_bytes())?;
+    }
+    let output = child
+        .wait_with_output()
+        .map_err(|e| EncryptionError::Keychain(e.to_string()))?;
+
+    // Interactive mode exits 0 even when a command fails, so read it back
+    if !output.status.success() || keychain_passphrase().as_deref() != Some(secret.as_str()) {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(EncryptionError::Keychain(format!(
+            "security add-generic-password failed: {}",
+            stderr.trim()
+        )));
+    }
+
+    Ok(secret)
+}
+
+/// Drop the generated passphrase (after decrypting for good); missing is fine
+pub fn delete_keychain_passphrase() {
+    let _ = Command::new("security")
+        .args(["delete-generic-password", "-s", KEYCHAIN_SERVICE])
+        .args(["-a", KEYCHAIN_ACCOUNT])
+        .output();
+}
+
+fn random_hex(len: usize) -> Result<String, std::io::Error> {
+    let mut bytes = vec![0u8; len];
+    std::io::Read::read_exact(&mut std::fs::File::open("/dev/urandom")?, &mut bytes)?;
+    Ok(bytes.iter().map(|b| format!("{:02x}", b)).collect())
+}
+
+/// A fresh 0700 directory with an unguessable name. Created exclusively, so
+/// an existing path (another user's directory or symlink) is never reused.
+fn create_runtime_dir() -> Result<PathBuf, std::io::Error> {
+    let mut builder = std::fs::DirBuilder::new();
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::DirBuilderExt;
+        builder.mode(0o700);
+    }
+
+    loop {
+        let dir = std::env::temp_dir().join(format!("launchpad-{}", random_hex(8)?));
+        match builder.create(&dir) {
+            Ok(()) => return Ok(dir),
+            Err(e) if e.kind() == std::io::ErrorKind::AlreadyExists => continue,
+            Err(e) => return Err(e),
+        }
+    }
+}
+
+/// Write a file only the current user can read (created 0600, never world-readable),
+/// replacing any existing one atomically
+pub fn write_private(path: &Path, content: &[u8]) -> Result<(), std::io::Error> {
+    files::replace(path, content, Some(0o600))
+}
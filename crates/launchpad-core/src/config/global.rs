@@ -0,0 +1,523 @@
+use super::encryption::{self, EncryptionError};
+use super::project::NotificationEvent;
+use crate::files;
+use crate::http;
+use crate::mock;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+pub enum ConfigError {
+    #[error("IO error: {0}")]
+    Io(#[from] std::io::Error),
+
+    #[error("TOML parse error: {0}")]
+    TomlParse(#[from] toml::de::Error),
+
+    #[error("TOML serialize error: {0}")]
+    TomlSerialize(#[from] toml::ser::Error),
+
+    #[error("Could not determine config directory")]
+    NoConfigDir,
+
+    #[error("No [environments.{name}] in .launchpad.toml (defined: {known})")]
+    UnknownEnvironment { name: String, known: String },
+
+    #[error("No [key_profiles.{name}] in ~/.launchpad/config.toml (defined: {known})")]
+    UnknownKeyProfile { name: String, known: String },
+
+    #[error("{0}")]
+    Encryption(#[from] EncryptionError),
+}
+
+/// `~/.launchpad/config.toml`: the App Store Connect key and the settings shared
+/// by every project on the machine
+#[derive(Debug, Serialize, Deserialize)]
+pub struct GlobalConfig {
+    pub apple: AppleConfig,
+
+    /// App Store Connect keys of other Apple accounts, used by environments that
+    /// name them in `[environments.<name>.apple] key_profile`
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub key_profiles: BTreeMap<String, AppleConfig>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub google_play: Option<GooglePlayConfig>,
+
+    #[serde(default, skip_serializing_if = "UiConfig::is_default")]
+    pub ui: UiConfig,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub email: Option<EmailSettings>,
+
+    #[serde(default, skip_serializing_if = "NetworkConfig::is_default")]
+    pub network: NetworkConfig,
+
+    #[serde(default, skip_serializing_if = "HistoryConfig::is_default")]
+    pub history: HistoryConfig,
+}
+
+/// Just the `[key_profiles]` of config.toml, for when APPLE_API_* replace `[apple]`
+#[derive(Deserialize)]
+struct KeyProfilesFile {
+    #[serde(default)]
+    key_profiles: BTreeMap<String, AppleConfig>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct AppleConfig {
+    pub key_id: String,
+    pub issuer_id: String,
+    pub key_path: String,
+
+    /// Sales and Trends vendor number, for the installs in `launchpad analytics`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub vendor_number: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct GooglePlayConfig {
+    /// Service account JSON key with release access in the Play Console
+    pub service_account_path: String,
+}
+
+impl GooglePlayConfig {
+    /// `GOOGLE_PLAY_SERVICE_ACCOUNT_PATH` wins over the config file, like the APPLE_API_* vars
+    pub fn resolve(config: Option<&GlobalConfig>) -> Option<String> {
+        std::env::var("GOOGLE_PLAY_SERVICE_ACCOUNT_PATH")
+            .ok()
+            .or_else(|| {
+                config
+                    .and_then(|c| c.google_play.as_ref())
+                    .map(|g| g.service_account_path.clone())
+            })
+            .map(|path| shellexpand::tilde(&path).to_string())
+    }
+}
+
+/// `[network]`: for corporate networks. HTTPS_PROXY, NO_PROXY and
+/// LAUNCHPAD_CA_BUNDLE win over these.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct NetworkConfig {
+    /// Proxy for every request, e.g. "http://proxy.example.com:3128"
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub proxy: Option<String>,
+
+    /// Hosts that bypass the proxy, comma-separated ("localhost,.corp.example.com")
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub no_proxy: Option<String>,
+
+    /// PEM file of extra root certificates, for proxies that inspect TLS
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ca_bundle: Option<String>,
+}
+
+impl NetworkConfig {
+    pub fn is_default(&self) -> bool {
+        self.proxy.is_none() && self.no_proxy.is_none() && self.ca_bundle.is_none()
+    }
+}
+
+/// `[history]`: where deploy records are kept. LAUNCHPAD_HISTORY_BACKEND and
+/// LAUNCHPAD_HISTORY_URL win over these.
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct HistoryConfig {
+    /// "sqlite" (default), "jsonl" or "http"
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub backend: Option<HistoryBackend>,
+
+    /// Base URL of the history service for "http", e.g. "https://releases.example.com/launchpad"
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub url: Option<String>,
+
+    /// Environment variable holding its bearer token (default: LAUNCHPAD_HISTORY_TOKEN)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub token_env: Option<String>,
+}
+
+impl HistoryConfig {
+    pub fn is_default(&self) -> bool {
+        self.backend.is_none() && self.url.is_none() && self.token_env.is_none()
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum HistoryBackend {
+    /// ~/.launchpad/history.db
+    #[default]
+    Sqlite,
+    /// ~/.launchpad/history.jsonl, one record per line
+    Jsonl,
+    /// A shared service, so releases of many repos end up in one place
+    Http,
+}
+
+impl std::str::FromStr for HistoryBackend {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value {
+            "sqlite" => Ok(HistoryBackend::Sqlite),
+            "jsonl" => Ok(HistoryBackend::Jsonl),
+            "http" => Ok(HistoryBackend::Http),
+            other => Err(format!(
+                "unknown history backend '{}' (sqlite, jsonl or http)",
+                other
+            )),
+        }
+    }
+}
+
+/// `[email]`: HTML deploy summaries for people who don't follow chat. Every field can
+/// be overridden by `[notifications.email]` in a project.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct EmailSettings {
+    /// Set to false in a project to opt out of the global email
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub enabled: Option<bool>,
+
+    /// "smtp" (default) or "sendgrid"
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub provider: Option<EmailProvider>,
+
+    /// Sender, e.g. "Launchpad <builds@example.com>"
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub from: Option<String>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub to: Option<Vec<String>>,
+
+    /// Outcomes that send an email (default: success)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub on: Option<Vec<NotificationEvent>>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub smtp_host: Option<String>,
+
+    /// Default 587 (STARTTLS); 465 uses implicit TLS
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub smtp_port: Option<u16>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub smtp_username: Option<String>,
+
+    /// Environment variable holding the SMTP password (default: LAUNCHPAD_SMTP_PASSWORD)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub smtp_password_env: Option<String>,
+
+    /// Environment variable holding the SendGrid API key (default: SENDGRID_API_KEY)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sendgrid_api_key_env: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum EmailProvider {
+    #[default]
+    Smtp,
+    Sendgrid,
+}
+
+impl EmailSettings {
+    /// These settings with every field the project sets taking precedence
+    pub fn merge(&self, project: &EmailSettings) -> EmailSettings {
+        EmailSettings {
+            enabled: project.enabled.or(self.enabled),
+            provider: project.provider.or(self.provider),
+            from: project.from.clone().or_else(|| self.from.clone()),
+            to: project.to.clone().or_else(|| self.to.clone()),
+            on: project.on.clone().or_else(|| self.on.clone()),
+            smtp_host: project.smtp_host.clone().or_else(|| self.smtp_host.clone()),
+            smtp_port: project.smtp_port.or(self.smtp_port),
+            smtp_username: project
+                .smtp_username
+                .clone()
+                .or_else(|| self.smtp_username.clone()),
+            smtp_password_env: project
+                .smtp_password_env
+                .clone()
+                .or_else(|| self.smtp_password_env.clone()),
+            sendgrid_api_key_env: project
+                .sendgrid_api_key_env
+                .clone()
+                .or_else(|| self.sendgrid_api_key_env.clone()),
+        }
+    }
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct UiConfig {
+    /// Language for prompts and messages (e.g. "es"); defaults to the system locale
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub locale: Option<String>,
+
+    /// "default", or "minimal" for plain `[info]`/`[warn]` log lines without color or spinners
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub profile: Option<UiProfile>,
+
+    /// Unicode symbols (✓ ⚠ ✗ →); false prints ASCII instead
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub emoji: Option<bool>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub spinner: Option<SpinnerStyle>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub color: Option<ColorMode>,
+
+    /// Colors by role: names ("cyan") or 256-color numbers ("208")
+    #[serde(default, skip_serializing_if = "Palette::is_empty")]
+    pub palette: Palette,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum UiProfile {
+    #[default]
+    Default,
+    Minimal,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SpinnerStyle {
+    /// Braille dots
+    #[default]
+    Dots,
+    /// `| / - \`
+    Ascii,
+    /// No animation; the message is printed once
+    None,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ColorMode {
+    /// Color on terminals, unless NO_COLOR is set
+    #[default]
+    Auto,
+    Always,
+    Never,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct Palette {
+    /// Headers and spinners (default: cyan)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub accent: Option<String>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub success: Option<String>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub warning: Option<String>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+impl Palette {
+    pub fn is_empty(&self) -> bool {
+        self.accent.is_none()
+            && self.success.is_none()
+            && self.warning.is_none()
+            && self.error.is_none()
+    }
+}
+
+impl UiConfig {
+    pub fn is_default(&self) -> bool {
+        self.locale.is_none()
+            && self.profile.is_none()
+            && self.emoji.is_none()
+            && self.spinner.is_none()
+            && self.color.is_none()
+            && self.palette.is_empty()
+    }
+}
+
+impl GlobalConfig {
+    /// `~/.launchpad`, or LAUNCHPAD_CONFIG_DIR; the deploy history lives here too
+    pub fn config_dir() -> Option<PathBuf> {
+        // Check for custom location via env var
+        if let Ok(path) = std::env::var("LAUNCHPAD_CONFIG_DIR") {
+            return Some(PathBuf::from(path));
+        }
+
+        // Default to ~/.launchpad
+        dirs::home_dir().map(|h| h.join(".launchpad"))
+    }
+
+    pub fn config_path() -> Option<PathBuf> {
+        Self::config_dir().map(|d| d.join("config.toml"))
+    }
+
+    /// The config launchpad would use: APPLE_API_KEY_ID, APPLE_API_ISSUER_ID and
+    /// APPLE_API_KEY_PATH when all three are set, else the config file (unlocked
+    /// first when encrypted), else placeholders under `--mock`. None when there
+    /// is neither.
+    pub fn load() -> Result<Option<Self>, ConfigError> {
+        // Check environment variables first
+        let key_id = std::env::var("APPLE_API_KEY_ID");
+        let issuer_id = std::env::var("APPLE_API_ISSUER_ID");
+        let key_path = std::env::var("APPLE_API_KEY_PATH");
+
+        if let (Ok(key_id), Ok(issuer_id), Ok(key_path)) = (key_id, issuer_id, key_path) {
+            return Ok(Some(GlobalConfig {
+                apple: AppleConfig {
+                    key_id,
+                    issuer_id,
+                    key_path: encryption::resolve_key_path(&key_path)?,
+                    vendor_number: std::env::var("APPLE_VENDOR_NUMBER").ok(),
+                },
+                // Read from the file when an environment asks for one
+                key_profiles: BTreeMap::new(),
+                google_play: None,
+                ui: UiConfig::default(),
+                email: None,
+                network: NetworkConfig::default(),
+                history: HistoryConfig::default(),
+            }));
+        }
+
+        // Fall back to config file; a mock run doesn't need credentials
+        match Self::load_file()? {
+            None if mock::enabled() => Ok(Some(Self::mock())),
+            config => Ok(config),
+        }
+    }
+
+    /// Placeholder credentials for `--mock` runs without a config
+    fn mock() -> Self {
+        GlobalConfig {
+            apple: AppleConfig {
+                key_id: "MOCK".to_string(),
+                issuer_id: "mock".to_string(),
+                key_path: String::new(),
+                vendor_number: None,
+            },
+            key_profiles: BTreeMap::new(),
+            google_play: None,
+            ui: UiConfig::default(),
+            email: None,
+            network: NetworkConfig::default(),
+            history: HistoryConfig::default(),
+        }
+    }
+
+    /// Read ~/.launchpad/config.toml, ignoring the APPLE_API_* overrides
+    pub fn load_file() -> Result<Option<Self>, ConfigError> {
+        let Some(mut config) = Self::read_file()? else {
+            return Ok(None);
+        };
+
+        // An encrypted key is decrypted to a private temp file for this run
+        config.apple.key_path = encryption::resolve_key_path(&config.apple.key_path)?;
+        Ok(Some(config))
+    }
+
+    /// The config file as written, with `key_path` exactly as configured
+    pub fn read_file() -> Result<Option<Self>, ConfigError> {
+        let config_path = Self::config_path().ok_or(ConfigError::NoConfigDir)?;
+
+        if config_path.exists() {
+            // A config cut short or mangled falls back to the last one saved
+            return files::read_recovering(&config_path, |content| {
+                toml::from_str::<Self>(content).map_err(ConfigError::from)
+            });
+        }
+        if !Self::is_encrypted() {
+            return Ok(None);
+        }
+
+        let plaintext = encryption::read(&config_path)?;
+        let content = String::from_utf8(plaintext).map_err(std::io::Error::other)?;
+        let config: Self = toml::from_str(&content)?;
+        // HTTP clients can't decrypt the file themselves
+        http::use_unlocked_network(&config.network);
+        Ok(Some(config))
+    }
+
+    /// config.toml's text, decrypted when `launchpad setup encrypt` has been run
+    fn read_text() -> Result<Option<String>, ConfigError> {
+        let config_path = Self::config_path().ok_or(ConfigError::NoConfigDir)?;
+
+        if config_path.exists() {
+            Ok(Some(std::fs::read_to_string(&config_path)?))
+        } else if Self::is_encrypted() {
+            let plaintext = encryption::read(&config_path)?;
+            Ok(Some(
+                String::from_utf8(plaintext).map_err(std::io::Error::other)?,
+            ))
+        } else {
+            Ok(None)
+        }
+    }
+
+    /// `[key_profiles]` from config.toml; CI passing the main key as APPLE_API_*
+    /// can still keep other accounts' keys in the file
+    fn load_key_profiles() -> Result<BTreeMap<String, AppleConfig>, ConfigError> {
+        let Some(content) = Self::read_text()? else {
+            return Ok(BTreeMap::new());
+        };
+        let file: KeyProfilesFile = toml::from_str(&content)?;
+        Ok(file.key_profiles)
+    }
+
+    /// Use `[key_profiles.<name>]` instead of `[apple]`
+    pub fn use_key_profile(&mut self, name: &str) -> Result<(), ConfigError> {
+        // Not read yet when APPLE_API_* replaced the file
+        if self.key_profiles.is_empty() {
+            self.key_profiles = Self::load_key_profiles()?;
+        }
+        let Some(mut profile) = self.key_profiles.remove(name) else {
+            // The placeholder credentials of a mock run stand in for every profile
+            if mock::enabled() {
+                return Ok(());
+            }
+            return Err(ConfigError::UnknownKeyProfile {
+                name: name.to_string(),
+                known: known_names(self.key_profiles.keys()),
+            });
+        };
+
+        // Encrypted like the main key when the config is
+        profile.key_path = encryption::resolve_key_path(&profile.key_path)?;
+        self.apple = profile;
+        Ok(())
+    }
+
+    /// Whether `launchpad setup encrypt` has been run
+    pub fn is_encrypted() -> bool {
+        Self::config_path().is_some_and(|p| encryption::encrypted_path(&p).exists())
+    }
+
+    /// Write the config file (encrypted when `setup encrypt` was run). The file is
+    /// replaced atomically under a lock, so concurrent runs never leave half of one.
+    pub fn save(&self) -> Result<(), ConfigError> {
+        let config_path = Self::config_path().ok_or(ConfigError::NoConfigDir)?;
+        let _lock = files::lock(&config_path)?;
+
+        let content = toml::to_string_pretty(self)?;
+        if Self::is_encrypted() {
+            encryption::write(&config_path, content.as_bytes())?;
+        } else {
+            files::write_atomic(&config_path, content.as_bytes())?;
+        }
+
+        Ok(())
+    }
+}
+
+/// "a, b" for an error message listing what is defined
+pub(crate) fn known_names<'a>(names: impl Iterator<Item = &'a String>) -> String {
+    let names = names.cloned().collect::<Vec<_>>();
+    if names.is_empty() {
+        "none".to_string()
+    } else {
+        names.join(", ")
+    }
+}
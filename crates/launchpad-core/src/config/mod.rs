@@ -0,0 +1,54 @@
+pub mod encryption;
+pub mod global;
+pub mod project;
+
+use global::{ConfigError, GlobalConfig};
+use project::ProjectConfig;
+
+/// Switch both configs to `[environments.<name>.apple]`: its key profile replaces
+/// `[apple]` and its bundle ID the project's, so the build and every App Store
+/// Connect call are for that account's app
+pub fn apply_environment(
+    global_config: &mut GlobalConfig,
+    project_config: &mut ProjectConfig,
+    name: &str,
+) -> Result<(), ConfigError> {
+    let Some(environment) = project_config.environments.get(name) else {
+        return Err(ConfigError::UnknownEnvironment {
+            name: name.to_string(),
+            known: global::known_names(project_config.environments.keys()),
+        });
+    };
+    if let Some(profile) = &environment.apple.key_profile {
+        global_config.use_key_profile(profile)?;
+    }
+    if let Some(bundle_id) = environment.apple.bundle_id.clone() {
+        // The recorded App Store Connect ID is the other account's app
+        if bundle_id != project_config.project.bundle_id {
+            project_config.project.asc_app_id = None;
+        }
+        project_config.project.bundle_id = bundle_id;
+    }
+    Ok(())
+}
+
+/// Apply the environment picked with --env (or LAUNCHPAD_ENVIRONMENT), so commands
+/// after a deploy talk to the same account and app it did. Nothing changes when
+/// there's no project config or it defines no `[environments]`.
+pub fn use_selected_environment(
+    global_config: &mut GlobalConfig,
+    project_config: Option<&mut ProjectConfig>,
+) -> Result<Option<String>, ConfigError> {
+    let Some(project_config) = project_config else {
+        return Ok(None);
+    };
+    if project_config.environments.is_empty() {
+        return Ok(None);
+    }
+    let Some(name) = crate::notifications::environment(project_config.notifications.as_ref())
+    else {
+        return Ok(None);
+    };
+    apply_environment(global_config, project_config, &name)?;
+    Ok(Some(name))
+}
@@ -0,0 +1,963 @@
+use super::global::EmailSettings;
+use crate::kmp::FrameworkIntegration;
+use crate::presets::Preset;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::Path;
+use thiserror::Error;
+
+const CONFIG_FILENAME: &str = ".launchpad.toml";
+
+#[derive(Error, Debug)]
+pub enum ProjectConfigError {
+    #[error("IO error: {0}")]
+    Io(#[from] std::io::Error),
+
+    #[error("TOML parse error: {0}")]
+    TomlParse(#[from] toml::de::Error),
+
+    #[error("TOML serialize error: {0}")]
+    TomlSerialize(#[from] toml::ser::Error),
+
+    #[error("TOML edit error: {0}")]
+    TomlEdit(#[from] toml_edit::TomlError),
+}
+
+/// A project's `.launchpad.toml`: what to build, where it goes and what runs
+/// around each deploy
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ProjectConfig {
+    pub project: ProjectSettings,
+    pub deploy: DeploySettings,
+
+    #[serde(default)]
+    pub build: BuildSettings,
+
+    #[serde(default)]
+    pub signing: SigningSettings,
+
+    /// `[environments.<name>]`, picked with `deploy --env` or LAUNCHPAD_ENVIRONMENT
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub environments: BTreeMap<String, EnvironmentSettings>,
+
+    #[serde(default, skip_serializing_if = "IntegrationsSettings::is_empty")]
+    pub integrations: IntegrationsSettings,
+
+    #[serde(default, skip_serializing_if = "HooksSettings::is_empty")]
+    pub hooks: HooksSettings,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub kmp: Option<KmpSettings>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub android: Option<AndroidSettings>,
+
+    /// Crash reporter that `launchpad symbols fetch` forwards dSYMs to
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub symbols: Option<CrashReporter>,
+
+    /// Opt-in build cache kept between deploys
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cache: Option<CacheSettings>,
+
+    /// Chat webhooks per environment and outcome
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub notifications: Option<NotificationSettings>,
+
+    /// App Thinning size report after export, with optional per-device budgets
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub size: Option<SizeSettings>,
+
+    /// Binary size warnings after each iOS build
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub analysis: Option<AnalysisSettings>,
+
+    /// Handlebars templates replacing the built-in tag, note, chat and PR comment text
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub templates: Option<TemplateSettings>,
+
+    /// `[brands.<name>]`, white-label variants picked with `deploy --brand`
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub brands: BTreeMap<String, BrandSettings>,
+
+    /// Environments × targets that `deploy --matrix` runs
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub matrix: Option<MatrixSettings>,
+
+    /// Export compliance answers applied to every uploaded build
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub compliance: Option<ComplianceSettings>,
+}
+
+/// One white-label variant of the app, built from the project's scheme with its
+/// own bundle ID, name and icon
+#[derive(Debug, Serialize, Deserialize)]
+pub struct BrandSettings {
+    pub bundle_id: String,
+
+    /// Name under the home screen icon (CFBundleDisplayName)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub display_name: Option<String>,
+
+    /// App icon set in the asset catalog
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub app_icon: Option<String>,
+
+    /// Color set in the asset catalog used as the accent color
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub accent_color: Option<String>,
+
+    /// Any other build settings, layered over the project's like `build_env`
+    /// of an environment
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub build_env: BTreeMap<String, String>,
+}
+
+impl BrandSettings {
+    /// Everything the brand sets, as build settings for the generated xcconfig;
+    /// `build_env` wins over the named fields
+    pub fn build_settings(&self) -> BTreeMap<String, String> {
+        let mut settings = BTreeMap::new();
+        settings.insert(
+            "PRODUCT_BUNDLE_IDENTIFIER".to_string(),
+            self.bundle_id.clone(),
+        );
+        if let Some(name) = &self.display_name {
+            settings.insert(
+                "INFOPLIST_KEY_CFBundleDisplayName".to_string(),
+                name.clone(),
+            );
+        }
+        if let Some(icon) = &self.app_icon {
+            settings.insert(
+                "ASSETCATALOG_COMPILER_APPICON_NAME".to_string(),
+                icon.clone(),
+            );
+        }
+        if let Some(color) = &self.accent_color {
+            settings.insert(
+                "ASSETCATALOG_COMPILER_GLOBAL_ACCENT_COLOR_NAME".to_string(),
+                color.clone(),
+            );
+        }
+        settings.extend(self.build_env.clone());
+        settings
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ProjectSettings {
+    pub ios_path: String,
+    pub scheme: String,
+    pub bundle_id: String,
+
+    /// App Store Connect app ID of bundle_id, recorded by init so API calls skip
+    /// the lookup (and don't depend on which team the key finds first)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub asc_app_id: Option<String>,
+
+    /// Preset chosen during init (drives Fastfile generation)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub preset: Option<Preset>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct DeploySettings {
+    #[serde(default = "default_true")]
+    pub git_tag: bool,
+
+    #[serde(default = "default_true")]
+    pub push_tags: bool,
+
+    /// Remote that tags, notes and the version bump are pushed to (default "origin")
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub git_remote: Option<String>,
+
+    /// Commit the version bump fastlane made to the project and push it with the tag
+    #[serde(default)]
+    pub push_version_bump: bool,
+
+    /// Sign release tags (`git tag -s`) with git's signing setup
+    #[serde(default)]
+    pub tag_sign: bool,
+
+    /// Key to sign tags with (default: git's user.signingkey)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tag_signing_key: Option<String>,
+
+    /// Remove the deploy's .ipa and archive after a successful upload (off by
+    /// default: `--reuse-artifact` and `install` need them)
+    #[serde(default)]
+    pub clean_artifacts: bool,
+
+    /// Where to write deploy-summary.json (default: inside build.output_dir)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub summary_path: Option<String>,
+
+    /// TestFlight groups to distribute the build to (default: none, upload only)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub groups: Vec<String>,
+
+    /// Email testers when the build becomes available to them
+    #[serde(default = "default_true")]
+    pub notify_testers: bool,
+
+    /// "immediate": every group gets the build; "staged": external groups wait for promote-beta
+    #[serde(default)]
+    pub distribution: DistributionMode,
+
+    /// Record each deploy as a git note on the released commit (pushed with the tags)
+    #[serde(default)]
+    pub git_notes: bool,
+
+    /// Notes ref (default "commits", which `git log` shows without extra flags)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub notes_ref: Option<String>,
+
+    /// Fail the deploy when App Store Connect never lists the uploaded build
+    #[serde(default = "default_true")]
+    pub verify_upload: bool,
+}
+
+impl DeploySettings {
+    pub fn remote(&self) -> &str {
+        self.git_remote.as_deref().unwrap_or("origin")
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DistributionMode {
+    #[default]
+    Immediate,
+    Staged,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct BuildSettings {
+    /// Directory (relative to project root) where the .ipa and dSYMs are written
+    #[serde(default = "default_output_dir")]
+    pub output_dir: String,
+
+    /// IPA file name; supports {app}, {version} and {build} placeholders
+    #[serde(default = "default_ipa_name_template")]
+    pub ipa_name_template: String,
+
+    /// Xcode build configuration to archive
+    #[serde(default = "default_configuration")]
+    pub configuration: String,
+
+    /// Export method for the .ipa ("app-store", "ad-hoc", "enterprise", ...)
+    #[serde(default = "default_export_method")]
+    pub export_method: String,
+
+    /// SDK to build against (xcodebuild -sdk), e.g. "iphoneos" or "macosx" for Mac Catalyst
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sdk: Option<String>,
+
+    /// xcodebuild -destination, e.g. "generic/platform=macOS,variant=Mac Catalyst"
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub destination: Option<String>,
+
+    /// `[build.metadata]`: stamp where the build came from into the app's Info.plist
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub metadata: Option<MetadataSettings>,
+
+    /// `[build.icon_badge]`: mark the app icon of test builds
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub icon_badge: Option<IconBadgeSettings>,
+}
+
+/// Info.plist keys written for the build only (the source file is restored after
+/// archiving): `<prefix>GitSHA`, `<prefix>GitBranch`, `<prefix>BuildDate` and
+/// `<prefix>Version` (the launchpad version)
+#[derive(Debug, Serialize, Deserialize)]
+pub struct MetadataSettings {
+    #[serde(default = "default_metadata_prefix")]
+    pub key_prefix: String,
+}
+
+/// A band across the bottom of the app icon, drawn for the build only (the icon
+/// files are restored after archiving)
+#[derive(Debug, Serialize, Deserialize)]
+pub struct IconBadgeSettings {
+    /// Text in the band; a template with `{{version}}`, `{{build}}` and `{{environment}}`
+    #[serde(default = "default_badge_text")]
+    pub text: String,
+
+    /// Band color as "#RRGGBB"
+    #[serde(default = "default_badge_color")]
+    pub color: String,
+
+    /// Environments whose builds get the badge; empty badges every build
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub environments: Vec<String>,
+}
+
+impl IconBadgeSettings {
+    pub fn applies_to(&self, environment: Option<&str>) -> bool {
+        self.environments.is_empty()
+            || environment.is_some_and(|name| self.environments.iter().any(|e| e == name))
+    }
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct SigningSettings {
+    /// Detected during init; re-detected at deploy time when unset
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub style: Option<SigningStyle>,
+
+    /// Bundle ID → provisioning profile name (required for manual signing)
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub profiles: BTreeMap<String, String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, clap::ValueEnum)]
+#[serde(rename_all = "lowercase")]
+pub enum SigningStyle {
+    Automatic,
+    Manual,
+}
+
+impl std::fmt::Display for SigningStyle {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SigningStyle::Automatic => write!(f, "automatic"),
+            SigningStyle::Manual => write!(f, "manual"),
+        }
+    }
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct EnvironmentSettings {
+    /// Build settings for the archive (API base URLs, feature flags), applied through
+    /// a generated xcconfig; Info.plist entries read them as `$(API_BASE_URL)`
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub build_env: BTreeMap<String, String>,
+
+    /// CFBundleDisplayName of this environment's builds, instead of the app's (or brand's)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub display_name: Option<String>,
+
+    /// Appended to the display name as-is, e.g. " Beta", so testers can tell the
+    /// environments' builds apart on the home screen
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub display_name_suffix: Option<String>,
+
+    /// For an environment whose app lives under another Apple account
+    #[serde(default, skip_serializing_if = "EnvironmentAppleSettings::is_default")]
+    pub apple: EnvironmentAppleSettings,
+}
+
+/// `[environments.<name>.apple]`
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct EnvironmentAppleSettings {
+    /// `[key_profiles.<name>]` in the global config, used instead of its `[apple]` key
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub key_profile: Option<String>,
+
+    /// Team the archive is signed for (DEVELOPMENT_TEAM), e.g. "ABCDE12345"
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub team_id: Option<String>,
+
+    /// The environment's app in that account; replaces `project.bundle_id` for the
+    /// build (PRODUCT_BUNDLE_IDENTIFIER) and the upload
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub bundle_id: Option<String>,
+}
+
+impl EnvironmentAppleSettings {
+    pub fn is_default(&self) -> bool {
+        self.key_profile.is_none() && self.team_id.is_none() && self.bundle_id.is_none()
+    }
+}
+
+/// Hooks per lifecycle event: `.rhai` scripts or shell commands, run in order
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct HooksSettings {
+    /// Before fastlane bumps the version; a failing hook blocks the deploy
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub pre_version_bump: Vec<String>,
+
+    /// After a successful upload to TestFlight
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub post_upload: Vec<String>,
+}
+
+impl HooksSettings {
+    pub fn is_empty(&self) -> bool {
+        self.pre_version_bump.is_empty() && self.post_upload.is_empty()
+    }
+}
+
+/// Kotlin Multiplatform: the Gradle module whose framework the iOS app embeds
+#[derive(Debug, Serialize, Deserialize)]
+pub struct KmpSettings {
+    /// Gradle project path without the leading colon (e.g. "shared")
+    pub shared_module: String,
+
+    #[serde(default)]
+    pub integration: FrameworkIntegration,
+}
+
+/// Google Play companion deploy (`launchpad deploy --platform android`)
+#[derive(Debug, Serialize, Deserialize)]
+pub struct AndroidSettings {
+    /// Gradle root (relative to project root)
+    #[serde(default = "default_android_path")]
+    pub project_path: String,
+
+    /// Application module that produces the bundle
+    #[serde(default = "default_android_module")]
+    pub module: String,
+
+    /// Gradle task building the release .aab
+    #[serde(default = "default_gradle_task")]
+    pub gradle_task: String,
+
+    pub package_name: String,
+
+    /// Play track to release to
+    #[serde(default = "default_play_track")]
+    pub track: String,
+}
+
+/// `[cache]`: DerivedData plus SPM/CocoaPods caches keyed by their lockfiles
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CacheSettings {
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+
+    /// Cache root (default: ~/.launchpad/cache/<bundle_id>); LAUNCHPAD_CACHE_DIR wins
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub dir: Option<String>,
+
+    /// Package caches kept per kind; older lockfile versions are pruned after a deploy
+    #[serde(default = "default_cache_keep")]
+    pub keep: usize,
+}
+
+/// `[compliance]`: what App Store Connect otherwise asks by hand for each build
+/// ("Missing Compliance")
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ComplianceSettings {
+    /// Whether the app uses encryption that isn't exempt from export rules
+    pub uses_non_exempt_encryption: bool,
+
+    /// App Encryption Declaration builds are attached to, for non-exempt apps
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub declaration_id: Option<String>,
+
+    /// Compliance documentation `launchpad compliance upload` sends to the declaration
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub document: Option<String>,
+}
+
+/// `[matrix]`: every listed environment × every target, one deploy each; for
+/// white-label apps built from one codebase
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct MatrixSettings {
+    /// `[environments]` to deploy; all of them when empty
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub environments: Vec<String>,
+
+    /// `[[matrix.targets]]`; just the `[project]` app when empty
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub targets: Vec<MatrixTarget>,
+
+    /// Stop at the first failed deploy instead of running the rest
+    #[serde(default)]
+    pub fail_fast: bool,
+}
+
+/// One app of the matrix; unset fields come from `[project]`
+#[derive(Debug, Serialize, Deserialize)]
+pub struct MatrixTarget {
+    pub name: String,
+
+    /// `[brands.<name>]` to build; scheme, bundle_id and ios_path still apply
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub brand: Option<String>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub scheme: Option<String>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub bundle_id: Option<String>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ios_path: Option<String>,
+}
+
+/// `[size]`: per-device download and install size limits, checked against the
+/// App Thinning size report before uploading. Keys are a device class
+/// ("iPhone SE", "iPhone", "iPad", "Universal") or a model identifier ("iPhone12,8").
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct SizeSettings {
+    /// Largest allowed download (compressed) size, e.g. "200 MB"
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub download_budgets: BTreeMap<String, String>,
+
+    /// Largest allowed install (uncompressed) size
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub install_budgets: BTreeMap<String, String>,
+}
+
+/// `[analysis]`: flag large frameworks, code linked into more than one binary and
+/// unstripped symbols in the built .ipa
+#[derive(Debug, Serialize, Deserialize)]
+pub struct AnalysisSettings {
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+
+    /// Frameworks bigger than this (uncompressed) are flagged
+    #[serde(default = "default_large_framework")]
+    pub large_framework: String,
+}
+
+/// `[templates]`: Handlebars templates for the text a deploy writes. Unset entries
+/// keep the built-in text.
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct TemplateSettings {
+    /// TestFlight "What to Test" notes, rendered before the build
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub release_notes: Option<String>,
+
+    /// Annotated release tag message
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tag_message: Option<String>,
+
+    /// Deploy note attached to the released commit
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub git_note: Option<String>,
+
+    /// Pull/merge request comment body
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pr_comment: Option<String>,
+
+    /// Chat messages, per outcome
+    #[serde(default, skip_serializing_if = "NotificationTemplates::is_empty")]
+    pub notifications: NotificationTemplates,
+}
+
+impl TemplateSettings {
+    /// Every template that is set, named as in .launchpad.toml
+    pub fn entries(&self) -> Vec<(&'static str, &str)> {
+        [
+            ("release_notes", self.release_notes.as_deref()),
+            ("tag_message", self.tag_message.as_deref()),
+            ("git_note", self.git_note.as_deref()),
+            ("pr_comment", self.pr_comment.as_deref()),
+            (
+                "notifications.success",
+                self.notifications.success.as_deref(),
+            ),
+            (
+                "notifications.failure",
+                self.notifications.failure.as_deref(),
+            ),
+            (
+                "notifications.processed",
+                self.notifications.processed.as_deref(),
+            ),
+        ]
+        .into_iter()
+        .filter_map(|(name, template)| Some((name, template?)))
+        .collect()
+    }
+}
+
+/// `[templates.notifications]`
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct NotificationTemplates {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub success: Option<String>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub failure: Option<String>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub processed: Option<String>,
+}
+
+impl NotificationTemplates {
+    pub fn is_empty(&self) -> bool {
+        self.success.is_none() && self.failure.is_none() && self.processed.is_none()
+    }
+
+    pub fn for_event(&self, event: NotificationEvent) -> Option<&str> {
+        match event {
+            NotificationEvent::Success => self.success.as_deref(),
+            NotificationEvent::Failure => self.failure.as_deref(),
+            NotificationEvent::Processed => self.processed.as_deref(),
+            // Review updates aren't about a deploy, so they keep the built-in text
+            NotificationEvent::Review => None,
+        }
+    }
+}
+
+/// `[notifications]`: where deploy outcomes are posted
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct NotificationSettings {
+    /// Environment this checkout deploys to ("staging", "production"...); LAUNCHPAD_ENVIRONMENT wins
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub environment: Option<String>,
+
+    /// `[[notifications.rules]]`; every matching rule is notified
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub rules: Vec<NotificationRule>,
+
+    /// Overrides for the global `[email]` settings
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub email: Option<EmailSettings>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct NotificationRule {
+    /// Outcomes this rule posts (default: all)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub on: Vec<NotificationEvent>,
+
+    /// Environments this rule applies to (default: all)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub environments: Vec<String>,
+
+    /// Webhook URL; prefer `webhook_env` so the URL stays out of the repo
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub webhook: Option<String>,
+
+    /// Environment variable holding the webhook URL
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub webhook_env: Option<String>,
+
+    /// Slack channel override ("#ios-alerts"), for webhooks that allow it
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub channel: Option<String>,
+
+    #[serde(default)]
+    pub format: WebhookFormat,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum NotificationEvent {
+    /// The build reached TestFlight or Google Play
+    Success,
+    /// The deploy failed
+    Failure,
+    /// App Store Connect finished processing the build
+    Processed,
+    /// An App Store version moved on in App Review (`launchpad review status --watch`)
+    Review,
+}
+
+impl std::fmt::Display for NotificationEvent {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            NotificationEvent::Success => write!(f, "success"),
+            NotificationEvent::Failure => write!(f, "failure"),
+            NotificationEvent::Processed => write!(f, "processed"),
+            NotificationEvent::Review => write!(f, "review"),
+        }
+    }
+}
+
+/// Payload posted to the webhook
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum WebhookFormat {
+    /// `{"text": ..., "channel": ...}` (Slack, Mattermost, Rocket.Chat)
+    #[default]
+    Slack,
+    /// The event, environment and deploy summary as JSON
+    Json,
+}
+
+/// `[symbols]`: where dSYMs are uploaded, chosen by `reporter`
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(tag = "reporter", rename_all = "lowercase")]
+pub enum CrashReporter {
+    /// `sentry-cli debug-files upload` (auth via SENTRY_AUTH_TOKEN)
+    Sentry { org: String, project: String },
+
+    /// Firebase Crashlytics `upload-symbols`
+    Crashlytics {
+        /// GoogleService-Info.plist (relative to project root)
+        google_service_plist: String,
+
+        /// upload-symbols script (default: the CocoaPods copy under ios_path)
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        upload_symbols: Option<String>,
+    },
+
+    /// `bugsnag-cli upload dsym`
+    Bugsnag {
+        /// Environment variable holding the Bugsnag API key
+        #[serde(default = "default_bugsnag_key_env")]
+        api_key_env: String,
+    },
+
+    /// Any shell command; `{path}` is replaced by the dSYM directory
+    Command { command: String },
+}
+
+impl std::fmt::Display for CrashReporter {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CrashReporter::Sentry { .. } => write!(f, "Sentry"),
+            CrashReporter::Crashlytics { .. } => write!(f, "Crashlytics"),
+            CrashReporter::Bugsnag { .. } => write!(f, "Bugsnag"),
+            CrashReporter::Command { .. } => write!(f, "custom command"),
+        }
+    }
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct IntegrationsSettings {
+    /// Forge to comment on; detected from the git remote (or CI env) when unset
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub forge: Option<Forge>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub github: Option<ForgeSettings>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub gitlab: Option<ForgeSettings>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub gitea: Option<ForgeSettings>,
+}
+
+impl IntegrationsSettings {
+    pub fn is_empty(&self) -> bool {
+        self.forge.is_none()
+            && self.github.is_none()
+            && self.gitlab.is_none()
+            && self.gitea.is_none()
+    }
+
+    /// TestFlight public link from whichever forge section sets one
+    pub fn public_link(&self) -> Option<&str> {
+        [&self.github, &self.gitlab, &self.gitea]
+            .into_iter()
+            .flatten()
+            .find_map(|forge| forge.public_link.as_deref())
+    }
+
+    /// Settings section for a forge, if configured
+    pub fn forge_settings(&self, forge: Forge) -> Option<&ForgeSettings> {
+        match forge {
+            Forge::Github => self.github.as_ref(),
+            Forge::Gitlab => self.gitlab.as_ref(),
+            Forge::Gitea => self.gitea.as_ref(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Forge {
+    Github,
+    Gitlab,
+    Gitea,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ForgeSettings {
+    /// Post/update a comment on the pull/merge request after a successful deploy
+    #[serde(default = "default_true")]
+    pub pr_comment: bool,
+
+    /// TestFlight public link (https://testflight.apple.com/join/...)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub public_link: Option<String>,
+
+    /// Environment variable holding the API token (default depends on the forge)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub token_env: Option<String>,
+
+    /// API base URL for self-hosted instances (default: from CI env)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub api_url: Option<String>,
+}
+
+fn default_true() -> bool {
+    true
+}
+
+fn default_android_path() -> String {
+    "android".to_string()
+}
+
+fn default_android_module() -> String {
+    "app".to_string()
+}
+
+fn default_gradle_task() -> String {
+    "bundleRelease".to_string()
+}
+
+fn default_play_track() -> String {
+    "internal".to_string()
+}
+
+fn default_bugsnag_key_env() -> String {
+    "BUGSNAG_API_KEY".to_string()
+}
+
+fn default_cache_keep() -> usize {
+    2
+}
+
+fn default_output_dir() -> String {
+    "build".to_string()
+}
+
+fn default_ipa_name_template() -> String {
+    "{app}-{version}-{build}.ipa".to_string()
+}
+
+fn default_configuration() -> String {
+    "Release".to_string()
+}
+
+fn default_large_framework() -> String {
+    "50 MB".to_string()
+}
+
+fn default_export_method() -> String {
+    "app-store".to_string()
+}
+
+fn default_metadata_prefix() -> String {
+    "Launchpad".to_string()
+}
+
+fn default_badge_text() -> String {
+    "BETA".to_string()
+}
+
+fn default_badge_color() -> String {
+    "#E8590C".to_string()
+}
+
+impl Default for DeploySettings {
+    fn default() -> Self {
+        Self {
+            git_tag: true,
+            push_tags: true,
+            git_remote: None,
+            push_version_bump: false,
+            tag_sign: false,
+            tag_signing_key: None,
+            clean_artifacts: false,
+            summary_path: None,
+            groups: Vec::new(),
+            notify_testers: true,
+            distribution: DistributionMode::Immediate,
+            git_notes: false,
+            notes_ref: None,
+            verify_upload: true,
+        }
+    }
+}
+
+impl Default for BuildSettings {
+    fn default() -> Self {
+        Self {
+            output_dir: default_output_dir(),
+            ipa_name_template: default_ipa_name_template(),
+            configuration: default_configuration(),
+            export_method: default_export_method(),
+            sdk: None,
+            destination: None,
+            metadata: None,
+            icon_badge: None,
+        }
+    }
+}
+
+impl ProjectConfig {
+    /// Defaults for everything but the project itself (no .launchpad.toml)
+    pub fn new(ios_path: String, scheme: String, bundle_id: String) -> Self {
+        Self {
+            project: ProjectSettings {
+                ios_path,
+                scheme,
+                bundle_id,
+                asc_app_id: None,
+                preset: None,
+            },
+            deploy: DeploySettings::default(),
+            build: BuildSettings::default(),
+            signing: SigningSettings::default(),
+            environments: BTreeMap::new(),
+            integrations: IntegrationsSettings::default(),
+            hooks: HooksSettings::default(),
+            kmp: None,
+            android: None,
+            symbols: None,
+            cache: None,
+            notifications: None,
+            size: None,
+            analysis: None,
+            templates: None,
+            brands: BTreeMap::new(),
+            matrix: None,
+            compliance: None,
+        }
+    }
+
+    /// `.launchpad.toml` in the current directory; None when it has none
+    pub fn load() -> Result<Option<Self>, ProjectConfigError> {
+        Self::load_from(Path::new("."))
+    }
+
+    /// `.launchpad.toml` of the project in `dir`, for tools that look at several
+    /// projects without changing directory
+    pub fn load_from(dir: &Path) -> Result<Option<Self>, ProjectConfigError> {
+        let config_path = dir.join(CONFIG_FILENAME);
+
+        if !config_path.exists() {
+            return Ok(None);
+        }
+
+        let content = std::fs::read_to_string(&config_path)?;
+        let config: ProjectConfig = toml::from_str(&content)?;
+
+        Ok(Some(config))
+    }
+
+    pub fn save(&self) -> Result<(), ProjectConfigError> {
+        let content = toml::to_string_pretty(self)?;
+        std::fs::write(CONFIG_FILENAME, content)?;
+        Ok(())
+    }
+
+    /// Set individual keys in .launchpad.toml, leaving the rest of the file (comments, order) untouched
+    pub fn update(values: &[(Vec<String>, toml_edit::Value)]) -> Result<(), ProjectConfigError> {
+        let content = std::fs::read_to_string(CONFIG_FILENAME)?;
+        let mut doc: toml_edit::DocumentMut = content.parse()?;
+
+        for (path, value) in values {
+            let Some((key, tables)) = path.split_last() else {
+                continue;
+            };
+            let mut item = doc.as_item_mut();
+            for table in tables {
+                item = &mut item[table.as_str()];
+            }
+
+            // Keep an inline comment on the old value
+            let slot = &mut item[key.as_str()];
+            let mut value = value.clone();
+            if let Some(old) = slot.as_value() {
+                *value.decor_mut() = old.decor().clone();
+            }
+            *slot = toml_edit::Item::Value(value);
+        }
+
+        std::fs::write(CONFIG_FILENAME, doc.to_string())?;
+        Ok(())
+    }
+}
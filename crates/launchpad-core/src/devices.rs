@@ -0,0 +1,76 @@
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use thiserror::Error;
+
+/// The team's test devices, next to .launchpad.toml so it's committed with the project
+pub const REGISTRY_FILENAME: &str = "devices.toml";
+
+const FILE_HEADER: &str = "# Team test devices. `launchpad devices sync` registers new ones on the\n# Developer portal and adds the ones only registered there.\n\n";
+
+#[derive(Error, Debug)]
+pub enum DevicesError {
+    #[error("IO error: {0}")]
+    Io(#[from] std::io::Error),
+
+    #[error("{REGISTRY_FILENAME}: {0}")]
+    Parse(#[from] toml::de::Error),
+
+    #[error("TOML serialize error: {0}")]
+    Serialize(#[from] toml::ser::Error),
+}
+
+/// devices.toml
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct Registry {
+    #[serde(default, rename = "device", skip_serializing_if = "Vec::is_empty")]
+    pub devices: Vec<TestDevice>,
+}
+
+/// `[[device]]`: one phone or tablet the team tests on
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TestDevice {
+    /// Name on the Developer portal ("Ana's iPhone 15")
+    pub name: String,
+    pub udid: String,
+
+    /// Who has the device
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub owner: Option<String>,
+
+    /// iOS version it runs ("17.4")
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ios_version: Option<String>,
+}
+
+impl Registry {
+    /// The registry in the current directory, if the project keeps one
+    pub fn load() -> Result<Option<Self>, DevicesError> {
+        let path = Path::new(REGISTRY_FILENAME);
+        if !path.exists() {
+            return Ok(None);
+        }
+        Ok(Some(toml::from_str(&std::fs::read_to_string(path)?)?))
+    }
+
+    pub fn save(&self) -> Result<(), DevicesError> {
+        let content = format!("{}{}", FILE_HEADER, toml::to_string_pretty(self)?);
+        std::fs::write(REGISTRY_FILENAME, content)?;
+        Ok(())
+    }
+
+    pub fn find(&self, udid: &str) -> Option<&TestDevice> {
+        self.devices
+            .iter()
+            .find(|d| d.udid.eq_ignore_ascii_case(udid))
+    }
+}
+
+/// A UDID as Apple formats it: "00008030-001A2B3C4D5E6F70" on current devices,
+/// 40 hex digits on ones from before the iPhone XS
+pub fn valid_udid(udid: &str) -> bool {
+    let hex = |part: &str| part.chars().all(|c| c.is_ascii_hexdigit());
+    match udid.split_once('-') {
+        Some((chip, serial)) => chip.len() == 8 && serial.len() == 16 && hex(chip) && hex(serial),
+        None => udid.len() == 40 && hex(udid),
+    }
+}
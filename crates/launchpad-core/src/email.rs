@@ -0,0 +1,372 @@
+use crate::config::global::{EmailProvider, EmailSettings, GlobalConfig};
+use crate::config::project::{NotificationEvent, ProjectConfig};
+use crate::history;
+use crate::http;
+use crate::integrations;
+use crate::summary::DeploySummary;
+use lettre::message::{Mailbox, MultiPart};
+use lettre::transport::smtp::authentication::Credentials;
+use lettre::{AsyncSmtpTransport, AsyncTransport, Message, Tokio1Executor};
+use serde_json::json;
+use thiserror::Error;
+
+const SENDGRID_URL: &str = "https://api.sendgrid.com/v3/mail/send";
+
+#[derive(Error, Debug)]
+pub enum EmailError {
+    #[error("Email settings need {0}")]
+    Incomplete(&'static str),
+
+    #[error("Missing email credentials: set {0}")]
+    MissingSecret(String),
+
+    #[error("Invalid email address: {0}")]
+    Address(#[from] lettre::address::AddressError),
+
+    #[error("Could not build email: {0}")]
+    Message(#[from] lettre::error::Error),
+
+    #[error("SMTP error: {0}")]
+    Smtp(#[from] lettre::transport::smtp::Error),
+
+    #[error("HTTP error: {0}")]
+    Http(#[from] reqwest::Error),
+
+    #[error("SendGrid error ({status}): {body}")]
+    Api { status: u16, body: String },
+}
+
+/// What the summary email describes
+pub struct EmailContext<'a> {
+    pub event: NotificationEvent,
+    pub app: &'a str,
+    pub bundle_id: &'a str,
+    /// "ios" or "android", as in the deploy history
+    pub platform: &'a str,
+    pub environment: Option<&'a str>,
+    /// TestFlight public link from `[integrations]`
+    pub public_link: Option<&'a str>,
+    pub summary: &'a DeploySummary,
+}
+
+/// Global `[email]` with the project's `[notifications.email]` on top; None when
+/// nobody would get an email
+pub fn settings(global: Option<&GlobalConfig>, project: &ProjectConfig) -> Option<EmailSettings> {
+    let project = project
+        .notifications
+        .as_ref()
+        .and_then(|n| n.email.as_ref());
+    let settings = match (global.and_then(|g| g.email.as_ref()), project) {
+        (Some(global), Some(project)) => global.merge(project),
+        (Some(settings), None) | (None, Some(settings)) => settings.clone(),
+        (None, None) => return None,
+    };
+
+    let has_recipients = settings.to.as_ref().is_some_and(|to| !to.is_empty());
+    (settings.enabled != Some(false) && has_recipients).then_some(settings)
+}
+
+/// Whether `event` sends an email (only successes unless `on` says otherwise)
+pub fn wants(settings: &EmailSettings, event: NotificationEvent) -> bool {
+    match &settings.on {
+        Some(on) => on.contains(&event),
+        None => event == NotificationEvent::Success,
+    }
+}
+
+/// Send the HTML summary to every recipient; returns how many there were
+pub async fn send(
+    settings: &EmailSettings,
+    context: &EmailContext<'_>,
+) -> Result<usize, EmailError> {
+    let from: Mailbox = settings
+        .from
+        .as_deref()
+        .ok_or(EmailError::Incomplete("a from address"))?
+        .parse()?;
+    let to = settings
+        .to
+        .iter()
+        .flatten()
+        .map(|address| address.parse())
+        .collect::<Result<Vec<Mailbox>, _>>()?;
+
+    let changelog = changelog(context);
+    let links = links(context);
+    let subject = subject(context);
+    let text = render_text(context, &changelog, &links);
+    let html = render_html(context, &changelog, &links);
+
+    match settings.provider.unwrap_or_default() {
+        EmailProvider::Smtp => send_smtp(settings, from, &to, subject, text, html).await?,
+        EmailProvider::Sendgrid => {
+            send_sendgrid(settings, &from, &to, &subject, &text, &html).await?
+        }
+    }
+    Ok(to.len())
+}
+
+async fn send_smtp(
+    settings: &EmailSettings,
+    from: Mailbox,
+    to: &[Mailbox],
+    subject: String,
+    text: String,
+    html: String,
+) -> Result<(), EmailError> {
+    let host = settings
+        .smtp_host
+        .as_deref()
+        .ok_or(EmailError::Incomplete("smtp_host"))?;
+    let port = settings.smtp_port.unwrap_or(587);
+
+    let mut message = Message::builder().from(from).subject(subject);
+    for mailbox in to {
+        message = message.to(mailbox.clone());
+    }
+    let message = message.multipart(MultiPart::alternative_plain_html(text, html))?;
+
+    // 465 is implicit TLS; anything else upgrades with STARTTLS
+    let mut transport = if port == 465 {
+        AsyncSmtpTransport::<Tokio1Executor>::relay(host)?
+    } else {
+        AsyncSmtpTransport::<Tokio1Executor>::starttls_relay(host)?
+    }
+    .port(port);
+
+    if let Some(username) = &settings.smtp_username {
+        let password_env = settings
+            .smtp_password_env
+            .as_deref()
+            .unwrap_or("LAUNCHPAD_SMTP_PASSWORD");
+        let password = secret(password_env)?;
+        transport = transport.credentials(Credentials::new(username.clone(), password));
+    }
+
+    transport.build().send(message).await?;
+    Ok(())
+}
+
+async fn send_sendgrid(
+    settings: &EmailSettings,
+    from: &Mailbox,
+    to: &[Mailbox],
+    subject: &str,
+    text: &str,
+    html: &str,
+) -> Result<(), EmailError> {
+    let api_key_env = settings
+        .sendgrid_api_key_env
+        .as_deref()
+        .unwrap_or("SENDGRID_API_KEY");
+    let api_key = secret(api_key_env)?;
+
+    let address = |mailbox: &Mailbox| match &mailbox.name {
+        Some(name) => json!({ "email": mailbox.email.to_string(), "name": name }),
+        None => json!({ "email": mailbox.email.to_string() }),
+    };
+    let body = json!({
+        "personalizations": [{ "to": to.iter().map(address).collect::<Vec<_>>() }],
+        "from": address(from),
+        "subject": subject,
+        "content": [
+            { "type": "text/plain", "value": text },
+            { "type": "text/html", "value": html },
+        ],
+    });
+
+    let response = http::client()?
+        .post(SENDGRID_URL)
+        .bearer_auth(api_key)
+        .header("User-Agent", "launchpad")
+        .json(&body)
+        .send()
+        .await?;
+
+    let status = response.status();
+    if status.is_success() {
+        return Ok(());
+    }
+    let body = response.text().await.unwrap_or_default();
+    Err(EmailError::Api {
+        status: status.as_u16(),
+        body,
+    })
+}
+
+fn secret(name: &str) -> Result<String, EmailError> {
+    match std::env::var(name) {
+        Ok(value) if !value.is_empty() => Ok(value),
+        _ => Err(EmailError::MissingSecret(name.to_string())),
+    }
+}
+
+fn subject(context: &EmailContext) -> String {
+    let prefix = context
+        .environment
+        .map(|environment| format!("[{}] ", environment))
+        .unwrap_or_default();
+    let version = version(context.summary);
+
+    match context.event {
+        NotificationEvent::Success => format!(
+            "{}{}{} is on {}",
+            prefix,
+            context.app,
+            version,
+            store(context.summary)
+        ),
+        NotificationEvent::Failure => format!("{}{} deploy failed", prefix, context.app),
+        NotificationEvent::Processed => {
+            format!("{}{}{} is ready to test", prefix, context.app, version)
+        }
+        NotificationEvent::Review => format!(
+            "{}{}{} App Review: {}",
+            prefix, context.app, version, context.summary.testflight_status
+        ),
+    }
+}
+
+fn version(summary: &DeploySummary) -> String {
+    match (&summary.version, &summary.build) {
+        (Some(version), Some(build)) => format!(" {} ({})", version, build),
+        (Some(version), None) => format!(" {}", version),
+        _ => String::new(),
+    }
+}
+
+fn store(summary: &DeploySummary) -> String {
+    match &summary.play_track {
+        Some(track) => format!("Google Play ({} track)", track),
+        None => "TestFlight".to_string(),
+    }
+}
+
+/// Commit subjects since the previous deploy of this app, newest first
+fn changelog(context: &EmailContext) -> Vec<String> {
+    history::commits_since_last_deploy(
+        context.bundle_id,
+        context.platform,
+        context.summary.git_sha.as_deref(),
+    )
+}
+
+/// CI run, install link and store console, whichever apply
+fn links(context: &EmailContext) -> Vec<(&'static str, String)> {
+    let mut links = Vec::new();
+    if let Some(url) = integrations::ci_run_url() {
+        links.push(("CI run", url));
+    }
+
+    if let Some(url) = context.public_link {
+        links.push(("Install via TestFlight", url.to_string()));
+    }
+    if context.summary.play_track.is_some() {
+        links.push((
+            "Google Play Console",
+            "https://play.google.com/console".to_string(),
+        ));
+    } else {
+        links.push((
+            "App Store Connect",
+            "https://appstoreconnect.apple.com/apps".to_string(),
+        ));
+    }
+
+    links
+}
+
+fn render_text(context: &EmailContext, changelog: &[String], links: &[(&str, String)]) -> String {
+    let mut text = subject(context);
+    text.push_str("\n\n");
+    for (label, value) in details(context) {
+        text.push_str(&format!("{}: {}\n", label, value));
+    }
+    if let Some(error) = &context.summary.error {
+        text.push_str(&format!("\nError:\n{}\n", error.trim()));
+    }
+    if !changelog.is_empty() {
+        text.push_str("\nChanges:\n");
+        for line in changelog {
+            text.push_str(&format!("- {}\n", line));
+        }
+    }
+    if !links.is_empty() {
+        text.push('\n');
+        for (label, url) in links {
+            text.push_str(&format!("{}: {}\n", label, url));
+        }
+    }
+    text
+}
+
+fn render_html(context: &EmailContext, changelog: &[String], links: &[(&str, String)]) -> String {
+    let mut html = String::from(
+        "<!DOCTYPE html><html><body style=\"font-family: -apple-system, Helvetica, Arial, sans-serif; color: #1d1d1f;\">",
+    );
+    html.push_str(&format!("<h2>{}</h2>", escape(&subject(context))));
+
+    html.push_str("<table cellpadding=\"4\" style=\"border-collapse: collapse;\">");
+    for (label, value) in details(context) {
+        html.push_str(&format!(
+            "<tr><td style=\"color: #6e6e73;\">{}</td><td>{}</td></tr>",
+            escape(label),
+            escape(&value)
+        ));
+    }
+    html.push_str("</table>");
+
+    if let Some(error) = &context.summary.error {
+        html.push_str(&format!(
+            "<h3>Error</h3><pre style=\"background: #f5f5f7; padding: 8px;\">{}</pre>",
+            escape(error.trim())
+        ));
+    }
+    if !changelog.is_empty() {
+        html.push_str("<h3>Changes</h3><ul>");
+        for line in changelog {
+            html.push_str(&format!("<li>{}</li>", escape(line)));
+        }
+        html.push_str("</ul>");
+    }
+    if !links.is_empty() {
+        html.push_str("<p>");
+        let anchors: Vec<String> = links
+            .iter()
+            .map(|(label, url)| format!("<a href=\"{}\">{}</a>", escape(url), escape(label)))
+            .collect();
+        html.push_str(&anchors.join(" · "));
+        html.push_str("</p>");
+    }
+
+    html.push_str("</body></html>");
+    html
+}
+
+/// Label/value rows shared by the HTML and plain-text bodies
+fn details(context: &EmailContext) -> Vec<(&'static str, String)> {
+    let summary = context.summary;
+    let mut rows = vec![("App", format!("{} ({})", context.app, context.bundle_id))];
+    if let Some(version) = &summary.version {
+        rows.push(("Version", version.clone()));
+    }
+    if let Some(build) = &summary.build {
+        rows.push(("Build", build.clone()));
+    }
+    if let Some(environment) = context.environment {
+        rows.push(("Environment", environment.to_string()));
+    }
+    rows.push(("Status", summary.testflight_status.clone()));
+    if let Some(sha) = &summary.git_sha {
+        rows.push(("Commit", sha[..sha.len().min(12)].to_string()));
+    }
+    rows.push(("Duration", format!("{}s", summary.duration_secs)));
+    rows
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
@@ -0,0 +1,104 @@
+use serde_json::{json, Value};
+use std::fs::OpenOptions;
+use std::io::Write;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Mutex, OnceLock};
+use std::time::{SystemTime, UNIX_EPOCH};
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+pub enum EventStreamError {
+    #[error("Could not open event stream {path}: {source}")]
+    Open {
+        path: String,
+        source: std::io::Error,
+    },
+}
+
+/// Where `--event-stream` events go; unset when the flag isn't given
+static SINK: OnceLock<Mutex<Box<dyn Write + Send>>> = OnceLock::new();
+
+/// Set when machine output owns stdout and human output moves to stderr
+static ON_STDOUT: AtomicBool = AtomicBool::new(false);
+
+/// Start streaming events to `target`: "-" for stdout, a listening Unix socket,
+/// or a file that is appended to
+pub fn open(target: &str) -> Result<(), EventStreamError> {
+    let sink: Box<dyn Write + Send> = if target == "-" {
+        ON_STDOUT.store(true, Ordering::Relaxed);
+        Box::new(std::io::stdout())
+    } else {
+        open_path(target).map_err(|source| EventStreamError::Open {
+            path: target.to_string(),
+            source,
+        })?
+    };
+    let _ = SINK.set(Mutex::new(sink));
+    Ok(())
+}
+
+#[cfg(unix)]
+fn open_path(path: &str) -> std::io::Result<Box<dyn Write + Send>> {
+    use std::os::unix::fs::FileTypeExt;
+    use std::os::unix::net::UnixStream;
+
+    if std::fs::metadata(path).is_ok_and(|m| m.file_type().is_socket()) {
+        return Ok(Box::new(UnixStream::connect(path)?));
+    }
+    append(path)
+}
+
+#[cfg(not(unix))]
+fn open_path(path: &str) -> std::io::Result<Box<dyn Write + Send>> {
+    append(path)
+}
+
+fn append(path: &str) -> std::io::Result<Box<dyn Write + Send>> {
+    let file = OpenOptions::new().create(true).append(true).open(path)?;
+    Ok(Box::new(file))
+}
+
+/// Reserve stdout for machine output that isn't an event stream (`launchpad serve`)
+pub fn claim_stdout() {
+    ON_STDOUT.store(true, Ordering::Relaxed);
+}
+
+/// Whether stdout carries machine output, so anything meant for people belongs on stderr
+pub fn owns_stdout() -> bool {
+    ON_STDOUT.load(Ordering::Relaxed)
+}
+
+/// Write one NDJSON line: `{"ts": <unix ms>, "event": <event>, ...fields}`.
+/// A no-op without `--event-stream`; a reader that went away doesn't fail the command.
+pub fn emit(event: &str, fields: Value) {
+    let Some(sink) = SINK.get() else {
+        return;
+    };
+
+    let ts = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis() as u64)
+        .unwrap_or_default();
+    let mut line = json!({ "ts": ts, "event": event });
+    if let (Some(line), Value::Object(fields)) = (line.as_object_mut(), fields) {
+        line.extend(fields);
+    }
+
+    if let Ok(mut sink) = sink.lock() {
+        let _ = writeln!(sink, "{}", line);
+        let _ = sink.flush();
+    }
+}
+
+/// A long-running part of a command begins ("build", "upload", "processing", ...)
+pub fn phase(name: &str, message: &str) {
+    emit("phase", json!({ "phase": name, "message": message }));
+}
+
+/// Bytes done out of `total` for the current phase
+pub fn progress(phase: &str, done: u64, total: u64) {
+    emit(
+        "progress",
+        json!({ "phase": phase, "done": done, "total": total }),
+    );
+}
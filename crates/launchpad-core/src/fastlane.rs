@@ -0,0 +1,781 @@
+use crate::cache::CachePaths;
+use crate::config::global::GlobalConfig;
+use crate::config::project::{EnvironmentAppleSettings, ProjectConfig, SigningStyle};
+use crate::events;
+use crate::http;
+use crate::mock;
+use crate::ui;
+use indicatif::ProgressBar;
+use serde::Deserialize;
+use std::collections::{BTreeMap, HashSet, VecDeque};
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::{Path, PathBuf};
+use std::process::Stdio;
+use thiserror::Error;
+use tokio::io::{AsyncBufReadExt, BufReader};
+use tokio::process::Command;
+
+#[derive(Error, Debug)]
+pub enum FastlaneError {
+    #[error("Fastlane command failed: {}", describe_failure(.output, .stderr, .log))]
+    CommandFailed {
+        /// Errors recognized in the output, else its last lines
+        output: String,
+        /// The end of stderr, which interleaved output tends to bury
+        stderr: String,
+        /// Everything the lane printed
+        log: Option<PathBuf>,
+    },
+
+    #[error("fastlane started an Apple ID login instead of using the App Store Connect API key (\"{0}\"), which would wait for a password or 2FA code. Add `app_store_connect_api_key` at the top of the lane (it reads the APP_STORE_CONNECT_API_KEY_* variables launchpad sets) or pass `api_key_path:` to the action, and remove `apple_id` from the Appfile.")]
+    SessionAuth(String),
+
+    #[error("fastlane finished, but App Store Connect never received build {0}. The upload didn't go through; if Apple is only slow to list it, check again with 'launchpad verify'.")]
+    NotReceived(String),
+
+    #[error("IO error: {0}")]
+    Io(#[from] std::io::Error),
+}
+
+/// Written by the generated lanes to LAUNCHPAD_RESULT_PATH after they run
+const RESULT_FILENAME: &str = "fastlane-result.json";
+
+/// What a lane built or uploaded. The generated lanes write it as JSON; for
+/// hand-written lanes it's read back from the output ("Version: 1.2.0 (43)").
+#[derive(Debug, Default, Deserialize)]
+pub struct LaneResult {
+    /// Marketing version ("1.2.0")
+    pub version: Option<String>,
+    /// Build number ("43")
+    pub build: Option<String>,
+    /// Absolute path of the .ipa
+    pub ipa: Option<PathBuf>,
+}
+
+impl LaneResult {
+    /// A "1.2.0 (43)" version from the output
+    fn from_label(label: &str) -> Self {
+        let (version, build) = split_version(label);
+        Self {
+            version: Some(version),
+            build,
+            ..Default::default()
+        }
+    }
+
+    /// "1.2.0 (43)", or "unknown" when the lane didn't say
+    pub fn label(&self) -> String {
+        match (&self.version, &self.build) {
+            (Some(version), Some(build)) => format!("{} ({})", version, build),
+            (Some(version), None) => version.clone(),
+            (None, _) => "unknown".to_string(),
+        }
+    }
+}
+
+pub struct Fastlane {
+    key_id: String,
+    issuer_id: String,
+    key_path: String,
+    ios_path: String,
+    bundle_id: String,
+    output_dir: String,
+    ipa_name: String,
+    configuration: String,
+    export_method: String,
+    /// `build.sdk` and `build.destination`, when set
+    sdk: Option<String>,
+    destination: Option<String>,
+    signing_style: Option<SigningStyle>,
+    provisioning_profiles: String,
+    /// DEVELOPMENT_TEAM and PRODUCT_BUNDLE_IDENTIFIER of `[environments.<name>.apple]`
+    team_id: Option<String>,
+    product_bundle_id: Option<String>,
+    derived_data_path: Option<PathBuf>,
+    spm_cache_path: Option<PathBuf>,
+    cocoapods_home: Option<PathBuf>,
+    /// `[size]` as JSON; turns on the thinned export and the budget check
+    size_budgets: Option<String>,
+    /// TestFlight "What to Test", from the `release_notes` template
+    release_notes: Option<String>,
+    /// `[build.metadata]` keys as JSON, and the Info.plist file they go in
+    build_metadata: Option<(PathBuf, String)>,
+    /// Generated xcconfig with the `[environments.<name>.build_env]` settings
+    xcconfig: Option<PathBuf>,
+    /// Build only (`deploy --skip-upload`)
+    skip_upload: bool,
+    /// Print fastlane's output as it runs instead of only keeping it for errors
+    stream_output: bool,
+    non_interactive: bool,
+}
+
+impl Fastlane {
+    pub fn new(
+        global_config: &GlobalConfig,
+        project_config: &ProjectConfig,
+        non_interactive: bool,
+    ) -> Self {
+        let key_path = shellexpand::tilde(&global_config.apple.key_path).to_string();
+
+        // fastlane runs inside ios_path, so hand it an absolute output directory
+        let build = &project_config.build;
+        let output_dir = std::path::absolute(shellexpand::tilde(&build.output_dir).as_ref())
+            .map(|p| p.to_string_lossy().to_string())
+            .unwrap_or_else(|_| build.output_dir.clone());
+
+        // {version} and {build} are only known inside the lane, after the bump
+        let ipa_name = build
+            .ipa_name_template
+            .replace("{app}", &project_config.project.scheme);
+
+        Self {
+            key_id: global_config.apple.key_id.clone(),
+            issuer_id: global_config.apple.issuer_id.clone(),
+            key_path,
+            ios_path: project_config.project.ios_path.clone(),
+            bundle_id: project_config.project.bundle_id.clone(),
+            output_dir,
+            ipa_name,
+            configuration: build.configuration.clone(),
+            export_method: build.export_method.clone(),
+            sdk: build.sdk.clone(),
+            destination: build.destination.clone(),
+            signing_style: project_config.signing.style,
+            provisioning_profiles: serde_json::to_string(&project_config.signing.profiles)
+                .unwrap_or_default(),
+            team_id: None,
+            product_bundle_id: None,
+            derived_data_path: None,
+            spm_cache_path: None,
+            cocoapods_home: None,
+            size_budgets: project_config
+                .size
+                .as_ref()
+                .and_then(|size| serde_json::to_string(size).ok()),
+            release_notes: None,
+            build_metadata: None,
+            xcconfig: None,
+            skip_upload: false,
+            stream_output: false,
+            non_interactive,
+        }
+    }
+
+    /// Sign for the environment's team and build its bundle ID
+    pub fn with_apple_account(mut self, apple: &EnvironmentAppleSettings) -> Self {
+        self.team_id = apple.team_id.clone();
+        self.product_bundle_id = apple.bundle_id.clone();
+        self
+    }
+
+    /// Build with the persisted DerivedData and package caches
+    pub fn with_cache(mut self, cache: &CachePaths) -> Self {
+        self.derived_data_path = Some(cache.derived_data.clone());
+        self.spm_cache_path = cache.spm.clone();
+        self.cocoapods_home = cache.cocoapods.clone();
+        self
+    }
+
+    /// Upload with these "What to Test" notes
+    pub fn with_release_notes(mut self, notes: String) -> Self {
+        self.release_notes = Some(notes);
+        self
+    }
+
+    /// Write these keys into the Info.plist for the build (restored afterwards)
+    pub fn with_build_metadata(
+        mut self,
+        info_plist: PathBuf,
+        metadata: &BTreeMap<String, String>,
+    ) -> Self {
+        if let Ok(json) = serde_json::to_string(metadata) {
+            self.build_metadata = Some((info_plist, json));
+        }
+        self
+    }
+
+    /// Archive with this xcconfig layered over the project's build settings
+    pub fn with_xcconfig(mut self, path: PathBuf) -> Self {
+        self.xcconfig = Some(path);
+        self
+    }
+
+    /// Stop after the .ipa is built; the lanes skip `upload_to_testflight`
+    pub fn with_skip_upload(mut self) -> Self {
+        self.skip_upload = true;
+        self
+    }
+
+    /// Show fastlane's output line by line
+    pub fn with_output_streaming(mut self) -> Self {
+        self.stream_output = true;
+        self
+    }
+
+    /// Absolute directory the lanes write build artifacts to
+    pub fn output_dir(&self) -> &str {
+        &self.output_dir
+    }
+
+    pub async fn deploy(&self, version_bump: Option<&str>) -> Result<LaneResult, FastlaneError> {
+        let lane = match version_bump {
+            Some("patch") => "beta_patch",
+            Some("minor") => "beta_minor",
+            Some("major") => "beta_major",
+            _ => "beta",
+        };
+        self.run_lane(lane, None, &[]).await
+    }
+
+    /// Upload an .ipa from an earlier build without archiving again
+    pub async fn upload(&self, ipa: &Path) -> Result<LaneResult, FastlaneError> {
+        self.run_lane("upload_only", Some(ipa), &[]).await
+    }
+
+    /// Run any lane of the Fastfile with the same environment; `options` are
+    /// fastlane's "key:value" lane options
+    pub async fn lane(&self, name: &str, options: &[String]) -> Result<(), FastlaneError> {
+        self.run_lane(name, None, options).await.map(|_| ())
+    }
+
+    async fn run_lane(
+        &self,
+        lane: &str,
+        ipa: Option<&Path>,
+        options: &[String],
+    ) -> Result<LaneResult, FastlaneError> {
+        // A result left over from an earlier run mustn't pass for this one's
+        let result_path = Path::new(&self.output_dir).join(RESULT_FILENAME);
+        let _ = std::fs::remove_file(&result_path);
+        if mock::enabled() {
+            return self.mock_lane(lane);
+        }
+
+        // Build the fastlane command
+        let mut cmd = Command::new("fastlane");
+        cmd.current_dir(&self.ios_path)
+            .arg(lane)
+            .args(options)
+            .env("APP_STORE_CONNECT_API_KEY_KEY_ID", &self.key_id)
+            .env("APP_STORE_CONNECT_API_KEY_ISSUER_ID", &self.issuer_id)
+            .env("APP_STORE_CONNECT_API_KEY_KEY_FILEPATH", &self.key_path)
+            .env("FASTLANE_XCODEBUILD_SETTINGS_TIMEOUT", "180")
+            .env("LAUNCHPAD_BUNDLE_ID", &self.bundle_id)
+            .env("LAUNCHPAD_OUTPUT_DIR", &self.output_dir)
+            .env("LAUNCHPAD_RESULT_PATH", &result_path)
+            .env("LAUNCHPAD_IPA_NAME", &self.ipa_name)
+            .env("LAUNCHPAD_CONFIGURATION", &self.configuration)
+            .env("LAUNCHPAD_EXPORT_METHOD", &self.export_method)
+            .envs(http::child_env())
+            .env(
+                "LAUNCHPAD_PROVISIONING_PROFILES",
+                &self.provisioning_profiles,
+            )
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped());
+
+        if let Some(style) = self.signing_style {
+            cmd.env("LAUNCHPAD_SIGNING_STYLE", style.to_string());
+        }
+        if let Some(team_id) = &self.team_id {
+            cmd.env("LAUNCHPAD_TEAM_ID", team_id);
+        }
+        if let Some(bundle_id) = &self.product_bundle_id {
+            cmd.env("LAUNCHPAD_PRODUCT_BUNDLE_ID", bundle_id);
+        }
+
+        if let Some(sdk) = &self.sdk {
+            cmd.env("LAUNCHPAD_SDK", sdk);
+        }
+        if let Some(destination) = &self.destination {
+            cmd.env("LAUNCHPAD_DESTINATION", destination);
+        }
+
+        if let Some(ipa) = ipa {
+            cmd.env("LAUNCHPAD_IPA_PATH", ipa);
+        }
+        if let Some(path) = &self.derived_data_path {
+            cmd.env("LAUNCHPAD_DERIVED_DATA_PATH", path);
+        }
+        if let Some(path) = &self.spm_cache_path {
+            cmd.env("LAUNCHPAD_SPM_CACHE_PATH", path);
+        }
+        if let Some(path) = &self.cocoapods_home {
+            cmd.env("CP_HOME_DIR", path);
+        }
+        if let Some(budgets) = &self.size_budgets {
+            cmd.env(crate::commands::size::BUDGETS_ENV, budgets);
+            // The lane runs `launchpad size --check` before uploading
+            if let Ok(launchpad) = std::env::current_exe() {
+                cmd.env("LAUNCHPAD_BIN", launchpad);
+            }
+        }
+
+        if let Some(notes) = &self.release_notes {
+            cmd.env("LAUNCHPAD_CHANGELOG", notes);
+        }
+        if let Some((info_plist, metadata)) = &self.build_metadata {
+            cmd.env("LAUNCHPAD_INFO_PLIST", info_plist)
+                .env("LAUNCHPAD_BUILD_METADATA", metadata);
+        }
+        if let Some(path) = &self.xcconfig {
+            cmd.env("LAUNCHPAD_XCCONFIG", path);
+        }
+        if self.skip_upload {
+            cmd.env("LAUNCHPAD_SKIP_UPLOAD", "1");
+        }
+
+        // A prompt from fastlane (e.g. an Apple ID login) should fail, not hang
+        if self.non_interactive {
+            cmd.stdin(Stdio::null());
+        }
+
+        let mut child = cmd.spawn()?;
+
+        let stdout = child.stdout.take().expect("stdout not captured");
+        let stderr = child.stderr.take().expect("stderr not captured");
+
+        let mut stdout_reader = BufReader::new(stdout).lines();
+        let mut stderr_reader = BufReader::new(stderr).lines();
+
+        let log = Path::new(&self.output_dir).join(format!("fastlane-{}.log", lane));
+        let mut output =
+            LaneOutput::new(self.stream_output, log).with_upload_progress(&self.output_dir, ipa);
+        let mut stdout_open = true;
+        let mut stderr_open = true;
+
+        // Stream output and capture version, until both pipes close
+        while stdout_open || stderr_open {
+            let (line, from_stderr) = tokio::select! {
+                line = stdout_reader.next_line(), if stdout_open => match line {
+                    Ok(Some(line)) => {
+                        output.read_version(&line);
+                        (line, false)
+                    }
+                    Ok(None) | Err(_) => {
+                        stdout_open = false;
+                        continue;
+                    }
+                },
+                line = stderr_reader.next_line(), if stderr_open => match line {
+                    Ok(Some(line)) => (line, true),
+                    Ok(None) | Err(_) => {
+                        stderr_open = false;
+                        continue;
+                    }
+                },
+            };
+            if let Err(e) = output.push(&line, from_stderr) {
+                let _ = child.kill().await;
+                return Err(e);
+            }
+        }
+
+        let status = child.wait().await?;
+        output.finish();
+
+        if !status.success() {
+            return Err(output.failure());
+        }
+
+        let result = std::fs::read_to_string(&result_path)
+            .ok()
+            .and_then(|content| serde_json::from_str(&content).ok());
+        Ok(match result {
+            Some(result) => result,
+            None if output.version.is_empty() => LaneResult::default(),
+            None => LaneResult::from_label(&output.version),
+        })
+    }
+
+    /// `--mock`: canned output in place of fastlane's, read the same way
+    fn mock_lane(&self, lane: &str) -> Result<LaneResult, FastlaneError> {
+        let log = Path::new(&self.output_dir).join(format!("fastlane-{}.log", lane));
+        let mut output =
+            LaneOutput::new(self.stream_output, log).with_upload_progress(&self.output_dir, None);
+        for line in mock::lane_output(lane, self.skip_upload) {
+            output.read_version(&line);
+            output.push(&line, false)?;
+        }
+        output.finish();
+        Ok(match output.version.as_str() {
+            "" => LaneResult::default(),
+            version => LaneResult::from_label(version),
+        })
+    }
+}
+
+/// What a line of fastlane or xcodebuild output reports
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum LineKind {
+    Info,
+    Warning,
+    Error,
+}
+
+/// Lines kept for the error message when nothing was recognized as an error
+const TAIL_LINES: usize = 10;
+
+/// Lines of stderr kept for the error message
+const STDERR_LINES: usize = 20;
+
+/// Classifies fastlane's output as it arrives: errors are shown right away (every
+/// line, with `stream`), a warning xcodebuild repeats for each architecture or
+/// target is shown once, and the errors are kept for the failure message
+struct LaneOutput {
+    stream: bool,
+    version: String,
+    errors: Vec<String>,
+    warnings: HashSet<String>,
+    repeated: usize,
+    tail: VecDeque<String>,
+    stderr: VecDeque<String>,
+    /// Every line, as printed; None when the file can't be created
+    log: Option<(PathBuf, BufWriter<File>)>,
+    upload: Option<UploadProgress>,
+}
+
+/// The TestFlight upload step, followed through the progress Transporter prints
+struct UploadProgress {
+    /// Where the lane writes the .ipa, or the one `upload_only` sends
+    output_dir: PathBuf,
+    ipa: Option<PathBuf>,
+    /// Inside the `upload_to_testflight` step
+    active: bool,
+    /// Shown from the first progress line, so Fastfiles that don't print any
+    /// keep the spinner
+    bar: Option<(ProgressBar, u64)>,
+    percent: u64,
+}
+
+impl UploadProgress {
+    fn line(&mut self, message: &str) {
+        if let Some(step) = message
+            .strip_prefix("--- Step: ")
+            .and_then(|step| step.strip_suffix(" ---"))
+        {
+            self.finish();
+            self.active = matches!(step, "upload_to_testflight" | "pilot");
+            if self.active {
+                events::phase("upload", "Uploading to TestFlight");
+            }
+            return;
+        }
+        if !self.active {
+            return;
+        }
+        let Some(percent) = upload_percent(message) else {
+            return;
+        };
+
+        let (bar, size) = match &self.bar {
+            Some(bar) => bar,
+            None => {
+                let Some(size) = self.ipa_size() else {
+                    return;
+                };
+                self.bar = Some((ui::progress_bar(size, "Uploading to TestFlight"), size));
+                self.bar.as_ref().expect("bar was just set")
+            }
+        };
+        let done = (size * percent / 100).min(*size);
+        bar.set_position(done);
+        if percent != self.percent {
+            self.percent = percent;
+            events::progress("upload", done, *size);
+        }
+    }
+
+    fn finish(&mut self) {
+        if let Some((bar, _)) = self.bar.take() {
+            bar.finish_and_clear();
+        }
+        self.active = false;
+    }
+
+    /// Size of the .ipa being uploaded: the given one, else the newest the lane built
+    fn ipa_size(&self) -> Option<u64> {
+        let ipa = match &self.ipa {
+            Some(ipa) => ipa.clone(),
+            None => {
+                std::fs::read_dir(&self.output_dir)
+                    .ok()?
+                    .flatten()
+                    .filter(|entry| entry.file_name().to_string_lossy().ends_with(".ipa"))
+                    .filter_map(|entry| {
+                        Some((entry.metadata().ok()?.modified().ok()?, entry.path()))
+                    })
+                    .max_by_key(|(modified, _)| *modified)?
+                    .1
+            }
+        };
+        std::fs::metadata(ipa)
+            .ok()
+            .map(|m| m.len())
+            .filter(|size| *size > 0)
+    }
+}
+
+/// Whole percent from a Transporter progress line ("Upload progress: 42.5%")
+fn upload_percent(message: &str) -> Option<u64> {
+    if !message.to_lowercase().contains("progress") {
+        return None;
+    }
+    let re = regex_lite::Regex::new(r"(\d{1,3})(?:\.\d+)?\s?%").expect("valid regex");
+    let percent: u64 = re.captures(message)?[1].parse().ok()?;
+    Some(percent).filter(|percent| *percent <= 100)
+}
+
+impl LaneOutput {
+    fn new(stream: bool, log: PathBuf) -> Self {
+        let file = log
+            .parent()
+            .map_or(Ok(()), std::fs::create_dir_all)
+            .and_then(|()| File::create(&log));
+        Self {
+            stream,
+            version: String::new(),
+            errors: Vec::new(),
+            warnings: HashSet::new(),
+            repeated: 0,
+            tail: VecDeque::with_capacity(TAIL_LINES),
+            stderr: VecDeque::with_capacity(STDERR_LINES),
+            log: file.ok().map(|file| (log, BufWriter::new(file))),
+            upload: None,
+        }
+    }
+
+    /// Turn Transporter's progress during `upload_to_testflight` into a progress
+    /// bar sized to the .ipa
+    fn with_upload_progress(mut self, output_dir: &str, ipa: Option<&Path>) -> Self {
+        self.upload = Some(UploadProgress {
+            output_dir: PathBuf::from(output_dir),
+            ipa: ipa.map(Path::to_path_buf),
+            active: false,
+            bar: None,
+            percent: 0,
+        });
+        self
+    }
+
+    fn push(&mut self, line: &str, from_stderr: bool) -> Result<(), FastlaneError> {
+        let line = console::strip_ansi_codes(line);
+        if let Some((_, file)) = &mut self.log {
+            let _ = writeln!(file, "{}", line);
+        }
+        if from_stderr {
+            keep_last(&mut self.stderr, STDERR_LINES, &line);
+        }
+        if let Some(e) = session_auth(&line) {
+            return Err(e);
+        }
+
+        let message = message(&line);
+        if let Some(upload) = &mut self.upload {
+            upload.line(message);
+        }
+        match classify(message) {
+            LineKind::Error => {
+                ui::output_error(message);
+                if !self.errors.iter().any(|e| e == message) {
+                    self.errors.push(message.to_string());
+                }
+            }
+            LineKind::Warning => {
+                if !self.warnings.insert(message.to_string()) {
+                    self.repeated += 1;
+                    return Ok(());
+                }
+                if self.stream {
+                    ui::output_warning(message);
+                }
+            }
+            LineKind::Info => {
+                if self.stream {
+                    ui::output(&line);
+                }
+            }
+        }
+
+        if !from_stderr {
+            keep_last(&mut self.tail, TAIL_LINES, &line);
+        }
+        Ok(())
+    }
+
+    /// Look for the version and build number the lane reports
+    fn read_version(&mut self, line: &str) {
+        let reports_version = line.contains("Version:")
+            || line.contains("version:")
+            || line.contains("Successfully uploaded")
+            || line.contains("Build");
+        if reports_version {
+            if let Some(version) = extract_version(line) {
+                self.version = version;
+            }
+        }
+    }
+
+    fn finish(&mut self) {
+        if let Some(upload) = &mut self.upload {
+            upload.finish();
+        }
+        if let Some((_, file)) = &mut self.log {
+            let _ = file.flush();
+        }
+        if self.stream && self.repeated > 0 {
+            ui::step(&format!(
+                "Hid {} repeated warning{}",
+                self.repeated,
+                if self.repeated == 1 { "" } else { "s" }
+            ));
+        }
+    }
+
+    /// The errors fastlane and xcodebuild reported (else the last lines of
+    /// stdout), the end of stderr and where the whole output went
+    fn failure(self) -> FastlaneError {
+        let output = if self.errors.is_empty() {
+            Vec::from(self.tail).join("\n")
+        } else {
+            let skip = self.errors.len().saturating_sub(TAIL_LINES);
+            self.errors[skip..].join("\n")
+        };
+        FastlaneError::CommandFailed {
+            output,
+            stderr: Vec::from(self.stderr).join("\n"),
+            log: self.log.map(|(path, _)| path),
+        }
+    }
+}
+
+fn keep_last(lines: &mut VecDeque<String>, limit: usize, line: &str) {
+    if lines.len() == limit {
+        lines.pop_front();
+    }
+    lines.push_back(line.to_string());
+}
+
+fn describe_failure(output: &str, stderr: &str, log: &Option<PathBuf>) -> String {
+    let mut text = output.to_string();
+    // Errors on stderr are usually among the recognized ones already
+    if !stderr.is_empty() && !stderr.lines().all(|line| output.contains(line)) {
+        text.push_str("\n\nstderr:\n");
+        text.push_str(stderr);
+    }
+    if let Some(log) = log {
+        text.push_str(&format!("\n\nFull log: {}", log.display()));
+    }
+    text
+}
+
+/// A line without fastlane's "[12:00:00]: " prefix and xcpretty's "▸ "
+fn message(line: &str) -> &str {
+    let line = line.trim();
+    let line = match line.split_once("]: ") {
+        Some((time, rest)) if time.starts_with('[') => rest,
+        _ => line,
+    };
+    line.trim_start_matches('▸').trim()
+}
+
+fn classify(message: &str) -> LineKind {
+    const ERRORS: &[&str] = &[
+        ": error:",
+        ": fatal error:",
+        "❌",
+        "** BUILD FAILED **",
+        "** ARCHIVE FAILED **",
+        "** EXPORT FAILED **",
+        "ERROR ITMS-",
+        "Error building the application",
+    ];
+    const WARNINGS: &[&str] = &[": warning:", "⚠️"];
+
+    if message.starts_with("error:")
+        || message.starts_with("[!]")
+        || ERRORS.iter().any(|pattern| message.contains(pattern))
+    {
+        LineKind::Error
+    } else if message.starts_with("warning:")
+        || WARNINGS.iter().any(|pattern| message.contains(pattern))
+    {
+        LineKind::Warning
+    } else {
+        LineKind::Info
+    }
+}
+
+/// Lanes a Fastfile defines that can be run from the command line (not `private_lane`)
+pub fn lanes(fastfile: &str) -> Vec<String> {
+    let re = regex_lite::Regex::new(r"^\s*lane\s+:(\w+)").expect("valid regex");
+    fastfile
+        .lines()
+        .filter_map(|line| re.captures(line).map(|caps| caps[1].to_string()))
+        .collect()
+}
+
+/// fastlane output that only shows up when it logs in with an Apple ID session
+/// (no API key reached the action), just before it prompts for a password or code
+const SESSION_AUTH_MARKERS: &[&str] = &[
+    "Login to App Store Connect (",
+    "Please provide your Apple Developer Program account credentials",
+    "Two-factor Authentication (6 digits code) is enabled",
+    "Two-step Verification (4 digits code) is enabled",
+    "Please enter the 6 digit code",
+    "Available session is not valid",
+    "Need to acknowledge to Apple's Apple ID and Privacy statement",
+];
+
+fn session_auth(line: &str) -> Option<FastlaneError> {
+    if !SESSION_AUTH_MARKERS
+        .iter()
+        .any(|marker| line.contains(marker))
+    {
+        return None;
+    }
+    Some(FastlaneError::SessionAuth(message(line).to_string()))
+}
+
+fn extract_version(line: &str) -> Option<String> {
+    // Try to find version patterns like "1.0.0", "1.0.0 (123)", etc.
+    let re = regex_lite::Regex::new(r"(\d+\.\d+\.\d+)(?:\s*\((\d+)\))?").ok()?;
+
+    if let Some(caps) = re.captures(line) {
+        let version = caps.get(1)?.as_str();
+        if let Some(build) = caps.get(2) {
+            return Some(format!("{} ({})", version, build.as_str()));
+        }
+        return Some(version.to_string());
+    }
+
+    None
+}
+
+/// Split a version string from `extract_version` ("1.0.0 (42)") into version and build
+pub fn split_version(version: &str) -> (String, Option<String>) {
+    match version.split_once(" (") {
+        Some((v, build)) => (v.to_string(), Some(build.trim_end_matches(')').to_string())),
+        None => (version.to_string(), None),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn upload_percent_reads_transporter_progress() {
+        assert_eq!(
+            upload_percent("[Transporter]: Upload progress: 42.5%, 10 MB of 24 MB"),
+            Some(42)
+        );
+        assert_eq!(
+            upload_percent("[Transporter]: Upload progress: 100%"),
+            Some(100)
+        );
+        assert_eq!(upload_percent("Compiling 50% of the sources"), None);
+        assert_eq!(upload_percent("Upload progress: pending"), None);
+    }
+}
@@ -0,0 +1,204 @@
+use crate::ui;
+use std::ffi::OsString;
+use std::fs::{File, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+/// Holds `<file>.lock` until dropped, so launchpad processes sharing
+/// ~/.launchpad (CI jobs on one runner) change a file one at a time
+pub struct FileLock {
+    _file: File,
+}
+
+/// Wait for exclusive use of `path`. Readers don't need it: writes are atomic.
+pub fn lock(path: &Path) -> std::io::Result<FileLock> {
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir)?;
+    }
+    let file = OpenOptions::new()
+        .create(true)
+        .truncate(false)
+        .write(true)
+        .open(sibling(path, "lock"))?;
+    file.lock()?;
+    Ok(FileLock { _file: file })
+}
+
+/// `<file>.<suffix>` next to `path`
+fn sibling(path: &Path, suffix: &str) -> PathBuf {
+    let mut name = OsString::from(path.as_os_str());
+    name.push(".");
+    name.push(suffix);
+    PathBuf::from(name)
+}
+
+/// Where the last good copy of `path` is kept
+pub fn backup_path(path: &Path) -> PathBuf {
+    sibling(path, "bak")
+}
+
+/// Replace `path` with `content` in one step, through a synced temp file that's
+/// renamed over it, so neither a concurrent reader nor a crash sees half a file.
+/// The same content goes to `<file>.bak` for [`read_recovering`].
+pub fn write_atomic(path: &Path, content: &[u8]) -> std::io::Result<()> {
+    // Keep a file that was private private, backup included
+    let mode = mode_of(path);
+    replace(path, content, mode)?;
+    replace(&backup_path(path), content, mode)
+}
+
+/// [`write_atomic`] without the backup; `mode` (Unix permissions) applies to a
+/// new file, and an existing one keeps its own
+pub fn replace(path: &Path, content: &[u8], mode: Option<u32>) -> std::io::Result<()> {
+    let temp = sibling(path, &format!("tmp-{}", std::process::id()));
+    let mut options = OpenOptions::new();
+    options.write(true).create(true).truncate(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::OpenOptionsExt;
+        if let Some(mode) = mode_of(path).or(mode) {
+            options.mode(mode);
+        }
+    }
+    #[cfg(not(unix))]
+    let _ = mode;
+
+    let written = options.open(&temp).and_then(|mut file| {
+        file.write_all(content)?;
+        file.sync_all()
+    });
+    if let Err(e) = written.and_then(|()| std::fs::rename(&temp, path)) {
+        let _ = std::fs::remove_file(&temp);
+        return Err(e);
+    }
+    Ok(())
+}
+
+#[cfg(unix)]
+fn mode_of(path: &Path) -> Option<u32> {
+    use std::os::unix::fs::MetadataExt;
+    std::fs::metadata(path).ok().map(|m| m.mode() & 0o777)
+}
+
+#[cfg(not(unix))]
+fn mode_of(_path: &Path) -> Option<u32> {
+    None
+}
+
+/// Read and parse `path`. When it doesn't parse, the backup launchpad last wrote
+/// takes its place and the broken file is kept as `<file>.corrupt` to look at;
+/// without a usable backup the original error stands. None when the file doesn't exist.
+/// Callers may hold [`lock`]; this doesn't take it, as a second lock would wait on the first.
+pub fn read_recovering<T, E>(
+    path: &Path,
+    parse: impl Fn(&str) -> Result<T, E>,
+) -> Result<Option<T>, E>
+where
+    E: std::fmt::Display + From<std::io::Error>,
+{
+    if !path.exists() {
+        return Ok(None);
+    }
+    let content = std::fs::read_to_string(path)?;
+    let error = match parse(&content) {
+        Ok(value) => return Ok(Some(value)),
+        Err(e) => e,
+    };
+
+    let backup = backup_path(path);
+    let Some(restored) = std::fs::read_to_string(&backup)
+        .ok()
+        .filter(|saved| *saved != content)
+        .and_then(|saved| Some((parse(&saved).ok()?, saved)))
+    else {
+        return Err(error);
+    };
+    let (value, saved) = restored;
+
+    let corrupt = sibling(path, "corrupt");
+    std::fs::rename(path, &corrupt)?;
+    replace(path, saved.as_bytes(), mode_of(&backup))?;
+    ui::warn(&format!(
+        "{} was corrupt ({}); restored the last good copy and kept the broken one as {}",
+        path.display(),
+        error,
+        corrupt.display()
+    ));
+    Ok(Some(value))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::Value;
+
+    /// An empty directory of the test's own
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir =
+            std::env::temp_dir().join(format!("launchpad-files-{}-{}", name, std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    fn read_json(path: &Path) -> std::io::Result<Option<Value>> {
+        read_recovering(path, |content| {
+            serde_json::from_str(content).map_err(std::io::Error::other)
+        })
+    }
+
+    #[test]
+    fn atomic_write_keeps_a_backup() {
+        let dir = temp_dir("backup");
+        let path = dir.join("state.json");
+        write_atomic(&path, br#"{"seen": 1}"#).unwrap();
+
+        assert_eq!(
+            std::fs::read(backup_path(&path)).unwrap(),
+            br#"{"seen": 1}"#
+        );
+        assert_eq!(read_json(&path).unwrap().unwrap()["seen"], 1);
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn corrupt_file_is_restored_from_the_backup() {
+        let dir = temp_dir("restore");
+        let path = dir.join("state.json");
+        write_atomic(&path, br#"{"seen": 1}"#).unwrap();
+        std::fs::write(&path, r#"{"seen": "#).unwrap();
+
+        assert_eq!(read_json(&path).unwrap().unwrap()["seen"], 1);
+        assert_eq!(std::fs::read(&path).unwrap(), br#"{"seen": 1}"#);
+        assert_eq!(
+            std::fs::read(sibling(&path, "corrupt")).unwrap(),
+            br#"{"seen": "#
+        );
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn corrupt_file_without_backup_is_an_error() {
+        let dir = temp_dir("no-backup");
+        let path = dir.join("state.json");
+        std::fs::write(&path, r#"{"seen": "#).unwrap();
+
+        assert!(read_json(&path).is_err());
+        assert!(!sibling(&path, "corrupt").exists());
+        assert!(read_json(&dir.join("missing.json")).unwrap().is_none());
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn private_files_stay_private() {
+        let dir = temp_dir("mode");
+        let path = dir.join("state.json");
+        replace(&path, b"{}", Some(0o600)).unwrap();
+        write_atomic(&path, br#"{"seen": 1}"#).unwrap();
+
+        assert_eq!(mode_of(&path), Some(0o600));
+        assert_eq!(mode_of(&backup_path(&path)), Some(0o600));
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,290 @@
+use std::path::Path;
+use std::process::Command;
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+pub enum GitError {
+    #[error("Not in a git repository: {0}")]
+    Discover(#[from] Box<gix::discover::Error>),
+
+    #[error("Couldn't read the working tree status: {0}")]
+    Status(#[from] Box<gix::status::Error>),
+
+    #[error("Couldn't read the working tree status: {0}")]
+    StatusIter(#[from] Box<gix::status::into_iter::Error>),
+
+    #[error("Couldn't read the working tree status: {0}")]
+    StatusItem(#[from] Box<gix::status::iter::Error>),
+
+    #[error("HEAD doesn't point at a commit: {0}")]
+    Head(#[from] Box<gix::reference::head_id::Error>),
+
+    #[error("Not a commit in this repository: {0}")]
+    Commit(String),
+
+    #[error("No committer identity; set user.name and user.email")]
+    NoIdentity,
+
+    #[error("Invalid committer identity: {0}")]
+    Identity(#[from] Box<gix::config::time::Error>),
+
+    #[error("Tag '{0}' already exists")]
+    TagExists(String),
+
+    #[error("{0}")]
+    Tag(#[from] Box<gix::tag::Error>),
+
+    #[error("{0}")]
+    Reference(#[from] Box<gix::reference::find::Error>),
+
+    #[error("Couldn't read the index: {0}")]
+    Index(#[from] Box<gix::worktree::open_index::Error>),
+
+    #[error("Couldn't read .gitmodules: {0}")]
+    Submodules(#[from] Box<gix::submodule::modules::Error>),
+
+    #[error("Couldn't read a submodule's status: {0}")]
+    SubmoduleStatus(#[from] Box<gix::submodule::status::Error>),
+
+    #[error("Invalid submodule path: {0}")]
+    SubmodulePath(#[from] Box<gix::submodule::config::path::Error>),
+
+    #[error("git isn't on PATH; `git {0}` needs it")]
+    NotInstalled(String),
+
+    #[error("`git {command}` failed: {stderr}")]
+    Failed { command: String, stderr: String },
+
+    #[error("IO error: {0}")]
+    Io(#[from] std::io::Error),
+}
+
+/// gix's errors are large; box them so `Result<_, GitError>` stays small
+macro_rules! boxed_from {
+    ($($source:ty),*) => {
+        $(impl From<$source> for GitError {
+            fn from(e: $source) -> Self {
+                Box::new(e).into()
+            }
+        })*
+    };
+}
+
+boxed_from!(
+    gix::discover::Error,
+    gix::status::Error,
+    gix::status::into_iter::Error,
+    gix::status::iter::Error,
+    gix::reference::head_id::Error,
+    gix::config::time::Error,
+    gix::tag::Error,
+    gix::reference::find::Error,
+    gix::worktree::open_index::Error,
+    gix::submodule::modules::Error,
+    gix::submodule::status::Error,
+    gix::submodule::config::path::Error
+);
+
+/// How a file checked out without `git lfs pull` starts
+const LFS_POINTER: &[u8] = b"version https://git-lfs.github.com/spec/v1";
+
+/// The repository containing the current directory; worktrees and submodules
+/// resolve to their own git directory
+pub fn open() -> Result<gix::Repository, GitError> {
+    Ok(gix::discover(".")?)
+}
+
+/// No changes, staged or not, and no untracked files
+pub fn is_clean() -> Result<bool, GitError> {
+    Ok(!changed(Vec::new(), true)?)
+}
+
+/// Whether tracked files under `path` differ from HEAD
+pub fn has_changes(path: &Path) -> Result<bool, GitError> {
+    let pattern = path.to_string_lossy().to_string();
+    changed(vec![pattern.into()], false)
+}
+
+fn changed(patterns: Vec<gix::bstr::BString>, untracked: bool) -> Result<bool, GitError> {
+    let repo = open()?;
+    let untracked = if untracked {
+        gix::status::UntrackedFiles::Collapsed
+    } else {
+        gix::status::UntrackedFiles::None
+    };
+    let mut changes = repo
+        .status(gix::progress::Discard)?
+        .untracked_files(untracked)
+        .into_iter(patterns)?;
+    match changes.next() {
+        Some(change) => {
+            change?;
+            Ok(true)
+        }
+        None => Ok(false),
+    }
+}
+
+/// Full SHA of the commit HEAD points at
+pub fn head_sha() -> Option<String> {
+    let repo = open().ok()?;
+    let id = repo.head_id().ok()?;
+    Some(id.to_string())
+}
+
+/// Name of the checked-out branch; None when HEAD is detached
+pub fn branch() -> Option<String> {
+    let repo = open().ok()?;
+    let name = repo.head_name().ok()??;
+    Some(name.shorten().to_string())
+}
+
+/// A config value as the repository sees it, or from the global config outside one
+pub fn config_value(key: &str) -> Option<String> {
+    let value = match open() {
+        Ok(repo) => repo.config_snapshot().string(key)?.to_string(),
+        Err(_) => gix::config::File::from_globals()
+            .ok()?
+            .string(key)?
+            .to_string(),
+    };
+    Some(value).filter(|value| !value.is_empty())
+}
+
+/// Submodules a build would be missing or get the wrong sources from: not
+/// checked out, or at another commit than the superproject records. None when
+/// there are no submodules; ones with `update = none` are skipped.
+pub fn submodule_problems() -> Result<Option<Vec<String>>, GitError> {
+    let repo = open()?;
+    let submodules = match repo.submodules()? {
+        Some(submodules) => submodules,
+        None => return Ok(None),
+    };
+
+    let mut problems = Vec::new();
+    for submodule in submodules {
+        if let Ok(Some(gix::submodule::config::Update::None)) = submodule.update() {
+            continue;
+        }
+        let path = submodule.path()?.to_string();
+        let status = submodule.status(gix::submodule::config::Ignore::Dirty, false)?;
+        if !status.state.repository_exists || !status.state.worktree_checkout {
+            problems.push(format!("{} isn't checked out", path));
+        } else if status.checked_out_head_id != status.index_id {
+            problems.push(format!("{} is at another commit than recorded", path));
+        }
+    }
+    Ok(Some(problems))
+}
+
+/// Tracked files that still hold a Git LFS pointer instead of their content.
+/// None when no .gitattributes routes files through LFS.
+pub fn lfs_pointers() -> Result<Option<Vec<String>>, GitError> {
+    let repo = open()?;
+    let root = match repo.workdir() {
+        Some(root) => root.to_path_buf(),
+        None => return Ok(None),
+    };
+    let index = repo.index_or_empty()?;
+    let files: Vec<_> = index
+        .entries()
+        .iter()
+        .filter(|entry| {
+            entry.mode == gix::index::entry::Mode::FILE
+                || entry.mode == gix::index::entry::Mode::FILE_EXECUTABLE
+        })
+        .map(|entry| (entry, root.join(gix::path::from_bstr(entry.path(&index)))))
+        .collect();
+
+    let uses_lfs = files.iter().any(|(_, path)| {
+        path.file_name()
+            .is_some_and(|name| name == ".gitattributes")
+            && std::fs::read_to_string(path).is_ok_and(|text| text.contains("filter=lfs"))
+    });
+    if !uses_lfs {
+        return Ok(None);
+    }
+
+    // Pointers are ~130 bytes; only small files are worth opening
+    let pointers = files
+        .iter()
+        .filter(|(entry, _)| entry.stat.size < 1024)
+        .filter(|(_, path)| {
+            std::fs::read(path).is_ok_and(|content| content.starts_with(LFS_POINTER))
+        })
+        .map(|(entry, _)| entry.path(&index).to_string())
+        .collect();
+    Ok(Some(pointers))
+}
+
+/// Whether `sha` names a commit in this repository
+pub fn has_commit(sha: &str) -> bool {
+    open()
+        .ok()
+        .and_then(|repo| resolve_commit(&repo, sha).ok())
+        .is_some()
+}
+
+/// Create an annotated tag on `target` (a commit SHA, or HEAD). Signed tags go
+/// through `git tag`, since gix can't sign.
+pub fn create_tag(name: &str, message: &str, target: Option<&str>) -> Result<(), GitError> {
+    let repo = open()?;
+    if repo
+        .try_find_reference(format!("refs/tags/{}", name).as_str())?
+        .is_some()
+    {
+        return Err(GitError::TagExists(name.to_string()));
+    }
+
+    let target = match target {
+        Some(sha) => resolve_commit(&repo, sha)?,
+        None => repo.head_id()?.detach(),
+    };
+    let tagger = repo.committer().ok_or(GitError::NoIdentity)??;
+    repo.tag(
+        name,
+        target,
+        gix::object::Kind::Commit,
+        Some(tagger),
+        message,
+        gix::refs::transaction::PreviousValue::MustNotExist,
+    )?;
+    Ok(())
+}
+
+fn resolve_commit(repo: &gix::Repository, sha: &str) -> Result<gix::ObjectId, GitError> {
+    let id =
+        gix::ObjectId::from_hex(sha.as_bytes()).map_err(|_| GitError::Commit(sha.to_string()))?;
+    repo.find_commit(id)
+        .map(|commit| commit.id)
+        .map_err(|_| GitError::Commit(sha.to_string()))
+}
+
+/// Run a git command gix doesn't cover (push, notes, commit, signing)
+pub fn run(args: &[&str], paths: &[&Path]) -> Result<(), GitError> {
+    execute(args, paths).map(|_| ())
+}
+
+/// Run a git command gix doesn't cover and return its stdout (log ranges,
+/// `ls-files` pathspecs, worktrees)
+pub fn output(args: &[&str]) -> Result<String, GitError> {
+    execute(args, &[])
+}
+
+fn execute(args: &[&str], paths: &[&Path]) -> Result<String, GitError> {
+    let command = args.join(" ");
+    let output = match Command::new("git").args(args).args(paths).output() {
+        Ok(output) => output,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+            return Err(GitError::NotInstalled(command))
+        }
+        Err(e) => return Err(e.into()),
+    };
+    if !output.status.success() {
+        return Err(GitError::Failed {
+            command,
+            stderr: String::from_utf8_lossy(&output.stderr).trim().to_string(),
+        });
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).to_string())
+}
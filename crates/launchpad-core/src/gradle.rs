@@ -0,0 +1,115 @@
+use crate::config::project::AndroidSettings;
+use crate::http;
+use std::path::{Path, PathBuf};
+use thiserror::Error;
+use tokio::process::Command;
+
+#[derive(Error, Debug)]
+pub enum GradleError {
+    #[error("Gradle build failed: {0}")]
+    BuildFailed(String),
+
+    #[error("No .aab found in {0}")]
+    BundleNotFound(String),
+
+    #[error("IO error: {0}")]
+    Io(#[from] std::io::Error),
+}
+
+pub struct Gradle {
+    project_path: String,
+    module: String,
+    task: String,
+}
+
+impl Gradle {
+    pub fn new(settings: &AndroidSettings) -> Self {
+        Self {
+            project_path: settings.project_path.clone(),
+            module: settings.module.clone(),
+            task: settings.gradle_task.clone(),
+        }
+    }
+
+    /// `versionName` declared in the module's build file, if it's a literal
+    pub fn version_name(&self) -> Option<String> {
+        let re = regex_lite::Regex::new(r#"versionName\s*=?\s*"([^"]+)""#).ok()?;
+        let content = self.build_file()?;
+        let caps = re.captures(&content)?;
+        Some(caps.get(1)?.as_str().to_string())
+    }
+
+    /// `versionCode` declared in the module's build file, if it's a literal
+    pub fn version_code(&self) -> Option<u64> {
+        let re = regex_lite::Regex::new(r"versionCode\s*=?\s*(\d+)").ok()?;
+        let content = self.build_file()?;
+        let caps = re.captures(&content)?;
+        caps.get(1)?.as_str().parse().ok()
+    }
+
+    /// Build the release bundle with the given version injected, and return the .aab.
+    /// Uses the Android Gradle plugin's injected-version properties so the build
+    /// files don't need to be rewritten.
+    pub async fn bundle(
+        &self,
+        version_code: u64,
+        version_name: Option<&str>,
+    ) -> Result<PathBuf, GradleError> {
+        // The wrapper lives in the Gradle root, which is where we run from
+        let gradle = if Path::new(&self.project_path).join("gradlew").exists() {
+            "./gradlew"
+        } else {
+            "gradle"
+        };
+
+        let mut cmd = Command::new(gradle);
+        cmd.current_dir(&self.project_path)
+            .envs(http::child_env())
+            .arg(format!(":{}:{}", self.module, self.task))
+            .arg(format!("-Pandroid.injected.version.code={}", version_code));
+
+        if let Some(name) = version_name {
+            cmd.arg(format!("-Pandroid.injected.version.name={}", name));
+        }
+
+        let output = cmd.output().await?;
+
+        if !output.status.success() {
+            // Gradle prints the useful part ("What went wrong") at the end
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            let context: Vec<_> = stderr.lines().rev().take(15).collect();
+            let error_msg = context.into_iter().rev().collect::<Vec<_>>().join("\n");
+            return Err(GradleError::BuildFailed(error_msg));
+        }
+
+        self.find_bundle()
+    }
+
+    /// Newest .aab under `<module>/build/outputs/bundle/<variant>/`
+    fn find_bundle(&self) -> Result<PathBuf, GradleError> {
+        let outputs = Path::new(&self.project_path)
+            .join(&self.module)
+            .join("build/outputs/bundle");
+
+        std::fs::read_dir(&outputs)
+            .into_iter()
+            .flat_map(|entries| entries.flatten())
+            .filter_map(|variant| std::fs::read_dir(variant.path()).ok())
+            .flat_map(|entries| entries.flatten())
+            .filter(|entry| entry.file_name().to_string_lossy().ends_with(".aab"))
+            .filter_map(|entry| {
+                let modified = entry.metadata().and_then(|m| m.modified()).ok()?;
+                Some((modified, entry.path()))
+            })
+            .max_by_key(|(modified, _)| *modified)
+            .map(|(_, path)| path)
+            .ok_or_else(|| GradleError::BundleNotFound(outputs.display().to_string()))
+    }
+
+    fn build_file(&self) -> Option<String> {
+        let dir = Path::new(&self.project_path).join(&self.module);
+        ["build.gradle.kts", "build.gradle"]
+            .iter()
+            .find_map(|name| std::fs::read_to_string(dir.join(name)).ok())
+    }
+}
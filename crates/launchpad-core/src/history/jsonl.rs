@@ -0,0 +1,42 @@
+use super::{DeployRecord, HistoryError};
+use crate::files;
+use std::path::Path;
+
+/// Every record in the file, oldest first
+pub fn load(path: &Path) -> Result<Vec<DeployRecord>, HistoryError> {
+    Ok(files::read_recovering(path, parse_records)?.unwrap_or_default())
+}
+
+fn parse_records(content: &str) -> Result<Vec<DeployRecord>, HistoryError> {
+    content
+        .lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty())
+        .map(|(i, line)| {
+            serde_json::from_str(line).map_err(|e| HistoryError::Parse {
+                line: i + 1,
+                message: e.to_string(),
+            })
+        })
+        .collect()
+}
+
+/// Other launchpad processes wait until the rewritten file is in place, so no
+/// deploy is lost
+pub fn update(
+    path: &Path,
+    change: impl FnOnce(&mut Vec<DeployRecord>),
+) -> Result<(), HistoryError> {
+    let _lock = files::lock(path)?;
+
+    let mut records = load(path)?;
+    change(&mut records);
+
+    let mut content = String::new();
+    for record in &records {
+        content.push_str(&serde_json::to_string(record).map_err(std::io::Error::other)?);
+        content.push('\n');
+    }
+    files::write_atomic(path, content.as_bytes())?;
+    Ok(())
+}
@@ -0,0 +1,358 @@
+mod jsonl;
+mod remote;
+mod sqlite;
+
+use crate::config::global::{GlobalConfig, HistoryBackend, HistoryConfig};
+use crate::config::project::DistributionMode;
+use crate::files;
+use crate::git;
+use crate::thinning::ThinnedSize;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+use std::time::{SystemTime, UNIX_EPOCH};
+use thiserror::Error;
+
+/// Commits listed when there is no earlier deploy to compare with
+const MAX_COMMITS: usize = 30;
+
+/// Holds the history service's bearer token unless `history.token_env` says otherwise
+const TOKEN_ENV: &str = "LAUNCHPAD_HISTORY_TOKEN";
+
+#[derive(Error, Debug)]
+pub enum HistoryError {
+    #[error("Could not determine the launchpad config directory")]
+    NoConfigDir,
+
+    #[error("IO error: {0}")]
+    Io(#[from] std::io::Error),
+
+    #[error("Corrupt history entry on line {line}: {message}")]
+    Parse { line: usize, message: String },
+
+    #[error("Invalid [history] config: {0}")]
+    Config(String),
+
+    #[error("Could not read [history] from the global config: {0}")]
+    GlobalConfig(String),
+
+    #[error("The http history backend needs history.url or LAUNCHPAD_HISTORY_URL")]
+    NoUrl,
+
+    #[error("History database error: {0}")]
+    Database(#[from] rusqlite::Error),
+
+    #[error("History service error: {0}")]
+    Remote(#[from] reqwest::Error),
+
+    #[error("Deploys can't be removed from the history service")]
+    RemoteRemove,
+}
+
+/// One successful deploy, added to the deploy history
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DeployRecord {
+    pub bundle_id: String,
+    /// "ios" or "android"
+    pub platform: String,
+    pub version: String,
+    pub build: String,
+    /// Unix timestamp (seconds)
+    pub deployed_at: u64,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub git_sha: Option<String>,
+
+    /// Release tag, when one was created
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tag: Option<String>,
+
+    /// .ipa or .aab size in bytes
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub size: Option<u64>,
+
+    /// SHA-256 of the .ipa or .aab, for `launchpad verify-ipa`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sha256: Option<String>,
+
+    /// Embedded framework → size in bytes
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub frameworks: BTreeMap<String, u64>,
+
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub entitlements: BTreeMap<String, serde_json::Value>,
+
+    /// Device class → largest thinned download and install size (with `[size]`)
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub thinned_sizes: BTreeMap<String, ThinnedSize>,
+
+    /// Seconds from the end of the upload until App Store Connect had processed
+    /// the build (Ready to Test); only known when the deploy waited for it
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub processing_secs: Option<u64>,
+
+    /// Built with `deploy --skip-upload`: the .ipa was left for another system to upload
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub upload_skipped: bool,
+}
+
+impl DeployRecord {
+    /// Git revision to diff from: the tag if one was made, otherwise the commit
+    pub fn git_ref(&self) -> Option<&str> {
+        self.tag.as_deref().or(self.git_sha.as_deref())
+    }
+}
+
+/// Post-upload App Store Connect work that failed for lack of connectivity,
+/// kept in ~/.launchpad/pending.json until `launchpad retry-pending` finishes it
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PendingOperation {
+    pub bundle_id: String,
+    /// "1.2.0 (43)" as reported by fastlane
+    pub version: String,
+    /// Unix timestamp (seconds)
+    pub queued_at: u64,
+
+    /// `[key_profiles.<name>]` the build was uploaded with, when not `[apple]`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub key_profile: Option<String>,
+
+    #[serde(flatten)]
+    pub action: PendingAction,
+
+    /// Retries so far, and why the last try failed
+    #[serde(default)]
+    pub attempts: u32,
+    pub last_error: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "action", rename_all = "snake_case")]
+pub enum PendingAction {
+    /// Group assignment, beta review submission and tester notification
+    Distribute {
+        groups: Vec<String>,
+        distribution: DistributionMode,
+        notify_testers: bool,
+    },
+}
+
+impl std::fmt::Display for PendingAction {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PendingAction::Distribute { groups, .. } if groups.is_empty() => {
+                write!(f, "tester notification settings")
+            }
+            PendingAction::Distribute { groups, .. } => {
+                write!(f, "distribution to {}", groups.join(", "))
+            }
+        }
+    }
+}
+
+/// `~/.launchpad/history.jsonl`, one [`DeployRecord`] per line: the history of
+/// the "jsonl" backend, and where a new SQLite history starts from
+pub fn history_path() -> Option<PathBuf> {
+    GlobalConfig::config_dir().map(|d| d.join("history.jsonl"))
+}
+
+/// `~/.launchpad/history.db`, for the default "sqlite" backend
+pub fn database_path() -> Option<PathBuf> {
+    GlobalConfig::config_dir().map(|d| d.join("history.db"))
+}
+
+/// Where the history is kept
+enum Store {
+    Sqlite(PathBuf),
+    Jsonl(PathBuf),
+    Http(remote::Remote),
+}
+
+impl Store {
+    /// `[history]` from the config file, with LAUNCHPAD_HISTORY_BACKEND and
+    /// LAUNCHPAD_HISTORY_URL winning over it
+    fn resolve() -> Result<Self, HistoryError> {
+        let env = |name: &str| std::env::var(name).ok().filter(|v| !v.is_empty());
+        let env_backend: Option<HistoryBackend> = match env("LAUNCHPAD_HISTORY_BACKEND") {
+            Some(value) => Some(value.parse().map_err(HistoryError::Config)?),
+            None => None,
+        };
+        // When the variables alone pick the store, an encrypted config isn't unlocked for it
+        let settled = match env_backend {
+            Some(HistoryBackend::Http) => env("LAUNCHPAD_HISTORY_URL").is_some(),
+            Some(_) => true,
+            None => false,
+        };
+        let config = if settled && GlobalConfig::is_encrypted() {
+            HistoryConfig::default()
+        } else {
+            history_config()?
+        };
+        let backend = env_backend.or(config.backend).unwrap_or_default();
+
+        match backend {
+            HistoryBackend::Sqlite => Ok(Store::Sqlite(
+                database_path().ok_or(HistoryError::NoConfigDir)?,
+            )),
+            HistoryBackend::Jsonl => Ok(Store::Jsonl(
+                history_path().ok_or(HistoryError::NoConfigDir)?,
+            )),
+            HistoryBackend::Http => {
+                let url = env("LAUNCHPAD_HISTORY_URL")
+                    .or(config.url)
+                    .ok_or(HistoryError::NoUrl)?;
+                let token_env = config.token_env.as_deref().unwrap_or(TOKEN_ENV);
+                Ok(Store::Http(remote::Remote {
+                    url,
+                    token: env(token_env),
+                }))
+            }
+        }
+    }
+}
+
+/// `[history]` from the global config, decrypted when it's encrypted (the
+/// passphrase is asked at most once per run). Falling back to the default
+/// backend instead would quietly split the history.
+fn history_config() -> Result<HistoryConfig, HistoryError> {
+    let config =
+        GlobalConfig::read_file().map_err(|e| HistoryError::GlobalConfig(e.to_string()))?;
+    Ok(config.map(|config| config.history).unwrap_or_default())
+}
+
+/// How backends tell whether a record changed
+fn record_json(record: &DeployRecord) -> Result<String, HistoryError> {
+    Ok(serde_json::to_string(record).map_err(std::io::Error::other)?)
+}
+
+pub fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or_default()
+}
+
+/// "5 min ago", "3 h ago" or "2 days ago" for a Unix timestamp
+pub fn age(timestamp: u64) -> String {
+    let seconds = now().saturating_sub(timestamp);
+    match seconds {
+        s if s < 3_600 => format!("{} min ago", s / 60),
+        s if s < 86_400 => format!("{} h ago", s / 3_600),
+        s => format!("{} days ago", s / 86_400),
+    }
+}
+
+/// Add a record, creating the history on first use
+pub fn append(record: &DeployRecord) -> Result<(), HistoryError> {
+    match Store::resolve()? {
+        Store::Sqlite(path) => sqlite::append(&path, record),
+        Store::Jsonl(path) => jsonl::update(&path, |records| records.push(record.clone())),
+        Store::Http(remote) => remote.append(record),
+    }
+}
+
+/// Every recorded deploy, oldest first
+pub fn load() -> Result<Vec<DeployRecord>, HistoryError> {
+    match Store::resolve()? {
+        Store::Sqlite(path) => sqlite::load(&path),
+        Store::Jsonl(path) => jsonl::load(&path),
+        Store::Http(remote) => remote.load(),
+    }
+}
+
+/// Change the history in place, e.g. to record a tag made later, without losing
+/// deploys other launchpad processes record meanwhile
+pub fn update(change: impl FnOnce(&mut Vec<DeployRecord>)) -> Result<(), HistoryError> {
+    match Store::resolve()? {
+        Store::Sqlite(path) => sqlite::update(&path, change),
+        Store::Jsonl(path) => jsonl::update(&path, change),
+        Store::Http(remote) => remote.update(change),
+    }
+}
+
+/// The most recent deploy of `build` for an app and platform
+pub fn find<'a>(
+    records: &'a [DeployRecord],
+    bundle_id: &str,
+    platform: &str,
+    build: &str,
+) -> Option<&'a DeployRecord> {
+    records
+        .iter()
+        .rev()
+        .find(|r| r.bundle_id == bundle_id && r.platform == platform && r.build == build)
+}
+
+/// Commit subjects since the previous deploy of an app, newest first. `head` is the
+/// deployed commit, which may already be recorded.
+pub fn commits_since_last_deploy(
+    bundle_id: &str,
+    platform: &str,
+    head: Option<&str>,
+) -> Vec<String> {
+    let previous = load().ok().and_then(|records| {
+        records
+            .into_iter()
+            .rev()
+            .filter(|r| r.bundle_id == bundle_id && r.platform == platform)
+            .find(|r| r.git_sha.as_deref() != head)
+    });
+
+    let range = match previous.as_ref().and_then(|r| r.git_ref()) {
+        Some(from) => format!("{}..HEAD", from),
+        None => format!("-{}", MAX_COMMITS),
+    };
+
+    match git::output(&["log", "--no-merges", "--pretty=format:%s", &range]) {
+        Ok(output) => output
+            .lines()
+            .filter(|line| !line.trim().is_empty())
+            .map(str::to_string)
+            .collect(),
+        _ => Vec::new(),
+    }
+}
+
+pub fn pending_path() -> Option<PathBuf> {
+    GlobalConfig::config_dir().map(|d| d.join("pending.json"))
+}
+
+/// Queued operations, oldest first
+pub fn load_pending() -> Result<Vec<PendingOperation>, HistoryError> {
+    let path = pending_path().ok_or(HistoryError::NoConfigDir)?;
+    let operations = files::read_recovering(&path, |content| {
+        serde_json::from_str(content).map_err(|e| HistoryError::Parse {
+            line: e.line(),
+            message: e.to_string(),
+        })
+    })?;
+    Ok(operations.unwrap_or_default())
+}
+
+/// Change the queue under the lock [`update`] uses for the history; an empty
+/// queue removes the file
+pub fn update_pending(change: impl FnOnce(&mut Vec<PendingOperation>)) -> Result<(), HistoryError> {
+    let path = pending_path().ok_or(HistoryError::NoConfigDir)?;
+    let _lock = files::lock(&path)?;
+
+    let mut operations = load_pending()?;
+    change(&mut operations);
+
+    if operations.is_empty() {
+        for file in [path.clone(), files::backup_path(&path)] {
+            if file.exists() {
+                std::fs::remove_file(file)?;
+            }
+        }
+        return Ok(());
+    }
+
+    let content = serde_json::to_string_pretty(&operations).map_err(std::io::Error::other)?;
+    files::write_atomic(&path, (content + "\n").as_bytes())?;
+    Ok(())
+}
+
+/// Add an operation to the queue
+pub fn queue_pending(operation: PendingOperation) -> Result<(), HistoryError> {
+    update_pending(|operations| operations.push(operation))
+}
@@ -0,0 +1,95 @@
+use super::{record_json, DeployRecord, HistoryError};
+use crate::http;
+use reqwest::Method;
+use std::future::Future;
+use std::time::Duration;
+
+const TIMEOUT: Duration = Duration::from_secs(30);
+
+/// A history service shared by many repos. `GET <url>/deploys` lists every
+/// record oldest first, `POST` adds one, and `PUT` replaces the record with the
+/// same `bundle_id`, `platform`, `build` and `deployed_at`.
+pub struct Remote {
+    pub url: String,
+    /// Sent as `Authorization: Bearer`
+    pub token: Option<String>,
+}
+
+impl Remote {
+    pub fn load(&self) -> Result<Vec<DeployRecord>, HistoryError> {
+        run(async {
+            let client = http::client()?;
+            let response = self.request(&client, Method::GET).send().await?;
+            Ok(response.error_for_status()?.json().await?)
+        })
+    }
+
+    pub fn append(&self, record: &DeployRecord) -> Result<(), HistoryError> {
+        run(async {
+            let client = http::client()?;
+            self.send(&client, Method::POST, record).await
+        })
+    }
+
+    /// Sends the records `change` touched; the service decides how concurrent
+    /// writers are ordered
+    pub fn update(&self, change: impl FnOnce(&mut Vec<DeployRecord>)) -> Result<(), HistoryError> {
+        let previous = self.load()?;
+        let mut records = previous.clone();
+        change(&mut records);
+        if records.len() < previous.len() {
+            return Err(HistoryError::RemoteRemove);
+        }
+
+        run(async {
+            let client = http::client()?;
+            for (i, record) in records.iter().enumerate() {
+                let method = match previous.get(i) {
+                    Some(old) if record_json(old)? == record_json(record)? => continue,
+                    Some(_) => Method::PUT,
+                    None => Method::POST,
+                };
+                self.send(&client, method, record).await?;
+            }
+            Ok(())
+        })
+    }
+
+    fn request(&self, client: &reqwest::Client, method: Method) -> reqwest::RequestBuilder {
+        let url = format!("{}/deploys", self.url.trim_end_matches('/'));
+        let request = client.request(method, url).timeout(TIMEOUT);
+        match &self.token {
+            Some(token) => request.bearer_auth(token),
+            None => request,
+        }
+    }
+
+    async fn send(
+        &self,
+        client: &reqwest::Client,
+        method: Method,
+        record: &DeployRecord,
+    ) -> Result<(), HistoryError> {
+        let response = self.request(client, method).json(record).send().await?;
+        response.error_for_status()?;
+        Ok(())
+    }
+}
+
+/// History calls are synchronous and often made inside the deploy's runtime, so
+/// requests get a thread and runtime of their own
+fn run<T: Send>(
+    request: impl Future<Output = Result<T, HistoryError>> + Send,
+) -> Result<T, HistoryError> {
+    std::thread::scope(|scope| {
+        scope
+            .spawn(|| {
+                tokio::runtime::Builder::new_current_thread()
+                    .enable_all()
+                    .build()?
+                    .block_on(request)
+            })
+            .join()
+            .unwrap_or_else(|panic| std::panic::resume_unwind(panic))
+    })
+}
@@ -0,0 +1,141 @@
+use super::{history_path, jsonl, record_json, DeployRecord, HistoryError};
+use crate::ui;
+use rusqlite::{params, Connection, TransactionBehavior};
+use std::path::Path;
+use std::time::Duration;
+
+/// How long a write waits for another launchpad process to finish its own
+const BUSY_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Bumped with every schema change, in `PRAGMA user_version`
+const SCHEMA_VERSION: i64 = 1;
+
+/// `record` is the whole [`DeployRecord`]; the other columns are there to query
+const SCHEMA: &str = "
+CREATE TABLE IF NOT EXISTS deploys (
+    id INTEGER PRIMARY KEY,
+    bundle_id TEXT NOT NULL,
+    platform TEXT NOT NULL,
+    version TEXT NOT NULL,
+    build TEXT NOT NULL,
+    deployed_at INTEGER NOT NULL,
+    record TEXT NOT NULL
+);
+CREATE INDEX IF NOT EXISTS deploys_build ON deploys (bundle_id, platform, build);
+";
+
+/// Every record in the database, oldest first
+pub fn load(path: &Path) -> Result<Vec<DeployRecord>, HistoryError> {
+    let connection = open(path)?;
+    Ok(rows(&connection)?.into_iter().map(|(_, r)| r).collect())
+}
+
+pub fn append(path: &Path, record: &DeployRecord) -> Result<(), HistoryError> {
+    write(&open(path)?, None, record)
+}
+
+/// In one transaction, writing back only the records `change` touched
+pub fn update(
+    path: &Path,
+    change: impl FnOnce(&mut Vec<DeployRecord>),
+) -> Result<(), HistoryError> {
+    let mut connection = open(path)?;
+    let transaction = connection.transaction_with_behavior(TransactionBehavior::Immediate)?;
+
+    let rows = rows(&transaction)?;
+    let mut records: Vec<DeployRecord> = rows.iter().map(|(_, r)| r.clone()).collect();
+    change(&mut records);
+
+    for (i, record) in records.iter().enumerate() {
+        match rows.get(i) {
+            Some((_, previous)) if record_json(previous)? == record_json(record)? => {}
+            Some((id, _)) => write(&transaction, Some(*id), record)?,
+            None => write(&transaction, None, record)?,
+        }
+    }
+    for (id, _) in rows.iter().skip(records.len()) {
+        transaction.execute("DELETE FROM deploys WHERE id = ?1", params![id])?;
+    }
+
+    transaction.commit()?;
+    Ok(())
+}
+
+/// Open the database, creating it on first use from history.jsonl, so switching
+/// backends keeps the deploys recorded so far
+fn open(path: &Path) -> Result<Connection, HistoryError> {
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir)?;
+    }
+    let mut connection = Connection::open(path)?;
+    connection.busy_timeout(BUSY_TIMEOUT)?;
+    if schema_version(&connection)? == SCHEMA_VERSION {
+        return Ok(connection);
+    }
+
+    // Another process may be creating it too
+    let transaction = connection.transaction_with_behavior(TransactionBehavior::Immediate)?;
+    if schema_version(&transaction)? == 0 {
+        transaction.execute_batch(SCHEMA)?;
+        import(&transaction)?;
+        transaction.pragma_update(None, "user_version", SCHEMA_VERSION)?;
+    }
+    transaction.commit()?;
+    Ok(connection)
+}
+
+fn schema_version(connection: &Connection) -> Result<i64, HistoryError> {
+    Ok(connection.pragma_query_value(None, "user_version", |row| row.get(0))?)
+}
+
+fn import(connection: &Connection) -> Result<(), HistoryError> {
+    let Some(path) = history_path().filter(|p| p.exists()) else {
+        return Ok(());
+    };
+    let records = jsonl::load(&path)?;
+    for record in &records {
+        write(connection, None, record)?;
+    }
+    ui::step(&format!(
+        "Imported {} deploys from {} into the history database",
+        records.len(),
+        path.display()
+    ));
+    Ok(())
+}
+
+fn rows(connection: &Connection) -> Result<Vec<(i64, DeployRecord)>, HistoryError> {
+    let mut statement = connection.prepare("SELECT id, record FROM deploys ORDER BY id")?;
+    let rows = statement.query_map([], |row| Ok((row.get(0)?, row.get::<_, String>(1)?)))?;
+    rows.map(|row| {
+        let (id, json) = row?;
+        let record = serde_json::from_str(&json).map_err(|e| HistoryError::Parse {
+            line: id as usize,
+            message: e.to_string(),
+        })?;
+        Ok((id, record))
+    })
+    .collect()
+}
+
+/// Insert a record, or replace row `id`
+fn write(
+    connection: &Connection,
+    id: Option<i64>,
+    record: &DeployRecord,
+) -> Result<(), HistoryError> {
+    connection.execute(
+        "INSERT OR REPLACE INTO deploys (id, bundle_id, platform, version, build, deployed_at, record)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+        params![
+            id,
+            record.bundle_id,
+            record.platform,
+            record.version,
+            record.build,
+            record.deployed_at as i64,
+            record_json(record)?,
+        ],
+    )?;
+    Ok(())
+}
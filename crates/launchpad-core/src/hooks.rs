@@ -0,0 +1,155 @@
+use crate::config::project::HooksSettings;
+use crate::events;
+use crate::ui;
+use rhai::{Dynamic, Engine, EvalAltResult, Map, Scope};
+use std::path::Path;
+use std::process::Command;
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+pub enum HookError {
+    #[error("Hook '{hook}' blocked the deploy: {reason}")]
+    Blocked { hook: String, reason: String },
+
+    #[error("Hook '{hook}' failed: {message}")]
+    Failed { hook: String, message: String },
+}
+
+#[derive(Debug, Clone, Copy)]
+pub enum HookEvent {
+    PreVersionBump,
+    PostUpload,
+}
+
+impl HookEvent {
+    pub fn name(&self) -> &'static str {
+        match self {
+            HookEvent::PreVersionBump => "pre_version_bump",
+            HookEvent::PostUpload => "post_upload",
+        }
+    }
+
+    fn hooks<'a>(&self, settings: &'a HooksSettings) -> &'a [String] {
+        match self {
+            HookEvent::PreVersionBump => &settings.pre_version_bump,
+            HookEvent::PostUpload => &settings.post_upload,
+        }
+    }
+}
+
+/// Run every hook registered for `event`, stopping at the first failure.
+/// `context` is exposed as `ctx` to scripts and as LAUNCHPAD_CONTEXT (JSON) to commands.
+pub fn run(
+    settings: &HooksSettings,
+    event: HookEvent,
+    context: &serde_json::Value,
+) -> Result<(), HookError> {
+    for hook in event.hooks(settings) {
+        ui::step(&format!("Running {} hook: {}", event.name(), hook));
+
+        if hook.ends_with(".rhai") {
+            run_script(hook, event, context)?;
+        } else {
+            run_command(hook, event, context)?;
+        }
+    }
+
+    Ok(())
+}
+
+fn run_command(hook: &str, event: HookEvent, context: &serde_json::Value) -> Result<(), HookError> {
+    let mut command = Command::new("sh");
+    command
+        .arg("-c")
+        .arg(hook)
+        .env("LAUNCHPAD_EVENT", event.name())
+        .env("LAUNCHPAD_CONTEXT", context.to_string());
+    // Hook output is for people; keep it out of `--event-stream -`
+    if events::owns_stdout() {
+        command.stdout(std::io::stderr());
+    }
+
+    let status = command.status().map_err(|e| HookError::Failed {
+        hook: hook.to_string(),
+        message: e.to_string(),
+    })?;
+
+    if !status.success() {
+        return Err(HookError::Blocked {
+            hook: hook.to_string(),
+            reason: format!("exited with status {}", status.code().unwrap_or(-1)),
+        });
+    }
+
+    Ok(())
+}
+
+fn run_script(hook: &str, event: HookEvent, context: &serde_json::Value) -> Result<(), HookError> {
+    let failed = |message: String| HookError::Failed {
+        hook: hook.to_string(),
+        message,
+    };
+
+    let engine = script_engine();
+    let mut ctx: Dynamic = rhai::serde::to_dynamic(context).map_err(|e| failed(e.to_string()))?;
+    if let Some(mut map) = ctx.write_lock::<Map>() {
+        map.insert("event".into(), event.name().into());
+    }
+
+    let mut scope = Scope::new();
+    scope.push_constant("ctx", ctx);
+
+    match engine.run_file_with_scope(&mut scope, Path::new(hook).to_path_buf()) {
+        Ok(()) => Ok(()),
+        // `block(reason)` and `throw` are policy decisions, not script bugs
+        Err(e) => match *e {
+            EvalAltResult::ErrorRuntime(reason, _) => Err(HookError::Blocked {
+                hook: hook.to_string(),
+                reason: reason.to_string(),
+            }),
+            other => Err(failed(other.to_string())),
+        },
+    }
+}
+
+/// Rhai engine with launchpad's helper functions registered
+fn script_engine() -> Engine {
+    let mut engine = Engine::new();
+
+    engine.register_fn("log", |message: &str| ui::step(message));
+    engine.register_fn("warn", |message: &str| ui::warn(message));
+    engine.register_fn("block", |reason: &str| -> Result<(), Box<EvalAltResult>> {
+        Err(EvalAltResult::ErrorRuntime(reason.into(), rhai::Position::NONE).into())
+    });
+    engine.register_fn("env", |name: &str| std::env::var(name).unwrap_or_default());
+    engine.register_fn(
+        "read_file",
+        |path: &str| -> Result<String, Box<EvalAltResult>> {
+            std::fs::read_to_string(path).map_err(|e| format!("{}: {}", path, e).into())
+        },
+    );
+    engine.register_fn("run", |command: &str| -> Result<Map, Box<EvalAltResult>> {
+        let output = Command::new("sh")
+            .arg("-c")
+            .arg(command)
+            .output()
+            .map_err(|e| e.to_string())?;
+
+        let mut result = Map::new();
+        result.insert(
+            "code".into(),
+            (output.status.code().unwrap_or(-1) as i64).into(),
+        );
+        result.insert(
+            "stdout".into(),
+            String::from_utf8_lossy(&output.stdout).to_string().into(),
+        );
+        result.insert(
+            "stderr".into(),
+            String::from_utf8_lossy(&output.stderr).to_string().into(),
+        );
+        Ok(result)
+    });
+
+    engine
+}
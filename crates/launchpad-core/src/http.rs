@@ -0,0 +1,383 @@
+use crate::config::global::{GlobalConfig, NetworkConfig};
+use crate::ui;
+use serde_json::Value;
+use std::fs::{File, OpenOptions};
+use std::io::Write;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::sync::{Mutex, OnceLock};
+use std::time::Duration;
+
+/// Proxy and extra root certificates for this run: `[network]` in the global
+/// config, with HTTPS_PROXY, NO_PROXY and LAUNCHPAD_CA_BUNDLE winning over it
+struct Network {
+    /// From `[network]`; proxies from the environment reqwest and child
+    /// processes already pick up themselves
+    proxy: Option<String>,
+    no_proxy: Option<String>,
+    /// PEM file the certificates came from, handed on to child processes
+    ca_bundle: Option<String>,
+    certificates: Vec<reqwest::Certificate>,
+}
+
+static NETWORK: OnceLock<Network> = OnceLock::new();
+
+fn network() -> &'static Network {
+    NETWORK.get_or_init(Network::resolve)
+}
+
+impl Network {
+    fn resolve() -> Self {
+        let config = network_config();
+        let env = |name: &str| std::env::var(name).ok().filter(|v| !v.is_empty());
+        let env_proxy = ["HTTPS_PROXY", "https_proxy", "ALL_PROXY", "all_proxy"]
+            .iter()
+            .any(|name| env(name).is_some());
+
+        let ca_bundle = env("LAUNCHPAD_CA_BUNDLE")
+            .or(config.ca_bundle)
+            .map(|path| shellexpand::tilde(&path).to_string());
+        let certificates = match ca_bundle.as_deref().map(read_certificates) {
+            Some(Ok(certificates)) => certificates,
+            Some(Err(e)) => {
+                ui::warn(&format!("CA bundle ignored: {}", e));
+                Vec::new()
+            }
+            None => Vec::new(),
+        };
+
+        Network {
+            proxy: config.proxy.filter(|_| !env_proxy),
+            no_proxy: env("NO_PROXY").or(env("no_proxy")).or(config.no_proxy),
+            ca_bundle,
+            certificates,
+        }
+    }
+}
+
+fn read_certificates(path: &str) -> Result<Vec<reqwest::Certificate>, String> {
+    let pem = std::fs::read(path).map_err(|e| format!("{}: {}", path, e))?;
+    let certificates =
+        reqwest::Certificate::from_pem_bundle(&pem).map_err(|e| format!("{}: {}", path, e))?;
+    // Certificates are only parsed once a client is built; keep that from failing every request
+    let total = certificates.len();
+    let certificates: Vec<reqwest::Certificate> = certificates
+        .into_iter()
+        .filter(|c| {
+            reqwest::Client::builder()
+                .add_root_certificate(c.clone())
+                .build()
+                .is_ok()
+        })
+        .collect();
+    match total - certificates.len() {
+        _ if certificates.is_empty() => Err(format!("{}: no usable PEM certificates", path)),
+        0 => Ok(certificates),
+        invalid => {
+            ui::warn(&format!(
+                "{} of {} certificates in {} are invalid and ignored",
+                invalid, total, path
+            ));
+            Ok(certificates)
+        }
+    }
+}
+
+/// `[network]` of an encrypted config, once this run has decrypted it
+static UNLOCKED_NETWORK: OnceLock<NetworkConfig> = OnceLock::new();
+
+/// Called with an encrypted config's `[network]` when it's loaded, so clients
+/// built afterwards use it without asking for the passphrase again
+pub(crate) fn use_unlocked_network(network: &NetworkConfig) {
+    let _ = UNLOCKED_NETWORK.set(network.clone());
+}
+
+/// `[network]` from the config file, or from the loaded config when it's encrypted
+fn network_config() -> NetworkConfig {
+    if GlobalConfig::is_encrypted() {
+        if let Some(network) = UNLOCKED_NETWORK.get() {
+            return network.clone();
+        }
+        ui::warn(
+            "The global config is encrypted and not unlocked yet, so its [network] settings \
+             don't apply; HTTPS_PROXY and LAUNCHPAD_CA_BUNDLE still do",
+        );
+        return NetworkConfig::default();
+    }
+    GlobalConfig::read_file()
+        .ok()
+        .flatten()
+        .map(|config| config.network)
+        .unwrap_or_default()
+}
+
+/// What every HTTP client starts from, so App Store Connect, Google Play,
+/// notifications and integrations all go through the same proxy and trust the
+/// same certificates
+pub fn client_builder() -> reqwest::ClientBuilder {
+    let network = network();
+    let mut builder = reqwest::Client::builder();
+    if let Some(url) = &network.proxy {
+        match reqwest::Proxy::all(url) {
+            Ok(proxy) => {
+                let no_proxy = network
+                    .no_proxy
+                    .as_deref()
+                    .and_then(reqwest::NoProxy::from_string);
+                builder = builder.proxy(proxy.no_proxy(no_proxy));
+            }
+            Err(e) => ui::warn(&format!("network.proxy '{}' ignored: {}", url, e)),
+        }
+    }
+    for certificate in &network.certificates {
+        builder = builder.add_root_certificate(certificate.clone());
+    }
+    builder
+}
+
+pub fn client() -> Result<reqwest::Client, reqwest::Error> {
+    client_builder().build()
+}
+
+/// Environment for fastlane, Gradle and other tools launchpad runs, so they
+/// use the `[network]` proxy and CA bundle too
+pub fn child_env() -> Vec<(&'static str, String)> {
+    let network = network();
+    let mut vars = Vec::new();
+    if let Some(proxy) = &network.proxy {
+        for name in ["HTTPS_PROXY", "https_proxy", "HTTP_PROXY", "http_proxy"] {
+            vars.push((name, proxy.clone()));
+        }
+        if let Some(no_proxy) = &network.no_proxy {
+            vars.push(("NO_PROXY", no_proxy.clone()));
+            vars.push(("no_proxy", no_proxy.clone()));
+        }
+    }
+    if let Some(ca_bundle) = &network.ca_bundle {
+        // Ruby (fastlane), curl and git read these
+        vars.push(("SSL_CERT_FILE", ca_bundle.clone()));
+        vars.push(("GIT_SSL_CAINFO", ca_bundle.clone()));
+    }
+    vars
+}
+
+/// Where `--debug-http` writes App Store Connect traffic; unset without the flag
+static DEBUG_LOG: OnceLock<Mutex<File>> = OnceLock::new();
+
+/// Numbers requests so a response can be matched to its request when retries interleave
+static EXCHANGE: AtomicU32 = AtomicU32::new(0);
+
+/// Bodies beyond this are cut short in the log (report downloads, large listings)
+const MAX_LOGGED_BODY: usize = 64 * 1024;
+
+/// Headers and JSON fields whose values never reach the log
+const SECRET_HEADERS: &[&str] = &["authorization", "cookie", "set-cookie", "x-api-key"];
+const SECRET_FIELDS: &[&str] = &["password", "token", "secret", "privatekey", "email"];
+
+/// Query parameters that make a pre-signed upload or download URL usable by anyone
+/// (`X-Amz-Signature`, `X-Amz-Security-Token`, ...), matched by suffix
+const SECRET_PARAMS: &[&str] = &["signature", "sig", "token", "credential"];
+
+/// `~/.launchpad/logs/http.log`
+pub fn debug_log_path() -> Option<PathBuf> {
+    GlobalConfig::config_dir().map(|dir| dir.join("logs").join("http.log"))
+}
+
+/// Start appending requests and responses to the debug log
+pub fn open_debug_log() -> std::io::Result<()> {
+    let path = debug_log_path()
+        .ok_or_else(|| std::io::Error::other("no home directory for the log file"))?;
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir)?;
+    }
+    let mut file = OpenOptions::new().create(true).append(true).open(&path)?;
+    writeln!(
+        file,
+        "\n=== launchpad {} {} ({}) ===",
+        env!("CARGO_PKG_VERSION"),
+        std::env::args().skip(1).collect::<Vec<_>>().join(" "),
+        plist::Date::from(std::time::SystemTime::now()).to_xml_format()
+    )?;
+    let _ = DEBUG_LOG.set(Mutex::new(file));
+    Ok(())
+}
+
+pub fn debug_enabled() -> bool {
+    DEBUG_LOG.get().is_some()
+}
+
+fn write_log(entry: &str) {
+    if let Some(file) = DEBUG_LOG.get() {
+        // A full disk shouldn't fail the deploy being debugged
+        let _ = file.lock().unwrap().write_all(entry.as_bytes());
+    }
+}
+
+/// Log a request about to be sent and return its number for [`log_response`]
+pub fn log_request(request: &reqwest::Request) -> u32 {
+    let id = EXCHANGE.fetch_add(1, Ordering::Relaxed) + 1;
+    let body = request
+        .body()
+        .and_then(reqwest::Body::as_bytes)
+        .map(describe_body)
+        .unwrap_or_default();
+    write_log(&format!(
+        "\n--> #{} {} {}\n{}{}",
+        id,
+        request.method(),
+        redact_url(request.url()),
+        describe_headers(request.headers()),
+        body
+    ));
+    id
+}
+
+/// Log the answer to request `id`, reading the body and handing back an
+/// equivalent response for the caller to use
+pub async fn log_response(
+    id: u32,
+    response: reqwest::Response,
+    elapsed: Duration,
+) -> Result<reqwest::Response, reqwest::Error> {
+    if !debug_enabled() {
+        return Ok(response);
+    }
+    let status = response.status();
+    let version = response.version();
+    let headers = response.headers().clone();
+    let body = response.bytes().await?;
+    write_log(&format!(
+        "\n<-- #{} {} ({} ms)\n{}{}",
+        id,
+        status,
+        elapsed.as_millis(),
+        describe_headers(&headers),
+        describe_body(&body)
+    ));
+
+    let mut rebuilt = ::http::Response::new(body);
+    *rebuilt.status_mut() = status;
+    *rebuilt.version_mut() = version;
+    *rebuilt.headers_mut() = headers;
+    Ok(reqwest::Response::from(rebuilt))
+}
+
+/// Log a request that got no answer (connection refused, timeout)
+pub fn log_failure(id: u32, error: &reqwest::Error, elapsed: Duration) {
+    write_log(&format!(
+        "\n<-- #{} failed after {} ms: {}\n",
+        id,
+        elapsed.as_millis(),
+        error
+    ));
+}
+
+fn describe_headers(headers: &reqwest::header::HeaderMap) -> String {
+    let mut lines = String::new();
+    for (name, value) in headers {
+        let value = if SECRET_HEADERS.contains(&name.as_str()) {
+            "<redacted>".into()
+        } else {
+            String::from_utf8_lossy(value.as_bytes())
+        };
+        lines.push_str(&format!("{}: {}\n", name, value));
+    }
+    lines
+}
+
+fn describe_body(body: &[u8]) -> String {
+    if body.is_empty() {
+        return String::new();
+    }
+    if let Ok(mut json) = serde_json::from_slice::<Value>(body) {
+        redact_fields(&mut json);
+        let text = serde_json::to_string_pretty(&json).unwrap_or_default();
+        return format!("\n{}\n", truncate(&text));
+    }
+    match std::str::from_utf8(body) {
+        Ok(text) => format!("\n{}\n", truncate(text)),
+        // Screenshots, IPAs and gzipped reports
+        Err(_) => format!("\n<{} bytes of binary data>\n", body.len()),
+    }
+}
+
+fn truncate(text: &str) -> String {
+    if text.len() <= MAX_LOGGED_BODY {
+        return text.to_string();
+    }
+    let mut end = MAX_LOGGED_BODY;
+    while !text.is_char_boundary(end) {
+        end -= 1;
+    }
+    format!("{}\n<{} more bytes>", &text[..end], text.len() - end)
+}
+
+fn redact_fields(value: &mut Value) {
+    match value {
+        Value::Object(map) => {
+            for (key, value) in map.iter_mut() {
+                let key = key.to_lowercase();
+                if value.is_string() && SECRET_FIELDS.iter().any(|s| key.contains(s)) {
+                    *value = Value::String("<redacted>".to_string());
+                } else {
+                    redact_fields(value);
+                }
+            }
+        }
+        Value::Array(items) => items.iter_mut().for_each(redact_fields),
+        _ => {}
+    }
+}
+
+fn redact_url(url: &reqwest::Url) -> String {
+    let secret = |name: &str| {
+        let name = name.to_lowercase();
+        SECRET_PARAMS.iter().any(|s| name.ends_with(s))
+    };
+    // Re-encoding would turn API filters into filter%5BbundleId%5D; only do it when needed
+    if !url.query_pairs().any(|(name, _)| secret(&name)) {
+        return url.to_string();
+    }
+    let pairs: Vec<(String, String)> = url
+        .query_pairs()
+        .map(|(name, value)| {
+            // Angle brackets would come out percent-encoded
+            let value = if secret(&name) {
+                "REDACTED".into()
+            } else {
+                value
+            };
+            (name.into_owned(), value.into_owned())
+        })
+        .collect();
+    let mut url = url.clone();
+    url.query_pairs_mut().clear().extend_pairs(pairs);
+    url.to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn redacted(url: &str) -> String {
+        redact_url(&reqwest::Url::parse(url).unwrap())
+    }
+
+    #[test]
+    fn signed_url_secrets_are_redacted() {
+        assert_eq!(
+            redacted("https://storage.example.com/ipa?X-Amz-Signature=abc&X-Amz-Credential=key&expires=60"),
+            "https://storage.example.com/ipa?X-Amz-Signature=REDACTED&X-Amz-Credential=REDACTED&expires=60"
+        );
+        assert_eq!(
+            redacted("https://example.com/upload?sig=abc&access_token=xyz"),
+            "https://example.com/upload?sig=REDACTED&access_token=REDACTED"
+        );
+    }
+
+    #[test]
+    fn urls_without_secrets_keep_their_encoding() {
+        let url = "https://api.appstoreconnect.apple.com/v1/apps?filter[bundleId]=com.example.app";
+        assert_eq!(redacted(url), url);
+    }
+}
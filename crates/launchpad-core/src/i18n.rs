@@ -0,0 +1,115 @@
+use crate::config::global::GlobalConfig;
+use fluent_bundle::concurrent::FluentBundle;
+use fluent_bundle::{FluentArgs, FluentResource};
+use std::sync::OnceLock;
+use unic_langid::LanguageIdentifier;
+
+/// Bundled catalogs; English is the fallback for anything missing
+const CATALOGS: &[(&str, &str)] = &[
+    ("en", include_str!("../locales/en.ftl")),
+    ("es", include_str!("../locales/es.ftl")),
+    ("de", include_str!("../locales/de.ftl")),
+];
+
+struct Bundles {
+    selected: Option<FluentBundle<FluentResource>>,
+    fallback: FluentBundle<FluentResource>,
+}
+
+static BUNDLES: OnceLock<Bundles> = OnceLock::new();
+
+/// Look up a localized message: `t!("init-header")`, `t!("init-found-project", path = p)`
+macro_rules! t {
+    ($id:expr) => {
+        $crate::i18n::message($id, &[])
+    };
+    ($id:expr, $($name:ident = $value:expr),+ $(,)?) => {
+        $crate::i18n::message($id, &[$((stringify!($name), $value.to_string())),+])
+    };
+}
+pub(crate) use t;
+
+/// Format a message from the active locale, falling back to English, then to the id
+pub fn message(id: &str, args: &[(&str, String)]) -> String {
+    let bundles = BUNDLES.get_or_init(load_bundles);
+
+    let mut fluent_args = FluentArgs::new();
+    for (name, value) in args {
+        fluent_args.set(*name, value.clone());
+    }
+
+    for bundle in bundles
+        .selected
+        .iter()
+        .chain(std::iter::once(&bundles.fallback))
+    {
+        if let Some(pattern) = bundle.get_message(id).and_then(|m| m.value()) {
+            let mut errors = Vec::new();
+            return bundle
+                .format_pattern(pattern, Some(&fluent_args), &mut errors)
+                .to_string();
+        }
+    }
+
+    id.to_string()
+}
+
+fn load_bundles() -> Bundles {
+    let locale = resolve_locale();
+
+    let selected = CATALOGS
+        .iter()
+        .find(|(lang, _)| *lang == locale && *lang != "en")
+        .map(|(lang, source)| bundle(lang, source));
+
+    Bundles {
+        selected,
+        fallback: bundle("en", CATALOGS[0].1),
+    }
+}
+
+fn bundle(lang: &str, source: &str) -> FluentBundle<FluentResource> {
+    let langid: LanguageIdentifier = lang.parse().expect("bundled locale id is valid");
+    let resource = FluentResource::try_new(source.to_string()).expect("bundled catalog parses");
+
+    let mut bundle = FluentBundle::new_concurrent(vec![langid]);
+    // Unicode isolation marks render as garbage in most terminals
+    bundle.set_use_isolating(false);
+    bundle
+        .add_resource(resource)
+        .expect("bundled catalog has no duplicate ids");
+    bundle
+}
+
+/// LAUNCHPAD_LANG, then `[ui] locale` in the global config, then the POSIX locale env
+fn resolve_locale() -> String {
+    let configured = std::env::var("LAUNCHPAD_LANG")
+        .ok()
+        .or_else(|| GlobalConfig::load().ok().flatten()?.ui.locale);
+
+    let system = ["LC_ALL", "LC_MESSAGES", "LANG"]
+        .iter()
+        .find_map(|var| std::env::var(var).ok().filter(|v| !v.is_empty()));
+
+    configured
+        .or(system)
+        .map(|value| language_code(&value))
+        .unwrap_or_else(|| "en".to_string())
+}
+
+/// "es_ES.UTF-8" / "pt-BR" → "es" / "pt"; "C" and "POSIX" → "en"
+fn language_code(value: &str) -> String {
+    let code = value
+        .split(['.', '@'])
+        .next()
+        .unwrap_or_default()
+        .split(['_', '-'])
+        .next()
+        .unwrap_or_default()
+        .to_lowercase();
+
+    match code.as_str() {
+        "" | "c" | "posix" => "en".to_string(),
+        _ => code,
+    }
+}
@@ -0,0 +1,53 @@
+use super::{actions_pr_number, token, CommentThread, IntegrationError};
+use crate::config::project::ForgeSettings;
+
+/// Comment thread of the pull request this Gitea Actions (or Woodpecker) run is for
+pub(super) fn detect(
+    settings: &ForgeSettings,
+) -> Result<Option<(String, CommentThread)>, IntegrationError> {
+    // Gitea Actions mirrors GitHub's env vars; Woodpecker uses its own
+    let context = if std::env::var("GITHUB_EVENT_NAME").is_ok() {
+        actions_pr_number()
+            .zip(std::env::var("GITHUB_REPOSITORY").ok())
+            .map(|(n, repo)| {
+                let server = std::env::var("GITHUB_SERVER_URL").ok();
+                (n, repo, server)
+            })
+    } else {
+        std::env::var("CI_COMMIT_PULL_REQUEST")
+            .ok()
+            .and_then(|n| n.parse().ok())
+            .zip(std::env::var("CI_REPO").ok())
+            .map(|(n, repo)| (n, repo, std::env::var("CI_FORGE_URL").ok()))
+    };
+
+    let Some((number, repository, server_url)) = context else {
+        return Ok(None);
+    };
+
+    let Some(api_url) = settings
+        .api_url
+        .clone()
+        .or_else(|| server_url.map(|url| format!("{}/api/v1", url.trim_end_matches('/'))))
+    else {
+        return Ok(None);
+    };
+    let token = token(settings, "GITEA_TOKEN")?;
+
+    let comments_url = format!(
+        "{}/repos/{}/issues/{}/comments",
+        api_url, repository, number
+    );
+
+    Ok(Some((
+        format!("PR #{}", number),
+        CommentThread {
+            list_url: format!("{}?limit=50", comments_url),
+            create_url: comments_url,
+            update_url: format!("{}/repos/{}/issues/comments", api_url, repository),
+            update_method: reqwest::Method::PATCH,
+            auth_header: "Authorization",
+            auth_value: format!("token {}", token),
+        },
+    )))
+}
@@ -0,0 +1,40 @@
+use super::{actions_pr_number, token, CommentThread, IntegrationError};
+use crate::config::project::ForgeSettings;
+
+/// Comment thread of the pull request this GitHub Actions run belongs to
+pub(super) fn detect(
+    settings: &ForgeSettings,
+) -> Result<Option<(String, CommentThread)>, IntegrationError> {
+    if std::env::var("GITHUB_ACTIONS").ok().as_deref() != Some("true") {
+        return Ok(None);
+    }
+
+    let (Some(number), Ok(repository)) = (actions_pr_number(), std::env::var("GITHUB_REPOSITORY"))
+    else {
+        return Ok(None);
+    };
+
+    let api_url = settings
+        .api_url
+        .clone()
+        .or_else(|| std::env::var("GITHUB_API_URL").ok())
+        .unwrap_or_else(|| "https://api.github.com".to_string());
+    let token = token(settings, "GITHUB_TOKEN")?;
+
+    let comments_url = format!(
+        "{}/repos/{}/issues/{}/comments",
+        api_url, repository, number
+    );
+
+    Ok(Some((
+        format!("PR #{}", number),
+        CommentThread {
+            list_url: format!("{}?per_page=100", comments_url),
+            create_url: comments_url,
+            update_url: format!("{}/repos/{}/issues/comments", api_url, repository),
+            update_method: reqwest::Method::PATCH,
+            auth_header: "Authorization",
+            auth_value: format!("Bearer {}", token),
+        },
+    )))
+}
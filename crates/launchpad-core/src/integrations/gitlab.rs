@@ -0,0 +1,41 @@
+use super::{token, CommentThread, IntegrationError};
+use crate::config::project::ForgeSettings;
+
+/// Comment thread of the merge request this GitLab CI pipeline runs for
+pub(super) fn detect(
+    settings: &ForgeSettings,
+) -> Result<Option<(String, CommentThread)>, IntegrationError> {
+    // Only merge request pipelines carry an MR IID
+    let (Ok(iid), Ok(project_id)) = (
+        std::env::var("CI_MERGE_REQUEST_IID"),
+        std::env::var("CI_PROJECT_ID"),
+    ) else {
+        return Ok(None);
+    };
+
+    let api_url = settings
+        .api_url
+        .clone()
+        .or_else(|| std::env::var("CI_API_V4_URL").ok())
+        .unwrap_or_else(|| "https://gitlab.com/api/v4".to_string());
+
+    // CI_JOB_TOKEN can't write notes, so a project/personal access token is required
+    let token = token(settings, "GITLAB_TOKEN")?;
+
+    let notes_url = format!(
+        "{}/projects/{}/merge_requests/{}/notes",
+        api_url, project_id, iid
+    );
+
+    Ok(Some((
+        format!("MR !{}", iid),
+        CommentThread {
+            list_url: format!("{}?per_page=100", notes_url),
+            create_url: notes_url.clone(),
+            update_url: notes_url,
+            update_method: reqwest::Method::PUT,
+            auth_header: "PRIVATE-TOKEN",
+            auth_value: token,
+        },
+    )))
+}
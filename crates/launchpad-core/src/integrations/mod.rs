@@ -0,0 +1,316 @@
+pub mod gitea;
+pub mod github;
+pub mod gitlab;
+
+use crate::config::project::{Forge, ForgeSettings, IntegrationsSettings};
+use crate::git;
+use crate::http;
+use crate::summary::DeploySummary;
+use crate::ui;
+use serde::Deserialize;
+use serde_json::json;
+use thiserror::Error;
+
+/// Hidden marker used to find (and update) launchpad's own comment
+const COMMENT_MARKER: &str = "<!-- launchpad-testflight";
+
+#[derive(Error, Debug)]
+pub enum IntegrationError {
+    #[error("HTTP error: {0}")]
+    Http(#[from] reqwest::Error),
+
+    #[error("API error ({status}): {body}")]
+    Api { status: u16, body: String },
+
+    #[error("Missing token: set {0}")]
+    MissingToken(String),
+
+    #[error("IO error: {0}")]
+    Io(#[from] std::io::Error),
+
+    #[error("JSON error: {0}")]
+    Json(#[from] serde_json::Error),
+}
+
+#[derive(Debug, Deserialize)]
+struct Comment {
+    id: u64,
+    body: Option<String>,
+}
+
+/// Endpoints and auth for the comment thread of one pull/merge request
+struct CommentThread {
+    /// GET: existing comments (first page is enough to find ours)
+    list_url: String,
+    /// POST: new comment
+    create_url: String,
+    /// `{update_url}/{id}`: existing comment
+    update_url: String,
+    update_method: reqwest::Method,
+    auth_header: &'static str,
+    auth_value: String,
+}
+
+/// Post or update the TestFlight comment on the current pull/merge request; `body`
+/// replaces the built-in table. Returns a label like "PR #12", or None when there
+/// is nothing to comment on.
+pub async fn comment_on_change_request(
+    settings: &IntegrationsSettings,
+    summary: &DeploySummary,
+    body: Option<&str>,
+) -> Result<Option<String>, IntegrationError> {
+    if settings.is_empty() {
+        return Ok(None);
+    }
+
+    let Some(forge) = settings.forge.or_else(detect_forge) else {
+        return Ok(None);
+    };
+
+    let Some(forge_settings) = settings.forge_settings(forge) else {
+        return Ok(None);
+    };
+
+    if !forge_settings.pr_comment {
+        return Ok(None);
+    }
+
+    let context = match forge {
+        Forge::Github => github::detect(forge_settings)?,
+        Forge::Gitlab => gitlab::detect(forge_settings)?,
+        Forge::Gitea => gitea::detect(forge_settings)?,
+    };
+
+    let Some((label, thread)) = context else {
+        return Ok(None);
+    };
+
+    upsert_comment(&thread, forge_settings, summary, body).await?;
+    Ok(Some(label))
+}
+
+async fn upsert_comment(
+    thread: &CommentThread,
+    settings: &ForgeSettings,
+    summary: &DeploySummary,
+    custom_body: Option<&str>,
+) -> Result<(), IntegrationError> {
+    let client = http::client()?;
+    let request = |method: reqwest::Method, url: &str| {
+        client
+            .request(method, url)
+            .header(thread.auth_header, &thread.auth_value)
+            .header("Accept", "application/json")
+            .header("User-Agent", "launchpad")
+    };
+
+    let response = request(reqwest::Method::GET, &thread.list_url)
+        .send()
+        .await?;
+    let comments: Vec<Comment> = check(response).await?.json().await?;
+
+    let existing = comments
+        .into_iter()
+        .find(|c| c.body.as_deref().is_some_and(is_launchpad_comment));
+
+    let body = match custom_body {
+        Some(custom) => format!("{}{}\n", marker(summary), custom),
+        None => render_comment(
+            summary,
+            settings.public_link.as_deref(),
+            existing.as_ref().and_then(|c| c.body.as_deref()),
+        ),
+    };
+
+    let response = match existing {
+        Some(comment) => {
+            let url = format!("{}/{}", thread.update_url, comment.id);
+            request(thread.update_method.clone(), &url)
+                .json(&json!({ "body": body }))
+                .send()
+                .await?
+        }
+        None => {
+            request(reqwest::Method::POST, &thread.create_url)
+                .json(&json!({ "body": body }))
+                .send()
+                .await?
+        }
+    };
+    check(response).await?;
+
+    Ok(())
+}
+
+/// Pick the forge from the origin remote's host, falling back to CI env vars
+fn detect_forge() -> Option<Forge> {
+    remote_forge().or_else(ci_forge)
+}
+
+fn remote_forge() -> Option<Forge> {
+    let url = git::config_value("remote.origin.url")?.to_lowercase();
+    let host = remote_host(&url)?;
+
+    if host.contains("github") {
+        Some(Forge::Github)
+    } else if host.contains("gitlab") {
+        Some(Forge::Gitlab)
+    } else if host.contains("gitea") || host == "codeberg.org" {
+        Some(Forge::Gitea)
+    } else {
+        None
+    }
+}
+
+/// Host part of a remote URL (`https://host/o/r`, `ssh://git@host:22/o/r`, `git@host:o/r`)
+fn remote_host(url: &str) -> Option<&str> {
+    let authority = match url.split_once("://") {
+        Some((_, rest)) => rest.split('/').next()?,
+        None => url.split(':').next()?,
+    };
+    authority.rsplit('@').next()?.split(':').next()
+}
+
+fn ci_forge() -> Option<Forge> {
+    let is_set = |name: &str| std::env::var(name).ok().as_deref() == Some("true");
+
+    if is_set("GITLAB_CI") {
+        Some(Forge::Gitlab)
+    } else if is_set("GITEA_ACTIONS")
+        || std::env::var("CI_FORGE_TYPE").ok().as_deref() == Some("gitea")
+    {
+        Some(Forge::Gitea)
+    } else if is_set("GITHUB_ACTIONS") {
+        Some(Forge::Github)
+    } else {
+        None
+    }
+}
+
+/// Web page of the CI run doing this deploy (GitHub/Gitea Actions, GitLab, Bitrise, Jenkins)
+pub fn ci_run_url() -> Option<String> {
+    let var = |name: &str| std::env::var(name).ok().filter(|v| !v.is_empty());
+
+    match (
+        var("GITHUB_SERVER_URL"),
+        var("GITHUB_REPOSITORY"),
+        var("GITHUB_RUN_ID"),
+    ) {
+        (Some(server), Some(repository), Some(run)) => {
+            Some(format!("{}/{}/actions/runs/{}", server, repository, run))
+        }
+        _ => var("CI_PIPELINE_URL")
+            .or_else(|| var("BITRISE_BUILD_URL"))
+            .or_else(|| var("BUILD_URL")),
+    }
+}
+
+/// Read the API token from the configured (or forge-default) env var
+fn token(settings: &ForgeSettings, default_env: &str) -> Result<String, IntegrationError> {
+    let env = settings.token_env.as_deref().unwrap_or(default_env);
+    std::env::var(env).map_err(|_| IntegrationError::MissingToken(env.to_string()))
+}
+
+/// PR number for GitHub-compatible Actions runs (GitHub, Gitea):
+/// from the event payload, falling back to refs/pull/<n>/merge
+fn actions_pr_number() -> Option<u64> {
+    let event = std::env::var("GITHUB_EVENT_NAME").ok()?;
+    if event != "pull_request" && event != "pull_request_target" {
+        return None;
+    }
+
+    std::env::var("GITHUB_EVENT_PATH")
+        .ok()
+        .and_then(|path| std::fs::read_to_string(path).ok())
+        .and_then(|content| serde_json::from_str::<serde_json::Value>(&content).ok())
+        .and_then(|event| event["pull_request"]["number"].as_u64())
+        .or_else(|| {
+            std::env::var("GITHUB_REF")
+                .ok()?
+                .strip_prefix("refs/pull/")?
+                .split('/')
+                .next()?
+                .parse()
+                .ok()
+        })
+}
+
+/// Render the PR/MR comment body for a finished deploy.
+/// `previous` is the body of an earlier launchpad comment, used for the size diff.
+fn render_comment(
+    summary: &DeploySummary,
+    public_link: Option<&str>,
+    previous: Option<&str>,
+) -> String {
+    let mut body = marker(summary);
+    body.push_str("### 🚀 TestFlight build uploaded\n\n");
+    body.push_str("| | |\n|---|---|\n");
+
+    let version = summary.version.as_deref().unwrap_or("unknown");
+    match &summary.build {
+        Some(build) => body.push_str(&format!("| Version | {} ({}) |\n", version, build)),
+        None => body.push_str(&format!("| Version | {} |\n", version)),
+    }
+
+    if let Some(size) = summary.ipa_size {
+        let previous_size = previous.and_then(parse_marker_size);
+        let diff = match previous_size {
+            Some(prev) if prev > 0 => {
+                format!(" ({})", ui::format_size_diff(size as i64 - prev as i64))
+            }
+            _ => String::new(),
+        };
+        body.push_str(&format!(
+            "| IPA size | {}{} |\n",
+            ui::format_size(size),
+            diff
+        ));
+    }
+
+    if let Some(sha) = &summary.git_sha {
+        body.push_str(&format!("| Commit | `{}` |\n", &sha[..sha.len().min(7)]));
+    }
+
+    if let Some(link) = public_link {
+        body.push_str(&format!(
+            "| Install | [Join the TestFlight beta]({}) |\n",
+            link
+        ));
+    }
+
+    body
+}
+
+/// First line of every launchpad comment; the size feeds the next comment's diff
+fn marker(summary: &DeploySummary) -> String {
+    format!(
+        "{} size={} -->\n",
+        COMMENT_MARKER,
+        summary.ipa_size.unwrap_or(0)
+    )
+}
+
+/// Turn a non-2xx response into an `IntegrationError::Api`
+async fn check(response: reqwest::Response) -> Result<reqwest::Response, IntegrationError> {
+    let status = response.status();
+    if status.is_success() {
+        return Ok(response);
+    }
+
+    let body = response.text().await.unwrap_or_default();
+    Err(IntegrationError::Api {
+        status: status.as_u16(),
+        body,
+    })
+}
+
+/// Whether a comment body was written by launchpad
+fn is_launchpad_comment(body: &str) -> bool {
+    body.starts_with(COMMENT_MARKER)
+}
+
+fn parse_marker_size(body: &str) -> Option<u64> {
+    let rest = body.strip_prefix(COMMENT_MARKER)?.trim_start();
+    let value = rest.strip_prefix("size=")?;
+    value.split_whitespace().next()?.parse().ok()
+}
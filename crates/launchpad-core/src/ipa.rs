@@ -0,0 +1,245 @@
+use std::collections::{BTreeMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+/// Local symbols a stripped binary keeps at most; anything above has its symbol table left in
+const UNSTRIPPED_LOCAL_SYMBOLS: usize = 500;
+
+/// Global symbols two binaries can share before it looks like one static library linked into both
+const DUPLICATE_SYMBOLS: usize = 50;
+
+/// What's inside an exported .ipa, recorded so builds can be compared later
+#[derive(Debug, Default)]
+pub struct IpaContents {
+    /// Embedded framework or dylib name → uncompressed size in bytes
+    pub frameworks: BTreeMap<String, u64>,
+    /// Entitlements the app binary was signed with
+    pub entitlements: BTreeMap<String, serde_json::Value>,
+}
+
+/// Inspect an .ipa. Best effort: anything that can't be read is left empty.
+pub fn inspect(ipa: &Path) -> IpaContents {
+    IpaContents {
+        frameworks: frameworks(ipa).unwrap_or_default(),
+        entitlements: entitlements(ipa).unwrap_or_default(),
+    }
+}
+
+/// Sum `unzip -l` sizes under Payload/<App>.app/Frameworks/<name>
+fn frameworks(ipa: &Path) -> Option<BTreeMap<String, u64>> {
+    let output = Command::new("unzip").arg("-l").arg(ipa).output().ok()?;
+    if !output.status.success() {
+        return None;
+    }
+
+    // "  length  date  time  name" (names may contain spaces)
+    let entry = regex_lite::Regex::new(r"^\s*(\d+)\s+\S+\s+\S+\s+(.+)$").ok()?;
+
+    let mut frameworks = BTreeMap::new();
+    for line in String::from_utf8_lossy(&output.stdout).lines() {
+        let Some(caps) = entry.captures(line) else {
+            continue;
+        };
+        let Ok(size) = caps[1].parse::<u64>() else {
+            continue;
+        };
+        let name = &caps[2];
+
+        let mut parts = name.split('/');
+        let in_frameworks = parts.next() == Some("Payload")
+            && parts.next().is_some_and(|app| app.ends_with(".app"))
+            && parts.next() == Some("Frameworks");
+        if let (true, Some(framework)) = (in_frameworks, parts.next()) {
+            if !framework.is_empty() {
+                *frameworks.entry(framework.to_string()).or_insert(0) += size;
+            }
+        }
+    }
+
+    Some(frameworks)
+}
+
+/// `codesign -d --entitlements` on the extracted app bundle (macOS only)
+fn entitlements(ipa: &Path) -> Option<BTreeMap<String, serde_json::Value>> {
+    let dir = std::env::temp_dir().join(format!("launchpad-ipa-{}", std::process::id()));
+    let result = extract_app(ipa, &dir).and_then(|app| {
+        let output = Command::new("codesign")
+            .args(["-d", "--entitlements", "-", "--xml"])
+            .arg(&app)
+            .output()
+            .ok()?;
+        if !output.status.success() || output.stdout.is_empty() {
+            return None;
+        }
+        plist::from_bytes(&output.stdout).ok()
+    });
+
+    let _ = std::fs::remove_dir_all(&dir);
+    result
+}
+
+/// Unzip an .ipa into `dir` and return the path of its Payload/<App>.app
+pub fn extract_app(ipa: &Path, dir: &Path) -> Option<PathBuf> {
+    let status = Command::new("unzip")
+        .args(["-q", "-o"])
+        .arg(ipa)
+        .arg("-d")
+        .arg(dir)
+        .status()
+        .ok()?;
+    if !status.success() {
+        return None;
+    }
+
+    std::fs::read_dir(dir.join("Payload"))
+        .ok()?
+        .filter_map(|e| e.ok())
+        .map(|e| e.path())
+        .find(|p| p.extension().is_some_and(|ext| ext == "app"))
+}
+
+/// "1.2.0 (43)" from the app's Info.plist, in the format fastlane output is parsed into
+pub fn version(ipa: &Path) -> Option<String> {
+    // -W keeps `*` from matching '/', so nested watch apps don't match
+    let output = Command::new("unzip")
+        .args(["-p", "-W"])
+        .arg(ipa)
+        .arg("Payload/*.app/Info.plist")
+        .output()
+        .ok()?;
+    if !output.status.success() {
+        return None;
+    }
+
+    let info: plist::Dictionary = plist::from_bytes(&output.stdout).ok()?;
+    let version = info.get("CFBundleShortVersionString")?.as_string()?;
+    match info.get("CFBundleVersion").and_then(|b| b.as_string()) {
+        Some(build) => Some(format!("{} ({})", version, build)),
+        None => Some(version.to_string()),
+    }
+}
+
+/// Binary size warnings for an .ipa: frameworks over `large_framework` bytes, code
+/// defined in more than one binary (a static library linked twice), and binaries
+/// that still carry their symbol tables. The last two need `nm` (macOS).
+pub fn analyze(ipa: &Path, large_framework: u64) -> Vec<String> {
+    let mut warnings: Vec<String> = frameworks(ipa)
+        .unwrap_or_default()
+        .into_iter()
+        .filter(|(_, size)| *size > large_framework)
+        .map(|(name, size)| {
+            format!(
+                "{} is {} uncompressed",
+                name,
+                crate::thinning::format_size(size)
+            )
+        })
+        .collect();
+
+    if which::which("nm").is_err() {
+        return warnings;
+    }
+    let dir = std::env::temp_dir().join(format!("launchpad-analyze-{}", std::process::id()));
+    if let Some(app) = extract_app(ipa, &dir) {
+        let mut defined: Vec<(String, HashSet<String>)> = Vec::new();
+        for (label, binary) in binaries(&app) {
+            let Some((globals, locals)) = symbols(&binary) else {
+                continue;
+            };
+            if locals > UNSTRIPPED_LOCAL_SYMBOLS {
+                warnings.push(format!(
+                    "{} isn't stripped ({} local symbols); set STRIP_INSTALLED_PRODUCT = YES for Release",
+                    label, locals
+                ));
+            }
+            defined.push((label, globals));
+        }
+
+        for (i, (a, a_symbols)) in defined.iter().enumerate() {
+            for (b, b_symbols) in &defined[i + 1..] {
+                let shared = a_symbols.intersection(b_symbols).count();
+                if shared >= DUPLICATE_SYMBOLS {
+                    warnings.push(format!(
+                        "{} and {} both define {} symbols; a static library is probably linked into both",
+                        a, b, shared
+                    ));
+                }
+            }
+        }
+    }
+
+    let _ = std::fs::remove_dir_all(&dir);
+    warnings
+}
+
+/// The app executable, embedded frameworks and dylibs, and extension executables
+fn binaries(app: &Path) -> Vec<(String, PathBuf)> {
+    let mut binaries = Vec::new();
+    if let Some(executable) = bundle_executable(app) {
+        binaries.push((file_name(app), executable));
+    }
+
+    for dir in [app.join("Frameworks"), app.join("PlugIns")] {
+        let Ok(entries) = std::fs::read_dir(&dir) else {
+            continue;
+        };
+        let mut paths: Vec<PathBuf> = entries.flatten().map(|e| e.path()).collect();
+        paths.sort();
+        for path in paths {
+            let binary = match path.extension().and_then(|ext| ext.to_str()) {
+                Some("dylib") => Some(path.clone()),
+                Some("framework") | Some("appex") => bundle_executable(&path),
+                _ => None,
+            };
+            if let Some(binary) = binary {
+                binaries.push((file_name(&path), binary));
+            }
+        }
+    }
+    binaries
+}
+
+/// CFBundleExecutable, or the bundle name without its extension
+fn bundle_executable(bundle: &Path) -> Option<PathBuf> {
+    let from_plist = plist::Value::from_file(bundle.join("Info.plist"))
+        .ok()
+        .and_then(|info| {
+            info.as_dictionary()?
+                .get("CFBundleExecutable")?
+                .as_string()
+                .map(str::to_string)
+        });
+    let name = from_plist.or_else(|| Some(bundle.file_stem()?.to_string_lossy().to_string()))?;
+    let path = bundle.join(name);
+    path.is_file().then_some(path)
+}
+
+fn file_name(path: &Path) -> String {
+    path.file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_default()
+}
+
+/// Defined global symbol names and the number of local symbols, from `nm -U`
+fn symbols(binary: &Path) -> Option<(HashSet<String>, usize)> {
+    let output = Command::new("nm").arg("-U").arg(binary).output().ok()?;
+    if !output.status.success() {
+        return None;
+    }
+
+    let mut globals = HashSet::new();
+    let mut locals = 0;
+    // "0000000100003f50 T _main"
+    for line in String::from_utf8_lossy(&output.stdout).lines() {
+        let mut parts = line.split_whitespace();
+        let (Some(_), Some(kind), Some(name)) = (parts.next(), parts.next(), parts.next()) else {
+            continue;
+        };
+        if kind.chars().all(|c| c.is_ascii_uppercase()) {
+            globals.insert(name.to_string());
+        } else {
+            locals += 1;
+        }
+    }
+    Some((globals, locals))
+}
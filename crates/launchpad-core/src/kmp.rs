@@ -0,0 +1,147 @@
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+/// How the Kotlin framework gets into the Xcode build
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum FrameworkIntegration {
+    /// Xcode build phase runs `:<module>:embedAndSignAppleFrameworkForXcode`
+    #[default]
+    EmbedAndSign,
+    /// `kotlin("native.cocoapods")` plugin; the framework is a local pod
+    Cocoapods,
+}
+
+impl std::fmt::Display for FrameworkIntegration {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            FrameworkIntegration::EmbedAndSign => write!(f, "embedAndSign"),
+            FrameworkIntegration::Cocoapods => write!(f, "CocoaPods"),
+        }
+    }
+}
+
+/// A Kotlin Multiplatform repo: the shared Gradle module and the Xcode app consuming it
+#[derive(Debug, Clone)]
+pub struct KmpProject {
+    /// Gradle project path without the leading colon (e.g. "shared")
+    pub shared_module: String,
+    /// Directory holding the .xcodeproj/.xcworkspace (usually "iosApp")
+    pub ios_path: String,
+    pub integration: FrameworkIntegration,
+}
+
+/// Detect a KMP layout under `root`: a settings.gradle(.kts) including a
+/// multiplatform module with iOS targets, plus an Xcode project next to it
+pub fn detect(root: &Path) -> Option<KmpProject> {
+    let settings = ["settings.gradle.kts", "settings.gradle"]
+        .iter()
+        .find_map(|name| std::fs::read_to_string(root.join(name)).ok())?;
+
+    let (shared_module, build_file) =
+        included_modules(&settings).into_iter().find_map(|module| {
+            let build_file = module_build_file(root, &module)?.to_lowercase();
+            let is_ios_multiplatform =
+                build_file.contains("multiplatform") && build_file.contains("ios");
+            is_ios_multiplatform.then_some((module, build_file))
+        })?;
+
+    let integration = if build_file.contains("cocoapods") {
+        FrameworkIntegration::Cocoapods
+    } else {
+        FrameworkIntegration::EmbedAndSign
+    };
+
+    Some(KmpProject {
+        shared_module,
+        ios_path: find_xcode_dir(root)?,
+        integration,
+    })
+}
+
+/// Gradle command for the repo: the wrapper when present
+pub fn gradle_command() -> &'static str {
+    if Path::new("gradlew").exists() {
+        "./gradlew"
+    } else {
+        "gradle"
+    }
+}
+
+/// Gradle tasks to run before the version bump. Compiling the release framework
+/// up front surfaces Kotlin errors before anything is bumped and leaves Gradle
+/// warm for Xcode's build phase; CocoaPods needs the dummy framework so
+/// `pod install` works on a clean checkout.
+pub fn prebuild_command(shared_module: &str, integration: FrameworkIntegration) -> String {
+    let task = match integration {
+        FrameworkIntegration::EmbedAndSign => "linkReleaseFrameworkIosArm64",
+        FrameworkIntegration::Cocoapods => "generateDummyFramework",
+    };
+    format!("{} :{}:{}", gradle_command(), shared_module, task)
+}
+
+/// Whether the Xcode project still calls the Gradle embed task
+pub fn has_embed_phase(ios_path: &str) -> bool {
+    let Ok(entries) = std::fs::read_dir(ios_path) else {
+        return false;
+    };
+
+    entries
+        .flatten()
+        .filter(|entry| entry.file_name().to_string_lossy().ends_with(".xcodeproj"))
+        .filter_map(|entry| std::fs::read_to_string(entry.path().join("project.pbxproj")).ok())
+        .any(|pbxproj| pbxproj.contains("embedAndSignAppleFrameworkForXcode"))
+}
+
+/// Module paths from `include(":shared", ":core:data")` / `include ':shared'`
+fn included_modules(settings: &str) -> Vec<String> {
+    settings
+        .lines()
+        .map(str::trim)
+        .filter(|line| line.starts_with("include"))
+        .flat_map(|line| {
+            line.split(['"', '\''])
+                .skip(1)
+                .step_by(2)
+                .filter_map(|name| name.strip_prefix(':'))
+                .map(str::to_string)
+                .collect::<Vec<_>>()
+        })
+        .collect()
+}
+
+fn module_build_file(root: &Path, module: &str) -> Option<String> {
+    let dir = root.join(module.replace(':', "/"));
+    ["build.gradle.kts", "build.gradle"]
+        .iter()
+        .find_map(|name| std::fs::read_to_string(dir.join(name)).ok())
+}
+
+/// Top-level directory with an Xcode project, preferring the KMP wizard's "iosApp"
+fn find_xcode_dir(root: &Path) -> Option<String> {
+    let has_xcode_project = |dir: &Path| {
+        std::fs::read_dir(dir)
+            .map(|entries| {
+                entries.flatten().any(|entry| {
+                    let name = entry.file_name();
+                    let name = name.to_string_lossy();
+                    name.ends_with(".xcodeproj") || name.ends_with(".xcworkspace")
+                })
+            })
+            .unwrap_or(false)
+    };
+
+    if has_xcode_project(&root.join("iosApp")) {
+        return Some("iosApp".to_string());
+    }
+
+    let mut dirs: Vec<String> = std::fs::read_dir(root)
+        .ok()?
+        .flatten()
+        .filter(|entry| entry.file_type().map(|t| t.is_dir()).unwrap_or(false))
+        .filter(|entry| has_xcode_project(&entry.path()))
+        .map(|entry| entry.file_name().to_string_lossy().to_string())
+        .collect();
+    dirs.sort();
+    dirs.into_iter().next()
+}
@@ -0,0 +1,89 @@
+//! Everything behind the `launchpad` CLI. The binary only parses arguments and
+//! calls into [`commands`]; other tools can drive the same deploys, App Store
+//! Connect calls and configuration directly:
+//!
+//! ```no_run
+//! use launchpad_core::commands::deploy::DeployOptions;
+//! use launchpad_core::version::VersionBump;
+//!
+//! # async fn example() -> Result<(), launchpad_core::commands::deploy::DeployError> {
+//! DeployOptions {
+//!     bump: Some(VersionBump::Patch),
+//!     non_interactive: true,
+//!     ..Default::default()
+//! }
+//! .run()
+//! .await
+//! # }
+//! ```
+//!
+//! Commands print the same output as the CLI; [`events`] streams it as NDJSON
+//! and [`mock`] runs them against canned fastlane and App Store Connect output.
+//!
+//! Dashboards and other tools that only read launchpad's state start from the
+//! types re-exported here: a project's [`ProjectConfig`], the machine's
+//! [`GlobalConfig`], the [`DeployRecord`]s in the deploy history and an
+//! [`AscClient`] for what App Store Connect knows:
+//!
+//! ```no_run
+//! use launchpad_core::{history, AscClient, GlobalConfig, ProjectConfig};
+//! use std::path::Path;
+//!
+//! # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+//! let config = ProjectConfig::load_from(Path::new("/src/myapp"))?.expect("not a launchpad project");
+//! let project = &config.project;
+//! let deploys: Vec<_> = history::load()?
+//!     .into_iter()
+//!     .filter(|record| record.bundle_id == project.bundle_id)
+//!     .collect();
+//!
+//! if let Some(global) = GlobalConfig::load()? {
+//!     let client = AscClient::new(&global.apple)?;
+//!     let app_id = client.project_app_id(&project.bundle_id, Some(project)).await?;
+//!     let latest = client.latest_build(&app_id).await?;
+//!     println!("{} deploys, latest build {:?}", deploys.len(), latest.map(|b| b.number));
+//! }
+//! # Ok(())
+//! # }
+//! ```
+
+pub mod artifact;
+pub mod asc;
+pub mod badge;
+pub mod cache;
+pub mod commands;
+pub mod config;
+pub mod devices;
+pub mod email;
+pub mod events;
+pub mod fastlane;
+pub mod files;
+pub mod git;
+pub mod gradle;
+pub mod history;
+pub mod hooks;
+pub mod http;
+pub mod i18n;
+pub mod integrations;
+pub mod ipa;
+pub mod kmp;
+pub mod mock;
+pub mod notary;
+pub mod notifications;
+pub mod play;
+pub mod presets;
+pub mod profiles;
+pub mod summary;
+pub mod symbols;
+pub mod templates;
+pub mod templating;
+pub mod testflight;
+pub mod thinning;
+pub mod ui;
+pub mod version;
+pub mod xcode;
+
+pub use asc::{AscClient, AscError};
+pub use config::global::GlobalConfig;
+pub use config::project::ProjectConfig;
+pub use history::DeployRecord;
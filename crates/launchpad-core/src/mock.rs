@@ -0,0 +1,207 @@
+use crate::config::project::ProjectConfig;
+use crate::version;
+use reqwest::header::{HeaderValue, CONTENT_TYPE};
+use reqwest::{Method, StatusCode};
+use serde_json::{json, Value};
+use std::sync::atomic::{AtomicU32, Ordering};
+
+/// Version the mocked Xcode project is at; mocked lanes build the next one
+pub const VERSION: &str = "1.0.0";
+pub const BUILD_NUMBER: u32 = 1;
+
+/// Numbers the resources mocked App Store Connect creates
+static NEXT_ID: AtomicU32 = AtomicU32::new(0);
+
+/// `--mock` or LAUNCHPAD_MOCK=1: fastlane, xcodebuild and App Store Connect
+/// answer with canned output, so hooks, notifications and CI wiring can be
+/// tried without building or uploading anything
+pub fn enabled() -> bool {
+    std::env::var("LAUNCHPAD_MOCK").is_ok_and(|v| v == "1")
+}
+
+fn next_id(kind: &str) -> String {
+    format!(
+        "mock-{}-{}",
+        kind,
+        NEXT_ID.fetch_add(1, Ordering::Relaxed) + 1
+    )
+}
+
+/// Scheme and bundle ID from .launchpad.toml, for answers that name the app
+fn project() -> (String, String) {
+    match ProjectConfig::load() {
+        Ok(Some(config)) => (config.project.scheme, config.project.bundle_id),
+        _ => ("App".to_string(), "com.example.app".to_string()),
+    }
+}
+
+/// `xcodebuild -showBuildSettings` for a project with one app target
+pub fn build_settings() -> String {
+    let (scheme, bundle_id) = project();
+    format!(
+        "Build settings for action build and target {scheme}:\n    \
+         CODE_SIGN_STYLE = Automatic\n    \
+         CURRENT_PROJECT_VERSION = {BUILD_NUMBER}\n    \
+         MARKETING_VERSION = {VERSION}\n    \
+         PRODUCT_BUNDLE_IDENTIFIER = {bundle_id}\n    \
+         PRODUCT_NAME = {scheme}\n    \
+         PRODUCT_TYPE = com.apple.product-type.application\n"
+    )
+}
+
+/// What fastlane would print running `lane`, ending with the version it built
+pub fn lane_output(lane: &str, skip_upload: bool) -> Vec<String> {
+    // A version bump starts the build numbers over, like the real lanes
+    let version = match lane {
+        "beta" => Some(format!("{} ({})", VERSION, BUILD_NUMBER + 1)),
+        "beta_patch" => version::bump(VERSION, "patch").map(|v| format!("{} (1)", v)),
+        "beta_minor" => version::bump(VERSION, "minor").map(|v| format!("{} (1)", v)),
+        "beta_major" => version::bump(VERSION, "major").map(|v| format!("{} (1)", v)),
+        _ => None,
+    };
+
+    let mut lines = vec![format!("[mock] fastlane {}", lane)];
+    let step = |name: &str| format!("--- Step: {} ---", name);
+    match (&version, lane) {
+        (Some(_), _) => {
+            lines.push(step("increment_build_number"));
+            if lane != "beta" {
+                lines.push(step("increment_version_number"));
+            }
+            lines.push(step("build_app"));
+        }
+        (None, "upload_only") => {}
+        (None, _) => lines.push(step(lane)),
+    }
+    if !skip_upload && (version.is_some() || lane == "upload_only") {
+        lines.push(step("upload_to_testflight"));
+        for percent in [25, 50, 75, 100] {
+            lines.push(format!("[Transporter]: Upload progress: {}%", percent));
+        }
+        lines.push("Successfully uploaded the new binary to App Store Connect".to_string());
+    }
+    if let Some(version) = version {
+        lines.push(format!("Version: {}", version));
+    }
+    lines.push("fastlane.tools finished successfully".to_string());
+    lines
+}
+
+/// App Store Connect's answer to `request`: the app and build a deploy looks
+/// for, processed right away, the project's TestFlight groups, and success
+/// for everything it changes
+pub fn asc_response(request: &reqwest::Request) -> reqwest::Response {
+    let body = request
+        .body()
+        .and_then(reqwest::Body::as_bytes)
+        .and_then(|bytes| serde_json::from_slice::<Value>(bytes).ok());
+    let (status, document) = asc_answer(request.method(), request.url(), body);
+
+    let mut response = ::http::Response::new(document.map(|d| d.to_string()).unwrap_or_default());
+    *response.status_mut() = status;
+    response
+        .headers_mut()
+        .insert(CONTENT_TYPE, HeaderValue::from_static("application/json"));
+    reqwest::Response::from(response)
+}
+
+fn asc_answer(
+    method: &Method,
+    url: &reqwest::Url,
+    body: Option<Value>,
+) -> (StatusCode, Option<Value>) {
+    // File uploads to Apple's storage and report downloads
+    if url.host_str() != Some("api.appstoreconnect.apple.com") {
+        return (StatusCode::OK, None);
+    }
+    // Past "/v1"
+    let segments: Vec<&str> = url
+        .path_segments()
+        .map(|segments| segments.skip(1).collect())
+        .unwrap_or_default();
+    let query = |name: &str| {
+        url.query_pairs()
+            .find(|(key, _)| key == name)
+            .map(|(_, value)| value.into_owned())
+    };
+
+    let data = body.and_then(|mut body| body.get_mut("data").map(Value::take));
+    match *method {
+        Method::GET => (
+            StatusCode::OK,
+            Some(json!({ "data": get(&segments, query("filter[version]")) })),
+        ),
+        Method::POST => match data {
+            Some(Value::Object(mut resource)) => {
+                let kind = resource
+                    .get("type")
+                    .and_then(Value::as_str)
+                    .unwrap_or("resource")
+                    .to_string();
+                resource.insert("id".to_string(), json!(next_id(&kind)));
+                resource.entry("attributes").or_insert_with(|| json!({}));
+                (StatusCode::CREATED, Some(json!({ "data": resource })))
+            }
+            // Relationship changes answer with no content
+            _ => (StatusCode::NO_CONTENT, None),
+        },
+        Method::PATCH => (StatusCode::OK, Some(json!({ "data": data }))),
+        _ => (StatusCode::NO_CONTENT, None),
+    }
+}
+
+fn get(segments: &[&str], build_number: Option<String>) -> Value {
+    let (scheme, bundle_id) = project();
+    let app = |id: &str| {
+        json!({
+            "type": "apps",
+            "id": id,
+            "attributes": { "name": scheme, "bundleId": bundle_id, "primaryLocale": "en-US" }
+        })
+    };
+    let build = |id: &str, number: String| {
+        json!({
+            "type": "builds",
+            "id": id,
+            "attributes": {
+                "version": number,
+                "processingState": "VALID",
+                "uploadedDate": plist::Date::from(std::time::SystemTime::now()).to_xml_format()
+            }
+        })
+    };
+    let latest = || (BUILD_NUMBER + 1).to_string();
+
+    match segments {
+        ["apps"] => json!([app("mock-app")]),
+        ["apps", id] => app(id),
+        ["builds"] => json!([build("mock-build", build_number.unwrap_or_else(latest))]),
+        ["builds", id] => build(id, latest()),
+        ["betaGroups"] | ["apps", _, "betaGroups"] => {
+            let groups = ProjectConfig::load()
+                .ok()
+                .flatten()
+                .map(|config| config.deploy.groups)
+                .unwrap_or_default();
+            groups
+                .iter()
+                .enumerate()
+                .map(|(i, name)| {
+                    json!({
+                        "type": "betaGroups",
+                        "id": format!("mock-group-{}", i + 1),
+                        "attributes": { "name": name, "isInternalGroup": false }
+                    })
+                })
+                .collect()
+        }
+        // "/builds/{id}/buildBetaDetail": a to-one relationship
+        [.., last] if !segments.len().is_multiple_of(2) && !last.ends_with('s') => {
+            json!({ "type": format!("{}s", last), "id": next_id(last), "attributes": {} })
+        }
+        [.., kind, id] if segments.len().is_multiple_of(2) => {
+            json!({ "type": kind, "id": id, "attributes": {} })
+        }
+        _ => json!([]),
+    }
+}
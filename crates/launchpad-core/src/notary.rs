@@ -0,0 +1,155 @@
+use crate::config::global::AppleConfig;
+use serde::Deserialize;
+use std::path::Path;
+use thiserror::Error;
+use tokio::process::Command;
+
+#[derive(Error, Debug)]
+pub enum NotaryError {
+    #[error("notarytool not found; notarization needs Xcode 13 or later")]
+    NotInstalled,
+
+    #[error("notarytool {command} failed: {message}")]
+    CommandFailed { command: String, message: String },
+
+    #[error("Unexpected notarytool output: {0}")]
+    Parse(#[from] serde_json::Error),
+
+    #[error("Stapling failed: {0}")]
+    Staple(String),
+
+    #[error("IO error: {0}")]
+    Io(#[from] std::io::Error),
+}
+
+/// Where a submission stands, as `notarytool info` reports it
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Status {
+    InProgress,
+    Accepted,
+    /// Signing or hardened runtime problems; the log lists them
+    Invalid,
+    Rejected,
+}
+
+#[derive(Deserialize)]
+struct SubmitOutput {
+    id: String,
+}
+
+#[derive(Deserialize)]
+struct InfoOutput {
+    status: String,
+}
+
+#[derive(Deserialize)]
+struct LogOutput {
+    #[serde(default)]
+    issues: Option<Vec<Issue>>,
+}
+
+/// One problem from the submission log
+#[derive(Deserialize)]
+pub struct Issue {
+    pub path: Option<String>,
+    pub message: String,
+}
+
+/// `xcrun notarytool` authenticated with the App Store Connect API key
+pub struct Notary {
+    key_id: String,
+    issuer_id: String,
+    key_path: String,
+}
+
+impl Notary {
+    pub fn new(apple: &AppleConfig) -> Self {
+        Self {
+            key_id: apple.key_id.clone(),
+            issuer_id: apple.issuer_id.clone(),
+            key_path: shellexpand::tilde(&apple.key_path).to_string(),
+        }
+    }
+
+    /// Check if notarytool is available (Xcode 13+)
+    pub async fn is_installed() -> bool {
+        Command::new("xcrun")
+            .args(["--find", "notarytool"])
+            .output()
+            .await
+            .is_ok_and(|output| output.status.success())
+    }
+
+    /// Upload a .zip, .dmg or .pkg; returns the submission ID
+    pub async fn submit(&self, path: &Path) -> Result<String, NotaryError> {
+        let path = path.to_string_lossy();
+        let output = self.run(&["submit", &path]).await?;
+        let submitted: SubmitOutput = serde_json::from_str(&output)?;
+        Ok(submitted.id)
+    }
+
+    pub async fn status(&self, id: &str) -> Result<Status, NotaryError> {
+        let output = self.run(&["info", id]).await?;
+        let info: InfoOutput = serde_json::from_str(&output)?;
+        Ok(match info.status.as_str() {
+            "Accepted" => Status::Accepted,
+            "Invalid" => Status::Invalid,
+            "Rejected" => Status::Rejected,
+            _ => Status::InProgress,
+        })
+    }
+
+    /// Problems found in a submission (empty for a clean one)
+    pub async fn issues(&self, id: &str) -> Result<Vec<Issue>, NotaryError> {
+        let output = self.run(&["log", id]).await?;
+        let log: LogOutput = serde_json::from_str(&output)?;
+        Ok(log.issues.unwrap_or_default())
+    }
+
+    /// Attach the ticket so Gatekeeper accepts the file offline
+    pub async fn staple(path: &Path) -> Result<(), NotaryError> {
+        let output = Command::new("xcrun")
+            .args(["stapler", "staple"])
+            .arg(path)
+            .output()
+            .await?;
+        if !output.status.success() {
+            let stdout = String::from_utf8_lossy(&output.stdout);
+            return Err(NotaryError::Staple(
+                stdout.lines().last().unwrap_or_default().to_string(),
+            ));
+        }
+        Ok(())
+    }
+
+    async fn run(&self, args: &[&str]) -> Result<String, NotaryError> {
+        let output = Command::new("xcrun")
+            .arg("notarytool")
+            .args(args)
+            .args(["--key", &self.key_path])
+            .args(["--key-id", &self.key_id])
+            .args(["--issuer", &self.issuer_id])
+            .args(["--output-format", "json"])
+            .output()
+            .await
+            .map_err(|e| match e.kind() {
+                std::io::ErrorKind::NotFound => NotaryError::NotInstalled,
+                _ => NotaryError::Io(e),
+            })?;
+
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            let stdout = String::from_utf8_lossy(&output.stdout);
+            let message = if stderr.trim().is_empty() {
+                stdout.trim()
+            } else {
+                stderr.trim()
+            };
+            return Err(NotaryError::CommandFailed {
+                command: args.first().copied().unwrap_or_default().to_string(),
+                message: message.to_string(),
+            });
+        }
+        Ok(String::from_utf8_lossy(&output.stdout).to_string())
+    }
+}
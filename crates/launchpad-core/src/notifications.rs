@@ -0,0 +1,200 @@
+use crate::config::project::{
+    NotificationEvent, NotificationRule, NotificationSettings, WebhookFormat,
+};
+use crate::http;
+use crate::summary::DeploySummary;
+use serde_json::json;
+use std::time::Duration;
+use thiserror::Error;
+
+/// A slow webhook shouldn't hold up the deploy
+const TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Longest error excerpt posted to chat; the full log stays in CI
+const MAX_ERROR_CHARS: usize = 1_000;
+
+#[derive(Error, Debug)]
+pub enum NotificationError {
+    #[error("HTTP error: {0}")]
+    Http(#[from] reqwest::Error),
+
+    #[error("Webhook returned {status}: {body}")]
+    Webhook { status: u16, body: String },
+
+    #[error("Missing webhook URL: set {0}")]
+    MissingWebhook(String),
+
+    #[error("Rule has neither webhook nor webhook_env")]
+    NoWebhook,
+}
+
+/// Environment this deploy is reported under: LAUNCHPAD_ENVIRONMENT, then `environment`
+pub fn environment(settings: Option<&NotificationSettings>) -> Option<String> {
+    match std::env::var("LAUNCHPAD_ENVIRONMENT") {
+        Ok(environment) if !environment.is_empty() => Some(environment),
+        _ => settings.and_then(|s| s.environment.clone()),
+    }
+}
+
+/// Whether any rule posts `event` in the current environment
+pub fn wants(settings: Option<&NotificationSettings>, event: NotificationEvent) -> bool {
+    let Some(settings) = settings else {
+        return false;
+    };
+    let environment = environment(Some(settings));
+    settings
+        .rules
+        .iter()
+        .any(|rule| matches(rule, event, environment.as_deref()))
+}
+
+/// Post `event` to every matching rule, as `text` or the built-in message; returns
+/// one result per webhook, labelled by its channel (or position in the config)
+pub async fn notify(
+    settings: &NotificationSettings,
+    event: NotificationEvent,
+    app: &str,
+    summary: &DeploySummary,
+    text: Option<String>,
+) -> Vec<(String, Result<(), NotificationError>)> {
+    let environment = environment(Some(settings));
+    let text = text.unwrap_or_else(|| message(event, app, environment.as_deref(), summary));
+    let client = match http::client_builder().timeout(TIMEOUT).build() {
+        Ok(client) => client,
+        Err(e) => return vec![("notifications".to_string(), Err(e.into()))],
+    };
+
+    let mut sent: Vec<(String, Option<String>)> = Vec::new();
+    let mut results = Vec::new();
+    for (index, rule) in settings.rules.iter().enumerate() {
+        if !matches(rule, event, environment.as_deref()) {
+            continue;
+        }
+        let label = rule
+            .channel
+            .clone()
+            .unwrap_or_else(|| format!("rule {}", index + 1));
+
+        let url = match webhook_url(rule) {
+            Ok(url) => url,
+            Err(e) => {
+                results.push((label, Err(e)));
+                continue;
+            }
+        };
+        // Two rules matching the same destination would post the message twice
+        let destination = (url, rule.channel.clone());
+        if sent.contains(&destination) {
+            continue;
+        }
+
+        let payload = match rule.format {
+            WebhookFormat::Slack => match &rule.channel {
+                Some(channel) => json!({ "text": text, "channel": channel }),
+                None => json!({ "text": text }),
+            },
+            WebhookFormat::Json => json!({
+                "event": event,
+                "environment": environment,
+                "app": app,
+                "text": text,
+                "summary": summary,
+            }),
+        };
+        results.push((label, post(&client, &destination.0, &payload).await));
+        sent.push(destination);
+    }
+
+    results
+}
+
+fn matches(rule: &NotificationRule, event: NotificationEvent, environment: Option<&str>) -> bool {
+    let event_matches = rule.on.is_empty() || rule.on.contains(&event);
+    let environment_matches = rule.environments.is_empty()
+        || environment.is_some_and(|environment| {
+            rule.environments
+                .iter()
+                .any(|e| e.eq_ignore_ascii_case(environment))
+        });
+    event_matches && environment_matches
+}
+
+fn webhook_url(rule: &NotificationRule) -> Result<String, NotificationError> {
+    if let Some(name) = &rule.webhook_env {
+        return match std::env::var(name) {
+            Ok(url) if !url.is_empty() => Ok(url),
+            _ => Err(NotificationError::MissingWebhook(name.clone())),
+        };
+    }
+    rule.webhook.clone().ok_or(NotificationError::NoWebhook)
+}
+
+async fn post(
+    client: &reqwest::Client,
+    url: &str,
+    payload: &serde_json::Value,
+) -> Result<(), NotificationError> {
+    let response = client
+        .post(url)
+        .header("User-Agent", "launchpad")
+        .json(payload)
+        .send()
+        .await?;
+
+    let status = response.status();
+    if status.is_success() {
+        return Ok(());
+    }
+    let body = response.text().await.unwrap_or_default();
+    Err(NotificationError::Webhook {
+        status: status.as_u16(),
+        body,
+    })
+}
+
+/// Slack-formatted one-liner (plus the error excerpt on failure)
+fn message(
+    event: NotificationEvent,
+    app: &str,
+    environment: Option<&str>,
+    summary: &DeploySummary,
+) -> String {
+    let version = match (&summary.version, &summary.build) {
+        (Some(version), Some(build)) => format!(" {} ({})", version, build),
+        (Some(version), None) => format!(" {}", version),
+        _ => String::new(),
+    };
+    let environment = environment
+        .map(|environment| format!(" [{}]", environment))
+        .unwrap_or_default();
+    let store = match &summary.play_track {
+        Some(track) => format!("Google Play ({} track)", track),
+        None => "TestFlight".to_string(),
+    };
+
+    let mut text = match event {
+        NotificationEvent::Success => format!(
+            ":white_check_mark: *{}*{} uploaded to {}{}",
+            app, version, store, environment
+        ),
+        NotificationEvent::Failure => format!(":x: *{}* deploy failed{}", app, environment),
+        NotificationEvent::Processed => format!(
+            ":package: *{}*{} finished processing and is ready to test{}",
+            app, version, environment
+        ),
+        NotificationEvent::Review => format!(
+            ":mag: *{}*{} App Review: {}{}",
+            app, version, summary.testflight_status, environment
+        ),
+    };
+
+    if let Some(sha) = &summary.git_sha {
+        text.push_str(&format!(" · `{}`", &sha[..sha.len().min(7)]));
+    }
+    if let Some(error) = &summary.error {
+        let excerpt: String = error.chars().take(MAX_ERROR_CHARS).collect();
+        text.push_str(&format!("\n```{}```", excerpt.trim()));
+    }
+
+    text
+}
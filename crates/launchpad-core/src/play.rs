@@ -0,0 +1,286 @@
+use crate::events;
+use crate::http;
+use indicatif::ProgressBar;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use std::path::Path;
+use std::time::{SystemTime, UNIX_EPOCH};
+use thiserror::Error;
+use tokio::io::AsyncReadExt;
+
+const API_URL: &str = "https://androidpublisher.googleapis.com/androidpublisher/v3/applications";
+const UPLOAD_URL: &str =
+    "https://androidpublisher.googleapis.com/upload/androidpublisher/v3/applications";
+const SCOPE: &str = "https://www.googleapis.com/auth/androidpublisher";
+
+/// Bundle bytes read per upload chunk; also how often the progress bar moves
+const UPLOAD_CHUNK: usize = 256 * 1024;
+
+#[derive(Error, Debug)]
+pub enum PlayError {
+    #[error("HTTP error: {0}")]
+    Http(#[from] reqwest::Error),
+
+    #[error("Play API error ({status}): {body}")]
+    Api { status: u16, body: String },
+
+    #[error("Invalid service account key: {0}")]
+    ServiceAccount(String),
+
+    #[error("IO error: {0}")]
+    Io(#[from] std::io::Error),
+}
+
+/// The fields launchpad needs from a Google Cloud service account JSON key
+#[derive(Debug, Deserialize)]
+struct ServiceAccount {
+    client_email: String,
+    private_key: String,
+    token_uri: String,
+}
+
+#[derive(Serialize)]
+struct Claims<'a> {
+    iss: &'a str,
+    scope: &'a str,
+    aud: &'a str,
+    iat: u64,
+    exp: u64,
+}
+
+#[derive(Deserialize)]
+struct TokenResponse {
+    access_token: String,
+}
+
+#[derive(Deserialize)]
+struct Edit {
+    id: String,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Bundle {
+    version_code: u64,
+}
+
+#[derive(Deserialize)]
+struct BundlesResponse {
+    #[serde(default)]
+    bundles: Vec<Bundle>,
+}
+
+/// Google Play Developer API client for one app
+pub struct PlayClient {
+    client: reqwest::Client,
+    token: String,
+    package_name: String,
+}
+
+impl PlayClient {
+    /// Exchange the service account key for an access token
+    pub async fn new(service_account_path: &Path, package_name: &str) -> Result<Self, PlayError> {
+        let content = std::fs::read_to_string(service_account_path)?;
+        let account: ServiceAccount =
+            serde_json::from_str(&content).map_err(|e| PlayError::ServiceAccount(e.to_string()))?;
+
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or_default();
+        let claims = Claims {
+            iss: &account.client_email,
+            scope: SCOPE,
+            aud: &account.token_uri,
+            iat: now,
+            exp: now + 3600,
+        };
+        let key = jsonwebtoken::EncodingKey::from_rsa_pem(account.private_key.as_bytes())
+            .map_err(|e| PlayError::ServiceAccount(e.to_string()))?;
+        let assertion = jsonwebtoken::encode(
+            &jsonwebtoken::Header::new(jsonwebtoken::Algorithm::RS256),
+            &claims,
+            &key,
+        )
+        .map_err(|e| PlayError::ServiceAccount(e.to_string()))?;
+
+        let client = http::client()?;
+        let response = client
+            .post(&account.token_uri)
+            .form(&[
+                ("grant_type", "urn:ietf:params:oauth:grant-type:jwt-bearer"),
+                ("assertion", assertion.as_str()),
+            ])
+            .send()
+            .await?;
+        let token: TokenResponse = check(response).await?.json().await?;
+
+        Ok(Self {
+            client,
+            token: token.access_token,
+            package_name: package_name.to_string(),
+        })
+    }
+
+    /// Highest version code uploaded so far (0 for a new app)
+    pub async fn latest_version_code(&self) -> Result<u64, PlayError> {
+        let edit = self.open_edit().await?;
+        let bundles = self.list_bundles(&edit).await;
+
+        // Read-only edit; discard it either way
+        let _ = self.delete_edit(&edit).await;
+
+        Ok(bundles?.iter().map(|b| b.version_code).max().unwrap_or(0))
+    }
+
+    /// Upload an .aab and release it to `track` in a single edit, advancing
+    /// `progress` by the bytes sent. Returns the version code Play assigned.
+    pub async fn upload_bundle(
+        &self,
+        aab_path: &Path,
+        track: &str,
+        release_name: &str,
+        progress: &ProgressBar,
+    ) -> Result<u64, PlayError> {
+        let edit = self.open_edit().await?;
+
+        let result = self
+            .upload_and_release(&edit, aab_path, track, release_name, progress)
+            .await;
+        if result.is_err() {
+            let _ = self.delete_edit(&edit).await;
+        }
+        result
+    }
+
+    async fn upload_and_release(
+        &self,
+        edit: &str,
+        aab_path: &Path,
+        track: &str,
+        release_name: &str,
+        progress: &ProgressBar,
+    ) -> Result<u64, PlayError> {
+        let url = format!(
+            "{}/{}/edits/{}/bundles?uploadType=media",
+            UPLOAD_URL, self.package_name, edit
+        );
+        let file = tokio::fs::File::open(aab_path).await?;
+        let size = file.metadata().await?.len();
+        let response = self
+            .client
+            .post(&url)
+            .bearer_auth(&self.token)
+            .header("Content-Type", "application/octet-stream")
+            .header("Content-Length", size)
+            .body(upload_body(file, size, progress.clone()))
+            .send()
+            .await?;
+        let bundle: Bundle = check(response).await?.json().await?;
+
+        let url = format!(
+            "{}/{}/edits/{}/tracks/{}",
+            API_URL, self.package_name, edit, track
+        );
+        let response = self
+            .client
+            .put(&url)
+            .bearer_auth(&self.token)
+            .json(&json!({
+                "track": track,
+                "releases": [{
+                    "name": release_name,
+                    "versionCodes": [bundle.version_code.to_string()],
+                    "status": "completed",
+                }],
+            }))
+            .send()
+            .await?;
+        check(response).await?;
+
+        let url = format!("{}/{}/edits/{}:commit", API_URL, self.package_name, edit);
+        let response = self
+            .client
+            .post(&url)
+            .bearer_auth(&self.token)
+            .header("Content-Length", "0")
+            .send()
+            .await?;
+        check(response).await?;
+
+        Ok(bundle.version_code)
+    }
+
+    async fn list_bundles(&self, edit: &str) -> Result<Vec<Bundle>, PlayError> {
+        let url = format!("{}/{}/edits/{}/bundles", API_URL, self.package_name, edit);
+        let response = self
+            .client
+            .get(&url)
+            .bearer_auth(&self.token)
+            .send()
+            .await?;
+        let bundles: BundlesResponse = check(response).await?.json().await?;
+        Ok(bundles.bundles)
+    }
+
+    async fn open_edit(&self) -> Result<String, PlayError> {
+        let url = format!("{}/{}/edits", API_URL, self.package_name);
+        let response = self
+            .client
+            .post(&url)
+            .bearer_auth(&self.token)
+            .json(&json!({}))
+            .send()
+            .await?;
+        let edit: Edit = check(response).await?.json().await?;
+        Ok(edit.id)
+    }
+
+    async fn delete_edit(&self, edit: &str) -> Result<(), PlayError> {
+        let url = format!("{}/{}/edits/{}", API_URL, self.package_name, edit);
+        let response = self
+            .client
+            .delete(&url)
+            .bearer_auth(&self.token)
+            .send()
+            .await?;
+        check(response).await?;
+        Ok(())
+    }
+}
+
+/// Stream the file in chunks, counting each one as it is handed to the connection
+fn upload_body(file: tokio::fs::File, size: u64, progress: ProgressBar) -> reqwest::Body {
+    let chunks = futures_util::stream::try_unfold((file, 0u64), move |(mut file, sent)| {
+        let progress = progress.clone();
+        async move {
+            let mut chunk = vec![0; UPLOAD_CHUNK];
+            let read = file.read(&mut chunk).await?;
+            if read == 0 {
+                return Ok::<_, std::io::Error>(None);
+            }
+            chunk.truncate(read);
+            progress.inc(read as u64);
+
+            // One event per percent is plenty for a wrapper's progress bar
+            let done = sent + read as u64;
+            if size > 0 && done * 100 / size != sent * 100 / size {
+                events::progress("upload", done, size);
+            }
+            Ok(Some((chunk, (file, done))))
+        }
+    });
+    reqwest::Body::wrap_stream(chunks)
+}
+
+async fn check(response: reqwest::Response) -> Result<reqwest::Response, PlayError> {
+    let status = response.status();
+    if status.is_success() {
+        return Ok(response);
+    }
+
+    Err(PlayError::Api {
+        status: status.as_u16(),
+        body: response.text().await.unwrap_or_default(),
+    })
+}
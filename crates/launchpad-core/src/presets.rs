@@ -0,0 +1,86 @@
+use crate::kmp;
+use clap::ValueEnum;
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+/// Project flavours `launchpad init --template` knows how to set up
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Preset {
+    /// Plain Xcode project or workspace
+    Native,
+    /// Flutter app (ios/Runner.xcworkspace)
+    Flutter,
+    /// React Native / Expo app (ios/ with CocoaPods)
+    ReactNative,
+    /// Kotlin Multiplatform app (iosApp/ + shared module)
+    Kmp,
+}
+
+impl Preset {
+    pub const ALL: [Preset; 4] = [
+        Preset::Native,
+        Preset::Flutter,
+        Preset::ReactNative,
+        Preset::Kmp,
+    ];
+
+    /// Guess the preset from marker files in the current directory
+    pub fn detect() -> Self {
+        if Path::new("pubspec.yaml").exists() {
+            return Preset::Flutter;
+        }
+
+        let is_react_native = std::fs::read_to_string("package.json")
+            .map(|content| content.contains("\"react-native\""))
+            .unwrap_or(false);
+        if is_react_native {
+            return Preset::ReactNative;
+        }
+
+        if kmp::detect(Path::new(".")).is_some() {
+            return Preset::Kmp;
+        }
+
+        Preset::Native
+    }
+
+    /// Where this kind of project usually keeps its Xcode project, most likely first
+    /// (ranks the directories init finds)
+    pub fn ios_path_candidates(&self) -> &'static [&'static str] {
+        match self {
+            Preset::Native => &["ios", ".", "App", "app"],
+            Preset::Flutter | Preset::ReactNative => &["ios"],
+            Preset::Kmp => &["iosApp"],
+        }
+    }
+
+    /// `pre_version_bump` hooks that prepare dependencies before the build
+    pub fn default_hooks(&self) -> Vec<String> {
+        match self {
+            Preset::Native | Preset::Kmp => Vec::new(),
+            Preset::Flutter => vec!["flutter pub get".to_string()],
+            Preset::ReactNative => {
+                let install = if Path::new("yarn.lock").exists() {
+                    "yarn install --frozen-lockfile"
+                } else if Path::new("pnpm-lock.yaml").exists() {
+                    "pnpm install --frozen-lockfile"
+                } else {
+                    "npm ci"
+                };
+                vec![install.to_string()]
+            }
+        }
+    }
+}
+
+impl std::fmt::Display for Preset {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Preset::Native => write!(f, "native"),
+            Preset::Flutter => write!(f, "flutter"),
+            Preset::ReactNative => write!(f, "react-native"),
+            Preset::Kmp => write!(f, "kmp"),
+        }
+    }
+}
@@ -0,0 +1,236 @@
+use crate::asc::PortalProfile;
+use serde::Deserialize;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+/// Where a profile was found
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Source {
+    /// On this machine (Xcode's profile directories)
+    Installed,
+    /// On the Developer portal (App Store Connect API)
+    Portal,
+}
+
+impl std::fmt::Display for Source {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Source::Installed => write!(f, "Installed"),
+            Source::Portal => write!(f, "Developer portal"),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct Profile {
+    pub name: String,
+    /// Bundle identifier without the team prefix; may be a wildcard
+    pub bundle_id: String,
+    pub expires: Option<plist::Date>,
+    pub source: Source,
+    /// Portal profile type (e.g. IOS_APP_STORE)
+    pub kind: Option<String>,
+    /// Revoked on the portal (certificate revoked, app ID changed, …)
+    pub invalid: bool,
+}
+
+impl Profile {
+    /// Whole days until expiry (negative once expired)
+    pub fn days_left(&self) -> Option<i64> {
+        let expires = SystemTime::from(self.expires?);
+        let secs = match expires.duration_since(SystemTime::now()) {
+            Ok(left) => left.as_secs() as i64,
+            Err(past) => -(past.duration().as_secs() as i64),
+        };
+        Some(secs.div_euclid(86_400))
+    }
+
+    /// Expiry as YYYY-MM-DD
+    pub fn expiry_date(&self) -> Option<String> {
+        let date = self.expires?.to_xml_format();
+        Some(date.chars().take(10).collect())
+    }
+
+    /// Whether this profile (possibly a wildcard) can sign `bundle_id` itself
+    pub fn covers(&self, bundle_id: &str) -> bool {
+        match self.bundle_id.strip_suffix('*') {
+            Some(prefix) => bundle_id.starts_with(prefix),
+            None => self.bundle_id == bundle_id,
+        }
+    }
+
+    /// Whether this profile signs one of the project's bundle IDs (or its extensions)
+    pub fn matches(&self, bundle_ids: &[String]) -> bool {
+        bundle_ids.iter().any(|id| {
+            if let Some(prefix) = self.bundle_id.strip_suffix('*') {
+                id.starts_with(prefix)
+            } else {
+                self.bundle_id == *id || self.bundle_id.starts_with(&format!("{}.", id))
+            }
+        })
+    }
+}
+
+impl From<PortalProfile> for Profile {
+    fn from(profile: PortalProfile) -> Self {
+        Profile {
+            name: profile.name,
+            bundle_id: profile.bundle_id.unwrap_or_default(),
+            // The API uses "+0000" offsets, which RFC 3339 spells "+00:00"
+            expires: profile
+                .expiration_date
+                .and_then(|d| plist::Date::from_xml_format(&d.replace("+0000", "Z")).ok()),
+            source: Source::Portal,
+            kind: profile.profile_type,
+            invalid: profile.state.as_deref() == Some("INVALID"),
+        }
+    }
+}
+
+/// What a profile embedded in a build allows, beyond its name and expiry
+#[derive(Debug)]
+pub struct Provisioning {
+    pub profile: Profile,
+    pub team: Option<String>,
+    /// UDIDs a development or ad-hoc profile installs on
+    pub devices: Vec<String>,
+    /// Enterprise (in-house) profiles install on any device
+    pub all_devices: bool,
+    /// get-task-allow: development profiles let the debugger attach
+    pub debuggable: bool,
+}
+
+impl Provisioning {
+    /// Distribution method, in Xcode's export method terms
+    pub fn method(&self) -> &'static str {
+        match (self.all_devices, self.devices.is_empty(), self.debuggable) {
+            (true, _, _) => "enterprise",
+            (false, true, _) => "app-store",
+            (false, false, true) => "development",
+            (false, false, false) => "ad-hoc",
+        }
+    }
+
+    /// Whether a device can install the build directly (App Store builds never can)
+    pub fn allows_device(&self, udid: &str) -> bool {
+        self.all_devices
+            || self
+                .devices
+                .iter()
+                .any(|device| device.eq_ignore_ascii_case(udid))
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct ProvisioningPlist {
+    name: String,
+    expiration_date: plist::Date,
+    entitlements: ProvisioningEntitlements,
+    team_name: Option<String>,
+    #[serde(default)]
+    provisioned_devices: Vec<String>,
+    #[serde(default)]
+    provisions_all_devices: bool,
+}
+
+#[derive(Deserialize)]
+struct ProvisioningEntitlements {
+    #[serde(rename = "application-identifier")]
+    application_identifier: Option<String>,
+    #[serde(rename = "get-task-allow", default)]
+    get_task_allow: bool,
+}
+
+/// Directories Xcode installs profiles into (the second one since Xcode 16)
+fn profile_dirs() -> Vec<PathBuf> {
+    let Some(home) = dirs::home_dir() else {
+        return Vec::new();
+    };
+
+    vec![
+        home.join("Library/MobileDevice/Provisioning Profiles"),
+        home.join("Library/Developer/Xcode/UserData/Provisioning Profiles"),
+    ]
+}
+
+/// Every readable .mobileprovision installed on this machine
+pub fn installed() -> Vec<Profile> {
+    installed_files()
+        .into_iter()
+        .filter_map(|path| read(&path))
+        .collect()
+}
+
+fn installed_files() -> Vec<PathBuf> {
+    profile_dirs()
+        .into_iter()
+        .filter_map(|dir| std::fs::read_dir(dir).ok())
+        .flatten()
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.path())
+        .filter(|path| path.extension().is_some_and(|ext| ext == "mobileprovision"))
+        .collect()
+}
+
+/// The installed profile called `name`; the one that expires last if there are several
+pub fn find_installed(name: &str) -> Option<(PathBuf, Profile)> {
+    installed_files()
+        .into_iter()
+        .filter_map(|path| Some((path.clone(), read(&path)?)))
+        .filter(|(_, profile)| profile.name == name)
+        .max_by_key(|(_, profile)| profile.days_left())
+}
+
+/// Parse a .mobileprovision file
+pub fn read(path: &Path) -> Option<Profile> {
+    parse(&std::fs::read(path).ok()?)
+}
+
+/// Parse a .mobileprovision file with its devices and distribution method
+pub fn read_provisioning(path: &Path) -> Option<Provisioning> {
+    let bytes = std::fs::read(path).ok()?;
+    let plist: ProvisioningPlist = plist::from_bytes(embedded_plist(&bytes)?).ok()?;
+    Some(Provisioning {
+        profile: parse(&bytes)?,
+        team: plist.team_name,
+        devices: plist.provisioned_devices,
+        all_devices: plist.provisions_all_devices,
+        debuggable: plist.entitlements.get_task_allow,
+    })
+}
+
+/// Entitlements a profile grants, which the app is signed with when using it
+pub fn entitlements(path: &Path) -> Option<plist::Dictionary> {
+    let bytes = std::fs::read(path).ok()?;
+    let plist: plist::Dictionary = plist::from_bytes(embedded_plist(&bytes)?).ok()?;
+    plist.get("Entitlements")?.as_dictionary().cloned()
+}
+
+/// The plist is embedded as-is in the CMS envelope, so slice it out
+fn embedded_plist(bytes: &[u8]) -> Option<&[u8]> {
+    let start = find(bytes, b"<?xml")?;
+    let end = find(&bytes[start..], b"</plist>")? + start + b"</plist>".len();
+    Some(&bytes[start..end])
+}
+
+fn parse(bytes: &[u8]) -> Option<Profile> {
+    let plist: ProvisioningPlist = plist::from_bytes(embedded_plist(bytes)?).ok()?;
+
+    // "TEAMID.com.example.app" → "com.example.app"
+    let app_id = plist.entitlements.application_identifier?;
+    let bundle_id = app_id.split_once('.').map(|(_, id)| id)?.to_string();
+
+    Some(Profile {
+        name: plist.name,
+        bundle_id,
+        expires: Some(plist.expiration_date),
+        source: Source::Installed,
+        kind: None,
+        invalid: false,
+    })
+}
+
+fn find(haystack: &[u8], needle: &[u8]) -> Option<usize> {
+    haystack.windows(needle.len()).position(|w| w == needle)
+}
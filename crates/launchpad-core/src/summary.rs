@@ -0,0 +1,37 @@
+use serde::Serialize;
+use std::path::Path;
+
+/// Machine-readable record of a deploy, written for downstream pipeline steps
+#[derive(Debug, Serialize)]
+pub struct DeploySummary {
+    pub version: Option<String>,
+    pub build: Option<String>,
+    pub git_sha: Option<String>,
+    pub ipa_path: Option<String>,
+    pub ipa_size: Option<u64>,
+    pub dsym_path: Option<String>,
+    /// Store processing state ("processing" on TestFlight, the Play release status on Android)
+    pub testflight_status: String,
+    pub duration_secs: u64,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub aab_path: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub play_track: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+impl DeploySummary {
+    /// Write the summary as pretty-printed JSON, creating parent directories
+    pub fn write(&self, path: &Path) -> Result<(), std::io::Error> {
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+
+        let content = serde_json::to_string_pretty(self).map_err(std::io::Error::other)?;
+        std::fs::write(path, content + "\n")
+    }
+}
@@ -0,0 +1,96 @@
+use crate::config::project::CrashReporter;
+use std::path::Path;
+use std::process::Command;
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+pub enum SymbolsError {
+    #[error("{tool} not found. Install it or adjust [symbols] in .launchpad.toml")]
+    ToolNotFound { tool: String },
+
+    #[error("{tool} failed with status {code}")]
+    Failed { tool: String, code: i32 },
+
+    #[error("Missing API key: set {0}")]
+    MissingKey(String),
+
+    #[error("IO error: {0}")]
+    Io(#[from] std::io::Error),
+}
+
+/// Unpack a dSYM archive downloaded from App Store Connect into `dest`
+pub fn extract(archive: &Path, dest: &Path) -> Result<(), SymbolsError> {
+    let status = Command::new("unzip")
+        .arg("-o")
+        .arg("-q")
+        .arg(archive)
+        .arg("-d")
+        .arg(dest)
+        .status()
+        .map_err(|e| not_found("unzip", e))?;
+
+    check("unzip", status)
+}
+
+/// Send every dSYM under `dir` to the configured crash reporter
+pub fn upload(reporter: &CrashReporter, ios_path: &str, dir: &Path) -> Result<(), SymbolsError> {
+    let (tool, mut cmd) = match reporter {
+        CrashReporter::Sentry { org, project } => {
+            let mut cmd = Command::new("sentry-cli");
+            cmd.args(["debug-files", "upload", "--org", org, "--project", project])
+                .arg(dir);
+            ("sentry-cli", cmd)
+        }
+        CrashReporter::Crashlytics {
+            google_service_plist,
+            upload_symbols,
+        } => {
+            let script = upload_symbols
+                .clone()
+                .unwrap_or_else(|| format!("{}/Pods/FirebaseCrashlytics/upload-symbols", ios_path));
+            let mut cmd = Command::new(&script);
+            cmd.args(["-gsp", google_service_plist, "-p", "ios"])
+                .arg(dir);
+            ("upload-symbols", cmd)
+        }
+        CrashReporter::Bugsnag { api_key_env } => {
+            let api_key = std::env::var(api_key_env)
+                .map_err(|_| SymbolsError::MissingKey(api_key_env.clone()))?;
+            let mut cmd = Command::new("bugsnag-cli");
+            cmd.args(["upload", "dsym", "--api-key", &api_key]).arg(dir);
+            ("bugsnag-cli", cmd)
+        }
+        CrashReporter::Command { command } => {
+            let dir = dir.to_string_lossy();
+            let mut cmd = Command::new("sh");
+            cmd.arg("-c")
+                .arg(command.replace("{path}", &dir))
+                .env("LAUNCHPAD_DSYM_PATH", dir.as_ref());
+            ("symbols command", cmd)
+        }
+    };
+
+    let status = cmd.status().map_err(|e| not_found(tool, e))?;
+    check(tool, status)
+}
+
+fn not_found(tool: &str, e: std::io::Error) -> SymbolsError {
+    if e.kind() == std::io::ErrorKind::NotFound {
+        SymbolsError::ToolNotFound {
+            tool: tool.to_string(),
+        }
+    } else {
+        SymbolsError::Io(e)
+    }
+}
+
+fn check(tool: &str, status: std::process::ExitStatus) -> Result<(), SymbolsError> {
+    if status.success() {
+        return Ok(());
+    }
+
+    Err(SymbolsError::Failed {
+        tool: tool.to_string(),
+        code: status.code().unwrap_or(-1),
+    })
+}
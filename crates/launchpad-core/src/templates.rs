@@ -0,0 +1,481 @@
+use crate::config::project::{KmpSettings, ProjectConfig, SigningStyle};
+use crate::kmp::FrameworkIntegration;
+use crate::presets::Preset;
+use crate::xcode::Xcode;
+use sha2::{Digest, Sha256};
+use std::collections::BTreeMap;
+use std::path::{Component, Path};
+
+/// Marker lines around the part of the Fastfile that `launchpad template update` owns
+pub const MANAGED_BEGIN: &str = "# launchpad:begin";
+pub const MANAGED_END: &str = "# launchpad:end";
+
+/// Fastfile template; `{{NAME}}` placeholders are filled from a [`FastfileContext`]
+pub const FASTFILE_TEMPLATE: &str = r#"# Managed by launchpad: `launchpad template update` rewrites everything up to
+# "launchpad:end". Add your own lanes below that line.
+default_platform(:ios)
+
+# Build the .ipa into LAUNCHPAD_OUTPUT_DIR and name it after LAUNCHPAD_IPA_NAME
+# ({version} and {build} are read back from the built app). The LAUNCHPAD_*
+# variables set by `launchpad deploy` win over the values baked in at init.
+def launchpad_build
+  options = {
+    scheme: "{{SCHEME}}",{{CONTAINER}}
+    configuration: ENV["LAUNCHPAD_CONFIGURATION"] || "{{CONFIGURATION}}",
+    export_method: ENV["LAUNCHPAD_EXPORT_METHOD"] || "{{EXPORT_METHOD}}",
+    output_directory: ENV["LAUNCHPAD_OUTPUT_DIR"] || "{{OUTPUT_DIR}}"
+  }
+  options[:archive_path] = File.join(options[:output_directory], "{{SCHEME}}.xcarchive")
+  # Build cache ([cache] in .launchpad.toml)
+  options[:derived_data_path] = ENV["LAUNCHPAD_DERIVED_DATA_PATH"] if ENV["LAUNCHPAD_DERIVED_DATA_PATH"]
+  options[:cloned_source_packages_path] = ENV["LAUNCHPAD_SPM_CACHE_PATH"] if ENV["LAUNCHPAD_SPM_CACHE_PATH"]
+  # Per-environment build settings ([environments.<name>.build_env] in .launchpad.toml)
+  options[:xcconfig] = ENV["LAUNCHPAD_XCCONFIG"] if ENV["LAUNCHPAD_XCCONFIG"]
+  # SDK and destination (build.sdk / build.destination), e.g. for Mac Catalyst
+  options[:sdk] = ENV["LAUNCHPAD_SDK"] if ENV["LAUNCHPAD_SDK"]
+  options[:destination] = ENV["LAUNCHPAD_DESTINATION"] if ENV["LAUNCHPAD_DESTINATION"]
+{{PREPARE}}
+  case ENV["LAUNCHPAD_SIGNING_STYLE"] || "{{SIGNING_STYLE}}"
+  when "automatic"
+    # Let Xcode create/refresh profiles using the App Store Connect API key
+    xcargs = [
+      "-allowProvisioningUpdates",
+      "-authenticationKeyPath #{ENV["APP_STORE_CONNECT_API_KEY_KEY_FILEPATH"].shellescape}",
+      "-authenticationKeyID #{ENV["APP_STORE_CONNECT_API_KEY_KEY_ID"]}",
+      "-authenticationKeyIssuerID #{ENV["APP_STORE_CONNECT_API_KEY_ISSUER_ID"]}"
+    ].join(" ")
+    options[:xcargs] = xcargs
+    options[:export_xcargs] = xcargs
+  when "manual"
+    options[:export_options] = {
+      method: options[:export_method],
+      signingStyle: "manual",
+      provisioningProfiles: JSON.parse(ENV["LAUNCHPAD_PROVISIONING_PROFILES"] || '{{PROFILES}}')
+    }
+  end
+  # Another Apple account's app ([environments.<name>.apple] in .launchpad.toml)
+  account = []
+  account << "DEVELOPMENT_TEAM=#{ENV["LAUNCHPAD_TEAM_ID"]}" if ENV["LAUNCHPAD_TEAM_ID"]
+  account << "PRODUCT_BUNDLE_IDENTIFIER=#{ENV["LAUNCHPAD_PRODUCT_BUNDLE_ID"]}" if ENV["LAUNCHPAD_PRODUCT_BUNDLE_ID"]
+  options[:xcargs] = [options[:xcargs], *account].compact.join(" ") unless account.empty?
+  options[:export_team_id] = ENV["LAUNCHPAD_TEAM_ID"] if ENV["LAUNCHPAD_TEAM_ID"]
+
+  # App Thinning size report ([size] in .launchpad.toml); over-budget builds stop here, before uploading
+  if ENV["LAUNCHPAD_SIZE_BUDGETS"]
+    options[:export_options] = (options[:export_options] || {}).merge(thinning: "<thin-for-all-variants>")
+  end
+
+  # Build metadata ([build.metadata] in .launchpad.toml), in the Info.plist for this build only
+  stamped = launchpad_stamp_info_plist
+  begin
+    build_app(options)
+  ensure
+    File.write(stamped[:path], stamped[:original]) if stamped
+  end
+
+  if ENV["LAUNCHPAD_SIZE_BUDGETS"]
+    report = File.join(options[:output_directory], "App Thinning Size Report.txt")
+    sh(ENV["LAUNCHPAD_BIN"] || "launchpad", "size", "--check", report) if File.exist?(report)
+  end
+
+  name_template = ENV["LAUNCHPAD_IPA_NAME"]
+  unless name_template.nil? || name_template.empty?
+    ipa = lane_context[SharedValues::IPA_OUTPUT_PATH]
+    version = get_ipa_info_plist_value(ipa: ipa, key: "CFBundleShortVersionString")
+    build = get_ipa_info_plist_value(ipa: ipa, key: "CFBundleVersion")
+    renamed = File.join(File.dirname(ipa), name_template.gsub("{version}", version).gsub("{build}", build))
+    File.rename(ipa, renamed) unless renamed == ipa
+    lane_context[SharedValues::IPA_OUTPUT_PATH] = renamed
+  end
+
+  launchpad_write_result
+end
+
+# Tell launchpad what the lane built (LAUNCHPAD_RESULT_PATH), so it doesn't
+# have to find the version in the log
+def launchpad_write_result
+  path = ENV["LAUNCHPAD_RESULT_PATH"]
+  ipa = lane_context[SharedValues::IPA_OUTPUT_PATH]
+  return if path.nil? || ipa.nil? || !File.exist?(ipa)
+
+  File.write(path, JSON.generate(
+    version: get_ipa_info_plist_value(ipa: ipa, key: "CFBundleShortVersionString"),
+    build: get_ipa_info_plist_value(ipa: ipa, key: "CFBundleVersion"),
+    ipa: File.expand_path(ipa)
+  ))
+end
+
+# Add the LAUNCHPAD_BUILD_METADATA keys to the Info.plist; returns what to restore
+def launchpad_stamp_info_plist
+  plist = ENV["LAUNCHPAD_INFO_PLIST"]
+  return nil if ENV["LAUNCHPAD_BUILD_METADATA"].nil? || plist.nil? || !File.exist?(plist)
+
+  original = File.read(plist)
+  JSON.parse(ENV["LAUNCHPAD_BUILD_METADATA"]).each do |key, value|
+    set_info_plist_value(path: plist, key: key, value: value)
+  end
+  { path: plist, original: original }
+end
+
+# Upload to TestFlight; launchpad assigns groups and notifies testers afterwards
+def launchpad_upload
+  # `launchpad deploy --skip-upload`: stop at the .ipa, another system uploads it
+  return UI.message("Skipping the upload (--skip-upload)") if ENV["LAUNCHPAD_SKIP_UPLOAD"]
+
+  options = {
+    api_key_path: ENV["APP_STORE_CONNECT_API_KEY_KEY_FILEPATH"],
+    app_identifier: ENV["LAUNCHPAD_BUNDLE_ID"] || "{{BUNDLE_ID}}",
+    skip_waiting_for_build_processing: true
+  }
+  # "What to Test" from the release_notes template in .launchpad.toml
+  options[:changelog] = ENV["LAUNCHPAD_CHANGELOG"] if ENV["LAUNCHPAD_CHANGELOG"]
+  # Transporter's upload progress is only printed in verbose mode; launchpad
+  # turns it into a progress bar
+  verbose = FastlaneCore::Globals.verbose?
+  begin
+    FastlaneCore::Globals.verbose = true
+    upload_to_testflight(options)
+  ensure
+    FastlaneCore::Globals.verbose = verbose
+  end
+end
+
+platform :ios do
+  lane :beta do
+    increment_build_number
+    launchpad_build
+    launchpad_upload
+  end
+
+  lane :beta_patch do
+    increment_version_number(bump_type: "patch")
+    increment_build_number(build_number: 1)
+    launchpad_build
+    launchpad_upload
+  end
+
+  lane :beta_minor do
+    increment_version_number(bump_type: "minor")
+    increment_build_number(build_number: 1)
+    launchpad_build
+    launchpad_upload
+  end
+
+  lane :beta_major do
+    increment_version_number(bump_type: "major")
+    increment_build_number(build_number: 1)
+    launchpad_build
+    launchpad_upload
+  end
+
+  # `launchpad deploy --reuse-artifact`: upload the .ipa of an earlier build as is
+  lane :upload_only do
+    lane_context[SharedValues::IPA_OUTPUT_PATH] = ENV["LAUNCHPAD_IPA_PATH"]
+    launchpad_write_result
+    launchpad_upload
+  end
+end
+"#;
+
+/// Written once after the managed section; template updates leave it alone
+const USER_SECTION: &str = r#"
+# Your own lanes go here, e.g.
+# platform :ios do
+#   lane :screenshots do
+#     capture_screenshots
+#   end
+# end
+"#;
+
+/// Flutter: regenerate Generated.xcconfig with the bumped version before archiving
+const FLUTTER_PREPARE: &str = r#"
+  flutter_args = ["--release", "--no-codesign", "--config-only"]
+  flutter_args << "--build-name=#{lane_context[SharedValues::VERSION_NUMBER]}" if lane_context[SharedValues::VERSION_NUMBER]
+  flutter_args << "--build-number=#{lane_context[SharedValues::BUILD_NUMBER]}" if lane_context[SharedValues::BUILD_NUMBER]
+  sh("cd ../.. && flutter build ios #{flutter_args.join(" ")}")
+"#;
+
+/// React Native and KMP-via-CocoaPods: make sure Pods are installed before archiving
+const COCOAPODS_PREPARE: &str = r#"
+  cocoapods(podfile: "Podfile")
+"#;
+
+/// KMP: Xcode's build phase runs embedAndSignAppleFrameworkForXcode; make it
+/// link a release framework regardless of the configuration name
+const KMP_PREPARE: &str = r#"
+  ENV["KOTLIN_FRAMEWORK_BUILD_TYPE"] = "release"
+"#;
+
+/// Values the Fastfile template can refer to
+pub struct FastfileContext<'a> {
+    pub scheme: &'a str,
+    pub bundle_id: &'a str,
+
+    /// Workspace or project file inside ios_path (None: fastlane looks for one)
+    pub container: Option<String>,
+
+    pub configuration: &'a str,
+
+    /// build.output_dir as seen from ios_path, where fastlane runs
+    pub output_dir: String,
+
+    pub export_method: &'a str,
+    pub signing_style: Option<SigningStyle>,
+
+    /// Bundle ID → provisioning profile name for manual signing
+    pub profiles: &'a BTreeMap<String, String>,
+
+    pub preset: Preset,
+    pub kmp: Option<&'a KmpSettings>,
+}
+
+impl<'a> FastfileContext<'a> {
+    pub fn new(config: &'a ProjectConfig) -> Self {
+        let project = &config.project;
+
+        Self {
+            scheme: &project.scheme,
+            bundle_id: &project.bundle_id,
+            container: Xcode::container(&project.ios_path),
+            configuration: &config.build.configuration,
+            output_dir: relative_to(&project.ios_path, &config.build.output_dir),
+            export_method: &config.build.export_method,
+            signing_style: config.signing.style,
+            profiles: &config.signing.profiles,
+            preset: project.preset.unwrap_or(Preset::Native),
+            kmp: config.kmp.as_ref(),
+        }
+    }
+}
+
+/// Generate a Fastfile for a preset with the project's settings filled in
+pub fn generate_fastfile(context: &FastfileContext) -> String {
+    let prepare = match context.preset {
+        Preset::Native => "",
+        Preset::Flutter => FLUTTER_PREPARE,
+        Preset::ReactNative => COCOAPODS_PREPARE,
+        Preset::Kmp => match context.kmp.map(|k| k.integration) {
+            Some(FrameworkIntegration::Cocoapods) => COCOAPODS_PREPARE,
+            _ => KMP_PREPARE,
+        },
+    };
+
+    let container = match &context.container {
+        Some(name) if name.ends_with(".xcworkspace") => {
+            format!("\n    workspace: \"{}\",", ruby_escape(name))
+        }
+        Some(name) => format!("\n    project: \"{}\",", ruby_escape(name)),
+        None => String::new(),
+    };
+    let signing_style = context
+        .signing_style
+        .map(|style| style.to_string())
+        .unwrap_or_default();
+    // Single-quoted in the template, so only backslashes and ' need escaping
+    let profiles = serde_json::to_string(context.profiles)
+        .unwrap_or_else(|_| "{}".to_string())
+        .replace('\\', "\\\\")
+        .replace('\'', "\\'");
+
+    let body = render(
+        FASTFILE_TEMPLATE,
+        &[
+            ("PREPARE", prepare),
+            ("CONTAINER", &container),
+            ("SCHEME", &ruby_escape(context.scheme)),
+            ("BUNDLE_ID", &ruby_escape(context.bundle_id)),
+            ("CONFIGURATION", &ruby_escape(context.configuration)),
+            ("OUTPUT_DIR", &ruby_escape(&context.output_dir)),
+            ("EXPORT_METHOD", &ruby_escape(context.export_method)),
+            ("SIGNING_STYLE", &signing_style),
+            ("PROFILES", &profiles),
+        ],
+    );
+
+    format!("{}{}", managed_section(&body), USER_SECTION)
+}
+
+/// The managed part of an existing Fastfile and what surrounds it
+pub struct ManagedSection<'a> {
+    pub before: &'a str,
+
+    /// Checksum of the body as launchpad last wrote it
+    pub checksum: Option<&'a str>,
+
+    pub body: &'a str,
+    pub after: &'a str,
+}
+
+impl ManagedSection<'_> {
+    /// Whether the body was edited by hand since launchpad wrote it
+    pub fn is_edited(&self) -> bool {
+        self.checksum != Some(checksum(self.body).as_str())
+    }
+
+    /// The same file with `body` as the managed part
+    pub fn replace_body(&self, body: &str) -> String {
+        format!("{}{}{}", self.before, managed_section(body), self.after)
+    }
+}
+
+/// Find the `launchpad:begin` / `launchpad:end` section of a Fastfile
+pub fn split_managed(content: &str) -> Option<ManagedSection<'_>> {
+    let begin = content.find(MANAGED_BEGIN)?;
+    let body_start = begin + content[begin..].find('\n')? + 1;
+    let end = body_start + content[body_start..].find(MANAGED_END)?;
+    let after_start = content[end..]
+        .find('\n')
+        .map(|i| end + i + 1)
+        .unwrap_or(content.len());
+
+    let checksum = content[begin..body_start]
+        .trim_end()
+        .strip_prefix(MANAGED_BEGIN)
+        .and_then(|rest| rest.trim().strip_prefix("checksum="));
+
+    Some(ManagedSection {
+        before: &content[..begin],
+        checksum,
+        body: &content[body_start..end],
+        after: &content[after_start..],
+    })
+}
+
+/// Wrap a generated body in the markers, recording its checksum
+fn managed_section(body: &str) -> String {
+    format!(
+        "{} checksum={}\n{}{}\n",
+        MANAGED_BEGIN,
+        checksum(body),
+        body,
+        MANAGED_END
+    )
+}
+
+fn checksum(body: &str) -> String {
+    let digest = format!("{:x}", Sha256::digest(body.as_bytes()));
+    digest[..16].to_string()
+}
+
+/// Replace every `{{NAME}}` in `template` with its value
+fn render(template: &str, values: &[(&str, &str)]) -> String {
+    values
+        .iter()
+        .fold(template.to_string(), |content, (name, value)| {
+            content.replace(&format!("{{{{{}}}}}", name), value)
+        })
+}
+
+/// Escape a value for a double-quoted Ruby string
+fn ruby_escape(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace("#{", "\\#{")
+}
+
+/// `path` (relative to the project root) as seen from `ios_path`
+fn relative_to(ios_path: &str, path: &str) -> String {
+    let expanded = shellexpand::tilde(path).to_string();
+    if Path::new(&expanded).is_absolute() {
+        return expanded;
+    }
+
+    let mut depth = 0;
+    for component in Path::new(ios_path).components() {
+        match component {
+            Component::Normal(_) => depth += 1,
+            Component::CurDir => {}
+            // ios_path leaves the project root; fall back to an absolute path
+            _ => {
+                return std::path::absolute(&expanded)
+                    .map(|p| p.to_string_lossy().to_string())
+                    .unwrap_or(expanded);
+            }
+        }
+    }
+
+    format!("{}{}", "../".repeat(depth), expanded)
+}
+
+/// Example .launchpad.toml for team reference
+pub const LAUNCHPAD_TOML_EXAMPLE: &str = r#"# Launchpad configuration file
+# Copy this to .launchpad.toml and customize for your project
+
+[project]
+ios_path = "ios"           # Path to iOS project directory
+scheme = "YourAppScheme"   # Xcode scheme name
+bundle_id = "com.example.app"
+# asc_app_id = "1234567890" # App Store Connect app ID (init looks it up)
+
+[deploy]
+git_tag = true             # Create git tags after deploy
+push_tags = true           # Push the release tag to the remote
+# git_remote = "upstream"   # Remote to push to (default: origin)
+# clean_artifacts = false  # Remove the .ipa and archive after upload (dSYMs are kept)
+# groups = ["Internal", "QA"]  # TestFlight groups that get the build
+# notify_testers = true        # Email testers when the build is available
+# distribution = "staged"      # External groups wait for `launchpad promote-beta`
+# git_notes = true            # Add a git note with the build to the released commit
+
+[signing]
+style = "automatic"        # "automatic" or "manual" (detected by launchpad init)
+# [signing.profiles]       # Required for manual signing: bundle ID → profile name
+# "com.example.app" = "com.example.app AppStore"
+
+# [environments.production.apple]  # `deploy --env production` under another Apple account
+# key_profile = "client-b"         # [key_profiles.client-b] in ~/.launchpad/config.toml
+# team_id = "ABCDE12345"
+# bundle_id = "com.client.app"
+
+[build]
+output_dir = "build"                           # Where the .ipa and dSYMs are written
+ipa_name_template = "{app}-{version}-{build}.ipa"  # {app} is the scheme name
+# configuration = "Release"                    # Xcode build configuration to archive
+# export_method = "app-store"                  # "app-store", "ad-hoc", "enterprise" or "development"
+# sdk = "iphoneos"                             # xcodebuild -sdk
+# destination = "generic/platform=iOS"         # xcodebuild -destination ("generic/platform=macOS,variant=Mac Catalyst")
+
+# [build.metadata]         # Git SHA, branch, build date and launchpad version in the Info.plist
+# key_prefix = "Launchpad" # Keys are LaunchpadGitSHA, LaunchpadGitBranch, ...
+
+# [build.icon_badge]       # A "BETA" band on the app icon of test builds
+# environments = ["staging"]
+
+# [compliance]             # Answer export compliance for every build
+# uses_non_exempt_encryption = false
+
+# [symbols]                # Crash reporter for `launchpad symbols fetch`
+# reporter = "sentry"      # "sentry", "crashlytics", "bugsnag" or "command"
+# org = "your-org"
+# project = "your-project"
+
+# [cache]                  # Keep DerivedData and SPM/CocoaPods caches between deploys
+# dir = "~/ci-cache/app"   # Default: ~/.launchpad/cache/<bundle_id> (LAUNCHPAD_CACHE_DIR wins)
+# keep = 2                 # Package caches kept per lockfile version
+
+# [environments.staging.build_env]   # Build settings for `deploy --env staging`, via an xcconfig
+# API_BASE_URL = "https://staging.example.com/api"
+
+# [notifications]          # Slack/webhook notifications per environment and outcome
+# environment = "staging"  # LAUNCHPAD_ENVIRONMENT wins
+# [[notifications.rules]]
+# on = ["failure"]         # "success", "failure" or "processed" (default: all)
+# environments = ["production"]
+# webhook_env = "SLACK_PAGER_WEBHOOK"
+# [notifications.email]    # HTML summary email; sender settings come from [email] in ~/.launchpad/config.toml
+# to = ["product@example.com"]
+
+# [size]                   # Export the App Thinning size report; budgets stop the lane before uploading
+# download_budgets = { "iPhone SE" = "200 MB" }   # Device class or model ("iPhone12,8")
+# install_budgets = { "iPad" = "1 GB" }
+
+# [analysis]               # Warn about large frameworks, duplicated static libraries and unstripped symbols
+# large_framework = "50 MB"
+
+# [templates]              # Handlebars templates for notes, tags, chat and PR comments
+# release_notes = "{{#each commits}}- {{this}}\n{{/each}}"
+# tag_message = "{{app}} {{version}} ({{build}}) by {{author}}"
+# [templates.notifications]
+# success = ":rocket: *{{app}}* {{version}} ({{build}}) is on {{store}}"
+"#;
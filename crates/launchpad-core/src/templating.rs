@@ -0,0 +1,110 @@
+use crate::config::project::NotificationEvent;
+use crate::summary::DeploySummary;
+use handlebars::{Handlebars, TemplateErrorReason};
+use serde::Serialize;
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+pub enum TemplatingError {
+    #[error("Invalid {name} template: {message}")]
+    Invalid { name: String, message: String },
+}
+
+/// What `[templates]` entries can use. Values that aren't known yet (the build
+/// number before the build, the tag when tagging is off) render empty.
+#[derive(Debug, Default, Serialize)]
+pub struct Variables {
+    pub app: String,
+    pub bundle_id: String,
+    /// "ios" or "android"
+    pub platform: String,
+    pub environment: Option<String>,
+    pub event: Option<NotificationEvent>,
+    pub version: Option<String>,
+    pub build: Option<String>,
+    pub tag: Option<String>,
+    pub sha: Option<String>,
+    pub short_sha: Option<String>,
+    /// Commit subjects since the previous deploy, newest first
+    pub commits: Vec<String>,
+    pub author: Option<String>,
+    /// "4m 12s"
+    pub duration: Option<String>,
+    /// "TestFlight" or "Google Play (internal track)"
+    pub store: String,
+    pub status: Option<String>,
+    pub ipa_size: Option<String>,
+    pub public_link: Option<String>,
+    pub ci_url: Option<String>,
+    pub error: Option<String>,
+}
+
+impl Variables {
+    /// Fill in what the deploy summary knows
+    pub fn with_summary(mut self, summary: &DeploySummary) -> Self {
+        self.version = summary.version.clone();
+        self.build = summary.build.clone();
+        self.sha = summary.git_sha.clone();
+        self.short_sha = summary
+            .git_sha
+            .as_ref()
+            .map(|sha| sha[..sha.len().min(7)].to_string());
+        self.duration = Some(format_duration(summary.duration_secs));
+        self.store = match &summary.play_track {
+            Some(track) => format!("Google Play ({} track)", track),
+            None => "TestFlight".to_string(),
+        };
+        self.status = Some(summary.testflight_status.clone());
+        self.ipa_size = summary.ipa_size.map(crate::ui::format_size);
+        self.error = summary.error.clone();
+        self
+    }
+}
+
+/// Render a Handlebars template. Output isn't HTML-escaped: it goes to git, chat
+/// and Markdown, not a web page.
+pub fn render(
+    name: &str,
+    template: &str,
+    variables: &Variables,
+) -> Result<String, TemplatingError> {
+    validate(name, template)?;
+    let mut registry = Handlebars::new();
+    registry.register_escape_fn(handlebars::no_escape);
+    registry
+        .render_template(template, variables)
+        .map(|text| text.trim().to_string())
+        .map_err(|e| TemplatingError::Invalid {
+            name: name.to_string(),
+            message: e.to_string(),
+        })
+}
+
+/// Check a template's syntax without rendering it
+pub fn validate(name: &str, template: &str) -> Result<(), TemplatingError> {
+    let e = match handlebars::Template::compile(template) {
+        Ok(_) => return Ok(()),
+        Err(e) => e,
+    };
+    // The parser's own message lists every token it expected
+    let mut message = match e.reason() {
+        TemplateErrorReason::InvalidSyntax(_) => "invalid syntax".to_string(),
+        reason => reason.to_string(),
+    };
+    if let Some((line, column)) = e.pos() {
+        message.push_str(&format!(" at line {}, column {}", line, column));
+    }
+    Err(TemplatingError::Invalid {
+        name: name.to_string(),
+        message,
+    })
+}
+
+/// Seconds as "42s", "4m 12s" or "1h 3m"
+pub fn format_duration(secs: u64) -> String {
+    match secs {
+        0..=59 => format!("{}s", secs),
+        60..=3599 => format!("{}m {}s", secs / 60, secs % 60),
+        _ => format!("{}h {}m", secs / 3600, secs % 3600 / 60),
+    }
+}
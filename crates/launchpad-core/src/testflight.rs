@@ -0,0 +1,229 @@
+use crate::asc::{AscClient, AscError, BetaGroup, Build};
+use crate::config::project::{ComplianceSettings, DistributionMode};
+use std::time::{Duration, Instant};
+
+/// How often to ask App Store Connect whether the build has processed
+const POLL_INTERVAL: Duration = Duration::from_secs(30);
+
+/// Give up waiting after this long (processing is usually 10-30 minutes)
+const PROCESSING_TIMEOUT: Duration = Duration::from_secs(60 * 60);
+
+/// Give up looking for an upload after this long (builds are usually listed
+/// within a few minutes, long before they've processed)
+pub const RECEIVE_TIMEOUT: Duration = Duration::from_secs(20 * 60);
+
+/// Which groups got the build now, and which wait for `launchpad promote-beta`
+#[derive(Debug, Default)]
+pub struct DistributionReport {
+    pub distributed: Vec<String>,
+    pub deferred: Vec<String>,
+}
+
+/// Wait until the uploaded build shows up and finishes processing.
+/// Without a build number, the newest upload is used.
+pub async fn wait_for_build(
+    client: &AscClient,
+    app_id: &str,
+    number: Option<&str>,
+    version: Option<&str>,
+) -> Result<Build, AscError> {
+    let started = Instant::now();
+    let label = number.unwrap_or("latest");
+
+    loop {
+        let build = match number {
+            Some(number) => client.find_build(app_id, number, version).await?,
+            None => client.latest_build(app_id).await?,
+        };
+
+        match build {
+            Some(build) if build.is_valid() => return Ok(build),
+            Some(build) if build.is_failed() => {
+                return Err(AscError::NotFound(format!(
+                    "Build {} failed processing on App Store Connect",
+                    build.number
+                )))
+            }
+            _ => {}
+        }
+
+        if started.elapsed() >= PROCESSING_TIMEOUT {
+            return Err(AscError::NotFound(format!(
+                "Build {} still not processed after {} minutes",
+                label,
+                PROCESSING_TIMEOUT.as_secs() / 60
+            )));
+        }
+
+        tokio::time::sleep(POLL_INTERVAL).await;
+    }
+}
+
+/// Wait until App Store Connect lists the build, processed or not. fastlane can
+/// exit 0 without the upload going through; `None` means it never showed up.
+pub async fn wait_for_upload(
+    client: &AscClient,
+    app_id: &str,
+    number: &str,
+    version: Option<&str>,
+) -> Result<Option<Build>, AscError> {
+    let started = Instant::now();
+
+    loop {
+        if let Some(build) = client.find_build(app_id, number, version).await? {
+            return Ok(Some(build));
+        }
+        if started.elapsed() >= RECEIVE_TIMEOUT {
+            return Ok(None);
+        }
+        tokio::time::sleep(POLL_INTERVAL).await;
+    }
+}
+
+/// Answer export compliance for a processed build, so it doesn't sit at Missing
+/// Compliance; a build whose Info.plist already answered it is left alone
+pub async fn answer_compliance(
+    client: &AscClient,
+    build: &Build,
+    compliance: &ComplianceSettings,
+) -> Result<(), AscError> {
+    ignore_conflict(
+        client
+            .set_uses_non_exempt_encryption(&build.id, compliance.uses_non_exempt_encryption)
+            .await,
+    )?;
+    if let (true, Some(declaration_id)) = (
+        compliance.uses_non_exempt_encryption,
+        &compliance.declaration_id,
+    ) {
+        client
+            .assign_encryption_declaration(&build.id, declaration_id)
+            .await?;
+    }
+    Ok(())
+}
+
+/// Hand a processed build to the configured groups. In staged mode external
+/// groups are left for `promote`.
+pub async fn distribute(
+    client: &AscClient,
+    app_id: &str,
+    build: &Build,
+    group_names: &[String],
+    mode: DistributionMode,
+    notify_testers: bool,
+) -> Result<DistributionReport, AscError> {
+    let groups = resolve_groups(client, app_id, group_names).await?;
+
+    // Must be off before anyone gets access, or TestFlight emails them anyway
+    if !notify_testers {
+        client.set_auto_notify(&build.id, false).await?;
+    }
+
+    let mut report = DistributionReport::default();
+    let mut external = Vec::new();
+
+    for group in groups {
+        if group.is_internal {
+            client.add_build_to_group(&group.id, &build.id).await?;
+            report.distributed.push(group.name);
+        } else if mode == DistributionMode::Staged {
+            report.deferred.push(group.name);
+        } else {
+            external.push(group);
+        }
+    }
+
+    if !external.is_empty() {
+        report
+            .distributed
+            .extend(add_external(client, build, external).await?);
+    }
+
+    if notify_testers && !report.distributed.is_empty() {
+        ignore_conflict(client.notify_testers(&build.id).await)?;
+    }
+
+    Ok(report)
+}
+
+/// Give a build to external groups, submitting it for beta app review (the second
+/// stage of a staged rollout). Internal groups are skipped, since they got the
+/// build with the upload, unless `include_internal` is set for groups picked by hand.
+pub async fn promote(
+    client: &AscClient,
+    app_id: &str,
+    build: &Build,
+    group_names: &[String],
+    include_internal: bool,
+) -> Result<Vec<String>, AscError> {
+    let (internal, external): (Vec<BetaGroup>, Vec<BetaGroup>) =
+        resolve_groups(client, app_id, group_names)
+            .await?
+            .into_iter()
+            .partition(|g| g.is_internal);
+
+    let mut names = Vec::new();
+    if include_internal {
+        for group in internal {
+            client.add_build_to_group(&group.id, &build.id).await?;
+            names.push(group.name);
+        }
+    }
+
+    if external.is_empty() {
+        if names.is_empty() {
+            return Err(AscError::NotFound(
+                "No external TestFlight groups to promote to".to_string(),
+            ));
+        }
+        return Ok(names);
+    }
+
+    names.extend(add_external(client, build, external).await?);
+    Ok(names)
+}
+
+/// External testers only see the build after beta app review, so submit it too
+async fn add_external(
+    client: &AscClient,
+    build: &Build,
+    groups: Vec<BetaGroup>,
+) -> Result<Vec<String>, AscError> {
+    let mut names = Vec::new();
+    for group in groups {
+        client.add_build_to_group(&group.id, &build.id).await?;
+        names.push(group.name);
+    }
+
+    ignore_conflict(client.submit_for_beta_review(&build.id).await)?;
+    Ok(names)
+}
+
+/// Look up groups by name, failing on typos rather than silently skipping them
+async fn resolve_groups(
+    client: &AscClient,
+    app_id: &str,
+    names: &[String],
+) -> Result<Vec<BetaGroup>, AscError> {
+    let mut available = client.beta_groups(app_id).await?;
+
+    names
+        .iter()
+        .map(|name| {
+            available
+                .iter()
+                .position(|g| g.name == *name)
+                .map(|i| available.swap_remove(i))
+                .ok_or_else(|| AscError::NotFound(format!("TestFlight group '{}' not found", name)))
+        })
+        .collect()
+}
+
+/// Review already submitted / testers already notified count as success
+fn ignore_conflict(result: Result<(), AscError>) -> Result<(), AscError> {
+    match result {
+        Err(e) if e.is_conflict() => Ok(()),
+        other => other,
+    }
+}
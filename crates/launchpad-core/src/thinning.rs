@@ -0,0 +1,205 @@
+use crate::config::project::SizeSettings;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use thiserror::Error;
+
+/// Written to the export directory when the export thins for all variants
+pub const REPORT_FILENAME: &str = "App Thinning Size Report.txt";
+
+/// Variant descriptor for builds that aren't thinned (older OS versions)
+const UNIVERSAL: &str = "Universal";
+
+#[derive(Error, Debug)]
+pub enum ThinningError {
+    #[error("Invalid size budget for {device}: '{value}' (expected e.g. \"200 MB\")")]
+    InvalidBudget { device: String, value: String },
+}
+
+/// One thinned .ipa from the report
+#[derive(Debug)]
+pub struct Variant {
+    pub name: String,
+    /// Model identifiers ("iPhone12,8"), or "Universal"
+    pub devices: Vec<String>,
+    /// App size compressed, in bytes
+    pub download: u64,
+    /// App size uncompressed, in bytes
+    pub install: u64,
+}
+
+/// Largest download and install size of a device class, kept in the deploy history
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct ThinnedSize {
+    pub download: u64,
+    pub install: u64,
+}
+
+/// Budgets over their limit, and budgets that matched no variant
+#[derive(Debug, Default)]
+pub struct BudgetReport {
+    pub exceeded: Vec<String>,
+    pub unmatched: Vec<String>,
+}
+
+/// Parse the "Variant: ..." blocks of an App Thinning Size Report
+pub fn parse(report: &str) -> Vec<Variant> {
+    // Model identifiers contain a comma themselves ("iPhone12,8")
+    let device = regex_lite::Regex::new(r"device: ([^\s,\]]+(?:,\d+)?)").expect("valid regex");
+    let sizes = regex_lite::Regex::new(r"^App size: (.+?) compressed, (.+?) uncompressed")
+        .expect("valid regex");
+
+    let mut variants: Vec<Variant> = Vec::new();
+    for line in report.lines().map(str::trim) {
+        if let Some(name) = line.strip_prefix("Variant:") {
+            variants.push(Variant {
+                name: name.trim().to_string(),
+                devices: Vec::new(),
+                download: 0,
+                install: 0,
+            });
+            continue;
+        }
+        let Some(variant) = variants.last_mut() else {
+            continue;
+        };
+
+        if let Some(descriptors) = line.strip_prefix("Supported variant descriptors:") {
+            variant.devices = device
+                .captures_iter(descriptors)
+                .map(|caps| caps[1].trim().to_string())
+                .collect();
+            if variant.devices.is_empty() && descriptors.contains(UNIVERSAL) {
+                variant.devices.push(UNIVERSAL.to_string());
+            }
+            variant.devices.dedup();
+        } else if let Some(caps) = sizes.captures(line) {
+            variant.download = parse_size(&caps[1]).unwrap_or_default();
+            variant.install = parse_size(&caps[2]).unwrap_or_default();
+        }
+    }
+    variants
+}
+
+/// "6.7 MB", "512 KB", "Zero KB" → bytes (decimal units, as App Store Connect shows them)
+pub fn parse_size(text: &str) -> Option<u64> {
+    let text = text.trim();
+    let split = text
+        .find(|c: char| c.is_ascii_alphabetic())
+        .unwrap_or(text.len());
+    let (number, unit) = text.split_at(split);
+    let number = number.trim();
+    let unit = unit.trim().to_ascii_uppercase();
+
+    // "Zero KB"
+    if unit.starts_with("ZERO") {
+        return Some(0);
+    }
+    let value: f64 = number.parse().ok()?;
+    let scale = match unit.as_str() {
+        "" | "B" | "BYTES" => 1.0,
+        "KB" => 1e3,
+        "MB" => 1e6,
+        "GB" => 1e9,
+        _ => return None,
+    };
+    Some((value * scale).round() as u64)
+}
+
+/// Bytes as "12.3 MB", in the report's decimal units
+pub fn format_size(bytes: u64) -> String {
+    format!("{:.1} MB", bytes as f64 / 1e6)
+}
+
+/// The device class a model identifier belongs to
+pub fn device_class(model: &str) -> &'static str {
+    match model {
+        // First, second and third generation
+        "iPhone8,4" | "iPhone12,8" | "iPhone14,6" => "iPhone SE",
+        UNIVERSAL => UNIVERSAL,
+        m if m.starts_with("iPhone") => "iPhone",
+        m if m.starts_with("iPad") => "iPad",
+        m if m.starts_with("iPod") => "iPod touch",
+        m if m.starts_with("Watch") => "Apple Watch",
+        m if m.starts_with("RealityDevice") => "Apple Vision Pro",
+        _ => "Other",
+    }
+}
+
+/// The largest variant each device class gets
+pub fn by_class(variants: &[Variant]) -> BTreeMap<String, ThinnedSize> {
+    let mut classes: BTreeMap<String, ThinnedSize> = BTreeMap::new();
+    for variant in variants {
+        for device in &variant.devices {
+            let size = classes
+                .entry(device_class(device).to_string())
+                .or_insert(ThinnedSize {
+                    download: 0,
+                    install: 0,
+                });
+            size.download = size.download.max(variant.download);
+            size.install = size.install.max(variant.install);
+        }
+    }
+    classes
+}
+
+/// Compare every variant against the `[size]` budgets
+pub fn check_budgets(
+    variants: &[Variant],
+    settings: &SizeSettings,
+) -> Result<BudgetReport, ThinningError> {
+    let mut report = BudgetReport::default();
+    check_kind(
+        "download",
+        &settings.download_budgets,
+        variants,
+        |v| v.download,
+        &mut report,
+    )?;
+    check_kind(
+        "install",
+        &settings.install_budgets,
+        variants,
+        |v| v.install,
+        &mut report,
+    )?;
+    Ok(report)
+}
+
+fn check_kind(
+    kind: &str,
+    budgets: &BTreeMap<String, String>,
+    variants: &[Variant],
+    size_of: fn(&Variant) -> u64,
+    report: &mut BudgetReport,
+) -> Result<(), ThinningError> {
+    for (device, value) in budgets {
+        let limit = parse_size(value).ok_or_else(|| ThinningError::InvalidBudget {
+            device: device.clone(),
+            value: value.clone(),
+        })?;
+
+        let largest = variants
+            .iter()
+            .filter(|v| v.devices.iter().any(|d| covers(device, d)))
+            .max_by_key(|v| size_of(v));
+        match largest {
+            None => report.unmatched.push(device.clone()),
+            Some(variant) if size_of(variant) > limit => report.exceeded.push(format!(
+                "{} {} {} > {} budget ({})",
+                device,
+                kind,
+                format_size(size_of(variant)),
+                format_size(limit),
+                variant.name
+            )),
+            Some(_) => {}
+        }
+    }
+    Ok(())
+}
+
+/// Whether a budget key names this model, or the class it belongs to
+fn covers(key: &str, model: &str) -> bool {
+    key.eq_ignore_ascii_case(model) || key.eq_ignore_ascii_case(device_class(model))
+}
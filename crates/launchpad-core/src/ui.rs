@@ -0,0 +1,443 @@
+use crate::config::global::{ColorMode, GlobalConfig, SpinnerStyle, UiConfig, UiProfile};
+use crate::events;
+use console::{style, Color, StyledObject};
+use indicatif::{ProgressBar, ProgressStyle};
+use serde_json::json;
+use std::fmt::Display;
+use std::sync::{Mutex, OnceLock};
+use std::time::Duration;
+
+/// How output looks for this run: `[ui]` in the global config, overridden by
+/// LAUNCHPAD_UI_PROFILE, LAUNCHPAD_UI_EMOJI, LAUNCHPAD_UI_SPINNER, LAUNCHPAD_UI_COLOR
+/// and NO_COLOR
+struct Theme {
+    symbols: Symbols,
+    spinner: SpinnerStyle,
+    minimal: bool,
+    ascii: bool,
+    accent: Color,
+    /// Same color as `accent`, in indicatif template syntax
+    accent_name: String,
+    success: Color,
+    warning: Color,
+    error: Color,
+}
+
+struct Symbols {
+    step: &'static str,
+    success: &'static str,
+    warn: &'static str,
+    error: &'static str,
+}
+
+const UNICODE: Symbols = Symbols {
+    step: "→",
+    success: "✓",
+    warn: "⚠",
+    error: "✗",
+};
+
+const ASCII: Symbols = Symbols {
+    step: "*",
+    success: "+",
+    warn: "!",
+    error: "x",
+};
+
+/// Level tags a plain logger would print
+const MINIMAL: Symbols = Symbols {
+    step: "[info]",
+    success: "[ok]",
+    warn: "[warn]",
+    error: "[error]",
+};
+
+static THEME: OnceLock<Theme> = OnceLock::new();
+
+/// The spinner drawn last; lines printed while it runs go above it
+static SPINNER: Mutex<Option<ProgressBar>> = Mutex::new(None);
+
+fn theme() -> &'static Theme {
+    THEME.get_or_init(Theme::resolve)
+}
+
+impl Theme {
+    fn resolve() -> Self {
+        let config = ui_config();
+        let env = |name: &str| std::env::var(name).ok().filter(|v| !v.is_empty());
+
+        let minimal = match env("LAUNCHPAD_UI_PROFILE").as_deref() {
+            Some("minimal") => true,
+            Some(_) => false,
+            None => config.profile == Some(UiProfile::Minimal),
+        };
+        let emoji = match env("LAUNCHPAD_UI_EMOJI").as_deref() {
+            Some(value) => !matches!(value, "0" | "false" | "off"),
+            None => config.emoji.unwrap_or(true),
+        };
+        let spinner = match env("LAUNCHPAD_UI_SPINNER").as_deref() {
+            Some("ascii") => SpinnerStyle::Ascii,
+            Some("none") => SpinnerStyle::None,
+            Some(_) => SpinnerStyle::Dots,
+            None if minimal => SpinnerStyle::None,
+            None => config.spinner.unwrap_or_default(),
+        };
+        let color = match env("LAUNCHPAD_UI_COLOR").as_deref() {
+            Some("always") => ColorMode::Always,
+            Some("never") => ColorMode::Never,
+            _ if minimal || env("NO_COLOR").is_some() => ColorMode::Never,
+            _ => config.color.unwrap_or_default(),
+        };
+
+        // Applies to every `style()` call, including the ones outside this module
+        match color {
+            ColorMode::Auto => {}
+            ColorMode::Always | ColorMode::Never => {
+                let enabled = color == ColorMode::Always;
+                console::set_colors_enabled(enabled);
+                console::set_colors_enabled_stderr(enabled);
+            }
+        }
+
+        let palette = &config.palette;
+        let accent_name = palette
+            .accent
+            .clone()
+            .filter(|name| parse_color(name).is_some())
+            .unwrap_or_else(|| "cyan".to_string());
+        let pick = |name: &Option<String>, default: Color| {
+            name.as_deref().and_then(parse_color).unwrap_or(default)
+        };
+
+        Theme {
+            symbols: if minimal {
+                MINIMAL
+            } else if emoji {
+                UNICODE
+            } else {
+                ASCII
+            },
+            spinner,
+            minimal,
+            ascii: minimal || !emoji,
+            accent: parse_color(&accent_name).unwrap_or(Color::Cyan),
+            accent_name,
+            success: pick(&palette.success, Color::Green),
+            warning: pick(&palette.warning, Color::Yellow),
+            error: pick(&palette.error, Color::Red),
+        }
+    }
+}
+
+/// `[ui]` from the config file. An encrypted config isn't opened just for this;
+/// the LAUNCHPAD_UI_* variables still apply.
+fn ui_config() -> UiConfig {
+    if GlobalConfig::is_encrypted() {
+        return UiConfig::default();
+    }
+    GlobalConfig::read_file()
+        .ok()
+        .flatten()
+        .map(|config| config.ui)
+        .unwrap_or_default()
+}
+
+/// ANSI color name ("cyan") or 256-color number ("208")
+fn parse_color(name: &str) -> Option<Color> {
+    match name.to_ascii_lowercase().as_str() {
+        "black" => Some(Color::Black),
+        "red" => Some(Color::Red),
+        "green" => Some(Color::Green),
+        "yellow" => Some(Color::Yellow),
+        "blue" => Some(Color::Blue),
+        "magenta" => Some(Color::Magenta),
+        "cyan" => Some(Color::Cyan),
+        "white" => Some(Color::White),
+        number => number.parse().ok().map(Color::Color256),
+    }
+}
+
+/// Apply the theme before anything is printed, so colors are consistent
+/// for output that doesn't go through this module
+pub fn init() {
+    theme();
+}
+
+/// Print a line of human output; stderr when `--event-stream -` owns stdout
+fn say(line: impl Display) {
+    above_spinner(|| {
+        if events::owns_stdout() {
+            eprintln!("{}", line);
+        } else {
+            println!("{}", line);
+        }
+    });
+}
+
+/// Run `print` with a running spinner cleared, then redraw it
+fn above_spinner(print: impl FnOnce()) {
+    let spinner = SPINNER.lock().ok().and_then(|spinner| spinner.clone());
+    match spinner {
+        Some(spinner) if !spinner.is_finished() => spinner.suspend(print),
+        _ => print(),
+    }
+}
+
+/// Mirror a message into the event stream
+fn log(level: &str, text: &str) {
+    events::emit("log", json!({ "level": level, "message": text }));
+}
+
+/// Print a header/title
+pub fn header(text: &str) {
+    log("header", text);
+    let theme = theme();
+    if theme.minimal {
+        say(format_args!("{} == {} ==", theme.symbols.step, text));
+        return;
+    }
+    say("");
+    say(style(text).bold().fg(theme.accent));
+}
+
+/// Print a step message
+pub fn step(text: &str) {
+    log("info", text);
+    say(format_args!(
+        "{} {}",
+        style(theme().symbols.step).dim(),
+        text
+    ));
+}
+
+/// Print a success message
+pub fn success(text: &str) {
+    log("success", text);
+    let theme = theme();
+    say(format_args!(
+        "{} {}",
+        style(theme.symbols.success).fg(theme.success),
+        text
+    ));
+}
+
+/// Print a warning message
+pub fn warn(text: &str) {
+    log("warning", text);
+    let theme = theme();
+    say(format_args!(
+        "{} {}",
+        style(theme.symbols.warn).fg(theme.warning),
+        text
+    ));
+}
+
+/// Print an error message
+pub fn error(text: &str) {
+    log("error", text);
+    let theme = theme();
+    above_spinner(|| eprintln!("{} {}", style(theme.symbols.error).fg(theme.error), text));
+}
+
+/// Print a line of a tool's own output (fastlane), dimmed under launchpad's
+pub fn output(line: &str) {
+    log("output", line);
+    say(format_args!("  {}", style(line).dim()));
+}
+
+/// Print a warning from a tool's output
+pub fn output_warning(line: &str) {
+    log("warning", line);
+    say(format_args!("  {}", style(line).fg(theme().warning)));
+}
+
+/// Print an error from a tool's output
+pub fn output_error(line: &str) {
+    log("error", line);
+    let theme = theme();
+    above_spinner(|| eprintln!("  {}", style(line).fg(theme.error)));
+}
+
+/// Print an aligned label/value line (previews, summaries)
+pub fn field(label: &str, value: &str) {
+    say(format_args!("  {:<12} {}", style(label).dim(), value));
+}
+
+/// Print a line laid out by the caller (listings, custom tables) as is
+pub fn line(text: impl Display) {
+    say(text);
+}
+
+/// Print rows under a dimmed header row: the first column left-aligned, the
+/// rest (numbers) right-aligned, each as wide as its widest cell
+pub fn table(headers: &[&str], rows: &[Vec<String>]) {
+    let mut widths: Vec<usize> = headers.iter().map(|h| h.chars().count()).collect();
+    for row in rows {
+        for (width, cell) in widths.iter_mut().zip(row) {
+            *width = (*width).max(cell.chars().count());
+        }
+    }
+    let line = |cells: &mut dyn Iterator<Item = &str>| {
+        cells
+            .zip(&widths)
+            .enumerate()
+            .map(|(i, (cell, width))| match i {
+                0 => format!("{:<width$}", cell),
+                _ => format!("{:>width$}", cell),
+            })
+            .collect::<Vec<_>>()
+            .join("  ")
+    };
+
+    say(format_args!(
+        "  {}",
+        style(line(&mut headers.iter().copied())).dim()
+    ));
+    for row in rows {
+        say(format_args!(
+            "  {}",
+            line(&mut row.iter().map(String::as_str))
+        ));
+    }
+}
+
+/// Print an empty line
+pub fn blank() {
+    say("");
+}
+
+/// "→", or "->" when the terminal gets ASCII only
+pub fn arrow() -> &'static str {
+    if theme().ascii {
+        "->"
+    } else {
+        "→"
+    }
+}
+
+/// Pass/fail marker for tables
+pub fn mark(passed: bool) -> StyledObject<&'static str> {
+    let theme = theme();
+    if passed {
+        style(theme.symbols.success).fg(theme.success)
+    } else {
+        style(theme.symbols.error).fg(theme.error)
+    }
+}
+
+/// Human-readable size in MB
+pub fn format_size(bytes: u64) -> String {
+    format!("{:.1} MB", bytes as f64 / 1_048_576.0)
+}
+
+/// Signed size change in MB
+pub fn format_size_diff(bytes: i64) -> String {
+    let sign = match (bytes >= 0, theme().ascii) {
+        (true, _) => "+",
+        (false, true) => "-",
+        (false, false) => "−",
+    };
+    format!(
+        "{}{:.1} MB",
+        sign,
+        bytes.unsigned_abs() as f64 / 1_048_576.0
+    )
+}
+
+/// Print a check pass result
+pub fn check_pass(name: &str, message: &str) {
+    log("success", &format!("{} {}", name, message));
+    say(format_args!(
+        "{} {} {}",
+        mark(true),
+        style(name).bold(),
+        style(message).dim()
+    ));
+}
+
+/// Print a check warning (fails only under --strict)
+pub fn check_warn(name: &str, message: &str) {
+    log("warning", &format!("{} {}", name, message));
+    let theme = theme();
+    say(format_args!(
+        "{} {} {}",
+        style(theme.symbols.warn).fg(theme.warning),
+        style(name).bold(),
+        style(message).dim()
+    ));
+}
+
+/// Print a check fail result
+pub fn check_fail(name: &str, message: &str) {
+    log("error", &format!("{} {}", name, message));
+    say(format_args!(
+        "{} {} {}",
+        mark(false),
+        style(name).bold(),
+        style(message).dim()
+    ));
+}
+
+/// Create a spinner for long-running operations. Without animation the message
+/// is printed once and the returned bar is hidden.
+pub fn spinner(message: &str) -> ProgressBar {
+    let theme = theme();
+    let tick_chars = match theme.spinner {
+        SpinnerStyle::Dots => "⠋⠙⠹⠸⠼⠴⠦⠧⠇⠏",
+        SpinnerStyle::Ascii => "|/-\\ ",
+        SpinnerStyle::None => {
+            step(message);
+            return ProgressBar::hidden();
+        }
+    };
+
+    log("info", message);
+    let pb = ProgressBar::new_spinner();
+    pb.set_style(
+        ProgressStyle::default_spinner()
+            .tick_chars(tick_chars)
+            .template(&format!("{{spinner:.{}}} {{msg}}", theme.accent_name))
+            .unwrap(),
+    );
+    pb.set_message(message.to_string());
+    pb.enable_steady_tick(Duration::from_millis(100));
+    if let Ok(mut spinner) = SPINNER.lock() {
+        *spinner = Some(pb.clone());
+    }
+    pb
+}
+
+/// Create a byte progress bar with throughput and ETA for uploads. It replaces
+/// a running spinner, and other output is printed above it. Without animation
+/// the message and size are printed once and the returned bar is hidden.
+pub fn progress_bar(total_bytes: u64, message: &str) -> ProgressBar {
+    let theme = theme();
+    let progress_chars = match theme.spinner {
+        SpinnerStyle::Dots if !theme.ascii => "█▉▊▋▌▍▎▏ ",
+        SpinnerStyle::Dots | SpinnerStyle::Ascii => "=> ",
+        SpinnerStyle::None => {
+            step(&format!("{} ({})", message, format_size(total_bytes)));
+            return ProgressBar::hidden();
+        }
+    };
+
+    log("info", message);
+    let pb = ProgressBar::new(total_bytes);
+    pb.set_style(
+        ProgressStyle::default_bar()
+            .progress_chars(progress_chars)
+            .template(&format!(
+                "{{msg}} [{{bar:30.{}}}] {{bytes}}/{{total_bytes}} ({{binary_bytes_per_sec}}, {{eta}} left)",
+                theme.accent_name
+            ))
+            .unwrap(),
+    );
+    pb.set_message(message.to_string());
+    if let Ok(mut running) = SPINNER.lock() {
+        // Two bars redrawing the same line would garble it
+        if let Some(spinner) = running.replace(pb.clone()) {
+            spinner.finish_and_clear();
+        }
+    }
+    pb
+}
@@ -0,0 +1,101 @@
+/// Which part of the version name a deploy bumps; none bumps only the build number
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum VersionBump {
+    Patch,
+    Minor,
+    Major,
+}
+
+impl VersionBump {
+    /// "patch", "minor" or "major", as [`bump`] and the Fastfile lanes name it
+    pub fn name(self) -> &'static str {
+        match self {
+            VersionBump::Patch => "patch",
+            VersionBump::Minor => "minor",
+            VersionBump::Major => "major",
+        }
+    }
+
+    /// From `--patch`, `--minor` and `--major`, which clap keeps exclusive
+    pub fn from_flags(patch: bool, minor: bool, major: bool) -> Option<Self> {
+        match (patch, minor, major) {
+            (true, _, _) => Some(VersionBump::Patch),
+            (_, true, _) => Some(VersionBump::Minor),
+            (_, _, true) => Some(VersionBump::Major),
+            _ => None,
+        }
+    }
+}
+
+/// Bump a "major.minor.patch" version name ("patch", "minor" or "major")
+pub fn bump(version: &str, bump: &str) -> Option<String> {
+    let mut parts = version
+        .split('.')
+        .map(|p| p.parse::<u64>().ok())
+        .collect::<Option<Vec<_>>>()?;
+    parts.resize(parts.len().max(3), 0);
+
+    match bump {
+        "patch" => parts[2] += 1,
+        "minor" => {
+            parts[1] += 1;
+            parts[2] = 0;
+        }
+        "major" => {
+            parts[0] += 1;
+            parts[1] = 0;
+            parts[2] = 0;
+        }
+        _ => return None,
+    }
+
+    Some(
+        parts
+            .iter()
+            .map(|p| p.to_string())
+            .collect::<Vec<_>>()
+            .join("."),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn patch_bump_increments_the_last_part() {
+        assert_eq!(bump("1.2.3", "patch").as_deref(), Some("1.2.4"));
+        assert_eq!(bump("1.2", "patch").as_deref(), Some("1.2.1"));
+        assert_eq!(bump("1.2.3.4", "patch").as_deref(), Some("1.2.4.4"));
+    }
+
+    #[test]
+    fn minor_bump_resets_patch() {
+        assert_eq!(bump("1.2.3", "minor").as_deref(), Some("1.3.0"));
+        assert_eq!(bump("1", "minor").as_deref(), Some("1.1.0"));
+    }
+
+    #[test]
+    fn major_bump_resets_minor_and_patch() {
+        assert_eq!(bump("1.2.3", "major").as_deref(), Some("2.0.0"));
+        assert_eq!(bump("1.2", "major").as_deref(), Some("2.0.0"));
+    }
+
+    #[test]
+    fn flags_pick_the_bump() {
+        assert_eq!(
+            VersionBump::from_flags(false, false, true),
+            Some(VersionBump::Major)
+        );
+        assert_eq!(VersionBump::from_flags(false, false, false), None);
+        assert_eq!(VersionBump::Major.name(), "major");
+    }
+
+    #[test]
+    fn invalid_versions_and_bumps_are_rejected() {
+        assert_eq!(bump("1.2.beta", "patch"), None);
+        assert_eq!(bump("", "patch"), None);
+        assert_eq!(bump("v1.2.3", "minor"), None);
+        assert_eq!(bump("1.2.3", "build"), None);
+    }
+}
@@ -0,0 +1,705 @@
+use crate::config::project::{BuildSettings, SigningStyle};
+use crate::mock;
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+pub enum XcodeError {
+    #[error("Xcode command failed: {0}")]
+    CommandFailed(String),
+
+    #[error("No Xcode project found at: {0}")]
+    NoProjectFound(String),
+
+    #[error("IO error: {0}")]
+    Io(#[from] std::io::Error),
+}
+
+pub struct Xcode;
+
+/// Where a scheme's .xcscheme file lives
+#[derive(Debug)]
+pub enum SchemeSharing {
+    /// Under xcshareddata, so it's committed and every checkout can build it
+    Shared,
+    /// Only under this user's xcuserdata; CI checkouts won't have it
+    UserOnly(PathBuf),
+    /// No scheme file at all (autocreated by Xcode on this machine)
+    Missing,
+}
+
+/// Version settings of one app or extension target
+#[derive(Debug)]
+pub struct TargetVersion {
+    pub target: String,
+    pub bundle_id: Option<String>,
+    pub product_type: Option<String>,
+    pub marketing_version: Option<String>,
+    pub build_number: Option<String>,
+    pub swift_version: Option<String>,
+}
+
+impl TargetVersion {
+    /// Whether this is an iOS app, as opposed to an extension or watch app
+    pub fn is_app(&self) -> bool {
+        self.product_type.as_deref() == Some("com.apple.product-type.application")
+    }
+}
+
+impl Xcode {
+    /// List available schemes in an Xcode project
+    pub fn list_schemes(ios_path: &str) -> Result<Vec<String>, XcodeError> {
+        let path = Path::new(ios_path);
+
+        // Find workspace or project file
+        let workspace = find_workspace(path);
+        let project = find_project(path);
+
+        let mut cmd = Command::new("xcodebuild");
+        cmd.arg("-list");
+
+        if let Some(ws) = workspace {
+            cmd.arg("-workspace").arg(ws);
+        } else if let Some(proj) = project {
+            cmd.arg("-project").arg(proj);
+        } else {
+            return Err(XcodeError::NoProjectFound(ios_path.to_string()));
+        }
+
+        let output = cmd.output()?;
+
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            return Err(XcodeError::CommandFailed(stderr.to_string()));
+        }
+
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        let schemes = parse_schemes(&stdout);
+
+        Ok(schemes)
+    }
+
+    /// Get bundle identifier for a scheme: its app target's, or else the first one listed
+    pub fn get_bundle_id(ios_path: &str, scheme: &str) -> Result<String, XcodeError> {
+        let stdout = show_build_settings(ios_path, scheme)?;
+
+        let targets = parse_target_versions(&stdout);
+        if let Some(bundle_id) = targets
+            .iter()
+            .find(|t| t.is_app())
+            .and_then(|t| t.bundle_id.clone())
+        {
+            return Ok(bundle_id);
+        }
+
+        // Parse PRODUCT_BUNDLE_IDENTIFIER
+        for line in stdout.lines() {
+            if line.contains("PRODUCT_BUNDLE_IDENTIFIER") {
+                if let Some(value) = line.split('=').nth(1) {
+                    return Ok(value.trim().to_string());
+                }
+            }
+        }
+
+        Err(XcodeError::CommandFailed(
+            "Could not find bundle identifier".to_string(),
+        ))
+    }
+
+    /// The app and extension targets a scheme builds that have a bundle ID, one
+    /// per bundle ID, in xcodebuild's order
+    pub fn scheme_targets(ios_path: &str, scheme: &str) -> Result<Vec<TargetVersion>, XcodeError> {
+        let stdout = show_build_settings(ios_path, scheme)?;
+
+        let mut targets: Vec<TargetVersion> = Vec::new();
+        for target in parse_target_versions(&stdout) {
+            let Some(bundle_id) = &target.bundle_id else {
+                continue;
+            };
+            if !targets
+                .iter()
+                .any(|t| t.bundle_id.as_ref() == Some(bundle_id))
+            {
+                targets.push(target);
+            }
+        }
+        Ok(targets)
+    }
+
+    /// Get the code signing style (CODE_SIGN_STYLE) for a scheme
+    pub fn signing_style(ios_path: &str, scheme: &str) -> Result<SigningStyle, XcodeError> {
+        let stdout = show_build_settings(ios_path, scheme)?;
+
+        for line in stdout.lines() {
+            if let Some(("CODE_SIGN_STYLE", value)) = line.trim().split_once(" = ") {
+                return Ok(if value.eq_ignore_ascii_case("manual") {
+                    SigningStyle::Manual
+                } else {
+                    SigningStyle::Automatic
+                });
+            }
+        }
+
+        // Xcode's default when the setting is absent
+        Ok(SigningStyle::Automatic)
+    }
+
+    /// Absolute path of the Info.plist file of the target that builds `bundle_id`.
+    /// None for targets with a generated Info.plist and no file to merge into it.
+    pub fn info_plist(
+        ios_path: &str,
+        scheme: &str,
+        bundle_id: &str,
+    ) -> Result<Option<PathBuf>, XcodeError> {
+        let stdout = show_build_settings(ios_path, scheme)?;
+        Ok(parse_info_plist(&stdout, bundle_id))
+    }
+
+    /// The name the target that builds `bundle_id` shows on the home screen: its
+    /// Info.plist's CFBundleDisplayName, INFOPLIST_KEY_CFBundleDisplayName, or else
+    /// its product name
+    pub fn display_name(
+        ios_path: &str,
+        scheme: &str,
+        bundle_id: &str,
+    ) -> Result<Option<String>, XcodeError> {
+        let stdout = show_build_settings(ios_path, scheme)?;
+        let Some(block) = settings_block(&stdout, bundle_id) else {
+            return Ok(None);
+        };
+
+        let from_plist = parse_info_plist(&stdout, bundle_id)
+            .and_then(|path| plist::from_file::<_, plist::Dictionary>(path).ok())
+            .and_then(|info| Some(info.get("CFBundleDisplayName")?.as_string()?.to_string()));
+        let name = match from_plist {
+            // "$(PRODUCT_NAME)" and the like come from the build settings
+            Some(value) => match value.strip_prefix("$(").and_then(|v| v.strip_suffix(')')) {
+                Some(setting) => block_setting(block, setting).map(str::to_string),
+                None => Some(value),
+            },
+            None => block_setting(block, "INFOPLIST_KEY_CFBundleDisplayName")
+                .or_else(|| block_setting(block, "PRODUCT_NAME"))
+                .map(str::to_string),
+        };
+        Ok(name.filter(|name| !name.is_empty()))
+    }
+
+    /// Get the bundle ID, MARKETING_VERSION, CURRENT_PROJECT_VERSION and SWIFT_VERSION of every app and extension target
+    pub fn target_versions(ios_path: &str) -> Result<Vec<TargetVersion>, XcodeError> {
+        if mock::enabled() {
+            return Ok(parse_target_versions(&mock::build_settings()));
+        }
+        let path = Path::new(ios_path);
+
+        // -alltargets only works with a project, not a workspace
+        let project =
+            find_project(path).ok_or_else(|| XcodeError::NoProjectFound(ios_path.to_string()))?;
+
+        let output = Command::new("xcodebuild")
+            .arg("-showBuildSettings")
+            .arg("-alltargets")
+            .arg("-configuration")
+            .arg("Release")
+            .arg("-project")
+            .arg(project)
+            .output()?;
+
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            return Err(XcodeError::CommandFailed(stderr.to_string()));
+        }
+
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        Ok(parse_target_versions(&stdout))
+    }
+
+    /// Resolve the scheme's Swift packages into `packages` (DerivedData's
+    /// SourcePackages when None)
+    pub fn resolve_packages(
+        ios_path: &str,
+        scheme: &str,
+        derived_data: &Path,
+        packages: Option<&Path>,
+    ) -> Result<(), XcodeError> {
+        let mut cmd = xcodebuild(ios_path)?;
+        cmd.arg("-resolvePackageDependencies")
+            .arg("-scheme")
+            .arg(scheme)
+            .arg("-derivedDataPath")
+            .arg(derived_data);
+        if let Some(packages) = packages {
+            cmd.arg("-clonedSourcePackagesDirPath").arg(packages);
+        }
+        run_quietly(cmd)
+    }
+
+    /// Build the scheme the way a deploy would, minus signing and archiving,
+    /// leaving compiled packages and the module cache in `derived_data`
+    pub fn build_unsigned(
+        ios_path: &str,
+        scheme: &str,
+        build: &BuildSettings,
+        derived_data: &Path,
+        packages: Option<&Path>,
+    ) -> Result<(), XcodeError> {
+        let mut cmd = xcodebuild(ios_path)?;
+        cmd.arg("build")
+            .arg("-scheme")
+            .arg(scheme)
+            .arg("-configuration")
+            .arg(&build.configuration)
+            .arg("-destination")
+            .arg(
+                build
+                    .destination
+                    .as_deref()
+                    .unwrap_or("generic/platform=iOS"),
+            )
+            .arg("-derivedDataPath")
+            .arg(derived_data);
+        if let Some(sdk) = &build.sdk {
+            cmd.arg("-sdk").arg(sdk);
+        }
+        if let Some(packages) = packages {
+            cmd.arg("-clonedSourcePackagesDirPath").arg(packages);
+        }
+        cmd.arg("CODE_SIGNING_ALLOWED=NO");
+        run_quietly(cmd)
+    }
+
+    /// Build the scheme for the simulator `udid`, unsigned, into `derived_data`;
+    /// returns the built .app
+    pub fn build_for_simulator(
+        ios_path: &str,
+        scheme: &str,
+        configuration: &str,
+        udid: &str,
+        derived_data: &Path,
+    ) -> Result<PathBuf, XcodeError> {
+        let mut cmd = xcodebuild(ios_path)?;
+        cmd.arg("build")
+            .arg("-scheme")
+            .arg(scheme)
+            .arg("-configuration")
+            .arg(configuration)
+            .arg("-destination")
+            .arg(format!("platform=iOS Simulator,id={}", udid))
+            .arg("-derivedDataPath")
+            .arg(derived_data)
+            .arg("CODE_SIGNING_ALLOWED=NO");
+        run_quietly(cmd)?;
+
+        let products = derived_data
+            .join("Build/Products")
+            .join(format!("{}-iphonesimulator", configuration));
+        let mut apps: Vec<PathBuf> = std::fs::read_dir(&products)?
+            .filter_map(|entry| entry.ok().map(|e| e.path()))
+            .filter(|path| path.extension().is_some_and(|ext| ext == "app"))
+            .collect();
+        // Frameworks' test hosts can land next to the app; the scheme's app has its name
+        apps.sort_by_key(|app| app.file_stem().is_none_or(|stem| stem != scheme));
+        apps.into_iter()
+            .next()
+            .ok_or_else(|| XcodeError::CommandFailed(format!("no .app in {}", products.display())))
+    }
+
+    /// File name of the workspace (or else the project) xcodebuild uses inside ios_path
+    pub fn container(ios_path: &str) -> Option<String> {
+        let path = Path::new(ios_path);
+        let container = find_workspace(path).or_else(|| find_project(path))?;
+
+        Path::new(&container)
+            .file_name()
+            .map(|name| name.to_string_lossy().to_string())
+    }
+
+    /// Whether `scheme` is shared in the workspace or project inside ios_path
+    pub fn scheme_sharing(ios_path: &str, scheme: &str) -> SchemeSharing {
+        let path = Path::new(ios_path);
+        let containers: Vec<PathBuf> = [find_workspace(path), find_project(path)]
+            .into_iter()
+            .flatten()
+            .map(PathBuf::from)
+            .collect();
+        let file_name = format!("{}.xcscheme", scheme);
+
+        for container in &containers {
+            let shared = container.join("xcshareddata/xcschemes").join(&file_name);
+            if shared.exists() {
+                return SchemeSharing::Shared;
+            }
+        }
+
+        for container in &containers {
+            let Ok(users) = std::fs::read_dir(container.join("xcuserdata")) else {
+                continue;
+            };
+            for user in users.flatten() {
+                let candidate = user.path().join("xcschemes").join(&file_name);
+                if candidate.exists() {
+                    return SchemeSharing::UserOnly(candidate);
+                }
+            }
+        }
+
+        SchemeSharing::Missing
+    }
+
+    /// Move a user scheme into its container's xcshareddata, as Xcode's
+    /// "Shared" checkbox does; returns the new path
+    pub fn share_scheme(user_scheme: &Path) -> Result<PathBuf, XcodeError> {
+        // <container>/xcuserdata/<user>.xcuserdatad/xcschemes/<scheme>.xcscheme
+        let container = user_scheme
+            .ancestors()
+            .nth(4)
+            .ok_or_else(|| XcodeError::NoProjectFound(user_scheme.display().to_string()))?;
+        let file_name = user_scheme
+            .file_name()
+            .ok_or_else(|| XcodeError::NoProjectFound(user_scheme.display().to_string()))?;
+
+        let shared_dir = container.join("xcshareddata/xcschemes");
+        std::fs::create_dir_all(&shared_dir)?;
+        let shared = shared_dir.join(file_name);
+        std::fs::rename(user_scheme, &shared)?;
+        Ok(shared)
+    }
+
+    /// Workspace settings file that switches to the legacy build system, which
+    /// Xcode 14 and later no longer have
+    pub fn legacy_build_system(ios_path: &str) -> Option<PathBuf> {
+        let path = Path::new(ios_path);
+        // Settings live in the workspace, or in the workspace embedded in the project
+        let workspaces = [
+            find_workspace(path).map(PathBuf::from),
+            find_project(path).map(|p| Path::new(&p).join("project.xcworkspace")),
+        ];
+
+        for workspace in workspaces.into_iter().flatten() {
+            let mut files = vec![workspace.join("xcshareddata/WorkspaceSettings.xcsettings")];
+            if let Ok(users) = std::fs::read_dir(workspace.join("xcuserdata")) {
+                files.extend(
+                    users
+                        .flatten()
+                        .map(|user| user.path().join("WorkspaceSettings.xcsettings")),
+                );
+            }
+
+            for file in files {
+                let Ok(settings) = plist::Value::from_file(&file) else {
+                    continue;
+                };
+                let build_system = settings
+                    .as_dictionary()
+                    .and_then(|d| d.get("BuildSystemType"))
+                    .and_then(|v| v.as_string());
+                if build_system == Some("Original") {
+                    return Some(file);
+                }
+            }
+        }
+        None
+    }
+
+    /// Check if Xcode is installed
+    pub fn is_installed() -> bool {
+        Command::new("xcode-select")
+            .arg("-p")
+            .output()
+            .map(|o| o.status.success())
+            .unwrap_or(false)
+    }
+
+    /// Get Xcode version
+    pub fn version() -> Option<String> {
+        let output = Command::new("xcodebuild").arg("-version").output().ok()?;
+
+        if output.status.success() {
+            let stdout = String::from_utf8_lossy(&output.stdout);
+            stdout.lines().next().map(|s| s.to_string())
+        } else {
+            None
+        }
+    }
+}
+
+/// Write `settings` as an xcconfig that `xcodebuild -xcconfig` layers over every target
+pub fn write_xcconfig(
+    path: &Path,
+    source: &str,
+    settings: &BTreeMap<String, String>,
+) -> Result<(), std::io::Error> {
+    let mut content = format!(
+        "// Generated by launchpad for {}; rewritten on every deploy\n",
+        source
+    );
+    for (key, value) in settings {
+        // "//" starts a comment even inside a value (URLs); $() expands to nothing
+        content.push_str(&format!("{} = {}\n", key, value.replace("//", "/$()/")));
+    }
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir)?;
+    }
+    std::fs::write(path, content)
+}
+
+/// xcodebuild pointed at the workspace, or else the project, in ios_path
+fn xcodebuild(ios_path: &str) -> Result<Command, XcodeError> {
+    let path = Path::new(ios_path);
+    let mut cmd = Command::new("xcodebuild");
+    if let Some(ws) = find_workspace(path) {
+        cmd.arg("-workspace").arg(ws);
+    } else if let Some(proj) = find_project(path) {
+        cmd.arg("-project").arg(proj);
+    } else {
+        return Err(XcodeError::NoProjectFound(ios_path.to_string()));
+    }
+    Ok(cmd)
+}
+
+/// Run a long xcodebuild command, keeping the end of its output for the error
+fn run_quietly(mut cmd: Command) -> Result<(), XcodeError> {
+    let output = cmd.output()?;
+    if output.status.success() {
+        return Ok(());
+    }
+
+    // xcodebuild prints compiler errors on stdout and the summary on stderr
+    let log = format!(
+        "{}{}",
+        String::from_utf8_lossy(&output.stdout),
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let lines: Vec<&str> = log.lines().collect();
+    let tail = lines[lines.len().saturating_sub(20)..].join("\n");
+    Err(XcodeError::CommandFailed(tail))
+}
+
+fn show_build_settings(ios_path: &str, scheme: &str) -> Result<String, XcodeError> {
+    if mock::enabled() {
+        return Ok(mock::build_settings());
+    }
+    let path = Path::new(ios_path);
+    let workspace = find_workspace(path);
+    let project = find_project(path);
+
+    let mut cmd = Command::new("xcodebuild");
+    cmd.arg("-showBuildSettings").arg("-scheme").arg(scheme);
+
+    if let Some(ws) = workspace {
+        cmd.arg("-workspace").arg(ws);
+    } else if let Some(proj) = project {
+        cmd.arg("-project").arg(proj);
+    }
+
+    let output = cmd.output()?;
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(XcodeError::CommandFailed(stderr.to_string()));
+    }
+
+    Ok(String::from_utf8_lossy(&output.stdout).to_string())
+}
+
+fn find_workspace(path: &Path) -> Option<String> {
+    if let Ok(entries) = std::fs::read_dir(path) {
+        for entry in entries.flatten() {
+            let name = entry.file_name();
+            let name_str = name.to_string_lossy();
+            if name_str.ends_with(".xcworkspace") && !name_str.starts_with("project.") {
+                return Some(entry.path().to_string_lossy().to_string());
+            }
+        }
+    }
+    None
+}
+
+fn find_project(path: &Path) -> Option<String> {
+    if let Ok(entries) = std::fs::read_dir(path) {
+        for entry in entries.flatten() {
+            let name = entry.file_name();
+            let name_str = name.to_string_lossy();
+            if name_str.ends_with(".xcodeproj") {
+                return Some(entry.path().to_string_lossy().to_string());
+            }
+        }
+    }
+    None
+}
+
+/// Describe SWIFT_VERSION settings the selected Xcode can't compile, if any.
+/// `xcode_version` is the first line of `xcodebuild -version` ("Xcode 16.0").
+pub fn swift_version_problem(targets: &[TargetVersion], xcode_version: &str) -> Option<String> {
+    let xcode_major: u32 = xcode_version
+        .split_whitespace()
+        .nth(1)?
+        .split('.')
+        .next()?
+        .parse()
+        .ok()?;
+
+    targets.iter().find_map(|target| {
+        let version = target.swift_version.as_deref()?;
+        let major: u32 = version.split('.').next()?.parse().ok()?;
+        if major < 4 {
+            Some(format!(
+                "{} uses Swift {}, which Xcode dropped in 10.2; migrate it to Swift 5",
+                target.target, version
+            ))
+        } else if major >= 6 && xcode_major < 16 {
+            Some(format!(
+                "{} uses Swift {}, which needs Xcode 16 or later (selected: {})",
+                target.target, version, xcode_version
+            ))
+        } else {
+            None
+        }
+    })
+}
+
+/// Describe MARKETING_VERSION / CURRENT_PROJECT_VERSION differences between targets, if any
+pub fn version_mismatch(targets: &[TargetVersion]) -> Option<String> {
+    let problems: Vec<String> = [
+        setting_mismatch("MARKETING_VERSION", targets, |t| &t.marketing_version),
+        setting_mismatch("CURRENT_PROJECT_VERSION", targets, |t| &t.build_number),
+    ]
+    .into_iter()
+    .flatten()
+    .collect();
+
+    if problems.is_empty() {
+        None
+    } else {
+        Some(problems.join("; "))
+    }
+}
+
+fn setting_mismatch(
+    setting: &str,
+    targets: &[TargetVersion],
+    value: impl Fn(&TargetVersion) -> &Option<String>,
+) -> Option<String> {
+    let mut values: Vec<_> = targets.iter().map(&value).collect();
+    values.sort();
+    values.dedup();
+
+    if values.len() <= 1 {
+        return None;
+    }
+
+    let detail = targets
+        .iter()
+        .map(|t| format!("{}={}", t.target, value(t).as_deref().unwrap_or("unset")))
+        .collect::<Vec<_>>()
+        .join(", ");
+
+    Some(format!("{} differs ({})", setting, detail))
+}
+
+fn parse_target_versions(output: &str) -> Vec<TargetVersion> {
+    let mut targets = Vec::new();
+    let mut current: Option<(TargetVersion, bool)> = None;
+
+    for line in output.lines() {
+        let trimmed = line.trim();
+
+        // "Build settings for action build and target MyApp:"
+        if let Some(rest) = trimmed.strip_prefix("Build settings for action build and target ") {
+            if let Some((target, shipped)) = current.take() {
+                if shipped {
+                    targets.push(target);
+                }
+            }
+            let target = TargetVersion {
+                target: rest.trim_end_matches(':').to_string(),
+                bundle_id: None,
+                product_type: None,
+                marketing_version: None,
+                build_number: None,
+                swift_version: None,
+            };
+            current = Some((target, false));
+            continue;
+        }
+
+        let Some((target, shipped)) = current.as_mut() else {
+            continue;
+        };
+        let Some((key, value)) = trimmed.split_once(" = ") else {
+            continue;
+        };
+
+        match key {
+            "PRODUCT_BUNDLE_IDENTIFIER" => target.bundle_id = Some(value.to_string()),
+            "MARKETING_VERSION" => target.marketing_version = Some(value.to_string()),
+            "CURRENT_PROJECT_VERSION" => target.build_number = Some(value.to_string()),
+            "SWIFT_VERSION" => target.swift_version = Some(value.to_string()),
+            // Only targets that end up in the uploaded bundle matter
+            "PRODUCT_TYPE" => {
+                target.product_type = Some(value.to_string());
+                *shipped = value.contains("application")
+                    || value.contains("app-extension")
+                    || value.contains("extensionkit-extension")
+                    || value.contains("watchapp")
+            }
+            _ => {}
+        }
+    }
+
+    if let Some((target, true)) = current {
+        targets.push(target);
+    }
+
+    targets
+}
+
+/// The -showBuildSettings block of the target that builds `bundle_id`
+fn settings_block<'a>(output: &'a str, bundle_id: &str) -> Option<&'a str> {
+    output
+        .split("Build settings for action")
+        .find(|block| block_setting(block, "PRODUCT_BUNDLE_IDENTIFIER") == Some(bundle_id))
+}
+
+fn block_setting<'a>(block: &'a str, name: &str) -> Option<&'a str> {
+    block
+        .lines()
+        .find_map(|line| match line.trim().split_once(" = ") {
+            Some((key, value)) if key == name => Some(value.trim()),
+            _ => None,
+        })
+}
+
+/// INFOPLIST_FILE of the settings block for `bundle_id`, resolved against its SRCROOT
+fn parse_info_plist(output: &str, bundle_id: &str) -> Option<PathBuf> {
+    let block = settings_block(output, bundle_id)?;
+    let file = Path::new(block_setting(block, "INFOPLIST_FILE").filter(|f| !f.is_empty())?);
+    if file.is_absolute() {
+        return Some(file.to_path_buf());
+    }
+    Some(Path::new(block_setting(block, "SRCROOT")?).join(file))
+}
+
+fn parse_schemes(output: &str) -> Vec<String> {
+    let mut schemes = Vec::new();
+    let mut in_schemes = false;
+
+    for line in output.lines() {
+        let trimmed = line.trim();
+
+        if trimmed == "Schemes:" {
+            in_schemes = true;
+            continue;
+        }
+
+        if in_schemes {
+            if trimmed.is_empty() || trimmed.ends_with(':') {
+                break;
+            }
+            schemes.push(trimmed.to_string());
+        }
+    }
+
+    schemes
+}
@@ -0,0 +1,53 @@
+//! A whole deploy driven through the library, against `--mock`'s canned fastlane,
+//! xcodebuild and App Store Connect output
+
+use launchpad_core::commands::deploy::{DeployOptions, ProjectArgs};
+use serde_json::Value;
+use std::path::PathBuf;
+
+#[tokio::test]
+async fn mock_deploy_bumps_the_build_number() {
+    let root = std::env::temp_dir().join(format!("launchpad-mock-deploy-{}", std::process::id()));
+    let home = root.join("home");
+    let project = root.join("project");
+    std::fs::create_dir_all(project.join("ios").join("App.xcodeproj")).unwrap();
+    std::fs::create_dir_all(&home).unwrap();
+
+    // Nothing from the machine running the tests: its configs, key or history
+    std::env::set_var("LAUNCHPAD_MOCK", "1");
+    std::env::set_var("HOME", &home);
+    std::env::set_var("LAUNCHPAD_CONFIG_DIR", home.join(".launchpad"));
+    for name in [
+        "APPLE_API_KEY_ID",
+        "APPLE_API_ISSUER_ID",
+        "APPLE_API_KEY_PATH",
+    ] {
+        std::env::remove_var(name);
+    }
+    std::env::set_current_dir(&project).unwrap();
+
+    let result = DeployOptions {
+        skip_git_check: true,
+        non_interactive: true,
+        project: ProjectArgs {
+            ios_path: Some("ios".to_string()),
+            scheme: Some("App".to_string()),
+            bundle_id: Some("com.example.app".to_string()),
+            ..Default::default()
+        },
+        ..Default::default()
+    }
+    .run()
+    .await;
+
+    let summary = PathBuf::from("build").join("deploy-summary.json");
+    let summary: Option<Value> = std::fs::read_to_string(&summary)
+        .ok()
+        .and_then(|content| serde_json::from_str(&content).ok());
+    let _ = std::fs::remove_dir_all(&root);
+
+    assert!(result.is_ok(), "deploy failed: {:?}", result.err());
+    let summary = summary.expect("no deploy summary written");
+    assert_eq!(summary["version"], "1.0.0");
+    assert_eq!(summary["build"], "2");
+}
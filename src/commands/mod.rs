@@ -1,4 +0,0 @@
-pub mod deploy;
-pub mod doctor;
-pub mod init;
-pub mod setup;
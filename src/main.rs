@@ -1,11 +1,6 @@
-mod commands;
-mod config;
-mod fastlane;
-mod templates;
-mod ui;
-mod xcode;
-
 use clap::{Parser, Subcommand};
+use launchpad_core::version::VersionBump;
+use launchpad_core::{commands, config, events, http, mock, presets, ui};
 use std::process::ExitCode;
 
 #[derive(Parser)]
@@ -15,6 +10,37 @@ use std::process::ExitCode;
 struct Cli {
     #[command(subcommand)]
     command: Commands,
+
+    /// Never prompt; every question takes its documented default
+    #[arg(long, short = 'y', global = true, visible_alias = "non-interactive")]
+    yes: bool,
+
+    /// Stream NDJSON progress events to a file, a listening Unix socket, or stdout (-)
+    #[arg(
+        long,
+        global = true,
+        value_name = "PATH|-",
+        env = "LAUNCHPAD_EVENT_STREAM"
+    )]
+    event_stream: Option<String>,
+
+    /// Log App Store Connect requests and responses, credentials redacted, to ~/.launchpad/logs/http.log
+    #[arg(long, global = true, env = "LAUNCHPAD_DEBUG_HTTP")]
+    debug_http: bool,
+
+    /// Simulate fastlane, xcodebuild and App Store Connect with canned output: nothing is built or uploaded
+    #[arg(long, global = true)]
+    mock: bool,
+
+    /// Environment to work with ([environments.<name>] in .launchpad.toml): its build
+    /// settings, Apple account and app
+    #[arg(
+        long = "env",
+        global = true,
+        value_name = "NAME",
+        env = "LAUNCHPAD_ENVIRONMENT"
+    )]
+    environment: Option<String>,
 }
 
 #[derive(Subcommand)]
@@ -22,13 +48,17 @@ enum Commands {
     /// Deploy to TestFlight
     Deploy {
         /// Bump patch version (1.0.0 → 1.0.1)
-        #[arg(long, conflicts_with = "minor")]
+        #[arg(long, conflicts_with_all = ["minor", "major"])]
         patch: bool,
 
         /// Bump minor version (1.0.0 → 1.1.0)
-        #[arg(long, conflicts_with = "patch")]
+        #[arg(long, conflicts_with_all = ["patch", "major"])]
         minor: bool,
 
+        /// Bump major version (1.2.3 → 2.0.0)
+        #[arg(long, conflicts_with_all = ["patch", "minor"])]
+        major: bool,
+
         /// Skip git tag creation
         #[arg(long)]
         no_tag: bool,
@@ -36,6 +66,29 @@ enum Commands {
         /// Skip pre-flight git checks
         #[arg(long)]
         skip_git_check: bool,
+
+        /// Keep build artifacts even if clean_artifacts is enabled
+        #[arg(long)]
+        keep_artifacts: bool,
+
+        /// Upload the .ipa of the last build when the source tree hasn't changed since
+        #[arg(long, conflicts_with_all = ["patch", "minor", "major"])]
+        reuse_artifact: bool,
+
+        /// Build the .ipa, bump and tag locally, and stop before uploading to TestFlight
+        #[arg(long, conflicts_with_all = ["reuse_artifact", "matrix", "platform"])]
+        skip_upload: bool,
+
+        /// Store to deploy to
+        #[arg(long, value_enum, default_value_t = commands::deploy::Platform::Ios)]
+        platform: commands::deploy::Platform,
+
+        /// Deploy every environment × target in [matrix], one after the other
+        #[arg(long, conflicts_with_all = ["reuse_artifact", "platform"])]
+        matrix: bool,
+
+        #[command(flatten)]
+        project: commands::deploy::ProjectArgs,
     },
 
     /// Initialize launchpad in current project
@@ -52,44 +105,635 @@ enum Commands {
         #[arg(long)]
         bundle_id: Option<String>,
 
-        /// Non-interactive mode (accept defaults)
-        #[arg(long, short = 'y')]
-        yes: bool,
+        /// Project preset (default: auto-detect)
+        #[arg(long, value_enum)]
+        template: Option<presets::Preset>,
     },
 
+    /// Change .launchpad.toml by answering the init questions again
+    Reconfigure {
+        /// Path to iOS project
+        #[arg(long)]
+        ios_path: Option<String>,
+
+        /// Xcode scheme to use
+        #[arg(long)]
+        scheme: Option<String>,
+
+        /// Bundle identifier
+        #[arg(long)]
+        bundle_id: Option<String>,
+    },
+
+    /// Re-detect schemes, bundle IDs and targets after the Xcode project changed
+    Refresh,
+
     /// Interactive first-time setup (global config)
-    Setup,
+    #[command(args_conflicts_with_subcommands = true)]
+    Setup {
+        #[command(subcommand)]
+        action: Option<commands::setup::SetupAction>,
+
+        /// Take credentials from APPLE_API_KEY_ID, APPLE_API_ISSUER_ID, APPLE_API_KEY_PATH
+        /// (and GOOGLE_PLAY_SERVICE_ACCOUNT_PATH, APPLE_VENDOR_NUMBER) without prompting
+        #[arg(long, conflicts_with = "from_json")]
+        from_env: bool,
+
+        /// Take the whole global config from a JSON or TOML file ("-" for stdin)
+        #[arg(long, value_name = "FILE")]
+        from_json: Option<String>,
+    },
 
     /// Check prerequisites (Xcode, fastlane, API key)
-    Doctor,
+    Doctor {
+        /// Check the project at this path instead of the current directory
+        #[arg(long, conflicts_with = "scan")]
+        project: Option<String>,
+
+        /// Find every .launchpad.toml under a directory and report each project
+        #[arg(long, value_name = "ROOT", num_args = 0..=1, default_missing_value = ".")]
+        scan: Option<String>,
+
+        /// Also time the App Store Connect API and measure upload speed to Apple
+        #[arg(long, conflicts_with = "scan")]
+        network: bool,
+
+        /// Fail on warnings too (outdated fastlane, expiring profiles, no release notes)
+        #[arg(long)]
+        strict: bool,
+    },
+
+    /// Give a processed build to external TestFlight groups and submit it for beta review
+    PromoteBeta {
+        /// Build number to promote (default: latest upload)
+        #[arg(long)]
+        build: Option<String>,
+
+        /// Marketing version the build number belongs to, when numbers repeat across versions
+        #[arg(long, requires = "build")]
+        version: Option<String>,
+
+        /// TestFlight group to add the build to (repeatable; default: deploy.groups)
+        #[arg(long = "group", value_name = "NAME")]
+        groups: Vec<String>,
+
+        /// App to promote (default: the bundle ID in .launchpad.toml)
+        #[arg(long)]
+        bundle_id: Option<String>,
+
+        /// Wait for the build to finish processing instead of failing
+        #[arg(long)]
+        wait: bool,
+    },
+
+    /// Check that App Store Connect received an uploaded build
+    Verify {
+        /// Build number to look for (default: the last deploy's)
+        #[arg(long)]
+        build: Option<String>,
+
+        /// Marketing version the build number belongs to, when numbers repeat across versions
+        #[arg(long, requires = "build")]
+        version: Option<String>,
+
+        /// App the build is for (default: the bundle ID in .launchpad.toml)
+        #[arg(long)]
+        bundle_id: Option<String>,
+
+        /// Keep checking for up to 20 minutes instead of failing right away
+        #[arg(long)]
+        wait: bool,
+    },
+
+    /// Check an .ipa's code signature, embedded profile and checksum before uploading it
+    VerifyIpa {
+        /// The .ipa to check
+        path: std::path::PathBuf,
+    },
+
+    /// Run a custom Fastfile lane with launchpad's credentials and output
+    RunLane {
+        /// Lane name (e.g. screenshots)
+        lane: String,
+
+        /// Lane options passed through to fastlane
+        #[arg(value_name = "KEY:VALUE")]
+        options: Vec<String>,
+    },
+
+    /// Finish TestFlight steps queued when App Store Connect couldn't be reached
+    RetryPending,
+
+    /// Compare two deployed builds (size, frameworks, entitlements, commits)
+    Diff {
+        /// Older build number
+        build_a: String,
+
+        /// Newer build number
+        build_b: String,
+
+        /// Which app's history to compare
+        #[arg(long, value_enum, default_value_t = commands::deploy::Platform::Ios)]
+        platform: commands::deploy::Platform,
+    },
+
+    /// Create the release tag for an earlier deploy, on the commit it was built from
+    Tag {
+        /// Build number of the deploy to tag
+        #[arg(long)]
+        build: String,
+
+        /// Which app's history the build is in
+        #[arg(long, value_enum, default_value_t = commands::deploy::Platform::Ios)]
+        platform: commands::deploy::Platform,
+
+        /// Don't push the tag, even with deploy.push_tags
+        #[arg(long)]
+        no_push: bool,
+    },
+
+    /// TestFlight state of the latest build on App Store Connect
+    Status {
+        /// Every build still processing or missing export compliance, across versions
+        #[arg(long)]
+        all: bool,
+
+        /// App to check (default: the project's)
+        #[arg(long)]
+        bundle_id: Option<String>,
+    },
+
+    /// Deploy counts and median App Store Connect processing times from the deploy history
+    Stats {
+        /// App to report on (default: the project's)
+        #[arg(long)]
+        bundle_id: Option<String>,
+    },
+
+    /// Deploy history kept in ~/.launchpad/history.db, or where [history] in the global config says
+    History {
+        #[command(subcommand)]
+        command: commands::history::HistoryCommand,
+    },
+
+    /// Installs, sessions and crashes per version from App Store Connect, for release reports
+    Analytics {
+        /// Period to cover, in days or weeks ("7d", "4w")
+        #[arg(long, default_value = "7d")]
+        last: String,
+
+        /// Print the snapshot as JSON
+        #[arg(long)]
+        json: bool,
+
+        /// App to report on (default: the project's)
+        #[arg(long)]
+        bundle_id: Option<String>,
+    },
+
+    /// Provisioning profiles for this project
+    Profiles {
+        #[command(subcommand)]
+        command: commands::profiles::ProfilesCommand,
+    },
+
+    /// Team test devices in devices.toml, synced with the Developer portal
+    Devices {
+        #[command(subcommand)]
+        command: commands::devices::DevicesCommand,
+    },
+
+    /// Re-sign an existing .ipa with another provisioning profile and certificate
+    Resign {
+        /// The .ipa to re-sign
+        ipa: std::path::PathBuf,
+
+        /// Installed profile name, or the path of a .mobileprovision file
+        #[arg(long)]
+        profile: String,
+
+        /// Signing certificate name ("Apple Distribution: Example Inc (TEAMID)") or SHA-1
+        #[arg(long)]
+        identity: String,
+
+        /// Where to write the result (default: <name>-resigned.ipa next to the input)
+        #[arg(long, short)]
+        output: Option<std::path::PathBuf>,
+    },
+
+    /// Install the last ad-hoc or development build on a connected device
+    Install {
+        /// Device UDID (default: the connected device, or a choice between several)
+        #[arg(long, value_name = "UDID")]
+        device: Option<String>,
+
+        /// .ipa to install (default: the last build in build.output_dir)
+        #[arg(long)]
+        ipa: Option<std::path::PathBuf>,
+    },
+
+    /// Build for a simulator, install there and launch with the app's output attached
+    Run {
+        /// Simulator name ("iPhone 15") or UDID (default: the booted simulator)
+        #[arg(long, value_name = "NAME")]
+        simulator: Option<String>,
+
+        /// Xcode build configuration
+        #[arg(long, default_value = "Debug")]
+        configuration: String,
+    },
+
+    /// Notarize a Developer ID signed macOS app, disk image or installer and staple the ticket
+    Notarize {
+        /// .app, .dmg, .pkg or .zip to submit
+        path: std::path::PathBuf,
+
+        /// Don't staple the ticket after notarization
+        #[arg(long)]
+        no_staple: bool,
+
+        /// Minutes to wait for Apple before giving up
+        #[arg(long, default_value_t = 30)]
+        timeout: u64,
+    },
+
+    /// Download and install sizes per device class from the App Thinning size report
+    Size {
+        /// Size report (default: "App Thinning Size Report.txt" in build.output_dir)
+        report: Option<std::path::PathBuf>,
+
+        /// Exit non-zero if a [size] budget is exceeded
+        #[arg(long)]
+        check: bool,
+    },
+
+    /// Crash-reporting symbols (dSYMs)
+    Symbols {
+        #[command(subcommand)]
+        command: commands::symbols::SymbolsCommand,
+    },
+
+    /// Bulk-manage external TestFlight testers with CSV files
+    Testers {
+        #[command(subcommand)]
+        command: commands::testers::TestersCommand,
+    },
+
+    /// Export compliance answers and encryption documentation on App Store Connect
+    Compliance {
+        #[command(subcommand)]
+        command: commands::compliance::ComplianceCommand,
+    },
+
+    /// App Store review state of the latest version
+    Review {
+        #[command(subcommand)]
+        command: commands::review::ReviewCommand,
+    },
+
+    /// App Store customer reviews: list them and respond
+    Reviews {
+        #[command(subcommand)]
+        command: commands::reviews::ReviewsCommand,
+    },
+
+    /// In-app purchases and subscriptions, kept in a local file
+    Iap {
+        #[command(subcommand)]
+        command: commands::iap::IapCommand,
+    },
+
+    /// Build cache kept between deploys
+    Cache {
+        #[command(subcommand)]
+        command: commands::cache::CacheCommand,
+    },
+
+    /// Generated Fastfile
+    Template {
+        #[command(subcommand)]
+        command: commands::template::TemplateCommand,
+    },
+
+    /// Deploy automatically whenever a release tag (or branch commit) is pushed
+    Watch {
+        /// Tags to deploy (glob)
+        #[arg(long, default_value = "release/*")]
+        tags: String,
+
+        /// Deploy each new commit on this remote branch instead of tags
+        #[arg(long, conflicts_with = "tags")]
+        branch: Option<String>,
+
+        /// Remote to fetch from
+        #[arg(long, default_value = "origin")]
+        remote: String,
+
+        /// Seconds between polls
+        #[arg(long, default_value_t = 60)]
+        interval: u64,
+
+        /// Store to deploy to
+        #[arg(long, value_enum, default_value_t = commands::deploy::Platform::Ios)]
+        platform: commands::deploy::Platform,
+
+        /// Poll once and exit (for cron or launchd)
+        #[arg(long)]
+        once: bool,
+
+        /// On the first run, deploy the tags that already exist instead of only new ones
+        #[arg(long)]
+        deploy_existing: bool,
+    },
+
+    /// Answer JSON-RPC requests from editor extensions (deploy, doctor, progress events)
+    Serve {
+        /// Listen on a Unix socket instead of stdin/stdout
+        #[arg(long)]
+        socket: Option<std::path::PathBuf>,
+    },
+
+    /// Run a `launchpad-<name>` plugin from PATH
+    #[command(external_subcommand)]
+    External(Vec<String>),
 }
 
 #[tokio::main]
 async fn main() -> ExitCode {
     let cli = Cli::parse();
+    ui::init();
+
+    // Decrypted key copies only live for the duration of the command
+    let _decrypted_keys = config::encryption::cleanup_on_exit();
+
+    if let Some(target) = &cli.event_stream {
+        if let Err(e) = events::open(target) {
+            ui::error(&e.to_string());
+            return ExitCode::FAILURE;
+        }
+        // Deploys started by `watch` report to the same place
+        std::env::set_var("LAUNCHPAD_EVENT_STREAM", target);
+        events::emit(
+            "started",
+            serde_json::json!({
+                "version": env!("CARGO_PKG_VERSION"),
+                "args": std::env::args().skip(1).collect::<Vec<_>>(),
+            }),
+        );
+    }
+
+    if cli.debug_http {
+        if let Err(e) = http::open_debug_log() {
+            ui::error(&format!("Could not open the HTTP debug log: {}", e));
+            return ExitCode::FAILURE;
+        }
+        // Deploys started by `watch` log to the same file
+        std::env::set_var("LAUNCHPAD_DEBUG_HTTP", "1");
+    }
+
+    // Child processes (hooks, plugins, fastlane) see the same mode
+    let yes = cli.yes || std::env::var("LAUNCHPAD_NON_INTERACTIVE").is_ok_and(|v| v == "1");
+    if yes {
+        std::env::set_var("LAUNCHPAD_NON_INTERACTIVE", "1");
+    }
+    if cli.mock || mock::enabled() {
+        std::env::set_var("LAUNCHPAD_MOCK", "1");
+        ui::warn("Mock mode: fastlane, xcodebuild and App Store Connect are simulated, nothing is built or uploaded");
+    }
+
+    // Notifications, hooks and fastlane see the same environment
+    if let Some(environment) = &cli.environment {
+        std::env::set_var("LAUNCHPAD_ENVIRONMENT", environment);
+    }
 
     let result: Result<(), Box<dyn std::error::Error>> = match cli.command {
         Commands::Deploy {
             patch,
             minor,
+            major,
             no_tag,
             skip_git_check,
-        } => commands::deploy::run(patch, minor, no_tag, skip_git_check)
+            keep_artifacts,
+            matrix: true,
+            project,
+            ..
+        } => commands::matrix::run(
+            commands::matrix::MatrixFlags {
+                bump: VersionBump::from_flags(patch, minor, major),
+                no_tag,
+                skip_git_check,
+                keep_artifacts,
+            },
+            project,
+            yes,
+        )
+        .await
+        .map_err(|e| e.into()),
+        Commands::Deploy {
+            patch,
+            minor,
+            major,
+            no_tag,
+            skip_git_check,
+            keep_artifacts,
+            reuse_artifact,
+            skip_upload,
+            platform,
+            matrix: false,
+            project,
+        } => commands::deploy::DeployOptions {
+            bump: VersionBump::from_flags(patch, minor, major),
+            no_tag,
+            skip_git_check,
+            keep_artifacts,
+            reuse_artifact,
+            skip_upload,
+            platform,
+            project,
+            non_interactive: yes,
+        }
+        .run()
+        .await
+        .map_err(|e| e.into()),
+        Commands::Init {
+            ios_path,
+            scheme,
+            bundle_id,
+            template,
+        } => commands::init::run(ios_path, scheme, bundle_id, template, yes)
+            .await
+            .map_err(|e| e.into()),
+        Commands::Reconfigure {
+            ios_path,
+            scheme,
+            bundle_id,
+        } => commands::reconfigure::run(ios_path, scheme, bundle_id, yes)
+            .await
+            .map_err(|e| e.into()),
+        Commands::Refresh => commands::refresh::run(yes).await.map_err(|e| e.into()),
+        Commands::Setup {
+            action,
+            from_env,
+            from_json,
+        } => commands::setup::run(action, from_env, from_json, yes)
+            .await
+            .map_err(|e| e.into()),
+        Commands::Doctor {
+            project,
+            scan,
+            network,
+            strict,
+        } => commands::doctor::run(project, scan, network, strict, yes)
+            .await
+            .map_err(|e| e.into()),
+        Commands::PromoteBeta {
+            build,
+            version,
+            groups,
+            bundle_id,
+            wait,
+        } => commands::promote::run(build, version, groups, bundle_id, wait)
+            .await
+            .map_err(|e| e.into()),
+        Commands::Verify {
+            build,
+            version,
+            bundle_id,
+            wait,
+        } => commands::verify::run(build, version, bundle_id, wait)
+            .await
+            .map_err(|e| e.into()),
+        Commands::VerifyIpa { path } => commands::verify_ipa::run(path).await.map_err(|e| e.into()),
+        Commands::RunLane { lane, options } => commands::lane::run(lane, options, yes)
+            .await
+            .map_err(|e| e.into()),
+        Commands::RetryPending => commands::pending::run().await.map_err(|e| e.into()),
+        Commands::Diff {
+            build_a,
+            build_b,
+            platform,
+        } => commands::diff::run(build_a, build_b, platform)
+            .await
+            .map_err(|e| e.into()),
+        Commands::Tag {
+            build,
+            platform,
+            no_push,
+        } => commands::tag::run(build, platform, no_push)
+            .await
+            .map_err(|e| e.into()),
+        Commands::Status { all, bundle_id } => commands::status::run(all, bundle_id)
             .await
             .map_err(|e| e.into()),
-        Commands::Init { ios_path, scheme, bundle_id, yes } => {
-            commands::init::run(ios_path, scheme, bundle_id, yes)
-                .await
-                .map_err(|e| e.into())
+        Commands::Stats { bundle_id } => {
+            commands::stats::run(bundle_id).await.map_err(|e| e.into())
         }
-        Commands::Setup => commands::setup::run().await.map_err(|e| e.into()),
-        Commands::Doctor => commands::doctor::run().await.map_err(|e| e.into()),
+        Commands::History { command } => {
+            commands::history::run(command).await.map_err(|e| e.into())
+        }
+        Commands::Analytics {
+            last,
+            json,
+            bundle_id,
+        } => commands::analytics::run(last, json, bundle_id)
+            .await
+            .map_err(|e| e.into()),
+        Commands::Profiles { command } => {
+            commands::profiles::run(command).await.map_err(|e| e.into())
+        }
+        Commands::Devices { command } => commands::devices::run(command, yes)
+            .await
+            .map_err(|e| e.into()),
+        Commands::Resign {
+            ipa,
+            profile,
+            identity,
+            output,
+        } => commands::resign::run(ipa, profile, identity, output)
+            .await
+            .map_err(|e| e.into()),
+        Commands::Install { device, ipa } => commands::install::run(device, ipa, yes)
+            .await
+            .map_err(|e| e.into()),
+        Commands::Run {
+            simulator,
+            configuration,
+        } => commands::run::run(simulator, configuration)
+            .await
+            .map_err(|e| e.into()),
+        Commands::Notarize {
+            path,
+            no_staple,
+            timeout,
+        } => commands::notarize::run(path, !no_staple, timeout)
+            .await
+            .map_err(|e| e.into()),
+        Commands::Size { report, check } => commands::size::run(report, check)
+            .await
+            .map_err(|e| e.into()),
+        Commands::Symbols { command } => {
+            commands::symbols::run(command).await.map_err(|e| e.into())
+        }
+        Commands::Testers { command } => {
+            commands::testers::run(command).await.map_err(|e| e.into())
+        }
+        Commands::Compliance { command } => commands::compliance::run(command)
+            .await
+            .map_err(|e| e.into()),
+        Commands::Review { command } => commands::review::run(command).await.map_err(|e| e.into()),
+        Commands::Reviews { command } => {
+            commands::reviews::run(command).await.map_err(|e| e.into())
+        }
+        Commands::Iap { command } => commands::iap::run(command, yes).await.map_err(|e| e.into()),
+        Commands::Cache { command } => commands::cache::run(command, yes)
+            .await
+            .map_err(|e| e.into()),
+        Commands::Template { command } => commands::template::run(command, yes)
+            .await
+            .map_err(|e| e.into()),
+        Commands::Watch {
+            tags,
+            branch,
+            remote,
+            interval,
+            platform,
+            once,
+            deploy_existing,
+        } => commands::watch::run(
+            tags,
+            branch,
+            remote,
+            interval,
+            platform,
+            once,
+            deploy_existing,
+        )
+        .await
+        .map_err(|e| e.into()),
+        Commands::Serve { socket } => commands::serve::run(socket).await.map_err(|e| e.into()),
+        Commands::External(args) => match commands::plugin::run(args, yes).await {
+            Ok(code) => {
+                events::emit(
+                    "exit",
+                    serde_json::json!({ "success": code == ExitCode::SUCCESS }),
+                );
+                return code;
+            }
+            Err(e) => Err(e.into()),
+        },
     };
 
     match result {
-        Ok(_) => ExitCode::SUCCESS,
+        Ok(_) => {
+            events::emit("exit", serde_json::json!({ "success": true }));
+            ExitCode::SUCCESS
+        }
         Err(e) => {
             ui::error(&e.to_string());
+            events::emit(
+                "exit",
+                serde_json::json!({ "success": false, "error": e.to_string() }),
+            );
             ExitCode::FAILURE
         }
     }